@@ -0,0 +1,4 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE user_posts ADD COLUMN post_status TEXT NOT NULL DEFAULT 'draft';
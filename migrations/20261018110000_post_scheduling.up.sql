@@ -0,0 +1,6 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE user_posts ADD COLUMN publish_at TIMESTAMPTZ;
+
+UPDATE user_posts SET publish_at = NOW() WHERE post_status = 'published';
@@ -0,0 +1,15 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+CREATE TABLE post_revisions(
+    revision_id TEXT NOT NULL PRIMARY KEY,
+    content_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    content_text TEXT NOT NULL,
+    extra_fields TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (content_id) REFERENCES user_posts(content_id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES cleo_users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX post_revisions_content_idx ON post_revisions(content_id, created_at);
@@ -0,0 +1,21 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE user_posts ADD COLUMN slug TEXT;
+
+UPDATE user_posts SET slug = LOWER(content_id);
+
+ALTER TABLE user_posts ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX user_posts_slug_idx ON user_posts(user_id, content_type, slug);
+
+CREATE TABLE slug_redirects(
+    user_id TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    old_slug TEXT NOT NULL,
+    content_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, content_type, old_slug),
+    FOREIGN KEY (content_id) REFERENCES user_posts(content_id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES cleo_users(user_id) ON DELETE CASCADE
+);
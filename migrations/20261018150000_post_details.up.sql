@@ -0,0 +1,12 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE user_posts ADD COLUMN title TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE user_posts ADD COLUMN excerpt TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE user_posts ADD COLUMN cover_file_id TEXT REFERENCES user_files(file_id) ON DELETE SET NULL;
+
+ALTER TABLE user_posts ADD COLUMN author_name TEXT NOT NULL DEFAULT '';
+
+UPDATE user_posts SET author_name = cleo_users.display_name FROM cleo_users WHERE cleo_users.user_id = user_posts.user_id;
@@ -0,0 +1,4 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE user_posts ADD COLUMN source_format TEXT NOT NULL DEFAULT 'html';
@@ -0,0 +1,22 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+CREATE TABLE content_types(
+    type_name TEXT NOT NULL PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE content_type_fields(
+    type_name TEXT NOT NULL,
+    field_key TEXT NOT NULL,
+    is_required BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (type_name, field_key),
+    FOREIGN KEY (type_name) REFERENCES content_types(type_name) ON DELETE CASCADE
+);
+
+INSERT INTO content_types (type_name, description) VALUES ('page', 'A standalone page.'), ('post', 'A blog post.');
+
+ALTER TABLE user_posts ADD FOREIGN KEY (content_type) REFERENCES content_types(type_name);
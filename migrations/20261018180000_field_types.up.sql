@@ -0,0 +1,6 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE extra_content_fields ADD COLUMN field_type TEXT NOT NULL DEFAULT 'string';
+
+ALTER TABLE content_type_fields ADD COLUMN field_type TEXT NOT NULL DEFAULT 'string';
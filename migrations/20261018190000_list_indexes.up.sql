@@ -0,0 +1,12 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+CREATE INDEX user_posts_user_created_idx ON user_posts(user_id, created_at);
+
+CREATE INDEX user_files_user_created_idx ON user_files(user_id, created_at);
+
+CREATE INDEX user_keys_user_idx ON user_keys(user_id);
+
+CREATE INDEX extra_content_fields_content_idx ON extra_content_fields(content_id);
+
+CREATE INDEX extra_content_fields_key_value_idx ON extra_content_fields(field_key, md5(field_value));
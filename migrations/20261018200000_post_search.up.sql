@@ -0,0 +1,54 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+CREATE TABLE post_search_index(
+    content_id TEXT NOT NULL PRIMARY KEY,
+    search_vector TSVECTOR NOT NULL,
+    FOREIGN KEY (content_id) REFERENCES user_posts(content_id) ON DELETE CASCADE
+);
+
+CREATE INDEX post_search_index_vector_idx ON post_search_index USING GIN(search_vector);
+
+CREATE FUNCTION refresh_post_search_index(target_id TEXT) RETURNS VOID AS $$
+    INSERT INTO post_search_index (content_id, search_vector)
+    SELECT
+        user_posts.content_id,
+        setweight(to_tsvector('english', user_posts.title), 'A') ||
+        setweight(to_tsvector('english', COALESCE((
+            SELECT string_agg(extra_content_fields.field_value, ' ')
+            FROM extra_content_fields
+            WHERE extra_content_fields.content_id = user_posts.content_id
+        ), '')), 'B') ||
+        setweight(to_tsvector('english', regexp_replace(user_posts.content_text, '<[^>]*>', ' ', 'g')), 'C')
+    FROM user_posts
+    WHERE user_posts.content_id = target_id
+    ON CONFLICT (content_id) DO UPDATE SET search_vector = EXCLUDED.search_vector;
+$$ LANGUAGE SQL;
+
+CREATE FUNCTION index_user_post() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM refresh_post_search_index(NEW.content_id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION index_extra_content_field() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM refresh_post_search_index(OLD.content_id);
+    ELSE
+        PERFORM refresh_post_search_index(NEW.content_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_posts_search_trigger
+    AFTER INSERT OR UPDATE OF title, content_text ON user_posts
+    FOR EACH ROW EXECUTE FUNCTION index_user_post();
+
+CREATE TRIGGER extra_content_fields_search_trigger
+    AFTER INSERT OR UPDATE OR DELETE ON extra_content_fields
+    FOR EACH ROW EXECUTE FUNCTION index_extra_content_field();
+
+SELECT refresh_post_search_index(content_id) FROM user_posts;
@@ -0,0 +1,29 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+CREATE TABLE taxonomy_terms(
+    term_id TEXT NOT NULL PRIMARY KEY,
+    taxonomy TEXT NOT NULL CHECK (taxonomy IN ('tag', 'category')),
+    term_name TEXT NOT NULL,
+    term_slug TEXT NOT NULL,
+    parent_id TEXT,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (taxonomy, term_slug),
+    CHECK (taxonomy = 'category' OR parent_id IS NULL),
+    FOREIGN KEY (parent_id) REFERENCES taxonomy_terms(term_id)
+);
+
+CREATE INDEX taxonomy_terms_parent_idx ON taxonomy_terms(parent_id);
+
+CREATE TABLE post_terms(
+    content_id TEXT NOT NULL,
+    term_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (content_id, term_id),
+    FOREIGN KEY (content_id) REFERENCES user_posts(content_id) ON DELETE CASCADE,
+    FOREIGN KEY (term_id) REFERENCES taxonomy_terms(term_id) ON DELETE CASCADE
+);
+
+CREATE INDEX post_terms_term_idx ON post_terms(term_id);
@@ -0,0 +1,8 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE user_posts ADD COLUMN parent_id TEXT REFERENCES user_posts(content_id) ON DELETE SET NULL;
+
+ALTER TABLE user_posts ADD COLUMN page_position INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX user_posts_parent_idx ON user_posts(user_id, parent_id, page_position);
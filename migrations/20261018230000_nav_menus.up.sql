@@ -0,0 +1,34 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+CREATE TABLE nav_menus(
+    menu_id TEXT NOT NULL PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    menu_name TEXT NOT NULL,
+    menu_slug TEXT NOT NULL,
+    max_depth INTEGER NOT NULL DEFAULT 3 CHECK (max_depth BETWEEN 1 AND 10),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, menu_slug),
+    FOREIGN KEY (user_id) REFERENCES cleo_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE menu_items(
+    item_id TEXT NOT NULL PRIMARY KEY,
+    menu_id TEXT NOT NULL,
+    parent_id TEXT,
+    label TEXT NOT NULL,
+    target_type TEXT NOT NULL CHECK (target_type IN ('post', 'page', 'file', 'url')),
+    content_id TEXT,
+    file_id TEXT,
+    target_url TEXT,
+    item_position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (menu_id) REFERENCES nav_menus(menu_id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_id) REFERENCES menu_items(item_id) ON DELETE CASCADE,
+    FOREIGN KEY (content_id) REFERENCES user_posts(content_id) ON DELETE SET NULL,
+    FOREIGN KEY (file_id) REFERENCES user_files(file_id) ON DELETE SET NULL
+);
+
+CREATE INDEX menu_items_menu_idx ON menu_items(menu_id, parent_id, item_position);
@@ -0,0 +1,15 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE user_api_tokens RENAME COLUMN token TO token_hash;
+UPDATE user_api_tokens SET token_hash = UPPER(ENCODE(SHA256(CONVERT_TO(token_hash, 'UTF8')), 'hex'));
+CREATE UNIQUE INDEX user_api_tokens_hash_idx ON user_api_tokens (token_hash);
+
+ALTER TABLE user_api_tokens ADD COLUMN label TEXT;
+ALTER TABLE user_api_tokens ADD COLUMN scopes TEXT[] NOT NULL DEFAULT ARRAY['content:read', 'content:write', 'files', 'account']::TEXT[];
+UPDATE user_api_tokens SET scopes = ARRAY['content:read', 'content:write', 'files', 'account', 'admin']::TEXT[]
+    FROM cleo_users
+    WHERE cleo_users.user_id = user_api_tokens.user_id AND cleo_users.is_admin;
+ALTER TABLE user_api_tokens ALTER COLUMN scopes DROP DEFAULT;
+ALTER TABLE user_api_tokens ADD COLUMN expires_at TIMESTAMPTZ;
+ALTER TABLE user_api_tokens ADD COLUMN last_used_at TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE instance_info ADD COLUMN webhook_allowlist TEXT[] NOT NULL DEFAULT '{}';
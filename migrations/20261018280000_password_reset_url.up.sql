@@ -0,0 +1,4 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE instance_info ADD COLUMN password_reset_url TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,12 @@
+-- Cleo by Alyx Shang.
+-- Licensed under the FSL v1.
+
+ALTER TABLE post_revisions ADD COLUMN title TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE post_revisions ADD COLUMN excerpt TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE post_revisions ADD COLUMN cover_file_id TEXT REFERENCES user_files(file_id) ON DELETE SET NULL;
+
+ALTER TABLE post_revisions ADD COLUMN author_name TEXT NOT NULL DEFAULT '';
+
+UPDATE post_revisions SET title = user_posts.title, excerpt = user_posts.excerpt, cover_file_id = user_posts.cover_file_id, author_name = user_posts.author_name FROM user_posts WHERE user_posts.content_id = post_revisions.content_id;
@@ -13,6 +13,57 @@ pub mod modules;
 /// from the database.
 pub use modules::db::*;
 
+/// Re-exporting the
+/// database module for
+/// post revisions. This
+/// and the database modules
+/// below share their names
+/// with service modules, so
+/// they are re-exported by
+/// name to settle which one
+/// the crate exports.
+pub use modules::db::revisions;
+
+/// Re-exporting the
+/// database module for
+/// searching posts.
+pub use modules::db::search;
+
+/// Re-exporting the
+/// database module for
+/// tags and categories.
+pub use modules::db::taxonomy;
+
+/// Re-exporting the
+/// database module for
+/// pages.
+pub use modules::db::pages;
+
+/// Re-exporting the
+/// database module for
+/// navigation menus.
+pub use modules::db::menus;
+
+/// Re-exporting the
+/// database module for
+/// feeds and sitemaps.
+pub use modules::db::feeds;
+
+/// Re-exporting the
+/// database module for
+/// webhooks.
+pub use modules::db::webhooks;
+
+/// Re-exporting the
+/// database module for
+/// changes to content.
+pub use modules::db::changes;
+
+/// Re-exporting the
+/// database module for
+/// content types.
+pub use modules::db::content_types;
+
 /// Re-exporting the
 /// module containing
 /// this crate's
@@ -20,6 +71,11 @@ pub use modules::db::*;
 /// structure.
 pub use modules::err::*;
 
+/// Re-exporting the
+/// module for validating
+/// and sanitizing HTML.
+pub use modules::html::*;
+
 /// Re-exporting 
 /// a module
 /// containing
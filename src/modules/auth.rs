@@ -0,0 +1,320 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a
+/// database.
+use sqlx::Pool;
+
+/// Importing the "Error"
+/// structure for errors
+/// of middleware.
+use actix_web::Error;
+
+/// Importing the "ready"
+/// function to resolve the
+/// extractor without waiting.
+use std::future::ready;
+
+/// Importing the "Ready"
+/// structure for explicit
+/// typing.
+use std::future::Ready;
+
+/// Importing the "Data"
+/// structure to access
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "HttpMessage"
+/// trait to access the extensions
+/// of a request.
+use actix_web::HttpMessage;
+
+/// Importing the "HttpRequest"
+/// structure to mark requests
+/// that used a deprecated token.
+use actix_web::HttpRequest;
+
+/// Importing the "FromRequest"
+/// trait to implement it for
+/// the authentication extractor.
+use actix_web::FromRequest;
+
+/// Importing the "Payload"
+/// enum for explicit typing.
+use actix_web::dev::Payload;
+
+/// Importing the
+/// "Postgres" structure
+/// for explicit typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the "Next"
+/// structure to call the
+/// rest of the chain of services.
+use actix_web::middleware::Next;
+
+/// Importing the "MessageBody"
+/// trait for the bodies of
+/// responses of middleware.
+use actix_web::body::MessageBody;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the "ServiceRequest"
+/// structure for requests passed
+/// to middleware.
+use actix_web::dev::ServiceRequest;
+
+/// Importing the "ServiceResponse"
+/// structure for responses passed
+/// back from middleware.
+use actix_web::dev::ServiceResponse;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the "HeaderMap"
+/// structure to read the headers
+/// of a request.
+use actix_web::http::header::HeaderMap;
+
+/// Importing the "HeaderName"
+/// structure to mark responses
+/// to requests that used a
+/// deprecated token.
+use actix_web::http::header::HeaderName;
+
+/// Importing the "HeaderValue"
+/// structure to mark responses
+/// to requests that used a
+/// deprecated token.
+use actix_web::http::header::HeaderValue;
+
+/// Importing the model for
+/// API tokens for explicit typing.
+use crate::modules::models::UserAPIToken;
+
+/// Importing the "AUTHORIZATION"
+/// constant to read the header
+/// carrying the API token.
+use actix_web::http::header::AUTHORIZATION;
+
+/// Importing the function to retrieve
+/// the user and the details of an API
+/// token.
+use crate::modules::db::tokens::authenticate_token;
+
+/// The scheme API tokens are
+/// sent with in the "Authorization"
+/// header.
+pub const BEARER_SCHEME: &str = "Bearer";
+
+/// A marker stored on requests
+/// that authenticated with an API
+/// token in their body or query
+/// string instead of the "Authorization"
+/// header.
+pub struct DeprecatedToken;
+
+/// Attempts to read the API token
+/// from the "Authorization" header of
+/// a request. If the header is missing,
+/// nothing is returned. If the header
+/// is not a bearer token, an error is
+/// returned.
+pub fn bearer_token(headers: &HeaderMap) -> Result<Option<String>, CleoErr> {
+    let header: &HeaderValue = match headers.get(AUTHORIZATION) {
+        Some(header) => header,
+        None => return Ok(None)
+    };
+    let value: &str = match header.to_str() {
+        Ok(value) => value,
+        Err(e) => return Err::<Option<String>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match value.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case(BEARER_SCHEME) && !token.trim().is_empty() => {
+            Ok(Some(token.trim().to_string()))
+        },
+        _ => {
+            let e: String = format!("The \"Authorization\" header must use the \"{}\" scheme.", BEARER_SCHEME);
+            Err::<Option<String>, CleoErr>(CleoErr::new(&e))
+        }
+    }
+}
+
+/// A marker stored on requests
+/// whose "Authorization" header could
+/// not be authenticated. It carries the
+/// reason so that services resolving the
+/// user can report it.
+pub struct RejectedToken(pub String);
+
+/// A middleware that authenticates
+/// the API token in the "Authorization"
+/// header of a request once and stores
+/// the user and the token for services.
+/// Requests with an invalid token are not
+/// rejected here, since public services do
+/// not need a user. Instead, the reason is
+/// stored and services that resolve the user
+/// fail with it. Responses to requests that
+/// authenticated with a deprecated token 
+/// carry a "Deprecation" header.
+pub async fn authenticate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    match bearer_token(req.headers()) {
+        Ok(Some(token)) => {
+            let pool: Pool<Postgres> = match req.app_data::<Data<AppData>>() {
+                Some(data) => data.pool.clone(),
+                None => return Err(Error::from(CleoErr::new("App data is missing.")))
+            };
+            match authenticate_token(&token, &pool).await {
+                Ok((user, token_obj)) => {
+                    req.extensions_mut().insert(user);
+                    req.extensions_mut().insert(token_obj);
+                },
+                Err(e) => {
+                    req.extensions_mut().insert(RejectedToken(e.to_string()));
+                }
+            };
+        },
+        Ok(None) => {},
+        Err(e) => {
+            req.extensions_mut().insert(RejectedToken(e.to_string()));
+        }
+    };
+    let mut res: ServiceResponse<_> = next.call(req).await?;
+    if res.request().extensions().contains::<DeprecatedToken>() {
+        res.headers_mut().insert(
+            HeaderName::from_static("deprecation"),
+            HeaderValue::from_static("true")
+        );
+    }
+    Ok(res)
+}
+
+/// A structure to hand services
+/// the user a request authenticated
+/// as and the token it used.
+pub struct AuthExtractor{
+    pub user: Option<CleoUser>,
+    pub token: Option<UserAPIToken>,
+    pub rejection: Option<String>,
+    request: HttpRequest
+}
+
+/// Implementing the "FromRequest"
+/// trait so that services can accept
+/// the "AuthExtractor" structure.
+impl FromRequest for AuthExtractor {
+
+    /// The extractor never fails.
+    type Error = CleoErr;
+
+    /// The extractor resolves
+    /// immediately.
+    type Future = Ready<Result<AuthExtractor, CleoErr>>;
+
+    /// Picks up the user and the
+    /// token the "authenticate" 
+    /// middleware stored on the
+    /// request or the reason the
+    /// token was rejected.
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(
+            Ok(
+                AuthExtractor{
+                    user: req.extensions().get::<CleoUser>().cloned(),
+                    token: req.extensions().get::<UserAPIToken>().cloned(),
+                    rejection: req.extensions().get::<RejectedToken>().map(|rejected| rejected.0.clone()),
+                    request: req.clone()
+                }
+            )
+        )
+    }
+
+}
+
+/// Implementing generic
+/// methods for the "AuthExtractor"
+/// structure.
+impl AuthExtractor {
+
+    /// Attempts to return the user
+    /// a request authenticated as if
+    /// its token carries the supplied
+    /// scope. If the request did not send
+    /// a bearer token, the deprecated token
+    /// from its body or query string is used
+    /// instead. If no token was supplied, the
+    /// token is invalid, or it lacks the scope,
+    /// an error is returned. A rejected bearer
+    /// token is never replaced by the deprecated
+    /// token.
+    pub async fn resolve(
+        &self,
+        fallback: &Option<String>,
+        scope: &str,
+        pool: &Pool<Postgres>
+    ) -> Result<CleoUser, CleoErr> {
+        match self.resolve_token(fallback, scope, pool).await {
+            Ok((user, _token_obj)) => Ok(user),
+            Err(e) => Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    }
+
+    /// Works like the "resolve" method
+    /// but also returns the token the 
+    /// request authenticated with.
+    pub async fn resolve_token(
+        &self,
+        fallback: &Option<String>,
+        scope: &str,
+        pool: &Pool<Postgres>
+    ) -> Result<(CleoUser, UserAPIToken), CleoErr> {
+        let (user, token_obj): (CleoUser, UserAPIToken) = match (&self.user, &self.token, &self.rejection) {
+            (Some(user), Some(token_obj), _) => (user.clone(), token_obj.clone()),
+            (_, _, Some(e)) => return Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(e)),
+            _ => {
+                let token: &String = match fallback {
+                    Some(token) => token,
+                    None => {
+                        let e: &str = "No API token was supplied.";
+                        return Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(e));
+                    }
+                };
+                let authenticated: (CleoUser, UserAPIToken) = match authenticate_token(token, pool).await {
+                    Ok(authenticated) => authenticated,
+                    Err(e) => return Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(&e.to_string()))
+                };
+                self.request.extensions_mut().insert(DeprecatedToken);
+                authenticated
+            }
+        };
+        if token_obj.scopes.iter().any(|held| held == scope){
+            Ok((user, token_obj))
+        }
+        else {
+            let e: String = format!("The supplied API token lacks the \"{}\" scope.", scope);
+            Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(&e))
+        }
+    }
+
+}
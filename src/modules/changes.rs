@@ -0,0 +1,262 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "Pin"
+/// structure to poll the
+/// body of a stream.
+use std::pin::Pin;
+
+/// Importing the "select"
+/// macro to wait on changes
+/// and keep-alive ticks at once.
+use tokio::select;
+
+/// Importing the "Poll"
+/// enum to answer polls
+/// of the body of a stream.
+use std::task::Poll;
+
+/// Importing the "mpsc" module
+/// to pass frames from a stream's
+/// task to its body.
+use tokio::sync::mpsc;
+
+/// Importing the "Context"
+/// structure to poll the body
+/// of a stream.
+use std::task::Context;
+
+/// Importing the "Duration"
+/// structure to space keep-alive
+/// comments.
+use std::time::Duration;
+
+/// Importing the "Bytes"
+/// structure for the frames
+/// of a stream.
+use actix_web::web::Bytes;
+
+/// Importing the "interval"
+/// function to send keep-alive
+/// comments.
+use tokio::time::interval;
+
+/// Importing the "Infallible"
+/// enum as the error of a body
+/// that never fails.
+use std::convert::Infallible;
+
+/// Importing the
+/// "Postgres" structure
+/// for explicit typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the "BodySize"
+/// enum to mark a body as a
+/// stream.
+use actix_web::body::BodySize;
+
+/// Importing the "PgListener"
+/// structure to listen for 
+/// notifications from the database.
+use sqlx::postgres::PgListener;
+
+/// Importing the "MessageBody"
+/// trait to implement it for streams
+/// of changes.
+use actix_web::body::MessageBody;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "Sender"
+/// structure to send changes
+/// to content to open streams.
+use tokio::sync::broadcast::Sender;
+
+/// Importing the "Receiver"
+/// structure to receive changes
+/// to content.
+use tokio::sync::broadcast::Receiver;
+
+/// Importing the structure
+/// describing a change to 
+/// content.
+use crate::modules::units::ChangeEvent;
+
+/// Importing the "RecvError"
+/// enum to handle streams that
+/// fell behind or were closed.
+use tokio::sync::broadcast::error::RecvError;
+
+/// Importing the name of the channel
+/// changes to content are announced on.
+use crate::modules::db::changes::CHANGE_CHANNEL;
+
+/// The number of changes to content
+/// held for open streams before slow
+/// streams miss some.
+pub const CHANGE_BUFFER: usize = 1024;
+
+/// The number of frames held for a 
+/// single stream before its task waits
+/// for the client to catch up.
+pub const CHANGE_STREAM_BUFFER: usize = 64;
+
+/// The number of seconds between
+/// keep-alive comments on an open
+/// stream.
+pub const CHANGE_KEEP_ALIVE: u64 = 15;
+
+/// The number of seconds to wait
+/// before listening for changes again
+/// after the connection was lost.
+pub const CHANGE_RETRY: u64 = 5;
+
+/// A structure for the body
+/// of a stream of Server-Sent
+/// Events.
+pub struct ChangeStream{
+    pub receiver: mpsc::Receiver<Bytes>
+}
+
+/// Implementing the "MessageBody"
+/// trait so that a stream of changes
+/// can be sent as the body of a
+/// response.
+impl MessageBody for ChangeStream {
+
+    /// The body of a stream
+    /// never fails.
+    type Error = Infallible;
+
+    /// The size of a stream is
+    /// not known beforehand.
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    /// Returns the next frame of
+    /// the stream once it is ready.
+    fn poll_next(
+        mut self: Pin<&mut Self>, 
+        cx: &mut Context<'_>
+    ) -> Poll<Option<Result<Bytes, Infallible>>> {
+        self.receiver.poll_recv(cx).map(|frame| frame.map(Ok))
+    }
+
+}
+
+/// Renders a change to content
+/// as an event of a Server-Sent
+/// Events stream.
+pub fn change_frame(change: &ChangeEvent) -> Result<String, CleoErr> {
+    let data: String = match serde_json::to_string(change){
+        Ok(data) => data,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(
+        format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            change.event_id,
+            change.event,
+            data
+        )
+    )
+}
+
+/// Attempts to listen for changes to
+/// content announced by any instance 
+/// sharing the database and to pass them
+/// on to all open streams. This function 
+/// only returns if the connection to the 
+/// database fails, in which case an error
+/// is returned.
+pub async fn listen_for_changes(
+    pool: &Pool<Postgres>,
+    sender: &Sender<ChangeEvent>
+) -> Result<(), CleoErr> {
+    let mut listener: PgListener = match PgListener::connect_with(pool).await {
+        Ok(listener) => listener,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match listener.listen(CHANGE_CHANNEL).await {
+        Ok(_listening) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    loop {
+        let notification = match listener.recv().await {
+            Ok(notification) => notification,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let change: ChangeEvent = match serde_json::from_str(notification.payload()){
+            Ok(change) => change,
+            Err(e) => {
+                eprintln!("{}", &e.to_string());
+                continue;
+            }
+        };
+        let _sent = sender.send(change);
+    }
+}
+
+/// Opens a stream of the changes 
+/// to the content of a user. If a 
+/// list of events is supplied, only
+/// those events are streamed. Streams
+/// that fall behind are told how many
+/// changes they missed. The stream ends
+/// once the client disconnects.
+pub fn stream_user_changes(
+    user_id: &str,
+    events: &Option<Vec<String>>,
+    mut changes: Receiver<ChangeEvent>
+) -> ChangeStream {
+    let (frames, receiver): (mpsc::Sender<Bytes>, mpsc::Receiver<Bytes>) = mpsc::channel(CHANGE_STREAM_BUFFER);
+    let user_id: String = user_id.to_owned();
+    let events: Option<Vec<String>> = events.clone();
+    actix_web::rt::spawn(async move {
+        let mut timer = interval(Duration::from_secs(CHANGE_KEEP_ALIVE));
+        loop {
+            let frame: String = select! {
+                received = changes.recv() => match received {
+                    Ok(change) => {
+                        if change.user_id != user_id {
+                            continue;
+                        }
+                        if let Some(events) = &events {
+                            if !events.contains(&change.event) {
+                                continue;
+                            }
+                        }
+                        match change_frame(&change){
+                            Ok(frame) => frame,
+                            Err(e) => {
+                                eprintln!("{}", &e.to_string());
+                                continue;
+                            }
+                        }
+                    },
+                    Err(RecvError::Lagged(missed)) => format!("event: lagged\ndata: {}\n\n", missed),
+                    Err(RecvError::Closed) => break
+                },
+                _tick = timer.tick() => ": keep-alive\n\n".to_string()
+            };
+            if frames.send(Bytes::from(frame)).await.is_err() {
+                break;
+            }
+        }
+    });
+    ChangeStream{ receiver }
+}
@@ -22,12 +22,22 @@ use sqlx::query;
 /// something.
 use sqlx::query_as;
 
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries to return a
+/// single value.
+use sqlx::query_scalar;
+
 /// Importing the "Postgres"
 /// structure to specify which
 /// database one is connecting
 /// to.
 use sqlx::postgres::Postgres;
 
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr"
 /// structure to catch and handle
 /// errors.
@@ -39,15 +49,10 @@ use crate::modules::err::CleoErr;
 /// database.
 use crate::modules::models::CleoUser;
 
-/// Importing the "hash_string" 
-/// function to generate hashes for
-/// strings.
-use crate::modules::utils::hash_string;
-
-/// Importing the "get_user_from_token"
-/// function to retrieve a Cleo
-/// user using an API token.
-use super::tokens::get_user_from_token;
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
 
 /// Importing the "InstanceInformation"
 /// structure to write information about
@@ -58,76 +63,108 @@ use crate::modules::models::InstanceInformation;
 /// record about the current Cleo instance.
 use crate::modules::db::general::get_instance_info;
 
+/// Importing the function to
+/// bring hosts of webhooks into
+/// a comparable form.
+use crate::modules::webhooks::normalize_webhook_host;
+
+/// The fields the users of
+/// an instance can be sorted
+/// by.
+pub const USER_SORT_FIELDS: [&str; 3] = [
+    "created_at", "updated_at", "username"
+];
+
 /// This function attempts to
-/// retrieve a list of users
-/// present on a Cleo instance.
-/// If the operation is successful,
-/// a vector containing  instances
-/// of the "CleoUser" structure is 
-/// returned. If the operation fails,
+/// retrieve a page of the users
+/// or administrators present on a
+/// Cleo instance. If the operation 
+/// is successful, a vector containing 
+/// instances of the "CleoUser" structure 
+/// and the total number of matching users
+/// are returned. If the operation fails,
 /// an error is returned.
-pub async fn get_instance_users(
-    api_token: &String,
+async fn get_instance_users_by_role(
+    user: &CleoUser,
+    is_admin: bool,
+    options: &ListOptions,
     pool: &Pool<Postgres>,
-) -> Result<Vec<CleoUser>, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<Vec<CleoUser>, CleoErr>(CleoErr::new(&e.to_string()))
-    };
+) -> Result<(Vec<CleoUser>, i64), CleoErr>{
     if user.is_admin {
         let cleo_users: Vec<CleoUser> = match query_as!(
             CleoUser,
-            "SELECT * FROM cleo_users WHERE is_admin = $1",
-            false
+            "SELECT * FROM cleo_users WHERE is_admin = $1 
+            ORDER BY 
+            CASE WHEN $2 = 'created_at' AND $3 = 'asc' THEN created_at END ASC, 
+            CASE WHEN $2 = 'created_at' AND $3 = 'desc' THEN created_at END DESC, 
+            CASE WHEN $2 = 'updated_at' AND $3 = 'asc' THEN updated_at END ASC, 
+            CASE WHEN $2 = 'updated_at' AND $3 = 'desc' THEN updated_at END DESC, 
+            CASE WHEN $2 = 'username' AND $3 = 'asc' THEN username END ASC, 
+            CASE WHEN $2 = 'username' AND $3 = 'desc' THEN username END DESC, 
+            user_id ASC 
+            LIMIT $4 OFFSET $5",
+            is_admin,
+            options.sort_by,
+            options.sort_order,
+            options.per_page,
+            options.offset()
         )   
             .fetch_all(pool)
             .await 
         {
             Ok(cleo_users) => cleo_users,
-            Err(e) => return Err::<Vec<CleoUser>, CleoErr>(CleoErr::new(&e.to_string()))
+            Err(e) => return Err::<(Vec<CleoUser>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let total: i64 = match query_scalar!(
+            "SELECT COUNT(*) AS \"total!\" FROM cleo_users WHERE is_admin = $1",
+            is_admin
+        )
+            .fetch_one(pool)
+            .await 
+        {
+            Ok(total) => total,
+            Err(e) => return Err::<(Vec<CleoUser>, i64), CleoErr>(CleoErr::new(&e.to_string()))
         };
-        Ok(cleo_users)
+        Ok((cleo_users, total))
     }
     else {
         let e: String = format!("User is not an administrator.");
-        Err::<Vec<CleoUser>, CleoErr>(CleoErr::new(&e.to_string()))
+        Err::<(Vec<CleoUser>, i64), CleoErr>(CleoErr::new(&e.to_string()))
     }
 }
 
 /// This function attempts to
-/// retrieve a list of admins
+/// retrieve a page of the users
 /// present on a Cleo instance.
 /// If the operation is successful,
-/// a vector containing  instances
-/// of the "CleoUser" structure is 
+/// a vector containing instances
+/// of the "CleoUser" structure and
+/// the total number of users are
+/// returned. If the operation fails,
+/// an error is returned.
+pub async fn get_instance_users(
+    user: &CleoUser,
+    options: &ListOptions,
+    pool: &Pool<Postgres>,
+) -> Result<(Vec<CleoUser>, i64), CleoErr>{
+    get_instance_users_by_role(user, false, options, pool).await
+}
+
+/// This function attempts to
+/// retrieve a page of the admins
+/// present on a Cleo instance.
+/// If the operation is successful,
+/// a vector containing instances
+/// of the "CleoUser" structure and
+/// the total number of admins are
 /// returned. If the operation fails,
 /// an error is returned.
 pub async fn get_instance_admins(
-    api_token: &String,
+    user: &CleoUser,
+    options: &ListOptions,
     pool: &Pool<Postgres>,
-) -> Result<Vec<CleoUser>, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<Vec<CleoUser>, CleoErr>(CleoErr::new(&e.to_string()))
-    };
-    if user.is_admin {
-        let cleo_users: Vec<CleoUser> = match query_as!(
-            CleoUser,
-            "SELECT * FROM cleo_users WHERE is_admin = $1",
-            true
-        )   
-            .fetch_all(pool)
-            .await 
-        {
-            Ok(cleo_users) => cleo_users,
-            Err(e) => return Err::<Vec<CleoUser>, CleoErr>(CleoErr::new(&e.to_string()))
-        };
-        Ok(cleo_users)
-    }
-    else {
-        let e: String = format!("User is not an administrator.");
-        Err::<Vec<CleoUser>, CleoErr>(CleoErr::new(&e.to_string()))
-    }
+) -> Result<(Vec<CleoUser>, i64), CleoErr>{
+    get_instance_users_by_role(user, true, options, pool).await
 }
 
 /// This function attempts to write
@@ -144,8 +181,10 @@ pub async fn create_instance_info(
     smtp_pass: &String,
     file_dir: &String
 ) -> Result<usize, CleoErr> {
-    let hashed_source: String = format!("{}{}", &hostname, &instance_name);
-    let instance_id: String = hash_string(&hashed_source);
+    let instance_id: String = match new_id() {
+        Ok(instance_id) => instance_id,
+        Err(e) => return Err::<usize, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let mut result: usize = 1;
     let info_obj: InstanceInformation = InstanceInformation{
         instance_id: instance_id,
@@ -154,7 +193,10 @@ pub async fn create_instance_info(
         smtp_server: smtp_server.to_owned(),
         smtp_username: smtp_username.to_owned(),
         smtp_pass: smtp_pass.to_owned(),
-        file_dir: file_dir.to_owned()
+        file_dir: file_dir.to_owned(),
+        webhook_allowlist: Vec::new(),
+        password_reset_url: String::new(),
+        site_url: String::new()
     };
     let _insert_op = match query!(
         "INSERT INTO instance_info (instance_id, hostname, instance_name, smtp_server, smtp_username, smtp_pass) VALUES ($1, $2, $3, $4, $5, $6)",
@@ -181,14 +223,10 @@ pub async fn create_instance_info(
 /// is returned. If this operation fails, an error is
 /// returned.
 pub async fn edit_instance_hostname(
-    api_token: &String,
+    user: &CleoUser,
     new_hostname: &String,
     pool: &Pool<Postgres>,
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let info: InstanceInformation = match get_instance_info(pool).await {
         Ok(info) => info,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
@@ -213,6 +251,88 @@ pub async fn edit_instance_hostname(
     }
 }
 
+/// This function attempts to
+/// edit the URL of the page users
+/// choose a new password on and save
+/// this information in the database.
+/// Tokens for resetting a password are
+/// appended to this URL in emails.
+/// If this operation is successful, an empty function
+/// is returned. If this operation fails, an error is
+/// returned.
+pub async fn edit_password_reset_url(
+    user: &CleoUser,
+    new_url: &str,
+    pool: &Pool<Postgres>,
+) -> Result<(), CleoErr>{
+    let info: InstanceInformation = match get_instance_info(pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if !user.is_admin {
+        let e: &str = "The acting user must be an administrator.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let new_url: &str = new_url.trim();
+    if !new_url.is_empty() && !new_url.starts_with("http://") && !new_url.starts_with("https://") {
+        let e: String = format!("The URL \"{}\" is not an absolute HTTP or HTTPS URL.", new_url);
+        return Err::<(), CleoErr>(CleoErr::new(&e));
+    }
+    let update_op: () = match query!(
+        "UPDATE instance_info SET password_reset_url = $1 WHERE instance_id = $2",
+        new_url, 
+        info.instance_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(update_op)
+}
+
+/// This function attempts to
+/// edit the URL of the public website
+/// that shows the posts of the instance
+/// and save this information in the database.
+/// Links to posts in feeds and sitemaps
+/// point to this website.
+/// If this operation is successful, an empty function
+/// is returned. If this operation fails, an error is
+/// returned.
+pub async fn edit_site_url(
+    user: &CleoUser,
+    new_url: &str,
+    pool: &Pool<Postgres>,
+) -> Result<(), CleoErr>{
+    let info: InstanceInformation = match get_instance_info(pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if !user.is_admin {
+        let e: &str = "The acting user must be an administrator.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let new_url: &str = new_url.trim();
+    if !new_url.is_empty() && !new_url.starts_with("http://") && !new_url.starts_with("https://") {
+        let e: String = format!("The URL \"{}\" is not an absolute HTTP or HTTPS URL.", new_url);
+        return Err::<(), CleoErr>(CleoErr::new(&e));
+    }
+    let update_op: () = match query!(
+        "UPDATE instance_info SET site_url = $1 WHERE instance_id = $2",
+        new_url, 
+        info.instance_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(update_op)
+}
+
 /// This function attempts to
 /// edit the instance's name
 /// and save this information in the database.
@@ -220,14 +340,10 @@ pub async fn edit_instance_hostname(
 /// is returned. If this operation fails, an error is
 /// returned.
 pub async fn edit_instance_name(
-    api_token: &String,
+    user: &CleoUser,
     new_name: &String,
     pool: &Pool<Postgres>,
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let info: InstanceInformation = match get_instance_info(pool).await {
         Ok(info) => info,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
@@ -259,14 +375,10 @@ pub async fn edit_instance_name(
 /// is returned. If this operation fails, an error is
 /// returned.
 pub async fn edit_smtp_username(
-    api_token: &String,
+    user: &CleoUser,
     new_name: &String,
     pool: &Pool<Postgres>,
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let info: InstanceInformation = match get_instance_info(pool).await {
         Ok(info) => info,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
@@ -298,14 +410,10 @@ pub async fn edit_smtp_username(
 /// is returned. If this operation fails, an error is
 /// returned.
 pub async fn edit_smtp_pass(
-    api_token: &String,
+    user: &CleoUser,
     new_name: &String,
     pool: &Pool<Postgres>,
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let info: InstanceInformation = match get_instance_info(pool).await {
         Ok(info) => info,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
@@ -337,14 +445,10 @@ pub async fn edit_smtp_pass(
 /// is returned. If this operation fails, an error is
 /// returned.
 pub async fn edit_instance_smtp_server(
-    api_token: &String,
+    user: &CleoUser,
     new_smtp_server: &String,
     pool: &Pool<Postgres>,
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let info: InstanceInformation = match get_instance_info(pool).await {
         Ok(info) => info,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
@@ -368,3 +472,47 @@ pub async fn edit_instance_smtp_server(
         return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
     }
 }
+
+/// This function attempts to replace
+/// the hosts webhooks may be delivered
+/// to even though they are loopback, private,
+/// or link-local addresses. Hosts are saved
+/// in lowercase without surrounding brackets.
+/// If this operation is successful, an empty 
+/// function is returned. If this operation 
+/// fails, an error is returned.
+pub async fn edit_webhook_allowlist(
+    user: &CleoUser,
+    hosts: &[String],
+    pool: &Pool<Postgres>,
+) -> Result<(), CleoErr>{
+    let info: InstanceInformation = match get_instance_info(pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if user.is_admin{
+        let mut allowlist: Vec<String> = hosts
+            .iter()
+            .map(|host| normalize_webhook_host(host))
+            .filter(|host| !host.is_empty())
+            .collect::<Vec<String>>();
+        allowlist.sort();
+        allowlist.dedup();
+        let update_op: () = match query!(
+            "UPDATE instance_info SET webhook_allowlist = $1 WHERE instance_id = $2",
+            allowlist.as_slice(), 
+            info.instance_id
+        )
+            .execute(pool)
+            .await 
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        Ok(update_op)
+    }
+    else {
+        let e: &str = "The acting user must be an administrator.";
+        Err::<(), CleoErr>(CleoErr::new(e))
+    }
+}
@@ -0,0 +1,100 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "query"
+/// macro to execute queries.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "Transaction"
+/// structure to announce changes
+/// together with the writes they
+/// describe.
+use sqlx::Transaction;
+
+/// Importing the "Value"
+/// enum for the data of 
+/// events.
+use serde_json::Value;
+
+/// Importing the
+/// "Postgres" structure
+/// for explicit typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the structure
+/// describing a change to 
+/// content.
+use crate::modules::units::ChangeEvent;
+
+/// Importing the function to queue the
+/// deliveries of an event to webhooks.
+use super::webhooks::enqueue_webhook_event;
+
+/// The name of the channel
+/// changes to content are
+/// announced on.
+pub const CHANGE_CHANNEL: &str = "cleo_changes";
+
+/// This function attempts to
+/// announce a change to the content
+/// of a user. The change is sent to 
+/// all open streams of changes of the
+/// user and deliveries of it are queued
+/// for their webhooks. Both happen in the
+/// supplied transaction, so that a change
+/// is only announced once the write it
+/// describes is committed. If the operation
+/// is successful, an empty function is 
+/// returned. If this operation fails, 
+/// an error is returned and the write 
+/// should be rolled back.
+pub async fn publish_change_event(
+    user_id: &String,
+    event: &String,
+    data: &Value,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), CleoErr> {
+    let event_id: String = match new_id() {
+        Ok(event_id) => event_id,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let change: ChangeEvent = ChangeEvent{
+        event_id,
+        user_id: user_id.clone(),
+        event: event.clone(),
+        created_at: Utc::now().to_rfc3339(),
+        data: data.clone()
+    };
+    let notification: String = match serde_json::to_string(&change){
+        Ok(notification) => notification,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "SELECT pg_notify($1, $2)",
+        CHANGE_CHANNEL,
+        notification
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    enqueue_webhook_event(user_id, event, data, tx).await
+}
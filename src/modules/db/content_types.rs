@@ -0,0 +1,498 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query"
+/// macro to execute SQL
+/// queries that return 
+/// nothing.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "HashMap"
+/// structure to read maps
+/// of extra content fields.
+use std::collections::HashMap;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "CleoUser" structure
+/// to read and write associated data
+/// about Cleo users.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure
+/// describing a field a type
+/// of content accepts.
+use crate::modules::units::FieldDefinition;
+
+/// Importing the function to check
+/// the value of an extra content field.
+use super::ecf::validate_extra_field_value;
+
+/// Importing the function to check
+/// whether a string is a valid slug.
+use crate::modules::db::slugs::validate_slug;
+
+/// Importing the "ContentTypeField" structure
+/// to read and write information about
+/// the fields a type of content accepts.
+use crate::modules::models::ContentTypeField;
+
+/// Importing the function to check
+/// whether a field type is valid.
+use crate::modules::utils::validate_field_type;
+
+/// Importing the "ContentTypeDefinition" structure
+/// to read and write information about
+/// types of content.
+use crate::modules::models::ContentTypeDefinition;
+
+/// The types of content that
+/// exist on every instance and 
+/// cannot be deleted.
+pub const BUILTIN_CONTENT_TYPES: [&str; 2] = ["page", "post"];
+
+/// This function checks that
+/// the acting user is an administrator.
+/// If the user is not an administrator,
+/// an error is returned.
+pub fn verify_admin(
+    user: &CleoUser
+) -> Result<(), CleoErr> {
+    if user.is_admin {
+        Ok(())
+    }
+    else {
+        let e: &str = "The acting user must be an administrator.";
+        Err::<(), CleoErr>(CleoErr::new(e))
+    }
+}
+
+/// This function attempts
+/// to define a new type of content
+/// together with the fields it accepts.
+/// Only administrators can do this.
+/// If the operation is successful, an 
+/// instance of the "ContentTypeDefinition"
+/// structure is returned. If this operation
+/// fails, an error is returned.
+pub async fn create_content_type(
+    user: &CleoUser,
+    type_name: &String,
+    description: &String,
+    fields: &Vec<FieldDefinition>,
+    pool: &Pool<Postgres>
+) -> Result<ContentTypeDefinition, CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_slug(type_name) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "INSERT INTO content_types (type_name, description, created_at, updated_at) VALUES ($1, $2, $3, $4)",
+        type_name,
+        description,
+        Utc::now(),
+        Utc::now()
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    for field in fields {
+        let _valid: () = match validate_field_type(&field.field_type) {
+            Ok(valid) => valid,
+            Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        match query!(
+            "INSERT INTO content_type_fields (type_name, field_key, is_required, created_at, field_type) VALUES ($1, $2, $3, $4, $5)",
+            type_name,
+            field.field_key,
+            field.is_required,
+            Utc::now(),
+            field.field_type
+        )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let content_type: ContentTypeDefinition = match get_content_type(type_name, pool).await {
+        Ok(content_type) => content_type,
+        Err(e) => return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(content_type)
+}
+
+/// This function attempts
+/// to retrieve a type of content
+/// given its name. If the operation
+/// is successful, an instance of the
+/// "ContentTypeDefinition" structure
+/// is returned. If this operation fails,
+/// an error is returned.
+pub async fn get_content_type(
+    type_name: &String,
+    pool: &Pool<Postgres>
+) -> Result<ContentTypeDefinition, CleoErr> {
+    let content_type: ContentTypeDefinition = match query_as!(
+        ContentTypeDefinition,
+        "SELECT * FROM content_types WHERE type_name = $1", 
+        type_name
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(content_type) => content_type,
+        Err(_e) => {
+            let e: String = format!("The content type \"{}\" does not exist.", type_name);
+            return Err::<ContentTypeDefinition, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(content_type)
+}
+
+/// This function attempts
+/// to retrieve all types of content.
+/// If the operation is successful, a
+/// vector of instances of the 
+/// "ContentTypeDefinition" structure
+/// is returned. If this operation fails,
+/// an error is returned.
+pub async fn get_content_types(
+    pool: &Pool<Postgres>
+) -> Result<Vec<ContentTypeDefinition>, CleoErr> {
+    let content_types: Vec<ContentTypeDefinition> = match query_as!(
+        ContentTypeDefinition,
+        "SELECT * FROM content_types ORDER BY type_name"
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(content_types) => content_types,
+        Err(e) => return Err::<Vec<ContentTypeDefinition>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(content_types)
+}
+
+/// This function attempts
+/// to retrieve the fields a type
+/// of content accepts. If the operation
+/// is successful, a vector of instances
+/// of the "ContentTypeField" structure
+/// is returned. If this operation fails,
+/// an error is returned.
+pub async fn get_content_type_fields(
+    type_name: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<ContentTypeField>, CleoErr> {
+    let fields: Vec<ContentTypeField> = match query_as!(
+        ContentTypeField,
+        "SELECT * FROM content_type_fields WHERE type_name = $1 ORDER BY created_at, field_key", 
+        type_name
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(fields) => fields,
+        Err(e) => return Err::<Vec<ContentTypeField>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(fields)
+}
+
+/// This function attempts
+/// to delete a type of content.
+/// Only administrators can do this
+/// and only if no posts of this type
+/// exist. The built-in types cannot
+/// be deleted. If the operation is 
+/// successful, an empty function is 
+/// returned. If this operation fails, 
+/// an error is returned.
+pub async fn delete_content_type(
+    user: &CleoUser,
+    type_name: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if BUILTIN_CONTENT_TYPES.contains(&type_name.as_str()) {
+        let e: String = format!("The content type \"{}\" cannot be deleted.", type_name);
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let del_op: () = match query!(
+        "DELETE FROM content_types WHERE type_name = $1", 
+        type_name
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_feedback) => {},
+        Err(_e) => {
+            let e: String = format!("The content type \"{}\" is still in use.", type_name);
+            return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(del_op)
+}
+
+/// This function attempts
+/// to add a field to the definition
+/// of a type of content or to change
+/// the type of an existing field and
+/// whether it is required.
+/// Only administrators can do this. If the 
+/// operation is successful, an empty function 
+/// is returned. If this operation fails, 
+/// an error is returned.
+pub async fn set_content_type_field(
+    user: &CleoUser,
+    type_name: &String,
+    field: &FieldDefinition,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _content_type: ContentTypeDefinition = match get_content_type(type_name, pool).await {
+        Ok(content_type) => content_type,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_field_type(&field.field_type) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let insert_op: () = match query!(
+        "INSERT INTO content_type_fields (type_name, field_key, is_required, created_at, field_type) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (type_name, field_key) DO UPDATE SET is_required = $3, field_type = $5",
+        type_name,
+        field.field_key,
+        field.is_required,
+        Utc::now(),
+        field.field_type
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(insert_op)
+}
+
+/// This function attempts
+/// to remove a field from the 
+/// definition of a type of content.
+/// Only administrators can do this. If the 
+/// operation is successful, an empty function 
+/// is returned. If this operation fails, 
+/// an error is returned.
+pub async fn delete_content_type_field(
+    user: &CleoUser,
+    type_name: &String,
+    field_key: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let del_op: () = match query!(
+        "DELETE FROM content_type_fields WHERE type_name = $1 AND field_key = $2", 
+        type_name,
+        field_key
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(del_op)
+}
+
+/// This function attempts
+/// to check that a field key is
+/// accepted by a type of content.
+/// Types of content without any
+/// defined fields accept any key.
+/// If the key is not accepted or the
+/// operation fails, an error is returned.
+pub async fn validate_field_key(
+    type_name: &String,
+    field_key: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let fields: Vec<ContentTypeField> = match get_content_type_fields(type_name, pool).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if fields.is_empty() || fields.iter().any(|field| &field.field_key == field_key) {
+        Ok(())
+    }
+    else {
+        let e: String = format!("The content type \"{}\" has no field \"{}\".", type_name, field_key);
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts
+/// to determine the type of an extra
+/// content field of a post of the supplied
+/// content type. If the content type defines
+/// the field, the defined type is used and 
+/// a different requested type is rejected.
+/// Otherwise the requested type is used
+/// or "string" if no type was requested.
+/// If this operation fails, an error is
+/// returned.
+pub async fn resolve_field_type(
+    type_name: &String,
+    field_key: &String,
+    requested: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<String, CleoErr> {
+    let fields: Vec<ContentTypeField> = match get_content_type_fields(type_name, pool).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let field_type: String = match fields.iter().find(|field| &field.field_key == field_key) {
+        Some(field) => {
+            if requested.is_some() && requested.as_ref() != Some(&field.field_type) {
+                let e: String = format!(
+                    "The field \"{}\" must be of the type \"{}\".", 
+                    field_key, 
+                    field.field_type
+                );
+                return Err::<String, CleoErr>(CleoErr::new(&e.to_string()));
+            }
+            field.field_type.clone()
+        },
+        None => requested.clone().unwrap_or("string".to_string())
+    };
+    let _valid: () = match validate_field_type(&field_type) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(field_type)
+}
+
+/// This function attempts
+/// to check that a field is not
+/// required by a type of content,
+/// so that it can be removed or
+/// renamed. If the field is required
+/// or the operation fails, an error is
+/// returned.
+pub async fn validate_field_removal(
+    type_name: &String,
+    field_key: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let fields: Vec<ContentTypeField> = match get_content_type_fields(type_name, pool).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if fields.iter().any(|field| &field.field_key == field_key && field.is_required) {
+        let e: String = format!("The field \"{}\" is required for the content type \"{}\".", field_key, type_name);
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+    else {
+        Ok(())
+    }
+}
+
+/// This function attempts
+/// to check a set of extra content 
+/// fields of a user's post against the 
+/// definition of a type of content. All 
+/// required fields must be present, all
+/// fields must be accepted by the type, 
+/// and all values must match the types
+/// of their fields. If the fields are not 
+/// valid or the operation fails, an error
+/// is returned.
+pub async fn validate_post_fields(
+    user_id: &String,
+    type_name: &String,
+    extra_fields: &HashMap<String, String>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _content_type: ContentTypeDefinition = match get_content_type(type_name, pool).await {
+        Ok(content_type) => content_type,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let fields: Vec<ContentTypeField> = match get_content_type_fields(type_name, pool).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    for field in &fields {
+        if let Some(value) = extra_fields.get(&field.field_key) {
+            let _valid: () = match validate_extra_field_value(user_id, &field.field_type, value, pool).await {
+                Ok(valid) => valid,
+                Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+        }
+    }
+    if fields.is_empty() {
+        return Ok(());
+    }
+    for field in &fields {
+        if field.is_required && !extra_fields.contains_key(&field.field_key) {
+            let e: String = format!("The field \"{}\" is required for the content type \"{}\".", field.field_key, type_name);
+            return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    }
+    for field_key in extra_fields.keys() {
+        if !fields.iter().any(|field| &field.field_key == field_key) {
+            let e: String = format!("The content type \"{}\" has no field \"{}\".", type_name, field_key);
+            return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    }
+    Ok(())
+}
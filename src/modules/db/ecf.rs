@@ -13,6 +13,11 @@ use sqlx::Pool;
 /// that return nothing.
 use sqlx::query;
 
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
 /// Importing the "query_as"
 /// macro to execute queries
 /// that return something.
@@ -23,6 +28,10 @@ use sqlx::query_as;
 /// typing.
 use sqlx::postgres::Postgres;
 
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr"
 /// structure to catch and
 /// handle errors.
@@ -33,11 +42,6 @@ use crate::modules::err::CleoErr;
 /// post's ID.
 use super::posts::get_post_by_id;
 
-/// Importing the "TimeNow"
-/// structure to get the current
-/// time.
-use crate::modules::utils::TimeNow;
-
 /// Importing the "CleoUser" structure
 /// for explicit typing.
 use crate::modules::models::CleoUser;
@@ -46,9 +50,30 @@ use crate::modules::models::CleoUser;
 /// for explicit typing.
 use crate::modules::models::UserPost;
 
-/// Importing the function to retieve
-/// a user given the ID of the user.
-use super::tokens::get_user_from_token;
+/// Importing the function to check that
+/// a file belongs to a user.
+use super::posts::verify_file_ownership;
+
+/// Importing the "PostRevision" structure
+/// for explicit typing.
+use crate::modules::models::PostRevision;
+
+/// Importing the function to announce
+/// a change to content.
+use super::changes::publish_change_event;
+
+/// Importing the function to save
+/// a revision of a post.
+use super::revisions::create_post_revision;
+
+/// Importing the function to check
+/// that a type of content accepts
+/// a field.
+use super::content_types::validate_field_key;
+
+/// Importing the function to determine
+/// the type of an extra content field.
+use super::content_types::resolve_field_type;
 
 /// Importing the structure representing
 /// the model in the database for an extra
@@ -56,47 +81,126 @@ use super::tokens::get_user_from_token;
 /// this entity.
 use crate::modules::models::ExtraContentField;
 
+/// Importing the function to describe
+/// a change to a field in the data of
+/// an event.
+use crate::modules::webhooks::field_event_data;
+
+/// Importing the function to check that
+/// a value matches a field type.
+use crate::modules::utils::validate_field_value;
+
+/// Importing the function to check
+/// that a field is not required by
+/// a type of content.
+use super::content_types::validate_field_removal;
+
+/// This function attempts to check
+/// that a value matches the type of an
+/// extra content field. Values of file 
+/// fields must be the ID of a file the
+/// user has uploaded. If the value is
+/// not valid or this operation fails, an 
+/// error is returned.
+pub async fn validate_extra_field_value(
+    user_id: &String,
+    field_type: &String,
+    field_value: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _valid: () = match validate_field_value(field_type, field_value) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if field_type == "file" {
+        let _verified: () = match verify_file_ownership(user_id, field_value, pool).await {
+            Ok(verified) => verified,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    Ok(())
+}
+
 /// This function attempts to create an 
 /// extra content field for a post with the given
 /// data. If the operation is successful, an instance
 /// of the "ExtraContentField" structure is returned.
 /// If this operation fails, an error is returned.
 pub async fn create_extra_field_for_post(
-    api_token: &String,
+    user: &CleoUser,
     content_id: &String,
     field_key: &String,
     field_value: &String,
+    field_type: &Option<String>,
     pool: &Pool<Postgres>,
 ) -> Result<ExtraContentField, CleoErr> {    
     let post: UserPost = match get_post_by_id(content_id, pool).await {
         Ok(post) => post,
         Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
-    };
     if user.user_id == post.user_id {
-        let field_id: String = format!("{}{}", &field_value, TimeNow::new().to_string());
+        let _valid: () = match validate_field_key(&post.content_type, field_key, pool).await {
+            Ok(valid) => valid,
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let field_type: String = match resolve_field_type(&post.content_type, field_key, field_type, pool).await {
+            Ok(field_type) => field_type,
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _valid: () = match validate_extra_field_value(&user.user_id, &field_type, field_value, pool).await {
+            Ok(valid) => valid,
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let field_id: String = match new_id() {
+            Ok(field_id) => field_id,
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
+        };
         let extra_field: ExtraContentField = ExtraContentField{
             field_id: field_id.clone(),
             content_id: content_id.to_owned(),
             field_key: field_key.to_owned(),
-            field_value: field_value.to_owned()
+            field_value: field_value.to_owned(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            field_type
+        };
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
         };
         let _insert_op = match query!(
-            "INSERT INTO extra_content_fields (field_id, content_id, field_key, field_value) VALUES ($1, $2, $3, $4)",
+            "INSERT INTO extra_content_fields (field_id, content_id, field_key, field_value, created_at, updated_at, field_type) VALUES ($1, $2, $3, $4, $5, $6, $7)",
             extra_field.field_id,
             extra_field.content_id,
             extra_field.field_key,
-            extra_field.field_value
+            extra_field.field_value,
+            extra_field.created_at,
+            extra_field.updated_at,
+            extra_field.field_type
         )
-            .execute(pool)
+            .execute(&mut *tx)
             .await
         {
             Ok(_feedback) => {},
             Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
         };
+        let _revision: PostRevision = match create_post_revision(content_id, &user.user_id, &mut tx).await {
+            Ok(revision) => revision,
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _published: () = match publish_change_event(
+            &post.user_id, 
+            &"field.changed".to_string(), 
+            &field_event_data(&post, &extra_field.field_id, &extra_field.field_key, "created"), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _commit_op: () = match tx.commit().await {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
+        };
         let field_obj: ExtraContentField = match get_extra_field_by_id(&field_id, pool).await {
             Ok(field_obj) => field_obj,
             Err(e) => return Err::<ExtraContentField, CleoErr>(CleoErr::new(&e.to_string()))
@@ -134,13 +238,88 @@ pub async fn get_extra_field_by_id(
     Ok(field_obj)
 }
 
+/// This function attempts to retrieve
+/// all extra content fields belonging to
+/// a post using the post's ID. If this 
+/// operation is successful, a vector of
+/// instances of the "ExtraContentField"
+/// structure is returned. If this operation
+/// fails, an error is returned.
+pub async fn get_extra_fields_for_post(
+    content_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<ExtraContentField>, CleoErr> {
+    let fields: Vec<ExtraContentField> = match query_as!(
+        ExtraContentField,
+        "SELECT * FROM extra_content_fields WHERE content_id = $1 ORDER BY created_at, field_key", 
+        content_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(fields) => fields,
+        Err(e) => return Err::<Vec<ExtraContentField>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(fields)
+}
+
+/// This function attempts to retrieve
+/// all extra content fields belonging to
+/// any of the posts with the supplied IDs 
+/// in a single query. If this operation is 
+/// successful, a vector of instances of the
+/// "ExtraContentField" structure is returned.
+/// If this operation fails, an error is returned.
+pub async fn get_extra_fields_for_posts(
+    content_ids: &Vec<String>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<ExtraContentField>, CleoErr> {
+    let fields: Vec<ExtraContentField> = match query_as!(
+        ExtraContentField,
+        "SELECT * FROM extra_content_fields WHERE content_id = ANY($1) ORDER BY created_at, field_key", 
+        content_ids.as_slice()
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(fields) => fields,
+        Err(e) => return Err::<Vec<ExtraContentField>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(fields)
+}
+
+/// This function attempts to retrieve
+/// all extra content fields belonging to
+/// a post of the supplied user. If this
+/// operation is successful,
+/// a vector of instances of the "ExtraContentField"
+/// structure is returned. If this operation
+/// fails, an error is returned.
+pub async fn get_user_extra_fields_for_post(
+    user: &CleoUser,
+    content_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<ExtraContentField>, CleoErr> {
+    let post: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post) => post,
+        Err(e) => return Err::<Vec<ExtraContentField>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if user.user_id == post.user_id {
+        get_extra_fields_for_post(content_id, pool).await
+    }
+    else {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        Err::<Vec<ExtraContentField>, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
 /// This function attempts to delete
 /// an extra content field given its
 /// ID. if the operation is successful,
 /// an empty function is returned. If the 
 /// operation fails, an error is returned.
 pub async fn delete_extra_field_for_post(
-    api_token: &String,
+    user: &CleoUser,
     content_id: &String,
     pool: &Pool<Postgres>,
     field_id: &String,
@@ -149,21 +328,46 @@ pub async fn delete_extra_field_for_post(
         Ok(post) => post,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     if user.user_id == post.user_id {
+        let field_obj: ExtraContentField = match get_extra_field_by_id(field_id, pool).await {
+            Ok(field_obj) => field_obj,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _valid: () = match validate_field_removal(&post.content_type, &field_obj.field_key, pool).await {
+            Ok(valid) => valid,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
         let del_op: () = match query!(
             "DELETE FROM extra_content_fields WHERE field_id = $1", 
             field_id
         )
-            .execute(pool)
+            .execute(&mut *tx)
             .await 
         {
             Ok(_feedback) => {},
             Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
         };
+        let _revision: PostRevision = match create_post_revision(content_id, &user.user_id, &mut tx).await {
+            Ok(revision) => revision,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _published: () = match publish_change_event(
+            &post.user_id, 
+            &"field.changed".to_string(), 
+            &field_event_data(&post, field_id, &field_obj.field_key, "deleted"), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _commit_op: () = match tx.commit().await {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
         Ok(del_op)
     }
     else {
@@ -178,7 +382,7 @@ pub async fn delete_extra_field_for_post(
 /// an empty function is returned. If this operation
 /// fails, an error is returned.
 pub async fn edit_extra_field_key_for_post(
-    api_token: &String,
+    user: &CleoUser,
     content_id: &String,
     field_id: &String,
     field_key_new: &String,
@@ -188,22 +392,63 @@ pub async fn edit_extra_field_key_for_post(
         Ok(post) => post,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     if user.user_id == post.user_id {
+        let field_obj: ExtraContentField = match get_extra_field_by_id(field_id, pool).await {
+            Ok(field_obj) => field_obj,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        if &field_obj.field_key != field_key_new {
+            let _valid: () = match validate_field_removal(&post.content_type, &field_obj.field_key, pool).await {
+                Ok(valid) => valid,
+                Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+        }
+        let _valid: () = match validate_field_key(&post.content_type, field_key_new, pool).await {
+            Ok(valid) => valid,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _field_type: String = match resolve_field_type(
+            &post.content_type, 
+            field_key_new, 
+            &Some(field_obj.field_type.clone()), 
+            pool
+        ).await {
+            Ok(field_type) => field_type,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
         let update_op: () = match query!(
-            "UPDATE extra_content_fields SET field_key = $1 WHERE field_id = $2", 
+            "UPDATE extra_content_fields SET field_key = $1, updated_at = $2 WHERE field_id = $3",
             field_key_new,
+            Utc::now(),
             field_id
         )
-            .execute(pool)
+            .execute(&mut *tx)
             .await 
         {
             Ok(_feedback) => {},
             Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
         };
+        let _revision: PostRevision = match create_post_revision(content_id, &user.user_id, &mut tx).await {
+            Ok(revision) => revision,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _published: () = match publish_change_event(
+            &post.user_id, 
+            &"field.changed".to_string(), 
+            &field_event_data(&post, field_id, field_key_new, "updated"), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _commit_op: () = match tx.commit().await {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
         Ok(update_op)
     }
     else {
@@ -213,12 +458,13 @@ pub async fn edit_extra_field_key_for_post(
 }
 
 /// This function attempts to edit the
-/// key of an extra content field in the 
-/// database. If this operation is successful,
-/// an empty function is returned. If this operation
-/// fails, an error is returned.
+/// value of an extra content field in the 
+/// database. The new value must match the
+/// type of the field. If this operation is 
+/// successful, an empty function is returned. 
+/// If this operation fails, an error is returned.
 pub async fn edit_extra_field_value_for_post(
-    api_token: &String,
+    user: &CleoUser,
     content_id: &String,
     field_id: &String,
     field_value_new: &String,
@@ -228,22 +474,48 @@ pub async fn edit_extra_field_value_for_post(
         Ok(post) => post,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     if user.user_id == post.user_id {
+        let field_obj: ExtraContentField = match get_extra_field_by_id(field_id, pool).await {
+            Ok(field_obj) => field_obj,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _valid: () = match validate_extra_field_value(&user.user_id, &field_obj.field_type, field_value_new, pool).await {
+            Ok(valid) => valid,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
         let update_op: () = match query!(
-            "UPDATE extra_content_fields SET field_value = $1 WHERE field_id = $2", 
+            "UPDATE extra_content_fields SET field_value = $1, updated_at = $2 WHERE field_id = $3",
             field_value_new,
+            Utc::now(),
             field_id
         )
-            .execute(pool)
+            .execute(&mut *tx)
             .await 
         {
             Ok(_feedback) => {},
             Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
         };
+        let _revision: PostRevision = match create_post_revision(content_id, &user.user_id, &mut tx).await {
+            Ok(revision) => revision,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _published: () = match publish_change_event(
+            &post.user_id, 
+            &"field.changed".to_string(), 
+            &field_event_data(&post, field_id, &field_obj.field_key, "updated"), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _commit_op: () = match tx.commit().await {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
         Ok(update_op)
     }
     else {
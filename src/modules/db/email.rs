@@ -13,34 +13,75 @@ use sqlx::Pool;
 /// that return nothing.
 use sqlx::query;
 
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "hash"
+/// function to hash new
+/// passwords.
+use bcrypt::hash;
+
 /// Importing the "query_as"
 /// macro to execute queries
 /// that return something.
 use sqlx::query_as;
 
+/// Importing the "TimeDelta"
+/// structure to compute when
+/// a password reset expires.
+use chrono::TimeDelta;
+
+/// Importing the default
+/// cost for hashing passwords.
+use bcrypt::DEFAULT_COST;
+
 /// Importing the "Postgres"
 /// structure for explicit 
 /// typing.
 use sqlx::postgres::Postgres;
 
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr"
 /// structure to catch and
 /// handle errors.
 use crate::modules::err::CleoErr;
 
-/// Importing the "TimeNow" structure
-/// to get the current time.
-use crate::modules::utils::TimeNow;
+/// Importing the function to
+/// generate random secrets.
+use crate::modules::ids::new_secret;
 
-/// Importing the function to hash 
-/// a string.
-use crate::modules::utils::hash_string;
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
 
 //// Importing the structure
 /// for modelling email tokens
 /// in the database.
 use crate::modules::models::EmailToken;
 
+/// Importing the function to hash
+/// a token.
+use crate::modules::utils::hash_string;
+
+/// Importing the structure
+/// for modelling requests to
+/// reset a password.
+use crate::modules::models::PasswordReset;
+
+/// The length of the tokens
+/// sent to users by email.
+pub const EMAIL_TOKEN_LENGTH: usize = 48;
+
+/// The number of seconds a
+/// token for resetting a password
+/// stays valid for.
+pub const PASSWORD_RESET_LIFETIME: i64 = 3600;
+
 /// This function attempts to
 /// create an email token for
 /// a user in the database. If the
@@ -52,14 +93,14 @@ pub async fn create_email_token(
     user_id: &String,
     pool: &Pool<Postgres>
 ) -> Result<EmailToken, CleoErr>{
-    let email_token: String = hash_string(
-        &format!(
-            "{}:{}",
-            user_id,
-            TimeNow::new().to_string()
-        )
-    );
-    let token_id: String = hash_string(&TimeNow::new().to_string());
+    let email_token: String = match new_secret(&EMAIL_TOKEN_LENGTH) {
+        Ok(email_token) => email_token,
+        Err(e) => return Err::<EmailToken, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let token_id: String = match new_id() {
+        Ok(token_id) => token_id,
+        Err(e) => return Err::<EmailToken, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let token: EmailToken = EmailToken{
         etoken_id: token_id,
         email_token: email_token.clone(),
@@ -131,3 +172,133 @@ pub async fn delete_email_token(
     };
     Ok(del_op)  
 }
+
+/// This function attempts to
+/// create a token for resetting
+/// the password of a user. Any
+/// earlier requests of the user and
+/// all expired requests are discarded.
+/// If the operation is successful, the
+/// token is returned. Only a hash of the
+/// token is stored. If the operation
+/// fails, an error is returned.
+pub async fn create_password_reset(
+    user: &CleoUser,
+    pool: &Pool<Postgres>
+) -> Result<String, CleoErr> {
+    let token: String = match new_secret(&EMAIL_TOKEN_LENGTH) {
+        Ok(token) => token,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let reset_id: String = match new_id() {
+        Ok(reset_id) => reset_id,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _del_op: () = match query!(
+        "DELETE FROM password_resets WHERE user_id = $1 OR expires_at <= $2",
+        user.user_id,
+        Utc::now()
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO password_resets (reset_id, user_id, token_hash, created_at, expires_at) VALUES ($1, $2, $3, $4, $5)",
+        reset_id,
+        user.user_id,
+        hash_string(&token),
+        Utc::now(),
+        Utc::now() + TimeDelta::seconds(PASSWORD_RESET_LIFETIME)
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(token)
+}
+
+/// This function attempts to
+/// set a new password for the user
+/// a password reset token was issued
+/// to. The token can only be used once.
+/// All API tokens of the user are revoked.
+/// If the operation is successful, the
+/// number of revoked API tokens is returned.
+/// If the token is unknown, has expired, or
+/// the operation fails, an error is returned.
+pub async fn redeem_password_reset(
+    token: &String,
+    new_password: &String,
+    pool: &Pool<Postgres>
+) -> Result<u64, CleoErr> {
+    if new_password.is_empty(){
+        let e: &str = "The new password must not be empty.";
+        return Err::<u64, CleoErr>(CleoErr::new(e));
+    }
+    let hashed_pwd: String = match hash(new_password, DEFAULT_COST){
+        Ok(hashed_pwd) => hashed_pwd,
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let reset: Option<PasswordReset> = match query_as!(
+        PasswordReset,
+        "DELETE FROM password_resets WHERE token_hash = $1 RETURNING *",
+        hash_string(token)
+    )
+        .fetch_optional(&mut *tx)
+        .await
+    {
+        Ok(reset) => reset,
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let reset: PasswordReset = match reset {
+        Some(reset) => reset,
+        None => {
+            let e: &str = "The password reset token is invalid or has already been used.";
+            return Err::<u64, CleoErr>(CleoErr::new(e));
+        }
+    };
+    if reset.expires_at <= Utc::now(){
+        let _commit_op: () = match tx.commit().await {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let e: &str = "The password reset token has expired.";
+        return Err::<u64, CleoErr>(CleoErr::new(e));
+    }
+    let _update_op: () = match query!(
+        "UPDATE cleo_users SET pwd = $1, updated_at = $2 WHERE user_id = $3",
+        hashed_pwd,
+        Utc::now(),
+        reset.user_id
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let revoked: u64 = match query!(
+        "DELETE FROM user_api_tokens WHERE user_id = $1",
+        reset.user_id
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(feedback) => feedback.rows_affected(),
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(revoked)
+}
@@ -0,0 +1,129 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries that return 
+/// single values.
+use sqlx::query_scalar;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "UserPost" structure
+/// to read information about posts 
+/// written by a user.
+use crate::modules::models::UserPost;
+
+/// Importing the structure holding
+/// the details of an entry of a sitemap.
+use crate::modules::units::SitemapEntry;
+
+/// The number of the most
+/// recent posts a feed contains.
+pub const FEED_SIZE: i64 = 20;
+
+/// This function attempts to retrieve
+/// the most recently published posts for
+/// a feed, newest first. The posts can be
+/// limited to those of a user and to a type
+/// of content. If no type of content is
+/// supplied, pages are left out. If the
+/// operation fails, an error is returned.
+pub async fn get_feed_posts(
+    user_id: &Option<String>,
+    content_type: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserPost>, CleoErr> {
+    let posts: Vec<UserPost> = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts 
+        WHERE post_status = 'published' 
+        AND ($1::TEXT IS NULL OR user_id = $1) 
+        AND (content_type = $2 OR ($2::TEXT IS NULL AND content_type <> 'page')) 
+        ORDER BY COALESCE(publish_at, created_at) DESC, content_id ASC 
+        LIMIT $3",
+        user_id.to_owned(),
+        content_type.to_owned(),
+        FEED_SIZE
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(posts) => posts,
+        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(posts)
+}
+
+/// This function attempts to count
+/// all published posts and pages on
+/// the instance. If the operation fails,
+/// an error is returned.
+pub async fn count_sitemap_entries(
+    pool: &Pool<Postgres>
+) -> Result<i64, CleoErr> {
+    let total: i64 = match query_scalar!(
+        "SELECT COUNT(*) AS \"total!\" FROM user_posts WHERE post_status = 'published'"
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<i64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(total)
+}
+
+/// This function attempts to retrieve
+/// a page of the published posts and pages
+/// on the instance for a sitemap, together
+/// with the usernames of their authors. Pages
+/// start at one. If the operation fails, an
+/// error is returned.
+pub async fn get_sitemap_entries(
+    page: &i64,
+    per_page: &i64,
+    pool: &Pool<Postgres>
+) -> Result<Vec<SitemapEntry>, CleoErr> {
+    let entries: Vec<SitemapEntry> = match query_as!(
+        SitemapEntry,
+        "SELECT cleo_users.username, user_posts.content_type, user_posts.slug, user_posts.updated_at 
+        FROM user_posts JOIN cleo_users ON cleo_users.user_id = user_posts.user_id 
+        WHERE user_posts.post_status = 'published' 
+        ORDER BY user_posts.created_at ASC, user_posts.content_id ASC 
+        LIMIT $1 OFFSET $2",
+        per_page,
+        (page - 1) * per_page
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(entries) => entries,
+        Err(e) => return Err::<Vec<SitemapEntry>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(entries)
+}
@@ -15,6 +15,11 @@ use sqlx::Pool;
 /// that return nothing.
 use sqlx::query;
 
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
 /// Importing the
 /// "query_as" macro to
 /// execute SQL queries
@@ -26,14 +31,14 @@ use sqlx::query_as;
 /// database one is connecting to.
 use sqlx::postgres::Postgres;
 
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr" structure
 /// to catch and handle errors.
 use crate::modules::err::CleoErr;
 
-/// Importing the "TimeNow" structure
-/// to get the current time.
-use crate::modules::utils::TimeNow;
-
 /// Importing the "UserFile" structure
 /// to read and write information about
 /// user-uploaded files.
@@ -44,10 +49,13 @@ use crate::modules::models::UserFile;
 /// a Cleo user.
 use crate::modules::models::CleoUser;
 
-/// Importing the "get_user_from_token" 
-/// function to retrieve a user using
-/// their API token.
-use crate::modules::db::users::get_user_from_token;
+/// Importing the function to describe
+/// a file in the data of an event.
+use crate::modules::webhooks::file_event_data;
+
+/// Importing the function to announce
+/// a change to content.
+use crate::modules::db::changes::publish_change_event;
 
 /// This function attempts to
 /// save the path of an uploaded
@@ -57,35 +65,55 @@ use crate::modules::db::users::get_user_from_token;
 /// is returned. If this operation fails
 /// an error is returned.
 pub async fn create_user_file(
-    api_token: &String,
-    file_path: &String,
+    user: &CleoUser,
+    file_path: &str,
     file_url: &String,
     pool: &Pool<Postgres>,
 ) -> Result<UserFile, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
+    let file_id: String = match new_id() {
+        Ok(file_id) => file_id,
         Err(e) => return Err::<UserFile, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let file_id: String = format!("{}{}", TimeNow::new().to_string(), file_path);
     let user_file_obj: UserFile = UserFile{
         file_id: file_id.clone(),
-        user_id: user.user_id,
-        file_path: file_path.clone(),
-        file_url: file_url.clone()
+        user_id: user.user_id.clone(),
+        file_path: file_path.to_owned(),
+        file_url: file_url.clone(),
+        created_at: Utc::now(),
+        updated_at: Utc::now()
+    };
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<UserFile, CleoErr>(CleoErr::new(&e.to_string()))
     };
     let _insert_op = match query!(
-        "INSERT INTO user_files (file_id, user_id, file_path, file_url) VALUES ($1, $2, $3, $4)",
+        "INSERT INTO user_files (file_id, user_id, file_path, file_url, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6)",
         user_file_obj.file_id,
         user_file_obj.user_id,
         user_file_obj.file_path,
-        user_file_obj.file_url
+        user_file_obj.file_url,
+        user_file_obj.created_at,
+        user_file_obj.updated_at
     )
-        .execute(pool)
+        .execute(&mut *tx)
         .await
     {
         Ok(_feedback) => {},
         Err(e) => return Err::<UserFile, CleoErr>(CleoErr::new(&e.to_string()))
     };
+    let _published: () = match publish_change_event(
+        &user_file_obj.user_id, 
+        &"file.uploaded".to_string(), 
+        &file_event_data(&user_file_obj), 
+        &mut tx
+    ).await {
+        Ok(published) => published,
+        Err(e) => return Err::<UserFile, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<UserFile, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let file_obj: UserFile = match get_file_by_id(&file_id, pool).await {
         Ok(file_obj) => file_obj,
         Err(e) => return Err::<UserFile, CleoErr>(CleoErr::new(&e.to_string()))
@@ -118,6 +146,28 @@ pub async fn get_file_by_id(
     Ok(file_obj)
 }
 
+/// This function attempts to retrieve
+/// the files with the given IDs. The files
+/// are returned in no particular order. If
+/// this operation fails, an error is returned.
+pub async fn get_files_by_ids(
+    file_ids: &Vec<String>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserFile>, CleoErr> {
+    let files: Vec<UserFile> = match query_as!(
+        UserFile,
+        "SELECT * FROM user_files WHERE file_id = ANY($1)", 
+        file_ids.as_slice()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(files) => files,
+        Err(e) => return Err::<Vec<UserFile>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(files)
+}
+
 /// This function attempts to 
 /// delete a record for a file
 /// uploaded by a user. If this
@@ -126,65 +176,46 @@ pub async fn get_file_by_id(
 /// successful, an empty function
 /// is returned.
 pub async fn delete_user_file(
-    api_token: &String,
+    user: &CleoUser,
     file_id: &String,
     pool: &Pool<Postgres>,
 ) -> Result<(), CleoErr> {
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let file_obj: UserFile = match get_file_by_id(&file_id, pool).await {
         Ok(file_obj) => file_obj,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
     if user.user_id == file_obj.file_id {
-        let del_op: () = match query!(
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _del_op: () = match query!(
             "DELETE FROM user_files WHERE file_id = $1", 
             file_id
         )
-            .execute(pool)
+            .execute(&mut *tx)
             .await 
         {
             Ok(_feedback) => {},
             Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
         };
-        Ok(del_op)
+        let _published: () = match publish_change_event(
+            &file_obj.user_id, 
+            &"file.deleted".to_string(), 
+            &file_event_data(&file_obj), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let commit_op: () = match tx.commit().await {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        Ok(commit_op)
     }
     else {
         let e: String = format!("Could not verify ownership of the file.");
         Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     } 
 }
-
-/// This function attempts
-/// to retrieve a list of 
-/// all records for files
-/// uploaded by a user given
-/// a user's API token. If this
-/// operation is successful,
-/// a vector of instances of
-/// the "UserFile" model is returned.
-/// If this operation fails, an error
-/// is returned.
-pub async fn get_user_files(
-    api_token: &String,
-    pool: &Pool<Postgres>,
-) -> Result<Vec<UserFile>, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<Vec<UserFile>, CleoErr>(CleoErr::new(&e.to_string()))
-    };
-    let user_files: Vec<UserFile> = match query_as!(
-        UserFile,
-        "SELECT * FROM user_files WHERE user_id = $1",
-        user.user_id
-    )   
-        .fetch_all(pool)
-        .await 
-    {
-        Ok(user_files) => user_files,
-        Err(e) => return Err::<Vec<UserFile>, CleoErr>(CleoErr::new(&e.to_string()))
-    };
-    Ok(user_files)
-}
@@ -16,6 +16,12 @@ use sqlx::Pool;
 /// that return something.
 use sqlx::query_as;
 
+/// Importing the
+/// "query_scalar" macro to
+/// execute SQL queries that
+/// return a single value.
+use sqlx::query_scalar;
+
 /// Importing the "Postgres"
 /// structure to specify the 
 /// database one is connecting to.
@@ -40,68 +46,227 @@ use crate::modules::models::CleoUser;
 /// posts written by a user.
 use crate::modules::models::UserPost;
 
+/// Importing the structure
+/// holding the filters for
+/// listing posts.
+use crate::modules::units::PostFilters;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
 /// Importing the "InstanceInformation"
 /// structure to write information about
 /// the instance to the database.
 use crate::modules::models::InstanceInformation;
 
-/// Importing the "get_user_from_token" 
-/// function to retrieve an entry for a Cleo
-/// user given their API token.
-use crate::modules::db::users::get_user_from_token;
+/// Importing the function to retrieve
+/// an entry for a Cleo user given their
+/// username.
+use crate::modules::db::users::get_user_by_username;
+
+/// The fields the posts of
+/// a user can be sorted by.
+pub const POST_SORT_FIELDS: [&str; 4] = [
+    "created_at", "updated_at", "publish_at", "title"
+];
+
+/// The fields the files of
+/// a user can be sorted by.
+pub const FILE_SORT_FIELDS: [&str; 3] = [
+    "created_at", "updated_at", "file_path"
+];
 
 /// This function attempts to fetch
-/// all posts a user has made. if the operation
-/// is successful, a vector of instances of the
-/// "UserPost" structure is returned. If the operation
+/// a page of the posts a user has made
+/// that match the supplied filters. If the
+/// operation is successful, a vector of instances
+/// of the "UserPost" structure and the total number
+/// of matching posts are returned. If the operation
 /// fails, an error is returned.
 pub async fn get_user_posts(
-    api_token: &String,
+    user: &CleoUser,
+    filters: &PostFilters,
+    options: &ListOptions,
     pool: &Pool<Postgres>,
-) -> Result<Vec<UserPost>, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
+) -> Result<(Vec<UserPost>, i64), CleoErr>{
+    if filters.field_value.is_some() && filters.field_key.is_none() {
+        let e: &str = "Filtering by a field value requires a field key.";
+        return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(e));
+    }
+    let content_type: Option<String> = filters.content_type
+        .as_ref()
+        .map(|content_type| content_type.to_lowercase());
+    let user_posts: Vec<UserPost> = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts WHERE user_id = $1 
+        AND ($2::TEXT IS NULL OR content_type = $2) 
+        AND ($3::TEXT IS NULL OR post_status = $3) 
+        AND ($4::TEXT IS NULL OR EXISTS (
+            SELECT 1 FROM extra_content_fields 
+            WHERE extra_content_fields.content_id = user_posts.content_id 
+            AND extra_content_fields.field_key = $4 
+            AND ($5::TEXT IS NULL OR (md5(extra_content_fields.field_value) = md5($5) AND extra_content_fields.field_value = $5))
+        )) 
+        ORDER BY 
+        CASE WHEN $6 = 'created_at' AND $7 = 'asc' THEN created_at END ASC, 
+        CASE WHEN $6 = 'created_at' AND $7 = 'desc' THEN created_at END DESC, 
+        CASE WHEN $6 = 'updated_at' AND $7 = 'asc' THEN updated_at END ASC, 
+        CASE WHEN $6 = 'updated_at' AND $7 = 'desc' THEN updated_at END DESC, 
+        CASE WHEN $6 = 'publish_at' AND $7 = 'asc' THEN publish_at END ASC, 
+        CASE WHEN $6 = 'publish_at' AND $7 = 'desc' THEN publish_at END DESC, 
+        CASE WHEN $6 = 'title' AND $7 = 'asc' THEN title END ASC, 
+        CASE WHEN $6 = 'title' AND $7 = 'desc' THEN title END DESC, 
+        content_id ASC 
+        LIMIT $8 OFFSET $9",
+        user.user_id,
+        content_type,
+        filters.post_status,
+        filters.field_key,
+        filters.field_value,
+        options.sort_by,
+        options.sort_order,
+        options.per_page,
+        options.offset()
+    )   
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(user_posts) => user_posts,
+        Err(e) => return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match query_scalar!(
+        "SELECT COUNT(*) AS \"total!\" FROM user_posts WHERE user_id = $1 
+        AND ($2::TEXT IS NULL OR content_type = $2) 
+        AND ($3::TEXT IS NULL OR post_status = $3) 
+        AND ($4::TEXT IS NULL OR EXISTS (
+            SELECT 1 FROM extra_content_fields 
+            WHERE extra_content_fields.content_id = user_posts.content_id 
+            AND extra_content_fields.field_key = $4 
+            AND ($5::TEXT IS NULL OR (md5(extra_content_fields.field_value) = md5($5) AND extra_content_fields.field_value = $5))
+        ))",
+        user.user_id,
+        content_type,
+        filters.post_status,
+        filters.field_key,
+        filters.field_value
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok((user_posts, total))
+}
+
+/// This function attempts to fetch
+/// a page of the published posts of the given
+/// content type a user has made without requiring
+/// any credentials. If the operation is successful, 
+/// a vector of instances of the "UserPost" structure
+/// and the total number of published posts are returned.
+/// If the operation fails, an error is returned.
+pub async fn get_public_user_posts(
+    username: &String,
+    content_type: &String,
+    options: &ListOptions,
+    pool: &Pool<Postgres>,
+) -> Result<(Vec<UserPost>, i64), CleoErr>{
+    let user: CleoUser = match get_user_by_username(username, pool).await {
         Ok(user) => user,
-        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+        Err(e) => return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
     };
     let user_posts: Vec<UserPost> = match query_as!(
         UserPost,
-        "SELECT * FROM user_posts WHERE user_id = $1",
-        user.user_id
+        "SELECT * FROM user_posts WHERE user_id = $1 AND content_type = $2 AND post_status = $3 
+        ORDER BY 
+        CASE WHEN $4 = 'created_at' AND $5 = 'asc' THEN created_at END ASC, 
+        CASE WHEN $4 = 'created_at' AND $5 = 'desc' THEN created_at END DESC, 
+        CASE WHEN $4 = 'updated_at' AND $5 = 'asc' THEN updated_at END ASC, 
+        CASE WHEN $4 = 'updated_at' AND $5 = 'desc' THEN updated_at END DESC, 
+        CASE WHEN $4 = 'publish_at' AND $5 = 'asc' THEN publish_at END ASC, 
+        CASE WHEN $4 = 'publish_at' AND $5 = 'desc' THEN publish_at END DESC, 
+        CASE WHEN $4 = 'title' AND $5 = 'asc' THEN title END ASC, 
+        CASE WHEN $4 = 'title' AND $5 = 'desc' THEN title END DESC, 
+        content_id ASC 
+        LIMIT $6 OFFSET $7",
+        user.user_id,
+        content_type,
+        "published",
+        options.sort_by,
+        options.sort_order,
+        options.per_page,
+        options.offset()
     )   
         .fetch_all(pool)
         .await 
     {
         Ok(user_posts) => user_posts,
-        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+        Err(e) => return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match query_scalar!(
+        "SELECT COUNT(*) AS \"total!\" FROM user_posts WHERE user_id = $1 AND content_type = $2 AND post_status = $3",
+        user.user_id,
+        content_type,
+        "published"
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
     };
-    Ok(user_posts)
+    Ok((user_posts, total))
 }
 
 /// This function attempts to fetch
-/// all files a user has uploaded. if the operation
-/// is successful, a vector of instances of the
-/// "UserFile" structure is returned. If the operation
+/// a page of the files a user has uploaded. 
+/// If the operation is successful, a vector of 
+/// instances of the "UserFile" structure and the
+/// total number of files are returned. If the operation
 /// fails, an error is returned.
 pub async fn get_user_files(
-    api_token: &String,
+    user: &CleoUser,
+    options: &ListOptions,
     pool: &Pool<Postgres>,
-) -> Result<Vec<UserFile>, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<Vec<UserFile>, CleoErr>(CleoErr::new(&e.to_string()))
-    };
+) -> Result<(Vec<UserFile>, i64), CleoErr>{
     let user_files: Vec<UserFile> = match query_as!(
         UserFile,
-        "SELECT * FROM user_files WHERE user_id = $1",
-        user.user_id
+        "SELECT * FROM user_files WHERE user_id = $1 
+        ORDER BY 
+        CASE WHEN $2 = 'created_at' AND $3 = 'asc' THEN created_at END ASC, 
+        CASE WHEN $2 = 'created_at' AND $3 = 'desc' THEN created_at END DESC, 
+        CASE WHEN $2 = 'updated_at' AND $3 = 'asc' THEN updated_at END ASC, 
+        CASE WHEN $2 = 'updated_at' AND $3 = 'desc' THEN updated_at END DESC, 
+        CASE WHEN $2 = 'file_path' AND $3 = 'asc' THEN file_path END ASC, 
+        CASE WHEN $2 = 'file_path' AND $3 = 'desc' THEN file_path END DESC, 
+        file_id ASC 
+        LIMIT $4 OFFSET $5",
+        user.user_id,
+        options.sort_by,
+        options.sort_order,
+        options.per_page,
+        options.offset()
     )   
         .fetch_all(pool)
         .await 
     {
         Ok(user_files) => user_files,
-        Err(e) => return Err::<Vec<UserFile>, CleoErr>(CleoErr::new(&e.to_string()))
+        Err(e) => return Err::<(Vec<UserFile>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match query_scalar!(
+        "SELECT COUNT(*) AS \"total!\" FROM user_files WHERE user_id = $1",
+        user.user_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<(Vec<UserFile>, i64), CleoErr>(CleoErr::new(&e.to_string()))
     };
-    Ok(user_files)
+    Ok((user_files, total))
 }
 
 /// This function attempts to fetch
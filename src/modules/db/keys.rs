@@ -20,38 +20,44 @@ use sqlx::query;
 /// that return something.
 use sqlx::query_as;
 
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries to return a
+/// single value.
+use sqlx::query_scalar;
+
 /// Importing the "Postgres"
 /// structure to specify the 
 /// database one is connecting to.
 use sqlx::postgres::Postgres;
 
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr"
 /// structure to catch and
 /// handle errors.
 use crate::modules::err::CleoErr;
 
-/// Importing the "TimeNow"
-/// structure to get the current
-/// time.
-use crate::modules::utils::TimeNow;
-
 /// Importing the "UserKey" structure
 /// to read and write information about
 /// user keys from and to the database.
 use crate::modules::models::UserKey;
 
+/// Importing the function to
+/// generate random secrets.
+use crate::modules::ids::new_secret;
+
 /// Importing the "CleoUser" structure
 /// to read and write information about
 /// a Cleo user.
 use crate::modules::models::CleoUser;
 
-/// Importing the function to generate
-/// a user key.
-use crate::modules::utils::generate_key;
-
-/// Importing the function to retieve
-/// a user given the ID of the user.
-use crate::modules::db::users::get_user_from_token;
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
 
 /// This function attempts to
 /// create a user key. If this operation
@@ -61,23 +67,19 @@ use crate::modules::db::users::get_user_from_token;
 pub async fn create_user_key(
     username: &String,
     key_type: &String,
-    api_token: &String,
+    user: &CleoUser,
     pool: &Pool<Postgres>,
 ) -> Result<UserKey, CleoErr> {
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<UserKey, CleoErr>(CleoErr::new(&e.to_string()))
-    };
     if user.is_admin{
         let user_key: String;
         if key_type == &("admin".to_string()){
-            user_key = match generate_key(&16){
+            user_key = match new_secret(&16){
                 Ok(user_key) => user_key,
                 Err(e) => return Err::<UserKey, CleoErr>(CleoErr::new(&e.to_string()))
             };        
         }
         else if key_type == &("normal".to_string()){
-            user_key = match generate_key(&10){
+            user_key = match new_secret(&10){
                 Ok(user_key) => user_key,
                 Err(e) => return Err::<UserKey, CleoErr>(CleoErr::new(&e.to_string()))
             };        
@@ -89,10 +91,13 @@ pub async fn create_user_key(
             );
             return Err::<UserKey, CleoErr>(CleoErr::new(&e.to_string()))
         }
-        let key_id: String = format!("{}{}", &user_key, TimeNow::new().to_string());
+        let key_id: String = match new_id() {
+            Ok(key_id) => key_id,
+            Err(e) => return Err::<UserKey, CleoErr>(CleoErr::new(&e.to_string()))
+        };
         let user_key_obj: UserKey = UserKey{
             key_id: key_id.clone(),
-            user_id: user.user_id,
+            user_id: user.user_id.clone(),
             user_key: user_key,
             key_type: key_type.to_owned(),
             key_used: false,
@@ -156,14 +161,10 @@ pub async fn get_user_key_by_id(
 /// operation fails, an error is 
 /// returned.
 pub async fn delete_user_key(
-    api_token: &String,
+    user: &CleoUser,
     key_id: &String,
     pool: &Pool<Postgres>,
 ) -> Result<(), CleoErr> {
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     if user.is_admin {
         let del_op: () = match query!(
             "DELETE FROM user_keys WHERE key_id = $1", 
@@ -206,37 +207,63 @@ pub async fn change_user_key_status(
     Ok(update_op)
 }
 
+/// The fields the keys of
+/// an administrator can be 
+/// sorted by.
+pub const KEY_SORT_FIELDS: [&str; 3] = [
+    "key_type", "key_used", "key_id"
+];
+
 /// This function attempts to retrieve
-/// all keys an administrator has created.
-/// If the operation is successful, a vector
-/// of instances of the "UserKey" structure
-/// is returned. If this operation fails,
+/// a page of the keys an administrator has 
+/// created. If the operation is successful, 
+/// a vector of instances of the "UserKey" 
+/// structure and the total number of keys
+/// are returned. If this operation fails,
 /// an error is returned.
 pub async fn get_user_keys(
-    api_token: &String,
+    user: &CleoUser,
+    options: &ListOptions,
     pool: &Pool<Postgres>,
-) -> Result<Vec<UserKey>, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<Vec<UserKey>, CleoErr>(CleoErr::new(&e.to_string()))
-    };
+) -> Result<(Vec<UserKey>, i64), CleoErr>{
     if user.is_admin{
         let user_keys: Vec<UserKey> = match query_as!(
             UserKey,
-            "SELECT * FROM user_keys WHERE user_id = $1",
-            user.user_id
+            "SELECT * FROM user_keys WHERE user_id = $1 
+            ORDER BY 
+            CASE WHEN $2 = 'key_type' AND $3 = 'asc' THEN key_type END ASC, 
+            CASE WHEN $2 = 'key_type' AND $3 = 'desc' THEN key_type END DESC, 
+            CASE WHEN $2 = 'key_used' AND $3 = 'asc' THEN key_used END ASC, 
+            CASE WHEN $2 = 'key_used' AND $3 = 'desc' THEN key_used END DESC, 
+            key_id ASC 
+            LIMIT $4 OFFSET $5",
+            user.user_id,
+            options.sort_by,
+            options.sort_order,
+            options.per_page,
+            options.offset()
         )   
             .fetch_all(pool)
             .await 
         {
             Ok(user_keys) => user_keys,
-            Err(e) => return Err::<Vec<UserKey>, CleoErr>(CleoErr::new(&e.to_string()))
+            Err(e) => return Err::<(Vec<UserKey>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let total: i64 = match query_scalar!(
+            "SELECT COUNT(*) AS \"total!\" FROM user_keys WHERE user_id = $1",
+            user.user_id
+        )
+            .fetch_one(pool)
+            .await 
+        {
+            Ok(total) => total,
+            Err(e) => return Err::<(Vec<UserKey>, i64), CleoErr>(CleoErr::new(&e.to_string()))
         };
-        Ok(user_keys)
+        Ok((user_keys, total))
     }
     else {
         let e: String = format!("User is not an administrator.");
-        Err::<Vec<UserKey>, CleoErr>(CleoErr::new(&e.to_string()))
+        Err::<(Vec<UserKey>, i64), CleoErr>(CleoErr::new(&e.to_string()))
     }
 }
 
@@ -0,0 +1,970 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query"
+/// macro to execute SQL
+/// queries that return 
+/// nothing.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "Transaction"
+/// structure to write several
+/// changes at once.
+use sqlx::Transaction;
+
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries that return 
+/// single values.
+use sqlx::query_scalar;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the function to turn
+/// a string into a slug.
+use crate::modules::utils::slugify;
+
+/// Importing the "NavMenu" structure
+/// to read and write information about
+/// navigation menus.
+use crate::modules::models::NavMenu;
+
+/// Importing the "CleoUser" structure
+/// to read and write associated data
+/// about Cleo users.
+use crate::modules::models::CleoUser;
+
+/// Importing the "UserPost" structure
+/// to read and write information about
+/// posts written by a user.
+use crate::modules::models::UserPost;
+
+/// Importing the "MenuItem" structure
+/// to read and write information about
+/// the items of navigation menus.
+use crate::modules::models::MenuItem;
+
+/// Importing the structure holding
+/// what an item of a menu points to.
+use crate::modules::units::MenuTarget;
+
+/// Importing the function to check
+/// whether a URL is safe to link to.
+use crate::modules::html::is_allowed_url;
+
+/// Importing the function to check
+/// whether a slug is valid.
+use crate::modules::db::slugs::validate_slug;
+
+/// Importing the function to retrieve
+/// a post given its ID.
+use crate::modules::db::posts::get_post_by_id;
+
+/// Importing the function to check that
+/// a user owns a file.
+use crate::modules::db::posts::verify_file_ownership;
+
+/// The kinds of targets the
+/// items of a menu can point to.
+pub const MENU_TARGET_TYPES: [&str; 4] = ["post", "page", "file", "url"];
+
+/// The number of levels a menu
+/// can have if no depth is given.
+pub const DEFAULT_MENU_DEPTH: i32 = 3;
+
+/// The largest number of levels
+/// a menu can have.
+pub const MAX_MENU_DEPTH: i32 = 10;
+
+/// This function checks whether
+/// the supplied number of levels is
+/// allowed for a menu. If it is not,
+/// an error is returned.
+pub fn validate_menu_depth(max_depth: &i32) -> Result<(), CleoErr> {
+    if (1..=MAX_MENU_DEPTH).contains(max_depth) {
+        Ok(())
+    }
+    else {
+        let e: String = format!("The depth of a menu must be between 1 and {}.", MAX_MENU_DEPTH);
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function checks the target 
+/// of a menu item. Posts, pages and files
+/// must belong to the user and URLs must
+/// be relative or use a safe scheme. If the
+/// target is valid, the ID of the post, the
+/// ID of the file and the URL are returned, 
+/// with only the one for the kind of target
+/// set. If the target is not valid, an error
+/// is returned.
+pub async fn validate_menu_target(
+    user_id: &String,
+    target: &MenuTarget,
+    pool: &Pool<Postgres>
+) -> Result<(Option<String>, Option<String>, Option<String>), CleoErr> {
+    match target.target_type.as_str() {
+        "post" | "page" => {
+            let content_id: String = match &target.target_id {
+                Some(content_id) => content_id.to_owned(),
+                None => {
+                    let e: String = format!("A target ID is required for the target type \"{}\".", target.target_type);
+                    return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(&e.to_string()));
+                }
+            };
+            let post: UserPost = match get_post_by_id(&content_id, pool).await {
+                Ok(post) => post,
+                Err(e) => return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            if &post.user_id != user_id {
+                let e: String = "Could not verify ownership of the post.".to_string();
+                return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(&e.to_string()));
+            }
+            if (post.content_type == "page") != (target.target_type == "page") {
+                let e: String = format!("\"{}\" is not a {}.", content_id, target.target_type);
+                return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(&e.to_string()));
+            }
+            Ok((Some(content_id), None, None))
+        },
+        "file" => {
+            let file_id: String = match &target.target_id {
+                Some(file_id) => file_id.to_owned(),
+                None => {
+                    let e: &str = "A target ID is required for the target type \"file\".";
+                    return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(e));
+                }
+            };
+            let _verified: () = match verify_file_ownership(user_id, &file_id, pool).await {
+                Ok(verified) => verified,
+                Err(e) => return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            Ok((None, Some(file_id), None))
+        },
+        "url" => {
+            let url: String = match &target.url {
+                Some(url) if !url.trim().is_empty() => url.trim().to_string(),
+                _ => {
+                    let e: &str = "A URL is required for the target type \"url\".";
+                    return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(e));
+                }
+            };
+            if !is_allowed_url(&url) {
+                let e: String = format!("The URL \"{}\" is not allowed.", url);
+                return Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(&e.to_string()));
+            }
+            Ok((None, None, Some(url)))
+        },
+        _ => {
+            let e: String = format!(
+                "\"{}\" is not a valid target type. Valid options are: {}.", 
+                target.target_type,
+                MENU_TARGET_TYPES.join(", ")
+            );
+            Err::<(Option<String>, Option<String>, Option<String>), CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    }
+}
+
+/// This function attempts
+/// to create a new navigation menu
+/// for a user. If no slug is supplied,
+/// one is generated from the name. If no
+/// depth is supplied, the default depth
+/// is used. If the operation is successful,
+/// an instance of the "NavMenu" structure is
+/// returned. If this operation fails, an 
+/// error is returned.
+pub async fn create_menu(
+    user_obj: &CleoUser,
+    menu_name: &str,
+    slug: &Option<String>,
+    max_depth: &Option<i32>,
+    pool: &Pool<Postgres>
+) -> Result<NavMenu, CleoErr> {
+    let menu_name: String = menu_name.trim().to_string();
+    if menu_name.is_empty() {
+        let e: &str = "The name of a menu cannot be empty.";
+        return Err::<NavMenu, CleoErr>(CleoErr::new(e));
+    }
+    let menu_slug: String = match slug {
+        Some(slug) => slug.to_owned(),
+        None => slugify(&menu_name)
+    };
+    let _valid: () = match validate_slug(&menu_slug) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let max_depth: i32 = max_depth.unwrap_or(DEFAULT_MENU_DEPTH);
+    let _valid: () = match validate_menu_depth(&max_depth) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let menu_id: String = match new_id() {
+        Ok(menu_id) => menu_id,
+        Err(e) => return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "INSERT INTO nav_menus (menu_id, user_id, menu_name, menu_slug, max_depth, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        menu_id,
+        user_obj.user_id,
+        menu_name,
+        menu_slug,
+        max_depth,
+        Utc::now(),
+        Utc::now()
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(_e) => {
+            let e: String = format!("The menu \"{}\" already exists.", menu_slug);
+            return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    let menu: NavMenu = match get_menu_by_id(&menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(menu)
+}
+
+/// This function attempts
+/// to retrieve a navigation menu
+/// given its ID. If the operation
+/// is successful, an instance of the
+/// "NavMenu" structure is returned.
+/// If this operation fails, an error 
+/// is returned.
+pub async fn get_menu_by_id(
+    menu_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<NavMenu, CleoErr> {
+    let menu: NavMenu = match query_as!(
+        NavMenu,
+        "SELECT * FROM nav_menus WHERE menu_id = $1", 
+        menu_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(menu) => menu,
+        Err(_e) => {
+            let e: String = format!("The menu \"{}\" does not exist.", menu_id);
+            return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(menu)
+}
+
+/// This function attempts
+/// to retrieve a navigation menu
+/// of a user given its slug. If the
+/// operation is successful, an instance
+/// of the "NavMenu" structure is returned.
+/// If this operation fails, an error 
+/// is returned.
+pub async fn get_menu_by_slug(
+    user_id: &String,
+    menu_slug: &String,
+    pool: &Pool<Postgres>
+) -> Result<NavMenu, CleoErr> {
+    let menu: NavMenu = match query_as!(
+        NavMenu,
+        "SELECT * FROM nav_menus WHERE user_id = $1 AND menu_slug = $2", 
+        user_id,
+        menu_slug
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(menu) => menu,
+        Err(_e) => {
+            let e: String = format!("The menu \"{}\" does not exist.", menu_slug);
+            return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(menu)
+}
+
+/// This function attempts
+/// to retrieve a navigation menu 
+/// and checks that the user owns it.
+/// If the user does not own it or the 
+/// operation fails, an error is returned.
+pub async fn get_owned_menu(
+    user_id: &String,
+    menu_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<NavMenu, CleoErr> {
+    let menu: NavMenu = match get_menu_by_id(menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if &menu.user_id == user_id {
+        Ok(menu)
+    }
+    else {
+        let e: String = "Could not verify ownership of the menu.".to_string();
+        Err::<NavMenu, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts
+/// to retrieve all navigation menus
+/// of a user. If the operation is
+/// successful, a vector of instances
+/// of the "NavMenu" structure is returned.
+/// If this operation fails, an error 
+/// is returned.
+pub async fn get_user_menus(
+    user_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<NavMenu>, CleoErr> {
+    let menus: Vec<NavMenu> = match query_as!(
+        NavMenu,
+        "SELECT * FROM nav_menus WHERE user_id = $1 ORDER BY menu_name, menu_id", 
+        user_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(menus) => menus,
+        Err(e) => return Err::<Vec<NavMenu>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(menus)
+}
+
+/// This function attempts
+/// to retrieve all items of a 
+/// navigation menu in the order
+/// they appear among their siblings.
+/// If the operation fails, an error 
+/// is returned.
+pub async fn get_menu_items(
+    menu_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<MenuItem>, CleoErr> {
+    let items: Vec<MenuItem> = match query_as!(
+        MenuItem,
+        "SELECT * FROM menu_items WHERE menu_id = $1 ORDER BY item_position, item_id", 
+        menu_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(items) => items,
+        Err(e) => return Err::<Vec<MenuItem>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(items)
+}
+
+/// This function attempts to get
+/// the number of levels of the deepest
+/// branch in a menu or below a menu item.
+/// If the operation fails, an error is
+/// returned.
+pub async fn get_menu_height(
+    menu_id: &String,
+    item_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<i32, CleoErr> {
+    let height: i32 = match query_scalar!(
+        "WITH RECURSIVE branch AS (
+            SELECT item_id, 1 AS depth FROM menu_items 
+            WHERE menu_id = $1 AND (item_id = $2 OR ($2::TEXT IS NULL AND parent_id IS NULL)) 
+            UNION ALL 
+            SELECT menu_items.item_id, branch.depth + 1 FROM menu_items 
+            JOIN branch ON menu_items.parent_id = branch.item_id 
+            WHERE branch.depth < 100
+        ) 
+        SELECT COALESCE(MAX(depth), 0) AS \"height!\" FROM branch",
+        menu_id,
+        item_id.to_owned()
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(height) => height,
+        Err(e) => return Err::<i32, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(height)
+}
+
+/// This function attempts to retrieve
+/// the IDs of a menu item and all its 
+/// ancestors. If the operation fails, an 
+/// error is returned.
+pub async fn get_item_ancestors(
+    item_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<String>, CleoErr> {
+    let ancestors: Vec<String> = match query_scalar!(
+        "WITH RECURSIVE ancestors AS (
+            SELECT item_id, parent_id FROM menu_items WHERE item_id = $1 
+            UNION 
+            SELECT menu_items.item_id, menu_items.parent_id FROM menu_items 
+            JOIN ancestors ON menu_items.item_id = ancestors.parent_id
+        ) 
+        SELECT item_id AS \"item_id!\" FROM ancestors",
+        item_id
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(ancestors) => ancestors,
+        Err(e) => return Err::<Vec<String>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(ancestors)
+}
+
+/// This function attempts to 
+/// retrieve the IDs of the items 
+/// directly below a menu item or at
+/// the top level of a menu if no parent
+/// is supplied, in their order. If the 
+/// operation fails, an error is returned.
+pub async fn get_sibling_items(
+    menu_id: &String,
+    parent_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<String>, CleoErr> {
+    let items: Vec<String> = match query_scalar!(
+        "SELECT item_id FROM menu_items WHERE menu_id = $1 AND parent_id IS NOT DISTINCT FROM $2 ORDER BY item_position, item_id",
+        menu_id,
+        parent_id.to_owned()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(items) => items,
+        Err(e) => return Err::<Vec<String>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(items)
+}
+
+/// This function checks that a
+/// menu item can be placed below the
+/// supplied parent in a menu. The parent
+/// must belong to the menu, must not be the
+/// item or one of its descendants, and the
+/// item and everything below it must fit
+/// within the depth of the menu. If the 
+/// item cannot be placed there, an error is
+/// returned.
+pub async fn validate_item_placement(
+    menu: &NavMenu,
+    item_id: &Option<String>,
+    parent_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let parent_depth: i32 = match parent_id {
+        Some(parent_id) => {
+            let parent: MenuItem = match get_menu_item_by_id(parent_id, pool).await {
+                Ok(parent) => parent,
+                Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            if parent.menu_id != menu.menu_id {
+                let e: &str = "The parent item belongs to a different menu.";
+                return Err::<(), CleoErr>(CleoErr::new(e));
+            }
+            let ancestors: Vec<String> = match get_item_ancestors(parent_id, pool).await {
+                Ok(ancestors) => ancestors,
+                Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            if let Some(item_id) = item_id {
+                if ancestors.contains(item_id) {
+                    let e: &str = "A menu item cannot be moved below itself or one of its descendants.";
+                    return Err::<(), CleoErr>(CleoErr::new(e));
+                }
+            }
+            ancestors.len() as i32
+        },
+        None => 0
+    };
+    let item_height: i32 = match item_id {
+        Some(_item_id) => match get_menu_height(&menu.menu_id, item_id, pool).await {
+            Ok(item_height) => item_height,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        },
+        None => 1
+    };
+    if parent_depth + item_height > menu.max_depth {
+        let e: String = format!("The menu \"{}\" cannot be deeper than {} levels.", menu.menu_slug, menu.max_depth);
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    Ok(())
+}
+
+/// This function attempts to save
+/// the order of the supplied menu items
+/// by numbering their positions from zero.
+/// The time of the last update is set
+/// on the items whose position changes.
+/// If the operation fails, an error is
+/// returned.
+async fn save_item_order(
+    item_ids: &[String],
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), CleoErr> {
+    for (position, item_id) in item_ids.iter().enumerate() {
+        let _update_op: () = match query!(
+            "UPDATE menu_items SET item_position = $1, updated_at = $2 WHERE item_id = $3 AND item_position <> $1",
+            position as i32,
+            Utc::now(),
+            item_id
+        )
+            .execute(&mut **tx)
+            .await
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    Ok(())
+}
+
+/// Works out the index a menu item
+/// is inserted at among its siblings.
+/// Items without a position are placed
+/// last. If the position is negative, an
+/// error is returned.
+fn resolve_item_index(
+    position: &Option<i32>,
+    sibling_count: usize
+) -> Result<usize, CleoErr> {
+    match position {
+        Some(position) if *position < 0 => {
+            let e: &str = "The position of a menu item cannot be negative.";
+            Err::<usize, CleoErr>(CleoErr::new(e))
+        },
+        Some(position) => Ok((*position as usize).min(sibling_count)),
+        None => Ok(sibling_count)
+    }
+}
+
+/// This function attempts
+/// to change the name, slug, or
+/// depth of a navigation menu. Only
+/// the values that are supplied are
+/// changed. The depth cannot be lowered
+/// below the depth of the existing items.
+/// If the operation is successful, an empty
+/// function is returned. If this operation
+/// fails, an error is returned.
+pub async fn edit_menu(
+    user_obj: &CleoUser,
+    menu_id: &String,
+    menu_name: &Option<String>,
+    slug: &Option<String>,
+    max_depth: &Option<i32>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let menu: NavMenu = match get_owned_menu(&user_obj.user_id, menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let new_name: String = match menu_name {
+        Some(menu_name) => menu_name.trim().to_string(),
+        None => menu.menu_name
+    };
+    if new_name.is_empty() {
+        let e: &str = "The name of a menu cannot be empty.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let new_slug: String = match slug {
+        Some(slug) => slug.to_owned(),
+        None => menu.menu_slug
+    };
+    let _valid: () = match validate_slug(&new_slug) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let new_depth: i32 = max_depth.unwrap_or(menu.max_depth);
+    let _valid: () = match validate_menu_depth(&new_depth) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let height: i32 = match get_menu_height(&menu.menu_id, &None, pool).await {
+        Ok(height) => height,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if height > new_depth {
+        let e: String = format!("The menu already has items {} levels deep.", height);
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let update_op: () = match query!(
+        "UPDATE nav_menus SET menu_name = $1, menu_slug = $2, max_depth = $3, updated_at = $4 WHERE menu_id = $5",
+        new_name,
+        new_slug,
+        new_depth,
+        Utc::now(),
+        menu.menu_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(_e) => {
+            let e: String = format!("The menu \"{}\" already exists.", new_slug);
+            return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(update_op)
+}
+
+/// This function attempts
+/// to delete a navigation menu 
+/// together with all its items.
+/// If the operation is successful,
+/// an empty function is returned. If
+/// this operation fails, an error is
+/// returned.
+pub async fn delete_menu(
+    user_obj: &CleoUser,
+    menu_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let menu: NavMenu = match get_owned_menu(&user_obj.user_id, menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let del_op: () = match query!(
+        "DELETE FROM nav_menus WHERE menu_id = $1",
+        menu.menu_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(del_op)
+}
+
+/// This function attempts
+/// to retrieve an item of a 
+/// navigation menu given its ID.
+/// If the operation is successful,
+/// an instance of the "MenuItem" 
+/// structure is returned. If this 
+/// operation fails, an error is 
+/// returned.
+pub async fn get_menu_item_by_id(
+    item_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<MenuItem, CleoErr> {
+    let item: MenuItem = match query_as!(
+        MenuItem,
+        "SELECT * FROM menu_items WHERE item_id = $1", 
+        item_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(item) => item,
+        Err(_e) => {
+            let e: String = format!("The menu item \"{}\" does not exist.", item_id);
+            return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(item)
+}
+
+/// This function attempts
+/// to add an item to a navigation
+/// menu, below the supplied parent item
+/// or at the top level. The item is placed
+/// at the supplied position among its siblings
+/// or after them if no position is supplied.
+/// If the operation is successful, an instance
+/// of the "MenuItem" structure is returned. If
+/// this operation fails, an error is returned.
+pub async fn add_menu_item(
+    user_obj: &CleoUser,
+    menu_id: &String,
+    parent_id: &Option<String>,
+    label: &str,
+    target: &MenuTarget,
+    position: &Option<i32>,
+    pool: &Pool<Postgres>
+) -> Result<MenuItem, CleoErr> {
+    let menu: NavMenu = match get_owned_menu(&user_obj.user_id, menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let label: String = label.trim().to_string();
+    if label.is_empty() {
+        let e: &str = "The label of a menu item cannot be empty.";
+        return Err::<MenuItem, CleoErr>(CleoErr::new(e));
+    }
+    let (content_id, file_id, target_url): (Option<String>, Option<String>, Option<String>) = match validate_menu_target(
+        &user_obj.user_id, 
+        target, 
+        pool
+    ).await {
+        Ok(resolved) => resolved,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_item_placement(&menu, &None, parent_id, pool).await {
+        Ok(valid) => valid,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut order: Vec<String> = match get_sibling_items(&menu.menu_id, parent_id, pool).await {
+        Ok(order) => order,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let index: usize = match resolve_item_index(position, order.len()) {
+        Ok(index) => index,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let item_id: String = match new_id() {
+        Ok(item_id) => item_id,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    order.insert(index, item_id.clone());
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "INSERT INTO menu_items (item_id, menu_id, parent_id, label, target_type, content_id, file_id, target_url, item_position, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        item_id,
+        menu.menu_id,
+        parent_id.to_owned(),
+        label,
+        target.target_type,
+        content_id,
+        file_id,
+        target_url,
+        index as i32,
+        Utc::now(),
+        Utc::now()
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _saved: () = match save_item_order(&order, &mut tx).await {
+        Ok(saved) => saved,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let item: MenuItem = match get_menu_item_by_id(&item_id, pool).await {
+        Ok(item) => item,
+        Err(e) => return Err::<MenuItem, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(item)
+}
+
+/// This function attempts
+/// to change the label or the target
+/// of a menu item. Only the values that
+/// are supplied are changed. If the 
+/// operation is successful, an empty 
+/// function is returned. If this operation
+/// fails, an error is returned.
+pub async fn edit_menu_item(
+    user_obj: &CleoUser,
+    item_id: &String,
+    label: &Option<String>,
+    target: &Option<MenuTarget>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let item: MenuItem = match get_menu_item_by_id(item_id, pool).await {
+        Ok(item) => item,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _menu: NavMenu = match get_owned_menu(&user_obj.user_id, &item.menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let new_label: String = match label {
+        Some(label) => label.trim().to_string(),
+        None => item.label
+    };
+    if new_label.is_empty() {
+        let e: &str = "The label of a menu item cannot be empty.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let (target_type, content_id, file_id, target_url): (String, Option<String>, Option<String>, Option<String>) = match target {
+        Some(target) => match validate_menu_target(&user_obj.user_id, target, pool).await {
+            Ok((content_id, file_id, target_url)) => (target.target_type.clone(), content_id, file_id, target_url),
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        },
+        None => (item.target_type, item.content_id, item.file_id, item.target_url)
+    };
+    let update_op: () = match query!(
+        "UPDATE menu_items SET label = $1, target_type = $2, content_id = $3, file_id = $4, target_url = $5, updated_at = $6 WHERE item_id = $7",
+        new_label,
+        target_type,
+        content_id,
+        file_id,
+        target_url,
+        Utc::now(),
+        item.item_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(update_op)
+}
+
+/// This function attempts to move
+/// a menu item below another item of the
+/// same menu or to the top level if no
+/// parent is supplied. The item is placed
+/// at the supplied position among its new
+/// siblings or after them if no position is
+/// supplied. Items below the moved item move
+/// with it. If the operation is successful, 
+/// an empty function is returned. If this
+/// operation fails, an error is returned.
+pub async fn move_menu_item(
+    user_obj: &CleoUser,
+    item_id: &String,
+    parent_id: &Option<String>,
+    position: &Option<i32>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let item: MenuItem = match get_menu_item_by_id(item_id, pool).await {
+        Ok(item) => item,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let menu: NavMenu = match get_owned_menu(&user_obj.user_id, &item.menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_item_placement(&menu, &Some(item.item_id.clone()), parent_id, pool).await {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let siblings: Vec<String> = match get_sibling_items(&menu.menu_id, parent_id, pool).await {
+        Ok(siblings) => siblings,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut new_order: Vec<String> = siblings
+        .into_iter()
+        .filter(|sibling_id| sibling_id != &item.item_id)
+        .collect();
+    let index: usize = match resolve_item_index(position, new_order.len()) {
+        Ok(index) => index,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    new_order.insert(index, item.item_id.clone());
+    let old_order: Vec<String> = if &item.parent_id != parent_id {
+        match get_sibling_items(&menu.menu_id, &item.parent_id, pool).await {
+            Ok(old_siblings) => old_siblings
+                .into_iter()
+                .filter(|sibling_id| sibling_id != &item.item_id)
+                .collect(),
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    }
+    else {
+        Vec::new()
+    };
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _update_op: () = match query!(
+        "UPDATE menu_items SET parent_id = $1, updated_at = $2 WHERE item_id = $3",
+        parent_id.to_owned(),
+        Utc::now(),
+        item.item_id
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _saved: () = match save_item_order(&new_order, &mut tx).await {
+        Ok(saved) => saved,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _saved: () = match save_item_order(&old_order, &mut tx).await {
+        Ok(saved) => saved,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(commit_op)
+}
+
+/// This function attempts
+/// to delete a menu item together
+/// with all items below it. If the
+/// operation is successful, an empty
+/// function is returned. If this operation
+/// fails, an error is returned.
+pub async fn delete_menu_item(
+    user_obj: &CleoUser,
+    item_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let item: MenuItem = match get_menu_item_by_id(item_id, pool).await {
+        Ok(item) => item,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _menu: NavMenu = match get_owned_menu(&user_obj.user_id, &item.menu_id, pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let del_op: () = match query!(
+        "DELETE FROM menu_items WHERE item_id = $1",
+        item.item_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(del_op)
+}
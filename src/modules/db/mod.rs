@@ -27,6 +27,78 @@ pub mod keys;
 /// website.
 pub mod posts;
 
+/// Exporting the
+/// functions for
+/// reading and writing
+/// information about
+/// post revisions to
+/// and from the database.
+pub mod revisions;
+
+/// Exporting the
+/// functions for
+/// reading and writing
+/// information about
+/// post slugs and their
+/// redirects.
+pub mod slugs;
+
+/// Exporting the
+/// functions for
+/// searching the text
+/// of published posts.
+pub mod search;
+
+/// Exporting the
+/// functions for
+/// reading and writing
+/// information about
+/// tags and categories.
+pub mod taxonomy;
+
+/// Exporting the
+/// functions for
+/// reading and writing
+/// information about
+/// the hierarchy of pages.
+pub mod pages;
+
+/// Exporting the
+/// functions for
+/// reading and writing
+/// information about
+/// navigation menus.
+pub mod menus;
+
+/// Exporting the
+/// functions for
+/// reading the posts
+/// that make up feeds
+/// and sitemaps.
+pub mod feeds;
+
+/// Exporting the
+/// functions for
+/// reading and writing
+/// information about
+/// webhooks and their
+/// deliveries.
+pub mod webhooks;
+
+/// Exporting the
+/// functions for
+/// announcing changes
+/// to content.
+pub mod changes;
+
+/// Exporting the
+/// functions for
+/// reading and writing
+/// information about
+/// types of content and
+/// the fields they accept.
+pub mod content_types;
+
 /// Exporting the
 /// functions for
 /// reading and writing
@@ -0,0 +1,420 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query"
+/// macro to execute SQL
+/// queries that return 
+/// nothing.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "Transaction"
+/// structure to write several
+/// changes at once.
+use sqlx::Transaction;
+
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries that return 
+/// single values.
+use sqlx::query_scalar;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "CleoUser" structure
+/// to read and write associated data
+/// about Cleo users.
+use crate::modules::models::CleoUser;
+
+/// Importing the "UserPost" structure
+/// to read and write information about
+/// posts written by a user.
+use crate::modules::models::UserPost;
+
+/// Importing the function to retrieve
+/// a post given its ID.
+use crate::modules::db::posts::get_post_by_id;
+
+/// This function attempts to
+/// retrieve a page of a user and
+/// checks that the user owns it.
+/// If the post is not a page, the
+/// user does not own it, or the 
+/// operation fails, an error is 
+/// returned.
+pub async fn get_owned_page(
+    user_id: &String,
+    content_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<UserPost, CleoErr> {
+    let page: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(page) => page,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if &page.user_id != user_id {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    if page.content_type != "page" {
+        let e: String = format!("\"{}\" is not a page.", content_id);
+        return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    Ok(page)
+}
+
+/// This function attempts to 
+/// retrieve the pages directly below
+/// a parent page of a user or the user's
+/// top-level pages if no parent is supplied.
+/// The pages are returned in their order.
+/// If the operation fails, an error is 
+/// returned.
+pub async fn get_sibling_pages(
+    user_id: &String,
+    parent_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserPost>, CleoErr> {
+    let pages: Vec<UserPost> = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts WHERE user_id = $1 AND content_type = 'page' AND parent_id IS NOT DISTINCT FROM $2 ORDER BY page_position, title, content_id",
+        user_id,
+        parent_id.to_owned()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(pages) => pages,
+        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(pages)
+}
+
+/// This function attempts to get
+/// the position after the last page
+/// below a parent page or at the top 
+/// level if no parent is supplied. If
+/// the operation fails, an error is 
+/// returned.
+pub async fn next_page_position(
+    user_id: &String,
+    parent_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<i32, CleoErr> {
+    let position: i32 = match query_scalar!(
+        "SELECT COALESCE(MAX(page_position) + 1, 0) AS \"position!\" FROM user_posts WHERE user_id = $1 AND content_type = 'page' AND parent_id IS NOT DISTINCT FROM $2",
+        user_id,
+        parent_id.to_owned()
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(position) => position,
+        Err(e) => return Err::<i32, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(position)
+}
+
+/// This function attempts to retrieve
+/// the IDs of a page and all its ancestors
+/// as seen by the supplied transaction. If
+/// the operation fails, an error is 
+/// returned.
+pub async fn get_page_ancestors(
+    content_id: &String,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<Vec<String>, CleoErr> {
+    let ancestors: Vec<String> = match query_scalar!(
+        "WITH RECURSIVE ancestors AS (
+            SELECT content_id, parent_id FROM user_posts WHERE content_id = $1 
+            UNION 
+            SELECT user_posts.content_id, user_posts.parent_id FROM user_posts 
+            JOIN ancestors ON user_posts.content_id = ancestors.parent_id
+        ) 
+        SELECT content_id AS \"content_id!\" FROM ancestors",
+        content_id
+    )
+        .fetch_all(&mut **tx)
+        .await
+    {
+        Ok(ancestors) => ancestors,
+        Err(e) => return Err::<Vec<String>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(ancestors)
+}
+
+/// This function attempts to lock
+/// a page that is moved, the new parent
+/// page, and all ancestors of the new parent
+/// until the transaction ends. Another move
+/// touching any of these pages waits until
+/// the transaction is done. The pages are
+/// locked in a fixed order so that two 
+/// moves cannot wait on each other. If the
+/// operation fails, an error is returned.
+async fn lock_pages_for_move(
+    content_id: &String,
+    parent_id: &Option<String>,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), CleoErr> {
+    let _locked: Vec<String> = match query_scalar!(
+        "SELECT content_id FROM user_posts WHERE content_id = $1 OR content_id IN (
+            WITH RECURSIVE ancestors AS (
+                SELECT content_id, parent_id FROM user_posts WHERE content_id = $2 
+                UNION 
+                SELECT user_posts.content_id, user_posts.parent_id FROM user_posts 
+                JOIN ancestors ON user_posts.content_id = ancestors.parent_id
+            ) 
+            SELECT content_id FROM ancestors
+        ) 
+        ORDER BY content_id 
+        FOR UPDATE",
+        content_id,
+        parent_id.to_owned()
+    )
+        .fetch_all(&mut **tx)
+        .await
+    {
+        Ok(locked) => locked,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(())
+}
+
+/// This function attempts to save
+/// the order of the supplied pages by 
+/// numbering their positions from zero.
+/// The time of the last update is set
+/// on the pages whose position changes.
+/// If the operation fails, an error is
+/// returned.
+async fn save_page_order(
+    content_ids: &[String],
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), CleoErr> {
+    for (position, content_id) in content_ids.iter().enumerate() {
+        let _update_op: () = match query!(
+            "UPDATE user_posts SET page_position = $1, updated_at = $2 WHERE content_id = $3 AND page_position <> $1",
+            position as i32,
+            Utc::now(),
+            content_id
+        )
+            .execute(&mut **tx)
+            .await
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    Ok(())
+}
+
+/// This function attempts to move
+/// a page below another page or to the
+/// top level if no parent is supplied. The
+/// page is placed at the supplied position 
+/// among its new siblings or after them if no
+/// position is supplied. A page cannot be moved
+/// below itself or one of its descendants. This
+/// is checked while the pages involved are locked,
+/// so that two moves at once cannot form a loop.
+/// Only the owner of both pages can do this. If the 
+/// operation is successful, an empty function is
+/// returned. If this operation fails, an error is
+/// returned.
+pub async fn move_page(
+    user_obj: &CleoUser,
+    content_id: &String,
+    parent_id: &Option<String>,
+    position: &Option<i32>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let page: UserPost = match get_owned_page(&user_obj.user_id, content_id, pool).await {
+        Ok(page) => page,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if let Some(parent_id) = parent_id {
+        let _parent: UserPost = match get_owned_page(&user_obj.user_id, parent_id, pool).await {
+            Ok(parent) => parent,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _locked: () = match lock_pages_for_move(&page.content_id, parent_id, &mut tx).await {
+        Ok(locked) => locked,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if let Some(parent_id) = parent_id {
+        let ancestors: Vec<String> = match get_page_ancestors(parent_id, &mut tx).await {
+            Ok(ancestors) => ancestors,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        if ancestors.contains(&page.content_id) {
+            let e: &str = "A page cannot be moved below itself or one of its descendants.";
+            return Err::<(), CleoErr>(CleoErr::new(e));
+        }
+    }
+    let siblings: Vec<UserPost> = match get_sibling_pages(&user_obj.user_id, parent_id, pool).await {
+        Ok(siblings) => siblings,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut new_order: Vec<String> = siblings
+        .into_iter()
+        .map(|sibling| sibling.content_id)
+        .filter(|sibling_id| sibling_id != &page.content_id)
+        .collect();
+    let index: usize = match position {
+        Some(position) if *position < 0 => {
+            let e: &str = "The position of a page cannot be negative.";
+            return Err::<(), CleoErr>(CleoErr::new(e));
+        },
+        Some(position) => (*position as usize).min(new_order.len()),
+        None => new_order.len()
+    };
+    new_order.insert(index, page.content_id.clone());
+    let old_order: Vec<String> = if &page.parent_id != parent_id {
+        match get_sibling_pages(&user_obj.user_id, &page.parent_id, pool).await {
+            Ok(old_siblings) => old_siblings
+                .into_iter()
+                .map(|sibling| sibling.content_id)
+                .filter(|sibling_id| sibling_id != &page.content_id)
+                .collect(),
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    }
+    else {
+        Vec::new()
+    };
+    let _update_op: () = match query!(
+        "UPDATE user_posts SET parent_id = $1, updated_at = $2 WHERE content_id = $3",
+        parent_id.to_owned(),
+        Utc::now(),
+        page.content_id
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _saved: () = match save_page_order(&new_order, &mut tx).await {
+        Ok(saved) => saved,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _saved: () = match save_page_order(&old_order, &mut tx).await {
+        Ok(saved) => saved,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(commit_op)
+}
+
+/// This function attempts to change
+/// the order of the pages directly below
+/// a parent page or of the top-level pages
+/// if no parent is supplied. The supplied IDs
+/// must contain every one of these pages exactly
+/// once. If the operation is successful, an empty
+/// function is returned. If this operation fails, 
+/// an error is returned.
+pub async fn reorder_pages(
+    user_obj: &CleoUser,
+    parent_id: &Option<String>,
+    content_ids: &Vec<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    if let Some(parent_id) = parent_id {
+        let _parent: UserPost = match get_owned_page(&user_obj.user_id, parent_id, pool).await {
+            Ok(parent) => parent,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    let siblings: Vec<UserPost> = match get_sibling_pages(&user_obj.user_id, parent_id, pool).await {
+        Ok(siblings) => siblings,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut current: Vec<String> = siblings
+        .into_iter()
+        .map(|sibling| sibling.content_id)
+        .collect();
+    let mut requested: Vec<String> = content_ids.to_owned();
+    current.sort();
+    requested.sort();
+    if current != requested {
+        let e: &str = "The new order must contain every page below the parent exactly once.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _saved: () = match save_page_order(content_ids, &mut tx).await {
+        Ok(saved) => saved,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(commit_op)
+}
+
+/// This function attempts to retrieve
+/// all pages of a user in the order they
+/// appear among their siblings. If only
+/// published pages are requested, pages 
+/// that are not published are left out. 
+/// If the operation fails, an error is 
+/// returned.
+pub async fn get_user_pages(
+    user_id: &String,
+    published_only: bool,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserPost>, CleoErr> {
+    let pages: Vec<UserPost> = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts WHERE user_id = $1 AND content_type = 'page' AND (NOT $2 OR post_status = 'published') ORDER BY page_position, title, content_id",
+        user_id,
+        published_only
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(pages) => pages,
+        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(pages)
+}
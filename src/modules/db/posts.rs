@@ -16,26 +16,46 @@ use sqlx::Pool;
 /// nothing.
 use sqlx::query;
 
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
 /// Importing the "query_as"
 /// macro to execute SQL
 /// queries that return 
 /// something.
 use sqlx::query_as;
 
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing of timestamps.
+use chrono::DateTime;
+
 /// Importing the "Postgres"
 /// to specify the type of
 /// database one is connecting
 /// to.
 use sqlx::postgres::Postgres;
 
+/// Importing the "HashMap"
+/// structure to accept maps
+/// of extra content fields.
+use std::collections::HashMap;
+
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr"
 /// structure to ctach and handle
 /// errors.
 use crate::modules::err::CleoErr;
 
-/// Importing the "TimeNow" structure
-/// to retrieve the current time.
-use crate::modules::utils::TimeNow;
+/// Importing the structure
+/// holding everything a new post
+/// is created from.
+use crate::modules::units::NewPost;
 
 /// Importing the "CleoUser" structure
 /// to read and write associated data
@@ -47,14 +67,75 @@ use crate::modules::models::CleoUser;
 /// posts written by a user.
 use crate::modules::models::UserPost;
 
+/// Importing the "UserFile" structure
+/// to verify cover images of posts.
+use crate::modules::models::UserFile;
+
+/// Importing the structure holding
+/// the descriptive fields of a post.
+use crate::modules::units::PostDetails;
+
+/// Importing the "PostRevision" structure
+/// for explicit typing.
+use crate::modules::models::PostRevision;
+
+/// Importing the function to get
+/// the plain text of a post.
+use crate::modules::html::render_post_text;
+
+/// Importing the function to check
+/// whether a slug is valid.
+use crate::modules::db::slugs::validate_slug;
+
+/// Importing the function to check
+/// whether a slug is already in use.
+use crate::modules::db::slugs::slug_is_taken;
+
+/// Importing the function to prepare
+/// the text of a post for saving.
+use crate::modules::html::prepare_post_source;
+
+/// Importing the function to retrieve
+/// a file given its ID.
+use crate::modules::db::files::get_file_by_id;
+
+/// Importing the function to describe
+/// a post in the data of an event.
+use crate::modules::webhooks::post_event_data;
+
+/// Importing the function to get the
+/// position after the last page below
+/// a parent.
+use crate::modules::db::pages::next_page_position;
+
 /// Importing the function to generate
-/// a hash for string.
-use crate::modules::utils::hash_string;
+/// a slug that is not in use yet.
+use crate::modules::db::slugs::generate_unique_slug;
+
+/// Importing the function to free
+/// a slug that was kept as a redirect.
+use crate::modules::db::slugs::release_slug_redirect;
+
+/// Importing the function to announce
+/// a change to content.
+use crate::modules::db::changes::publish_change_event;
+
+/// Importing the function to save the
+/// current state of a post as a revision.
+use crate::modules::db::revisions::create_post_revision;
+
+/// Importing the function to retrieve
+/// all revisions of a post.
+use crate::modules::db::revisions::get_revisions_for_post;
 
-/// Importing the "get_user_from_token" 
-/// function to retrieve an entry for a Cleo
-/// user given their API token.
-use crate::modules::db::users::get_user_from_token;
+/// Importing the function to determine
+/// the type of an extra content field.
+use crate::modules::db::content_types::resolve_field_type;
+
+/// Importing the function to check extra
+/// content fields against the definition
+/// of a content type.
+use crate::modules::db::content_types::validate_post_fields;
 
 /// This function attempts
 /// to create a post for a user.
@@ -62,42 +143,183 @@ use crate::modules::db::users::get_user_from_token;
 /// an instance of the "UserPost"
 /// structure is returned.
 pub async fn create_user_post(
-    api_token: &String,
-    content_type: &String, // "page" or "post"
-    content_text: &String, // needs to be html
+    user_obj: &CleoUser,
+    new_post: &NewPost,
     pool: &Pool<Postgres>
 ) -> Result<UserPost, CleoErr>{
-    let user_obj: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user_obj) => user_obj,
+    let content_type: &String = &new_post.content_type; // name of a defined content type
+    let content_text: &String = &new_post.content_text; // html or markdown
+    let source_format: &String = &new_post.source_format; // "html" or "markdown"
+    let slug: &Option<String> = &new_post.slug;
+    let details: &PostDetails = &new_post.details;
+    let extra_fields: &HashMap<String, String> = &new_post.extra_fields;
+    let _valid: () = match validate_post_fields(&user_obj.user_id, content_type, extra_fields, pool).await {
+        Ok(valid) => valid,
         Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let content_snippet: String = content_text.chars().take(16).collect();
-    let content_id: String = hash_string(
-        &format!(
-            "{}{}",
-            TimeNow::new().to_string(),
-            content_snippet
-        )
-    );
+    let content_text: String = match prepare_post_source(source_format, content_text) {
+        Ok(content_text) => content_text,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let title: String = details.title.clone().unwrap_or_default();
+    let cover_file_id: Option<String> = match &details.cover_file_id {
+        Some(file_id) if !file_id.is_empty() => {
+            let _verified: () = match verify_file_ownership(&user_obj.user_id, file_id, pool).await {
+                Ok(verified) => verified,
+                Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            Some(file_id.to_owned())
+        },
+        _ => None
+    };
+    let slug_source: String = if title.is_empty() {
+        match render_post_text(source_format, &content_text) {
+            Ok(text) => text,
+            Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    }
+    else {
+        title.clone()
+    };
+    let content_id: String = match new_id() {
+        Ok(content_id) => content_id,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let post_slug: String = match slug {
+        Some(slug) => {
+            let _valid: () = match validate_slug(slug) {
+                Ok(valid) => valid,
+                Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            let taken: bool = match slug_is_taken(&user_obj.user_id, content_type, slug, pool).await {
+                Ok(taken) => taken,
+                Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            if taken {
+                let e: String = format!("The slug \"{}\" is already taken.", slug);
+                return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()));
+            }
+            slug.to_owned()
+        },
+        None => match generate_unique_slug(
+            &user_obj.user_id, 
+            content_type, 
+            &slug_source, 
+            &content_id, 
+            pool
+        ).await {
+            Ok(generated) => generated,
+            Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    };
+    let page_position: i32 = if content_type == "page" {
+        match next_page_position(&user_obj.user_id, &None, pool).await {
+            Ok(page_position) => page_position,
+            Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    }
+    else {
+        0
+    };
     let post_obj: UserPost = UserPost{
-        user_id: user_obj.user_id,
+        user_id: user_obj.user_id.clone(),
         content_text: content_text.clone(),
         content_type: content_type.to_owned(),
         content_id: content_id.clone(),
+        post_status: "draft".to_string(),
+        publish_at: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        slug: post_slug,
+        title: title.clone(),
+        excerpt: details.excerpt.clone().unwrap_or_default(),
+        cover_file_id,
+        author_name: details.author_name.clone().unwrap_or(user_obj.display_name.clone()),
+        source_format: source_format.to_owned(),
+        parent_id: None,
+        page_position
+    };
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
     };
     let _insert_op = match query!(
-        "INSERT INTO user_posts (user_id, content_text, content_type, content_id) VALUES ($1, $2, $3, $4)",
+        "INSERT INTO user_posts (user_id, content_text, content_type, content_id, post_status, publish_at, created_at, updated_at, slug, title, excerpt, cover_file_id, author_name, source_format, parent_id, page_position) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)",
         post_obj.user_id,
         post_obj.content_text,
         post_obj.content_type,
-        post_obj.content_id
+        post_obj.content_id,
+        post_obj.post_status,
+        post_obj.publish_at,
+        post_obj.created_at,
+        post_obj.updated_at,
+        post_obj.slug,
+        post_obj.title,
+        post_obj.excerpt,
+        post_obj.cover_file_id,
+        post_obj.author_name,
+        post_obj.source_format,
+        post_obj.parent_id,
+        post_obj.page_position
     )
-        .execute(pool)
+        .execute(&mut *tx)
         .await
     {
         Ok(_feedback) => {},
         Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
     };
+    for (field_key, field_value) in extra_fields {
+        let field_type: String = match resolve_field_type(content_type, field_key, &None, pool).await {
+            Ok(field_type) => field_type,
+            Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let field_id: String = match new_id() {
+            Ok(field_id) => field_id,
+            Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        match query!(
+            "INSERT INTO extra_content_fields (field_id, content_id, field_key, field_value, created_at, updated_at, field_type) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            field_id,
+            content_id,
+            field_key,
+            field_value,
+            Utc::now(),
+            Utc::now(),
+            field_type
+        )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    let _revision: PostRevision = match create_post_revision(&content_id, &post_obj.user_id, &mut tx).await {
+        Ok(revision) => revision,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _published: () = match publish_change_event(
+        &post_obj.user_id, 
+        &"post.created".to_string(), 
+        &post_event_data(&post_obj), 
+        &mut tx
+    ).await {
+        Ok(published) => published,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _release_op: () = match release_slug_redirect(
+        &post_obj.user_id, 
+        &post_obj.content_type, 
+        &post_obj.slug, 
+        pool
+    ).await {
+        Ok(release_op) => release_op,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let post_obj: UserPost = match get_post_by_id(&content_id, pool).await {
         Ok(post_obj) => post_obj,
         Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
@@ -137,32 +359,65 @@ pub async fn get_post_by_id(
 /// is returned. If this operation fails,
 /// an error is returned.
 pub async fn update_post_text(
-    api_token: &String,
+    user_obj: &CleoUser,
     content_id: &String,
     text: &String,
     pool: &Pool<Postgres>
 ) -> Result<(), CleoErr> {
-    let user_obj: CleoUser = match get_user_from_token(&api_token, pool).await {
-        Ok(user_obj) => user_obj,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let post_obj: UserPost = match get_post_by_id(&content_id, pool).await {
         Ok(post_obj) => post_obj,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
+    let text: String = match prepare_post_source(&post_obj.source_format, text) {
+        Ok(text) => text,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
     if user_obj.user_id == post_obj.user_id {
-        let update_op: () = match query!(
-            "UPDATE user_posts SET content_text = $1 WHERE content_id = $2", 
+        let revisions: Vec<PostRevision> = match get_revisions_for_post(content_id, pool).await {
+            Ok(revisions) => revisions,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        if revisions.is_empty() {
+            let _revision: PostRevision = match create_post_revision(content_id, &post_obj.user_id, &mut tx).await {
+                Ok(revision) => revision,
+                Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+        }
+        let updated_post: UserPost = match query_as!(
+            UserPost,
+            "UPDATE user_posts SET content_text = $1, updated_at = $2 WHERE content_id = $3 RETURNING *",
             text,
+            Utc::now(),
             content_id
         )
-            .execute(pool)
+            .fetch_one(&mut *tx)
             .await 
         {
+            Ok(updated_post) => updated_post,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _revision: PostRevision = match create_post_revision(content_id, &user_obj.user_id, &mut tx).await {
+            Ok(revision) => revision,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _published: () = match publish_change_event(
+            &updated_post.user_id, 
+            &"post.updated".to_string(), 
+            &post_event_data(&updated_post), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _commit_op: () = match tx.commit().await {
             Ok(_feedback) => {},
             Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
         };
-        Ok(update_op)
+        Ok(())
     }
     else {
         let e: String = "Could not verify ownership of the post.".to_string();
@@ -170,68 +425,302 @@ pub async fn update_post_text(
     }
 }
 
-/// This function attempts to
-/// delete a post. If this operation
-/// is successful, an empty function
-/// is returned. If the operation fails,
+/// This function attempts
+/// to verify that a file exists
+/// and was uploaded by the user 
+/// with the supplied ID, so that it
+/// can be referenced by a post. If 
+/// the file does not belong to the 
+/// user or the operation fails, an 
+/// error is returned.
+pub async fn verify_file_ownership(
+    user_id: &String,
+    file_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let file_obj: UserFile = match get_file_by_id(file_id, pool).await {
+        Ok(file_obj) => file_obj,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if &file_obj.user_id == user_id {
+        Ok(())
+    }
+    else {
+        let e: String = "Could not verify ownership of the file.".to_string();
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts
+/// to update the title, excerpt,
+/// cover image, and author name of
+/// a post. Only the fields that are
+/// supplied are changed. An empty
+/// cover file ID removes the cover
+/// image. If the operation is
+/// successful, an empty function
+/// is returned. If this operation fails,
 /// an error is returned.
-pub async fn delete_post(
-    api_token: &String,
+pub async fn update_post_details(
+    user_obj: &CleoUser,
     content_id: &String,
-    pool: &Pool<Postgres>,
+    details: &PostDetails,
+    pool: &Pool<Postgres>
 ) -> Result<(), CleoErr> {
-    let user_obj: CleoUser = match get_user_from_token(&api_token, pool).await {
-        Ok(user_obj) => user_obj,
+    let post_obj: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post_obj) => post_obj,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let post_obj: UserPost = match get_post_by_id(&content_id, pool).await {
+    if user_obj.user_id != post_obj.user_id {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let cover_file_id: Option<String> = match &details.cover_file_id {
+        Some(file_id) if file_id.is_empty() => None,
+        Some(file_id) => {
+            let _verified: () = match verify_file_ownership(&user_obj.user_id, file_id, pool).await {
+                Ok(verified) => verified,
+                Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            Some(file_id.to_owned())
+        },
+        None => post_obj.cover_file_id.clone()
+    };
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let updated_post: UserPost = match query_as!(
+        UserPost,
+        "UPDATE user_posts SET title = $1, excerpt = $2, cover_file_id = $3, author_name = $4, updated_at = $5 WHERE content_id = $6 RETURNING *",
+        details.title.clone().unwrap_or(post_obj.title.clone()),
+        details.excerpt.clone().unwrap_or(post_obj.excerpt.clone()),
+        cover_file_id,
+        details.author_name.clone().unwrap_or(post_obj.author_name.clone()),
+        Utc::now(),
+        content_id
+    )
+        .fetch_one(&mut *tx)
+        .await 
+    {
+        Ok(updated_post) => updated_post,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _revision: PostRevision = match create_post_revision(content_id, &user_obj.user_id, &mut tx).await {
+        Ok(revision) => revision,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _published: () = match publish_change_event(
+        &updated_post.user_id, 
+        &"post.updated".to_string(), 
+        &post_event_data(&updated_post), 
+        &mut tx
+    ).await {
+        Ok(published) => published,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(commit_op)
+}
+
+/// This function attempts
+/// to retrieve a post given the
+/// post's ID without requiring
+/// any credentials. Only posts
+/// that have been published
+/// are returned. If the operation
+/// fails or the post is not published,
+/// an error is returned.
+pub async fn get_public_post_by_id(
+    content_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<UserPost, CleoErr> {
+    let post_obj: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if post_obj.post_status == "published" {
+        Ok(post_obj)
+    }
+    else {
+        let e: String = format!("The post \"{}\" could not be found.", content_id);
+        Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts
+/// to change the publication
+/// status of a post. Valid statuses
+/// are "draft", "published", "scheduled",
+/// and "archived". A time of publication
+/// must be supplied for scheduled posts.
+/// If the operation is successful, an empty
+/// function is returned. If this operation fails,
+/// an error is returned.
+pub async fn update_post_status(
+    user_obj: &CleoUser,
+    content_id: &String,
+    post_status: &String,
+    publish_at: &Option<DateTime<Utc>>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let valid_statuses: Vec<&str> = vec!["draft", "published", "scheduled", "archived"];
+    if !valid_statuses.contains(&post_status.as_str()) {
+        let e: String = format!("\"{}\" is not a valid post status.", post_status);
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    if post_status == "scheduled" && publish_at.is_none() {
+        let e: String = "Scheduled posts require a time of publication.".to_string();
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let post_obj: UserPost = match get_post_by_id(content_id, pool).await {
         Ok(post_obj) => post_obj,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
     if user_obj.user_id == post_obj.user_id {
-        let del_op: () = match query!(
-            "DELETE FROM user_posts WHERE content_id = $1", 
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let updated_post: UserPost = match query_as!(
+            UserPost,
+            "UPDATE user_posts SET post_status = $1, publish_at = $2, updated_at = $3 WHERE content_id = $4 RETURNING *", 
+            post_status,
+            publish_at.to_owned(),
+            Utc::now(),
             content_id
         )
-            .execute(pool)
+            .fetch_one(&mut *tx)
             .await 
         {
+            Ok(updated_post) => updated_post,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let event: String = if post_status == "published" {
+            "post.published".to_string()
+        }
+        else {
+            "post.updated".to_string()
+        };
+        let _published: () = match publish_change_event(
+            &updated_post.user_id, 
+            &event, 
+            &post_event_data(&updated_post), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let commit_op: () = match tx.commit().await {
             Ok(_feedback) => {},
             Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
         };
-        Ok(del_op)
+        Ok(commit_op)
     }
     else {
         let e: String = "Could not verify ownership of the post.".to_string();
         Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-
     }
 }
 
 /// This function attempts
-/// to retrieve all of a user's
-/// posts. If this operation is succesful,
-/// a vector of instances of the "UserPost"
-/// structure is returned. If this operation
-/// fails, an error is returned.
-pub async fn get_user_posts(
-    api_token: &String,
-    pool: &Pool<Postgres>,
-) -> Result<Vec<UserPost>, CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+/// to publish all scheduled posts
+/// whose time of publication has
+/// passed and queues an event for
+/// each of them in the same transaction.
+/// If the operation is successful, the
+/// number of published posts is returned.
+/// If this operation fails, an error
+/// is returned.
+pub async fn publish_scheduled_posts(
+    pool: &Pool<Postgres>
+) -> Result<u64, CleoErr> {
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let user_posts: Vec<UserPost> = match query_as!(
+    let published: Vec<UserPost> = match query_as!(
         UserPost,
-        "SELECT * FROM user_posts WHERE user_id = $1",
-        user.user_id
-    )   
-        .fetch_all(pool)
+        "UPDATE user_posts SET post_status = $1, updated_at = $2 WHERE post_status = $3 AND publish_at <= $4 RETURNING *", 
+        "published",
+        Utc::now(),
+        "scheduled",
+        Utc::now()
+    )
+        .fetch_all(&mut *tx)
         .await 
     {
-        Ok(user_posts) => user_posts,
-        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+        Ok(published) => published,
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    for post_obj in &published {
+        let _published: () = match publish_change_event(
+            &post_obj.user_id, 
+            &"post.published".to_string(), 
+            &post_event_data(post_obj), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    let _commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(published.len() as u64)
+}
+
+/// This function attempts to
+/// delete a post. If this operation
+/// is successful, an empty function
+/// is returned. If the operation fails,
+/// an error is returned.
+pub async fn delete_post(
+    user_obj: &CleoUser,
+    content_id: &String,
+    pool: &Pool<Postgres>,
+) -> Result<(), CleoErr> {
+    let post_obj: UserPost = match get_post_by_id(&content_id, pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
-    Ok(user_posts)
+    if user_obj.user_id == post_obj.user_id {
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _del_op: () = match query!(
+            "DELETE FROM user_posts WHERE content_id = $1", 
+            content_id
+        )
+            .execute(&mut *tx)
+            .await 
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let _published: () = match publish_change_event(
+            &post_obj.user_id, 
+            &"post.deleted".to_string(), 
+            &post_event_data(&post_obj), 
+            &mut tx
+        ).await {
+            Ok(published) => published,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let commit_op: () = match tx.commit().await {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        Ok(commit_op)
+    }
+    else {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+
+    }
 }
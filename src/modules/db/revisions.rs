@@ -0,0 +1,382 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Pool"
+/// structure to use a pool
+/// of connections.
+use sqlx::Pool;
+
+/// Importing the "query"
+/// macro to execute queries
+/// that return nothing.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "query_as"
+/// macro to execute queries
+/// that return something.
+use sqlx::query_as;
+
+/// Importing the "Transaction"
+/// structure to write several
+/// changes at once.
+use sqlx::Transaction;
+
+/// Importing the function to
+/// deserialize JSON strings.
+use serde_json::from_str;
+
+/// Importing the function to
+/// serialize data structures
+/// as JSON strings.
+use serde_json::to_string;
+
+/// Importing the "Postgres"
+/// structure for explicit 
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
+/// Importing the "CleoErr"
+/// structure to catch and
+/// handle errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the function to
+/// retrieve a post given the 
+/// post's ID.
+use super::posts::get_post_by_id;
+
+/// Importing the "CleoUser" structure
+/// for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the "UserPost" structure
+/// for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the function to
+/// announce a change to the
+/// content of a user.
+use super::changes::publish_change_event;
+
+/// Importing the structure holding
+/// a snapshot of an extra content
+/// field.
+use crate::modules::units::FieldSnapshot;
+
+/// Importing the structure representing
+/// the model in the database for a post
+/// revision for reading and writing
+/// this entity.
+use crate::modules::models::PostRevision;
+
+/// Importing the function to
+/// build the data of events
+/// for posts.
+use crate::modules::webhooks::post_event_data;
+
+/// Importing the function to prepare
+/// the text of a post for saving.
+use crate::modules::html::prepare_post_source;
+
+/// Importing the structure representing
+/// the model in the database for an extra
+/// content field for explicit typing.
+use crate::modules::models::ExtraContentField;
+
+/// This function attempts to save
+/// the current text, details, and extra
+/// content fields of a post as a new revision
+/// edited by the user with the given ID.
+/// The revision is written in the supplied
+/// transaction so that it sees and is saved
+/// together with the change it records.
+/// If the operation is successful, an instance
+/// of the "PostRevision" structure is returned.
+/// If this operation fails, an error is returned.
+pub async fn create_post_revision(
+    content_id: &String,
+    user_id: &String,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<PostRevision, CleoErr> {
+    let post: UserPost = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts WHERE content_id = $1",
+        content_id
+    )
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(post) => post,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let fields: Vec<ExtraContentField> = match query_as!(
+        ExtraContentField,
+        "SELECT * FROM extra_content_fields WHERE content_id = $1 ORDER BY created_at, field_key",
+        content_id
+    )
+        .fetch_all(&mut **tx)
+        .await
+    {
+        Ok(fields) => fields,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut snapshots: Vec<FieldSnapshot> = Vec::new();
+    for field in fields {
+        snapshots.push(
+            FieldSnapshot{
+                field_id: field.field_id,
+                field_key: field.field_key,
+                field_value: field.field_value,
+                field_type: Some(field.field_type)
+            }
+        );
+    }
+    let extra_fields: String = match to_string(&snapshots) {
+        Ok(extra_fields) => extra_fields,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let revision_id: String = match new_id() {
+        Ok(revision_id) => revision_id,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let revision_obj: PostRevision = match query_as!(
+        PostRevision,
+        "INSERT INTO post_revisions (revision_id, content_id, user_id, content_text, extra_fields, created_at, title, excerpt, cover_file_id, author_name) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING *",
+        revision_id,
+        content_id,
+        user_id,
+        post.content_text,
+        extra_fields,
+        Utc::now(),
+        post.title,
+        post.excerpt,
+        post.cover_file_id,
+        post.author_name
+    )
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(revision_obj) => revision_obj,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(revision_obj)
+}
+
+/// This function attempts to retrieve
+/// a post revision given the revision's
+/// ID. If this operation is successful,
+/// an instance of the "PostRevision"
+/// structure is returned. If this operation
+/// fails, an error is returned.
+pub async fn get_revision_by_id(
+    revision_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<PostRevision, CleoErr> {
+    let revision: PostRevision = match query_as!(
+        PostRevision,
+        "SELECT * FROM post_revisions WHERE revision_id = $1", 
+        revision_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(revision) => revision,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(revision)
+}
+
+/// This function attempts to retrieve
+/// all revisions of a post, oldest first.
+/// No ownership checks are performed.
+/// If this operation is successful, a vector
+/// of instances of the "PostRevision" structure
+/// is returned. If this operation fails, an error
+/// is returned.
+pub async fn get_revisions_for_post(
+    content_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<PostRevision>, CleoErr> {
+    let revisions: Vec<PostRevision> = match query_as!(
+        PostRevision,
+        "SELECT * FROM post_revisions WHERE content_id = $1 ORDER BY created_at ASC", 
+        content_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(revisions) => revisions,
+        Err(e) => return Err::<Vec<PostRevision>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(revisions)
+}
+
+/// This function attempts to retrieve
+/// all revisions of a post the supplied
+/// user has made.
+/// If this operation is successful, a vector
+/// of instances of the "PostRevision" structure
+/// is returned. If this operation fails, an error
+/// is returned.
+pub async fn get_user_post_revisions(
+    user: &CleoUser,
+    content_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<PostRevision>, CleoErr> {
+    let post: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post) => post,
+        Err(e) => return Err::<Vec<PostRevision>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if user.user_id == post.user_id {
+        get_revisions_for_post(content_id, pool).await
+    }
+    else {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        Err::<Vec<PostRevision>, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts to retrieve
+/// a single revision of a post the 
+/// supplied user has made.
+/// If this operation is successful, an instance
+/// of the "PostRevision" structure is returned.
+/// If this operation fails, an error is returned.
+pub async fn get_user_post_revision(
+    user: &CleoUser,
+    content_id: &String,
+    revision_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<PostRevision, CleoErr> {
+    let post: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post) => post,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let revision: PostRevision = match get_revision_by_id(revision_id, pool).await {
+        Ok(revision) => revision,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if user.user_id == post.user_id && revision.content_id == post.content_id {
+        Ok(revision)
+    }
+    else {
+        let e: String = "Could not verify ownership of the revision.".to_string();
+        Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts to restore
+/// the text, details, and extra content
+/// fields of a post from one of its revisions.
+/// The restored state is saved as a
+/// new revision. If this operation is
+/// successful, the new revision is returned
+/// as an instance of the "PostRevision"
+/// structure. If this operation fails,
+/// an error is returned.
+pub async fn restore_post_revision(
+    user: &CleoUser,
+    content_id: &String,
+    revision_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<PostRevision, CleoErr> {
+    let revision: PostRevision = match get_user_post_revision(
+        user, 
+        content_id, 
+        revision_id, 
+        pool
+    ).await {
+        Ok(revision) => revision,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let snapshots: Vec<FieldSnapshot> = match from_str(&revision.extra_fields) {
+        Ok(snapshots) => snapshots,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let post_obj: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let content_text: String = match prepare_post_source(&post_obj.source_format, &revision.content_text) {
+        Ok(content_text) => content_text,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let updated_post: UserPost = match query_as!(
+        UserPost,
+        "UPDATE user_posts SET content_text = $1, title = $2, excerpt = $3, cover_file_id = $4, author_name = $5, updated_at = $6 WHERE content_id = $7 RETURNING *",
+        content_text,
+        revision.title,
+        revision.excerpt,
+        revision.cover_file_id,
+        revision.author_name,
+        Utc::now(),
+        content_id
+    )
+        .fetch_one(&mut *tx)
+        .await 
+    {
+        Ok(updated_post) => updated_post,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "DELETE FROM extra_content_fields WHERE content_id = $1",
+        content_id
+    )
+        .execute(&mut *tx)
+        .await 
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    for snapshot in snapshots {
+        match query!(
+            "INSERT INTO extra_content_fields (field_id, content_id, field_key, field_value, created_at, updated_at, field_type) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            snapshot.field_id,
+            content_id,
+            snapshot.field_key,
+            snapshot.field_value,
+            Utc::now(),
+            Utc::now(),
+            snapshot.field_type.unwrap_or("string".to_string())
+        )
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    let restored: PostRevision = match create_post_revision(content_id, &user.user_id, &mut tx).await {
+        Ok(restored) => restored,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _published: () = match publish_change_event(
+        &updated_post.user_id, 
+        &"post.updated".to_string(), 
+        &post_event_data(&updated_post), 
+        &mut tx
+    ).await {
+        Ok(published) => published,
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<PostRevision, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(restored)
+}
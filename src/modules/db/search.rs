@@ -0,0 +1,212 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries that return a
+/// single value.
+use sqlx::query_scalar;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "HashMap"
+/// structure to order posts
+/// by their search rank.
+use std::collections::HashMap;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the structure
+/// holding a single match of
+/// a search.
+use crate::modules::units::SearchHit;
+
+/// Importing the "UserPost" structure
+/// to read and write information about
+/// posts written by a user.
+use crate::modules::models::UserPost;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure
+/// holding the filters for
+/// searching posts.
+use crate::modules::units::SearchFilters;
+
+/// The longest search query
+/// that is accepted.
+pub const MAX_SEARCH_QUERY_LENGTH: usize = 256;
+
+/// The fields the results of
+/// a search can be sorted by.
+pub const SEARCH_SORT_FIELDS: [&str; 3] = [
+    "rank", "created_at", "publish_at"
+];
+
+/// This function attempts to retrieve
+/// the posts with the given IDs. The posts
+/// are returned in no particular order. If
+/// this operation fails, an error is returned.
+pub async fn get_posts_by_ids(
+    content_ids: &Vec<String>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserPost>, CleoErr> {
+    let posts: Vec<UserPost> = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts WHERE content_id = ANY($1)", 
+        content_ids.as_slice()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(posts) => posts,
+        Err(e) => return Err::<Vec<UserPost>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(posts)
+}
+
+/// This function attempts to search
+/// the titles, texts and extra content
+/// field values of all published posts on
+/// the instance for the given query. The query
+/// accepts the syntax of web search engines,
+/// like quoted phrases and "-" for excluding
+/// words. If the operation is successful, a page
+/// of matches with their rank and a highlighted
+/// snippet, the matching posts in the same order
+/// and the total number of matches are returned.
+/// If the operation fails, an error is returned.
+pub async fn search_posts(
+    search_query: &str,
+    filters: &SearchFilters,
+    options: &ListOptions,
+    pool: &Pool<Postgres>
+) -> Result<(Vec<SearchHit>, Vec<UserPost>, i64), CleoErr> {
+    let trimmed: String = search_query.trim().to_string();
+    if trimmed.is_empty() {
+        let e: &str = "A search query is required.";
+        return Err::<(Vec<SearchHit>, Vec<UserPost>, i64), CleoErr>(CleoErr::new(e));
+    }
+    if trimmed.chars().count() > MAX_SEARCH_QUERY_LENGTH {
+        let e: String = format!(
+            "Search queries cannot be longer than {} characters.", 
+            MAX_SEARCH_QUERY_LENGTH
+        );
+        return Err::<(Vec<SearchHit>, Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let content_type: Option<String> = filters.content_type
+        .as_ref()
+        .map(|content_type| content_type.to_lowercase());
+    let hits: Vec<SearchHit> = match query_as!(
+        SearchHit,
+        "SELECT 
+            user_posts.content_id, 
+            ts_rank(post_search_index.search_vector, search_query) AS \"rank!\", 
+            btrim(ts_headline(
+                'english', 
+                CASE WHEN user_posts.source_format = 'html' 
+                THEN regexp_replace(user_posts.content_text, '<[^>]*>', ' ', 'g') 
+                ELSE replace(replace(replace(user_posts.content_text, '&', '&amp;'), '<', '&lt;'), '>', '&gt;') 
+                END, 
+                search_query, 
+                'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=30, MinWords=10'
+            )) AS \"snippet!\" 
+        FROM user_posts 
+        JOIN post_search_index ON post_search_index.content_id = user_posts.content_id 
+        JOIN cleo_users ON cleo_users.user_id = user_posts.user_id, 
+        websearch_to_tsquery('english', $1) AS search_query 
+        WHERE post_search_index.search_vector @@ search_query 
+        AND user_posts.post_status = 'published' 
+        AND ($2::TEXT IS NULL OR cleo_users.username = $2) 
+        AND ($3::TEXT IS NULL OR user_posts.content_type = $3) 
+        ORDER BY 
+        CASE WHEN $4 = 'rank' AND $5 = 'asc' THEN ts_rank(post_search_index.search_vector, search_query) END ASC, 
+        CASE WHEN $4 = 'rank' AND $5 = 'desc' THEN ts_rank(post_search_index.search_vector, search_query) END DESC, 
+        CASE WHEN $4 = 'created_at' AND $5 = 'asc' THEN user_posts.created_at END ASC, 
+        CASE WHEN $4 = 'created_at' AND $5 = 'desc' THEN user_posts.created_at END DESC, 
+        CASE WHEN $4 = 'publish_at' AND $5 = 'asc' THEN user_posts.publish_at END ASC, 
+        CASE WHEN $4 = 'publish_at' AND $5 = 'desc' THEN user_posts.publish_at END DESC, 
+        user_posts.content_id ASC 
+        LIMIT $6 OFFSET $7",
+        trimmed,
+        filters.author,
+        content_type,
+        options.sort_by,
+        options.sort_order,
+        options.per_page,
+        options.offset()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(hits) => hits,
+        Err(e) => return Err::<(Vec<SearchHit>, Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match query_scalar!(
+        "SELECT COUNT(*) AS \"total!\" 
+        FROM user_posts 
+        JOIN post_search_index ON post_search_index.content_id = user_posts.content_id 
+        JOIN cleo_users ON cleo_users.user_id = user_posts.user_id 
+        WHERE post_search_index.search_vector @@ websearch_to_tsquery('english', $1) 
+        AND user_posts.post_status = 'published' 
+        AND ($2::TEXT IS NULL OR cleo_users.username = $2) 
+        AND ($3::TEXT IS NULL OR user_posts.content_type = $3)",
+        trimmed,
+        filters.author,
+        content_type
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<(Vec<SearchHit>, Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let content_ids: Vec<String> = hits
+        .iter()
+        .map(|hit| hit.content_id.clone())
+        .collect();
+    let fetched: Vec<UserPost> = match get_posts_by_ids(&content_ids, pool).await {
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<(Vec<SearchHit>, Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut by_id: HashMap<String, UserPost> = fetched
+        .into_iter()
+        .map(|post| (post.content_id.clone(), post))
+        .collect();
+    let mut posts: Vec<UserPost> = Vec::new();
+    for content_id in content_ids {
+        match by_id.remove(&content_id) {
+            Some(post) => posts.push(post),
+            None => {
+                let e: String = format!("Could not retrieve the post \"{}\".", content_id);
+                return Err::<(Vec<SearchHit>, Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()));
+            }
+        };
+    }
+    Ok((hits, posts, total))
+}
@@ -0,0 +1,309 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query"
+/// macro to execute SQL
+/// queries that return 
+/// nothing.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the function to turn
+/// a string into a slug.
+use crate::modules::utils::slugify;
+
+/// Importing the "CleoUser" structure
+/// to read and write associated data
+/// about Cleo users.
+use crate::modules::models::CleoUser;
+
+/// Importing the "UserPost" structure
+/// to read and write information about
+/// posts written by a user.
+use crate::modules::models::UserPost;
+
+/// Importing the "SlugRedirect" structure
+/// to read and write information about
+/// previous slugs of posts.
+use crate::modules::models::SlugRedirect;
+
+/// Importing the function to retrieve
+/// a post given its ID.
+use crate::modules::db::posts::get_post_by_id;
+
+/// This function checks whether
+/// the supplied slug is a valid slug.
+/// If it is not, an error is returned.
+pub fn validate_slug(slug: &String) -> Result<(), CleoErr> {
+    if slug.is_empty() || &slugify(slug) != slug {
+        let e: String = format!("\"{}\" is not a valid slug.", slug);
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+    else {
+        Ok(())
+    }
+}
+
+/// This function attempts
+/// to check whether a user already
+/// has a post of the supplied content
+/// type with the supplied slug. If this 
+/// operation fails, an error is returned.
+pub async fn slug_is_taken(
+    user_id: &String,
+    content_type: &String,
+    slug: &String,
+    pool: &Pool<Postgres>
+) -> Result<bool, CleoErr> {
+    let posts: Vec<UserPost> = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts WHERE user_id = $1 AND content_type = $2 AND slug = $3", 
+        user_id,
+        content_type,
+        slug
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(posts) => posts,
+        Err(e) => return Err::<bool, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(!posts.is_empty())
+}
+
+/// This function attempts
+/// to generate a slug from the
+/// supplied source text that is
+/// not yet taken by another post
+/// of the same user and content type.
+/// A numbered suffix is added if the 
+/// slug is already taken. If no slug can
+/// be made from the source text, the
+/// fallback is used instead. If this
+/// operation fails, an error is returned.
+pub async fn generate_unique_slug(
+    user_id: &String,
+    content_type: &String,
+    source: &str,
+    fallback: &str,
+    pool: &Pool<Postgres>
+) -> Result<String, CleoErr> {
+    let mut base: String = slugify(source);
+    if base.is_empty() {
+        base = slugify(fallback);
+    }
+    let mut slug: String = base.clone();
+    let mut suffix: usize = 2;
+    loop {
+        let taken: bool = match slug_is_taken(user_id, content_type, &slug, pool).await {
+            Ok(taken) => taken,
+            Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        if !taken {
+            break;
+        }
+        slug = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+    Ok(slug)
+}
+
+/// This function attempts
+/// to retrieve a published post
+/// given the ID of its author, its
+/// content type, and its slug. If the
+/// operation fails or the post is
+/// not published, an error is returned.
+pub async fn get_public_post_by_slug(
+    user_id: &String,
+    content_type: &String,
+    slug: &String,
+    pool: &Pool<Postgres>
+) -> Result<UserPost, CleoErr> {
+    let post_obj: UserPost = match query_as!(
+        UserPost,
+        "SELECT * FROM user_posts WHERE user_id = $1 AND content_type = $2 AND slug = $3 AND post_status = $4", 
+        user_id,
+        content_type,
+        slug,
+        "published"
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<UserPost, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(post_obj)
+}
+
+/// This function attempts
+/// to retrieve the redirect for
+/// a slug a post was previously
+/// reachable under. If this operation
+/// is successful, an instance of the
+/// "SlugRedirect" structure is returned.
+/// If this operation fails, an error
+/// is returned.
+pub async fn get_slug_redirect(
+    user_id: &String,
+    content_type: &String,
+    slug: &String,
+    pool: &Pool<Postgres>
+) -> Result<SlugRedirect, CleoErr> {
+    let redirect: SlugRedirect = match query_as!(
+        SlugRedirect,
+        "SELECT * FROM slug_redirects WHERE user_id = $1 AND content_type = $2 AND old_slug = $3", 
+        user_id,
+        content_type,
+        slug
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(redirect) => redirect,
+        Err(e) => return Err::<SlugRedirect, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(redirect)
+}
+
+/// This function attempts
+/// to delete the redirect for a
+/// slug so that the slug can be used
+/// by a post again. If this operation 
+/// fails, an error is returned.
+pub async fn release_slug_redirect(
+    user_id: &String,
+    content_type: &String,
+    slug: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let del_op: () = match query!(
+        "DELETE FROM slug_redirects WHERE user_id = $1 AND content_type = $2 AND old_slug = $3", 
+        user_id,
+        content_type,
+        slug
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(del_op)
+}
+
+/// This function attempts
+/// to change the slug of a post.
+/// The old slug is kept as a redirect
+/// to the post. If the operation is
+/// successful, an empty function
+/// is returned. If this operation fails,
+/// an error is returned.
+pub async fn update_post_slug(
+    user_obj: &CleoUser,
+    content_id: &String,
+    slug: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _valid: () = match validate_slug(slug) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let post_obj: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if user_obj.user_id != post_obj.user_id {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    if &post_obj.slug == slug {
+        return Ok(());
+    }
+    let taken: bool = match slug_is_taken(&post_obj.user_id, &post_obj.content_type, slug, pool).await {
+        Ok(taken) => taken,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if taken {
+        let e: String = format!("The slug \"{}\" is already taken.", slug);
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "INSERT INTO slug_redirects (user_id, content_type, old_slug, content_id, created_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (user_id, content_type, old_slug) DO UPDATE SET content_id = $4, created_at = $5",
+        post_obj.user_id,
+        post_obj.content_type,
+        post_obj.slug,
+        content_id,
+        Utc::now()
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "DELETE FROM slug_redirects WHERE user_id = $1 AND content_type = $2 AND old_slug = $3",
+        post_obj.user_id,
+        post_obj.content_type,
+        slug
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "UPDATE user_posts SET slug = $1, updated_at = $2 WHERE content_id = $3",
+        slug,
+        Utc::now(),
+        content_id
+    )
+        .execute(&mut *tx)
+        .await 
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let commit_op: () = match tx.commit().await {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(commit_op)
+}
@@ -0,0 +1,692 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query"
+/// macro to execute SQL
+/// queries that return 
+/// nothing.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries that return 
+/// single values.
+use sqlx::query_scalar;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the function to turn
+/// a string into a slug.
+use crate::modules::utils::slugify;
+
+/// Importing the "CleoUser" structure
+/// to read and write associated data
+/// about Cleo users.
+use crate::modules::models::CleoUser;
+
+/// Importing the "UserPost" structure
+/// to read and write information about
+/// posts written by a user.
+use crate::modules::models::UserPost;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure holding
+/// a term together with the post it
+/// is assigned to.
+use crate::modules::units::AssignedTerm;
+
+/// Importing the "TaxonomyTerm" structure
+/// to read and write information about
+/// tags and categories.
+use crate::modules::models::TaxonomyTerm;
+
+/// Importing the function to check
+/// whether a slug is valid.
+use crate::modules::db::slugs::validate_slug;
+
+/// Importing the function to retrieve
+/// a post given its ID.
+use crate::modules::db::posts::get_post_by_id;
+
+/// Importing the function to check
+/// that the acting user is an 
+/// administrator.
+use crate::modules::db::content_types::verify_admin;
+
+/// The taxonomies posts can
+/// be grouped by. Only categories
+/// can be nested.
+pub const TAXONOMIES: [&str; 2] = ["tag", "category"];
+
+/// This function checks whether
+/// the supplied string is the name
+/// of a taxonomy. If it is not, an
+/// error is returned.
+pub fn validate_taxonomy(taxonomy: &String) -> Result<(), CleoErr> {
+    if TAXONOMIES.contains(&taxonomy.as_str()) {
+        Ok(())
+    }
+    else {
+        let e: String = format!("\"{}\" is not a valid taxonomy.", taxonomy);
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function works out the slug
+/// for a term. A supplied slug is validated
+/// and used as-is, otherwise one is generated
+/// from the name of the term. If no valid slug
+/// can be found, an error is returned.
+pub fn resolve_term_slug(
+    term_name: &String,
+    slug: &Option<String>
+) -> Result<String, CleoErr> {
+    match slug {
+        Some(slug) => {
+            let _valid: () = match validate_slug(slug) {
+                Ok(valid) => valid,
+                Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            Ok(slug.to_owned())
+        },
+        None => {
+            let generated: String = slugify(term_name);
+            if generated.is_empty() {
+                let e: String = format!("Could not generate a slug for \"{}\".", term_name);
+                Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+            }
+            else {
+                Ok(generated)
+            }
+        }
+    }
+}
+
+/// This function checks whether
+/// the category with the given ID can
+/// become the parent of a term. The parent
+/// must be a category and the term must not
+/// be the parent itself or one of its ancestors.
+/// If the parent is not valid, an error is returned.
+pub async fn validate_term_parent(
+    term_id: &Option<String>,
+    taxonomy: &String,
+    parent_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    if taxonomy != "category" {
+        let e: &str = "Only categories can have a parent.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let parent: TaxonomyTerm = match get_term_by_id(parent_id, pool).await {
+        Ok(parent) => parent,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if parent.taxonomy != "category" {
+        let e: &str = "The parent of a category must be a category.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    if let Some(term_id) = term_id {
+        let ancestors: Vec<String> = match query_scalar!(
+            "WITH RECURSIVE ancestors AS (
+                SELECT term_id, parent_id FROM taxonomy_terms WHERE term_id = $1 
+                UNION ALL 
+                SELECT taxonomy_terms.term_id, taxonomy_terms.parent_id FROM taxonomy_terms 
+                JOIN ancestors ON taxonomy_terms.term_id = ancestors.parent_id
+            ) 
+            SELECT term_id AS \"term_id!\" FROM ancestors",
+            parent_id
+        )
+            .fetch_all(pool)
+            .await
+        {
+            Ok(ancestors) => ancestors,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        if ancestors.contains(term_id) {
+            let e: &str = "A category cannot be moved into itself or one of its subcategories.";
+            return Err::<(), CleoErr>(CleoErr::new(e));
+        }
+    }
+    Ok(())
+}
+
+/// This function attempts
+/// to create a new tag or category.
+/// Only administrators can do this.
+/// If no slug is supplied, one is
+/// generated from the name. If the 
+/// operation is successful, an instance
+/// of the "TaxonomyTerm" structure is 
+/// returned. If this operation fails, 
+/// an error is returned.
+pub async fn create_term(
+    user: &CleoUser,
+    taxonomy: &String,
+    term_name: &str,
+    slug: &Option<String>,
+    description: &String,
+    parent_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<TaxonomyTerm, CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_taxonomy(taxonomy) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let term_name: String = term_name.trim().to_string();
+    if term_name.is_empty() {
+        let e: &str = "The name of a term cannot be empty.";
+        return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(e));
+    }
+    let term_slug: String = match resolve_term_slug(&term_name, slug) {
+        Ok(term_slug) => term_slug,
+        Err(e) => return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if let Some(parent_id) = parent_id {
+        let _valid: () = match validate_term_parent(&None, taxonomy, parent_id, pool).await {
+            Ok(valid) => valid,
+            Err(e) => return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    let term_id: String = match new_id() {
+        Ok(term_id) => term_id,
+        Err(e) => return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    match query!(
+        "INSERT INTO taxonomy_terms (term_id, taxonomy, term_name, term_slug, parent_id, description, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        term_id,
+        taxonomy,
+        term_name,
+        term_slug,
+        parent_id.to_owned(),
+        description,
+        Utc::now(),
+        Utc::now()
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(_e) => {
+            let e: String = format!("The {} \"{}\" already exists.", taxonomy, term_slug);
+            return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    let term: TaxonomyTerm = match get_term_by_id(&term_id, pool).await {
+        Ok(term) => term,
+        Err(e) => return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(term)
+}
+
+/// This function attempts
+/// to retrieve a tag or category
+/// given its ID. If the operation
+/// is successful, an instance of the
+/// "TaxonomyTerm" structure is returned.
+/// If this operation fails, an error is
+/// returned.
+pub async fn get_term_by_id(
+    term_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<TaxonomyTerm, CleoErr> {
+    let term: TaxonomyTerm = match query_as!(
+        TaxonomyTerm,
+        "SELECT * FROM taxonomy_terms WHERE term_id = $1", 
+        term_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(term) => term,
+        Err(_e) => {
+            let e: String = format!("The term \"{}\" does not exist.", term_id);
+            return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(term)
+}
+
+/// This function attempts
+/// to retrieve a tag or category
+/// given its taxonomy and slug. If 
+/// the operation is successful, an 
+/// instance of the "TaxonomyTerm" 
+/// structure is returned. If this 
+/// operation fails, an error is
+/// returned.
+pub async fn get_term_by_slug(
+    taxonomy: &String,
+    term_slug: &String,
+    pool: &Pool<Postgres>
+) -> Result<TaxonomyTerm, CleoErr> {
+    let term: TaxonomyTerm = match query_as!(
+        TaxonomyTerm,
+        "SELECT * FROM taxonomy_terms WHERE taxonomy = $1 AND term_slug = $2", 
+        taxonomy,
+        term_slug
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(term) => term,
+        Err(_e) => {
+            let e: String = format!("The {} \"{}\" does not exist.", taxonomy, term_slug);
+            return Err::<TaxonomyTerm, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(term)
+}
+
+/// This function attempts
+/// to retrieve all tags or all 
+/// categories of the instance. If 
+/// the operation is successful, a
+/// vector of instances of the 
+/// "TaxonomyTerm" structure is returned.
+/// If this operation fails, an error is
+/// returned.
+pub async fn get_terms(
+    taxonomy: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<TaxonomyTerm>, CleoErr> {
+    let _valid: () = match validate_taxonomy(taxonomy) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<Vec<TaxonomyTerm>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let terms: Vec<TaxonomyTerm> = match query_as!(
+        TaxonomyTerm,
+        "SELECT * FROM taxonomy_terms WHERE taxonomy = $1 ORDER BY term_name, term_id", 
+        taxonomy
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(terms) => terms,
+        Err(e) => return Err::<Vec<TaxonomyTerm>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(terms)
+}
+
+/// This function attempts
+/// to change the name, slug, or
+/// description of a tag or category.
+/// Only the values that are supplied
+/// are changed. Only administrators
+/// can do this. If the operation is
+/// successful, an empty function is
+/// returned. If this operation fails,
+/// an error is returned.
+pub async fn edit_term(
+    user: &CleoUser,
+    term_id: &String,
+    term_name: &Option<String>,
+    slug: &Option<String>,
+    description: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let term: TaxonomyTerm = match get_term_by_id(term_id, pool).await {
+        Ok(term) => term,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let new_name: String = match term_name {
+        Some(term_name) => term_name.trim().to_string(),
+        None => term.term_name
+    };
+    if new_name.is_empty() {
+        let e: &str = "The name of a term cannot be empty.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let new_slug: String = match slug {
+        Some(_slug) => match resolve_term_slug(&new_name, slug) {
+            Ok(new_slug) => new_slug,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        },
+        None => term.term_slug
+    };
+    let new_description: String = match description {
+        Some(description) => description.to_owned(),
+        None => term.description
+    };
+    let update_op: () = match query!(
+        "UPDATE taxonomy_terms SET term_name = $1, term_slug = $2, description = $3, updated_at = $4 WHERE term_id = $5",
+        new_name,
+        new_slug,
+        new_description,
+        Utc::now(),
+        term_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(_e) => {
+            let e: String = format!("The {} \"{}\" already exists.", term.taxonomy, new_slug);
+            return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(update_op)
+}
+
+/// This function attempts
+/// to move a category below another
+/// category or to the top level if
+/// no parent is supplied. A category
+/// cannot be moved below itself or one
+/// of its subcategories. Only administrators
+/// can do this. If the operation is 
+/// successful, an empty function is
+/// returned. If this operation fails,
+/// an error is returned.
+pub async fn move_term(
+    user: &CleoUser,
+    term_id: &String,
+    parent_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let term: TaxonomyTerm = match get_term_by_id(term_id, pool).await {
+        Ok(term) => term,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if let Some(parent_id) = parent_id {
+        let _valid: () = match validate_term_parent(
+            &Some(term.term_id.clone()), 
+            &term.taxonomy, 
+            parent_id, 
+            pool
+        ).await {
+            Ok(valid) => valid,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    let update_op: () = match query!(
+        "UPDATE taxonomy_terms SET parent_id = $1, updated_at = $2 WHERE term_id = $3",
+        parent_id.to_owned(),
+        Utc::now(),
+        term_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(update_op)
+}
+
+/// This function attempts
+/// to delete a tag or category.
+/// The term is removed from all posts.
+/// Categories that still have subcategories
+/// cannot be deleted. Only administrators 
+/// can do this. If the operation is 
+/// successful, an empty function is
+/// returned. If this operation fails,
+/// an error is returned.
+pub async fn delete_term(
+    user: &CleoUser,
+    term_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let _admin: () = match verify_admin(user) {
+        Ok(admin) => admin,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let term: TaxonomyTerm = match get_term_by_id(term_id, pool).await {
+        Ok(term) => term,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let children: i64 = match query_scalar!(
+        "SELECT COUNT(*) AS \"children!\" FROM taxonomy_terms WHERE parent_id = $1",
+        term.term_id
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(children) => children,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if children > 0 {
+        let e: String = format!("The {} \"{}\" still has subcategories.", term.taxonomy, term.term_slug);
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let del_op: () = match query!(
+        "DELETE FROM taxonomy_terms WHERE term_id = $1",
+        term.term_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(del_op)
+}
+
+/// This function attempts
+/// to assign a tag or category to
+/// a post. Only the owner of the post
+/// can do this. Assigning a term a post
+/// already has does nothing. If the 
+/// operation is successful, an empty 
+/// function is returned. If this operation
+/// fails, an error is returned.
+pub async fn assign_term(
+    user_obj: &CleoUser,
+    content_id: &String,
+    term_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let post_obj: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if user_obj.user_id != post_obj.user_id {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let term: TaxonomyTerm = match get_term_by_id(term_id, pool).await {
+        Ok(term) => term,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let insert_op: () = match query!(
+        "INSERT INTO post_terms (content_id, term_id, created_at) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        post_obj.content_id,
+        term.term_id,
+        Utc::now()
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(insert_op)
+}
+
+/// This function attempts
+/// to remove a tag or category from
+/// a post. Only the owner of the post
+/// can do this. If the operation is 
+/// successful, an empty function is 
+/// returned. If this operation fails, 
+/// an error is returned.
+pub async fn unassign_term(
+    user_obj: &CleoUser,
+    content_id: &String,
+    term_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let post_obj: UserPost = match get_post_by_id(content_id, pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if user_obj.user_id != post_obj.user_id {
+        let e: String = "Could not verify ownership of the post.".to_string();
+        return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let del_op: () = match query!(
+        "DELETE FROM post_terms WHERE content_id = $1 AND term_id = $2",
+        post_obj.content_id,
+        term_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(del_op)
+}
+
+/// This function attempts
+/// to retrieve the tags and categories
+/// assigned to several posts at once. If 
+/// the operation is successful, a vector of
+/// instances of the "AssignedTerm" structure 
+/// is returned. If this operation fails, an
+/// error is returned.
+pub async fn get_terms_for_posts(
+    content_ids: &Vec<String>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<AssignedTerm>, CleoErr> {
+    let terms: Vec<AssignedTerm> = match query_as!(
+        AssignedTerm,
+        "SELECT post_terms.content_id, taxonomy_terms.term_id, taxonomy_terms.taxonomy, 
+        taxonomy_terms.term_name, taxonomy_terms.term_slug, taxonomy_terms.parent_id 
+        FROM post_terms 
+        JOIN taxonomy_terms ON taxonomy_terms.term_id = post_terms.term_id 
+        WHERE post_terms.content_id = ANY($1) 
+        ORDER BY taxonomy_terms.term_name, taxonomy_terms.term_id",
+        content_ids.as_slice()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(terms) => terms,
+        Err(e) => return Err::<Vec<AssignedTerm>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(terms)
+}
+
+/// This function attempts to fetch
+/// a page of the published posts that
+/// have a tag or category. Posts in the
+/// subcategories of a category are included.
+/// No credentials are required. If the operation
+/// is successful, a vector of instances of the
+/// "UserPost" structure and the total number of
+/// matching posts are returned. If the operation
+/// fails, an error is returned.
+pub async fn get_public_posts_by_term(
+    term_id: &String,
+    options: &ListOptions,
+    pool: &Pool<Postgres>
+) -> Result<(Vec<UserPost>, i64), CleoErr> {
+    let posts: Vec<UserPost> = match query_as!(
+        UserPost,
+        "WITH RECURSIVE matched_terms AS (
+            SELECT term_id FROM taxonomy_terms WHERE term_id = $1 
+            UNION ALL 
+            SELECT taxonomy_terms.term_id FROM taxonomy_terms 
+            JOIN matched_terms ON taxonomy_terms.parent_id = matched_terms.term_id
+        ) 
+        SELECT * FROM user_posts WHERE post_status = 'published' 
+        AND content_id IN (
+            SELECT content_id FROM post_terms 
+            WHERE term_id IN (SELECT term_id FROM matched_terms)
+        ) 
+        ORDER BY 
+        CASE WHEN $2 = 'created_at' AND $3 = 'asc' THEN created_at END ASC, 
+        CASE WHEN $2 = 'created_at' AND $3 = 'desc' THEN created_at END DESC, 
+        CASE WHEN $2 = 'updated_at' AND $3 = 'asc' THEN updated_at END ASC, 
+        CASE WHEN $2 = 'updated_at' AND $3 = 'desc' THEN updated_at END DESC, 
+        CASE WHEN $2 = 'publish_at' AND $3 = 'asc' THEN publish_at END ASC, 
+        CASE WHEN $2 = 'publish_at' AND $3 = 'desc' THEN publish_at END DESC, 
+        CASE WHEN $2 = 'title' AND $3 = 'asc' THEN title END ASC, 
+        CASE WHEN $2 = 'title' AND $3 = 'desc' THEN title END DESC, 
+        content_id ASC 
+        LIMIT $4 OFFSET $5",
+        term_id,
+        options.sort_by,
+        options.sort_order,
+        options.per_page,
+        options.offset()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(posts) => posts,
+        Err(e) => return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match query_scalar!(
+        "WITH RECURSIVE matched_terms AS (
+            SELECT term_id FROM taxonomy_terms WHERE term_id = $1 
+            UNION ALL 
+            SELECT taxonomy_terms.term_id FROM taxonomy_terms 
+            JOIN matched_terms ON taxonomy_terms.parent_id = matched_terms.term_id
+        ) 
+        SELECT COUNT(*) AS \"total!\" FROM user_posts WHERE post_status = 'published' 
+        AND content_id IN (
+            SELECT content_id FROM post_terms 
+            WHERE term_id IN (SELECT term_id FROM matched_terms)
+        )",
+        term_id
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<(Vec<UserPost>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok((posts, total))
+}
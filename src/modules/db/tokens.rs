@@ -16,6 +16,11 @@ use sqlx::Pool;
 /// nothing.
 use sqlx::query;
 
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
 /// Importing the
 /// function to verify
 /// a hashed string.
@@ -27,20 +32,29 @@ use bcrypt::verify;
 /// something.
 use sqlx::query_as;
 
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing.
+use chrono::DateTime;
+
 /// Importing the "Postgres"
 /// structure to specify which
 /// database one is connecting
 /// to.
 use sqlx::postgres::Postgres;
 
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr"
 /// structure to catch and handle
 /// errors.
 use crate::modules::err::CleoErr;
 
-/// Importing the "TimeNow" structure
-/// to get the current time.
-use crate::modules::utils::TimeNow;
+/// Importing the function to
+/// generate random secrets.
+use crate::modules::ids::new_secret;
 
 /// Importing the "CleoUser" structure
 /// to read and write information about
@@ -57,79 +71,234 @@ use crate::modules::utils::hash_string;
 /// entities.
 use crate::modules::models::UserAPIToken;
 
-/// Importing the "get_user_from_token"
+/// Importing the "get_user_by_id"
 /// function to retrieve a Cleo
 /// user using their ID.
 use crate::modules::db::users::get_user_by_id;
 
-/// Importing the "get_user_from_token"
+/// Importing the "get_user_by_username"
 /// function to retrieve a Cleo
 /// user using their username.
 use crate::modules::db::users::get_user_by_username;
 
-/// This function attempts to create
-/// an API token for a user. If this operation
-/// is successful, an instance of the "UserAPIToken"
-/// structure is returned. If this operation fails,
+/// The scope for reading private
+/// content like drafts, revisions,
+/// menus, and webhooks.
+pub const SCOPE_CONTENT_READ: &str = "content:read";
+
+/// The scope for writing content
+/// like posts, fields, pages, menus,
+/// and webhooks.
+pub const SCOPE_CONTENT_WRITE: &str = "content:write";
+
+/// The scope for uploading, listing,
+/// and deleting files.
+pub const SCOPE_FILES: &str = "files";
+
+/// The scope for changing the
+/// details of one's own account.
+pub const SCOPE_ACCOUNT: &str = "account";
+
+/// The scope for administering
+/// the instance. Only administrators
+/// can create tokens with it.
+pub const SCOPE_ADMIN: &str = "admin";
+
+/// All scopes an API token
+/// can carry.
+pub const TOKEN_SCOPES: [&str; 5] = [
+    SCOPE_CONTENT_READ,
+    SCOPE_CONTENT_WRITE,
+    SCOPE_FILES,
+    SCOPE_ACCOUNT,
+    SCOPE_ADMIN
+];
+
+/// The number of characters
+/// of a new API token.
+pub const TOKEN_LENGTH: usize = 48;
+
+/// The maximum number of
+/// characters of the label
+/// of an API token.
+pub const MAX_TOKEN_LABEL_LENGTH: usize = 100;
+
+/// The number of seconds the
+/// time an API token was last used
+/// at may lag behind.
+pub const TOKEN_USAGE_INTERVAL: i64 = 60;
+
+/// This function checks that all
+/// supplied scopes are known and that
+/// only administrators ask for the 
+/// "admin" scope. If no scopes are 
+/// supplied, all scopes the user may
+/// hold are returned. If a scope is
+/// not valid, an error is returned.
+pub fn validate_token_scopes(
+    scopes: &Option<Vec<String>>,
+    is_admin: bool
+) -> Result<Vec<String>, CleoErr> {
+    let scopes: Vec<String> = match scopes {
+        Some(scopes) => scopes.clone(),
+        None => return Ok(
+            TOKEN_SCOPES
+                .iter()
+                .filter(|scope| is_admin || **scope != SCOPE_ADMIN)
+                .map(|scope| scope.to_string())
+                .collect()
+        )
+    };
+    if scopes.is_empty(){
+        let e: &str = "An API token needs at least one scope.";
+        return Err::<Vec<String>, CleoErr>(CleoErr::new(e));
+    }
+    for scope in &scopes {
+        if !TOKEN_SCOPES.contains(&scope.as_str()){
+            let e: String = format!(
+                "\"{}\" is not a valid scope. Valid options are: {}.",
+                scope,
+                TOKEN_SCOPES.join(", ")
+            );
+            return Err::<Vec<String>, CleoErr>(CleoErr::new(&e));
+        }
+        if scope == SCOPE_ADMIN && !is_admin {
+            let e: &str = "Only administrators can create tokens with the \"admin\" scope.";
+            return Err::<Vec<String>, CleoErr>(CleoErr::new(e));
+        }
+    }
+    let mut unique: Vec<String> = Vec::new();
+    for scope in scopes {
+        if !unique.contains(&scope){
+            unique.push(scope);
+        }
+    }
+    Ok(unique)
+}
+
+/// This function checks the label
+/// of an API token. Empty labels are
+/// dropped. If the label is too long,
 /// an error is returned.
+pub fn validate_token_label(
+    label: &Option<String>
+) -> Result<Option<String>, CleoErr> {
+    match label {
+        Some(label) => {
+            let label: String = label.trim().to_string();
+            if label.is_empty(){
+                Ok(None)
+            }
+            else if label.chars().count() > MAX_TOKEN_LABEL_LENGTH {
+                let e: String = format!("The label of a token may not be longer than {} characters.", MAX_TOKEN_LABEL_LENGTH);
+                Err::<Option<String>, CleoErr>(CleoErr::new(&e))
+            }
+            else {
+                Ok(Some(label))
+            }
+        },
+        None => Ok(None)
+    }
+}
+
+/// This function attempts to create
+/// an API token for a user. Only a hash
+/// of the token is stored, so the token
+/// itself is returned alongside the 
+/// instance of the "UserAPIToken" structure
+/// and cannot be retrieved again. If no 
+/// scopes are supplied, the token carries
+/// all scopes the user may hold. If this
+/// operation fails, an error is returned.
 pub async fn create_api_token_for_user(
     username: &String,
     password: &String,
+    label: &Option<String>,
+    scopes: &Option<Vec<String>>,
+    expires_at: &Option<DateTime<Utc>>,
     pool: &Pool<Postgres>
-) -> Result<UserAPIToken, CleoErr> {
+) -> Result<(UserAPIToken, String), CleoErr> {
     let user_obj: CleoUser = match get_user_by_username(username, pool).await {
         Ok(user_obj) => user_obj,
-        Err(e) => return Err::<UserAPIToken, CleoErr>(CleoErr::new(&e.to_string()))
+        Err(e) => return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
     };
     let verified: bool = match verify(password, &user_obj.pwd){
         Ok(verified) => verified,
-        Err(e) => return Err::<UserAPIToken, CleoErr>(CleoErr::new(&e.to_string()))
+        Err(e) => return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
     };
     if verified{
-        let token_id: String = hash_string(&format!("{}{}", user_obj.username, TimeNow::new().to_string()));
-        let token: String = hash_string(&format!("{}{}", user_obj.user_id, TimeNow::new().to_string()));
+        let label: Option<String> = match validate_token_label(label){
+            Ok(label) => label,
+            Err(e) => return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let scopes: Vec<String> = match validate_token_scopes(scopes, user_obj.is_admin){
+            Ok(scopes) => scopes,
+            Err(e) => return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        if let Some(expires_at) = expires_at {
+            if *expires_at <= Utc::now(){
+                let e: &str = "The expiry of a token must lie in the future.";
+                return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(e));
+            }
+        }
+        let token: String = match new_secret(&TOKEN_LENGTH){
+            Ok(token) => token,
+            Err(e) => return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let token_id: String = match new_id() {
+            Ok(token_id) => token_id,
+            Err(e) => return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
+        };
         let uat_obj: UserAPIToken = UserAPIToken{
             user_id: user_obj.user_id.clone(),
             token_id: token_id,
-            token: token.clone()
+            token_hash: hash_string(&token),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            label,
+            scopes,
+            expires_at: *expires_at,
+            last_used_at: None
         };
         let _insert_op = match query!(
-            "INSERT INTO user_api_tokens (user_id, token_id, token) VALUES ($1, $2, $3)",
+            "INSERT INTO user_api_tokens (user_id, token_id, token_hash, created_at, updated_at, label, scopes, expires_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
             uat_obj.user_id,
             uat_obj.token_id,
-            uat_obj.token
+            uat_obj.token_hash,
+            uat_obj.created_at,
+            uat_obj.updated_at,
+            uat_obj.label,
+            &uat_obj.scopes,
+            uat_obj.expires_at
         )
             .execute(pool)
             .await
         {
             Ok(_feedback) => {},
-            Err(e) => return Err::<UserAPIToken, CleoErr>(CleoErr::new(&e.to_string()))
-        };
-        let token_obj: UserAPIToken = match get_token(&token, pool).await {
-            Ok(token_obj) => token_obj,
-            Err(e) => return Err::<UserAPIToken, CleoErr>(CleoErr::new(&e.to_string()))
+            Err(e) => return Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
         };
-        Ok(token_obj)
+        Ok((uat_obj, token))
     }
     else {
         let e: String = format!("Could not verify password for user with the username \"{}\"", username);
-        Err::<UserAPIToken, CleoErr>(CleoErr::new(&e.to_string()))
+        Err::<(UserAPIToken, String), CleoErr>(CleoErr::new(&e.to_string()))
     }
 }
 
 /// This function attempts to
 /// retrieve an instance of the
 /// "UserAPIToken" structure given
-/// the token. If this operation fails,
-/// an error is returned.
+/// the token. Tokens are looked up
+/// by their hash. If this operation 
+/// fails, an error is returned.
 pub async fn get_token(
     token: &String,  
     pool: &Pool<Postgres>
 ) -> Result<UserAPIToken, CleoErr>{
     let uat_obj: UserAPIToken = match query_as!(
         UserAPIToken,
-        "SELECT * FROM user_api_tokens WHERE token = $1", 
-        token
+        "SELECT * FROM user_api_tokens WHERE token_hash = $1", 
+        hash_string(token)
     )
         .fetch_one(pool)
         .await 
@@ -184,21 +353,142 @@ pub async fn delete_token(
 }
 
 /// This function attempts
-/// to retrieve an instance of
-/// the "CleoUser" structure given
-/// an API token. If this operation
-/// fails, an error is returned.
-pub async fn get_user_from_token(
+/// to retrieve the user an API
+/// token belongs to together with
+/// the token. Expired tokens are
+/// rejected and the time the token
+/// was last used at is recorded. If 
+/// this operation fails, an error is
+/// returned.
+pub async fn authenticate_token(
     token: &String,
     pool: &Pool<Postgres>
-) -> Result<CleoUser, CleoErr>{
+) -> Result<(CleoUser, UserAPIToken), CleoErr>{
     let token_obj: UserAPIToken = match get_token(token, pool).await {
         Ok(token_obj) => token_obj,
-        Err(e) => return Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
+        Err(_e) => {
+            let e: &str = "The supplied API token is not valid.";
+            return Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(e));
+        }
+    };
+    if let Some(expires_at) = token_obj.expires_at {
+        if expires_at <= Utc::now(){
+            let e: &str = "The supplied API token has expired.";
+            return Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(e));
+        }
+    }
+    match query!(
+        "UPDATE user_api_tokens SET last_used_at = NOW() WHERE token_id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - make_interval(secs => $2))",
+        token_obj.token_id,
+        TOKEN_USAGE_INTERVAL as f64
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(&e.to_string()))
     };
     let user_obj: CleoUser = match get_user_by_id(&token_obj.user_id, pool).await {
         Ok(user_obj) => user_obj,
-        Err(e) => return Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
+        Err(e) => return Err::<(CleoUser, UserAPIToken), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok((user_obj, token_obj))
+}
+
+/// This function attempts
+/// to retrieve an instance of
+/// the "CleoUser" structure given
+/// an API token. Expired tokens are
+/// rejected. If this operation fails,
+/// an error is returned.
+pub async fn get_user_from_token(
+    token: &String,
+    pool: &Pool<Postgres>
+) -> Result<CleoUser, CleoErr>{
+    match authenticate_token(token, pool).await {
+        Ok((user_obj, _token_obj)) => Ok(user_obj),
+        Err(e) => Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts to
+/// retrieve all API tokens of a 
+/// user, newest first. If this operation
+/// is successful, a vector of instances
+/// of the "UserAPIToken" structure is
+/// returned. If this operation fails,
+/// an error is returned.
+pub async fn get_user_tokens(
+    user_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserAPIToken>, CleoErr> {
+    let tokens: Vec<UserAPIToken> = match query_as!(
+        UserAPIToken,
+        "SELECT * FROM user_api_tokens WHERE user_id = $1 ORDER BY created_at DESC, token_id",
+        user_id
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(tokens) => tokens,
+        Err(e) => return Err::<Vec<UserAPIToken>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(tokens)
+}
+
+/// This function attempts to
+/// revoke an API token of a user
+/// given the ID of the token. If 
+/// this operation is successful, an
+/// empty function is returned. If the
+/// token does not belong to the user
+/// or the operation fails, an error
+/// is returned.
+pub async fn revoke_token(
+    user: &CleoUser,
+    token_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let revoked: u64 = match query!(
+        "DELETE FROM user_api_tokens WHERE token_id = $1 AND user_id = $2",
+        token_id,
+        user.user_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(feedback) => feedback.rows_affected(),
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if revoked == 0 {
+        let e: &str = "Could not verify ownership of the API token.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    Ok(())
+}
+
+/// This function attempts to
+/// revoke all API tokens of a user
+/// except the supplied one. If this
+/// operation is successful, the number
+/// of revoked tokens is returned. If 
+/// this operation fails, an error is 
+/// returned.
+pub async fn revoke_other_tokens(
+    user: &CleoUser,
+    kept_token_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<u64, CleoErr> {
+    let revoked: u64 = match query!(
+        "DELETE FROM user_api_tokens WHERE user_id = $1 AND token_id <> $2",
+        user.user_id,
+        kept_token_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(feedback) => feedback.rows_affected(),
+        Err(e) => return Err::<u64, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    Ok(user_obj)
+    Ok(revoked)
 }
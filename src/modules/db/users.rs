@@ -16,6 +16,11 @@ use sqlx::Pool;
 /// nothing.
 use sqlx::query;
 
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
 /// Importing the function
 /// to hash a string using
 /// the "BCrypt" algorithm.
@@ -42,16 +47,15 @@ use bcrypt::DEFAULT_COST;
 /// typing.
 use sqlx::postgres::Postgres;
 
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
 /// Importing the "CleoErr"
 /// structure to catch and
 /// handle errors.
 use crate::modules::err::CleoErr;
 
-/// Importing the "TimeNow"
-/// structure to get the current
-/// time.
-use crate::modules::utils::TimeNow;
-
 /// Importing the structure that
 /// models user keys in the 
 /// database.
@@ -61,21 +65,6 @@ use crate::modules::models::UserKey;
 /// for explicit typing.
 use crate::modules::models::CleoUser;
 
-/// Importing the function to generate
-/// a general hash of a string.
-use crate::modules::utils::hash_string;
-
-/// Importing the structure for the model
-/// for a user's API tokens to read and write
-/// from and to the database about these
-/// entities.
-use crate::modules::models::UserAPIToken;
-
-/// Importing the function to get an entry
-/// in the database for an API token given
-/// the token itself.
-use crate::modules::db::tokens::get_token;
-
 /// Importing the function to check whether
 /// a user key exists. This is relevant for
 /// signing users up.
@@ -101,16 +90,14 @@ pub async fn create_user(
     user_key: &String,
     pool: &Pool<Postgres>
 ) -> Result<CleoUser, CleoErr>{
-    let hashed_source: String = format!(
-        "{}{}", 
-        TimeNow::new().to_string(),
-        username
-    );
+    let user_id: String = match new_id() {
+        Ok(user_id) => user_id,
+        Err(e) => return Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let hashed_pwd: String = match hash(password, DEFAULT_COST){
         Ok(hashed_pwd) => hashed_pwd,
         Err(e) => return Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let user_id: String = hash_string(&hashed_source);
     let key_length: usize = user_key.chars().collect::<Vec<char>>().len();
     let is_admin: bool;
     let key_valid: bool = user_key_exists(user_key, pool).await;
@@ -137,10 +124,12 @@ pub async fn create_user(
         pwd: hashed_pwd,  
         email_addr: email_addr.to_owned(),
         pfp_url: pfp_url.to_owned(), 
-        is_admin: is_admin.to_owned()
+        is_admin: is_admin.to_owned(),
+        created_at: Utc::now(),
+        updated_at: Utc::now()
     };
     let _insert_op = match query!(
-        "INSERT INTO cleo_users (user_id, display_name, is_verified, username, pwd, email_addr, pfp_url, is_admin) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        "INSERT INTO cleo_users (user_id, display_name, is_verified, username, pwd, email_addr, pfp_url, is_admin, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
         user_obj.user_id,
         user_obj.display_name,
         user_obj.is_verified,
@@ -149,6 +138,8 @@ pub async fn create_user(
         user_obj.email_addr,
         user_obj.pfp_url,
         user_obj.is_admin,
+        user_obj.created_at,
+        user_obj.updated_at
     )
         .execute(pool)
         .await
@@ -193,6 +184,28 @@ pub async fn get_user_by_username(
     Ok(user_obj)
 }
 
+/// This function attempts to retrieve
+/// the users with the given IDs. The users
+/// are returned in no particular order. If
+/// this operation fails, an error is returned.
+pub async fn get_users_by_ids(
+    user_ids: &Vec<String>, 
+    pool: &Pool<Postgres>
+) -> Result<Vec<CleoUser>, CleoErr> {
+    let users: Vec<CleoUser> = match query_as!(
+        CleoUser,
+        "SELECT * FROM cleo_users WHERE user_id = ANY($1)", 
+        user_ids.as_slice()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(users) => users,
+        Err(e) => return Err::<Vec<CleoUser>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(users)
+}
+
 pub async fn set_verified(
     user_id: &str,
     pool: &Pool<Postgres>
@@ -202,8 +215,9 @@ pub async fn set_verified(
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
     let update_op: () = match query!(
-        "UPDATE cleo_users SET is_verified = $1 WHERE user_id = $2",
-        true, 
+        "UPDATE cleo_users SET is_verified = $1, updated_at = $2 WHERE user_id = $3",
+        true,
+        Utc::now(),
         user.user_id
     )
         .execute(pool)
@@ -222,17 +236,14 @@ pub async fn set_verified(
 /// If this operation fails, an error
 /// is returned.
 pub async fn update_username(
-    api_token: &String,
+    user: &CleoUser,
     new_username: &String,
     pool: &Pool<Postgres>
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let update_op: () = match query!(
-        "UPDATE cleo_users SET username = $1 WHERE user_id = $2",
-        new_username, 
+        "UPDATE cleo_users SET username = $1, updated_at = $2 WHERE user_id = $3",
+        new_username,
+        Utc::now(),
         user.user_id
     )
         .execute(pool)
@@ -251,17 +262,14 @@ pub async fn update_username(
 /// If this operation fails, an error
 /// is returned.
 pub async fn update_email(
-    api_token: &String,
+    user: &CleoUser,
     new_email: &String,
     pool: &Pool<Postgres>
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let update_op: () = match query!(
-        "UPDATE cleo_users SET email_addr = $1 WHERE user_id = $2",
-        new_email, 
+        "UPDATE cleo_users SET email_addr = $1, updated_at = $2 WHERE user_id = $3",
+        new_email,
+        Utc::now(),
         user.user_id
     )
         .execute(pool)
@@ -279,17 +287,14 @@ pub async fn update_email(
 /// If this operation fails, an error
 /// is returned.
 pub async fn update_display_name(
-    api_token: &String,
+    user: &CleoUser,
     new_name: &String,
     pool: &Pool<Postgres>
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let update_op: () = match query!(
-        "UPDATE cleo_users SET display_name = $1 WHERE user_id = $2",
-        new_name, 
+        "UPDATE cleo_users SET display_name = $1, updated_at = $2 WHERE user_id = $3",
+        new_name,
+        Utc::now(),
         user.user_id
     )
         .execute(pool)
@@ -313,8 +318,9 @@ pub async fn update_verified(
     pool: &Pool<Postgres>
 ) -> Result<(), CleoErr>{
     let update_op: () = match query!(
-        "UPDATE cleo_users SET is_verified = $1 WHERE user_id = $2",
-        verified, 
+        "UPDATE cleo_users SET is_verified = $1, updated_at = $2 WHERE user_id = $3",
+        verified,
+        Utc::now(),
         user_id
     )
         .execute(pool)
@@ -333,17 +339,14 @@ pub async fn update_verified(
 /// If this operation fails, an error
 /// is returned.
 pub async fn update_pfp(
-    api_token: &String,
+    user: &CleoUser,
     new_pfp_url: &String,
     pool: &Pool<Postgres>
 ) -> Result<(), CleoErr>{
-    let user: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user) => user,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let update_op: () = match query!(
-        "UPDATE cleo_users SET pfp_url = $1 WHERE user_id = $2",
-        new_pfp_url, 
+        "UPDATE cleo_users SET pfp_url = $1, updated_at = $2 WHERE user_id = $3",
+        new_pfp_url,
+        Utc::now(),
         user.user_id
     )
         .execute(pool)
@@ -362,21 +365,18 @@ pub async fn update_pfp(
 /// If this operation fails, an error
 /// is returned.
 pub async fn update_password(
-    api_token: &String,
+    user_obj: &CleoUser,
     new_password: &String,
     pool: &Pool<Postgres>
 ) -> Result<(), CleoErr>{
-    let user_obj: CleoUser = match get_user_from_token(api_token, pool).await {
-        Ok(user_obj) => user_obj,
-        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let hashed_pwd: String = match hash(new_password, DEFAULT_COST){
         Ok(hashed_pwd) => hashed_pwd,
         Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
     };
     let update_op: () = match query!(
-        "UPDATE cleo_users SET pwd = $1 WHERE user_id = $2", 
+        "UPDATE cleo_users SET pwd = $1, updated_at = $2 WHERE user_id = $3",
         hashed_pwd,
+        Utc::now(),
         user_obj.user_id
     )
         .execute(pool)
@@ -426,25 +426,6 @@ pub async fn delete_user_from_db(
     }
 }
 
-/// This function attempts to retrieve
-/// an instance of the "CleoUser" sructure
-/// from the database given their API token.
-/// If this operation fails, an error is returned.
-pub async fn get_user_from_token(
-    token: &String,
-    pool: &Pool<Postgres>
-) -> Result<CleoUser, CleoErr>{
-    let token_obj: UserAPIToken = match get_token(token, pool).await {
-        Ok(token_obj) => token_obj,
-        Err(e) => return Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
-    };
-    let user_obj: CleoUser = match get_user_by_id(&token_obj.user_id, pool).await {
-        Ok(user_obj) => user_obj,
-        Err(e) => return Err::<CleoUser, CleoErr>(CleoErr::new(&e.to_string()))
-    };
-    Ok(user_obj)
-}
-
 /// This function attempts
 /// to check wheter a user exists given
 /// the user's ID in the database. 
@@ -0,0 +1,585 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "query"
+/// macro to execute SQL
+/// queries that return 
+/// nothing.
+use sqlx::query;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "query_as"
+/// macro to execute SQL
+/// queries that return 
+/// something.
+use sqlx::query_as;
+
+/// Importing the "json"
+/// macro to build the bodies
+/// of deliveries.
+use serde_json::json;
+
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing.
+use chrono::DateTime;
+
+/// Importing the "Transaction"
+/// structure to queue deliveries
+/// together with the changes they
+/// announce.
+use sqlx::Transaction;
+
+/// Importing the "Value"
+/// enum for the data of 
+/// events.
+use serde_json::Value;
+
+/// Importing the "TimeDelta"
+/// structure to compute how
+/// long claimed deliveries are
+/// hidden.
+use chrono::TimeDelta;
+
+/// Importing the "query_scalar"
+/// macro to execute SQL
+/// queries that return 
+/// single values.
+use sqlx::query_scalar;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "Webhook" structure
+/// to read and write information about
+/// webhooks.
+use crate::modules::models::Webhook;
+
+/// Importing the function to
+/// generate random secrets.
+use crate::modules::ids::new_secret;
+
+/// Importing the "CleoUser" structure
+/// to read and write associated data
+/// about Cleo users.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure holding
+/// the options for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing how long claimed deliveries
+/// are hidden from other dispatchers.
+use crate::modules::webhooks::WEBHOOK_LEASE;
+
+/// Importing the "WebhookDelivery" 
+/// structure to read and write information
+/// about deliveries of webhooks.
+use crate::modules::models::WebhookDelivery;
+
+/// Importing the "InstanceInformation"
+/// structure to read the allowlist
+/// of the instance.
+use crate::modules::models::InstanceInformation;
+
+/// Importing the function to read
+/// the allowlist of the instance.
+use crate::modules::db::general::get_instance_info;
+
+/// Importing the function to check
+/// whether a URL points to a public
+/// host.
+use crate::modules::webhooks::check_webhook_target;
+
+/// The events webhooks can
+/// subscribe to.
+pub const WEBHOOK_EVENTS: [&str; 7] = [
+    "post.created", "post.updated", "post.deleted", "post.published",
+    "file.uploaded", "file.deleted", "field.changed"
+];
+
+/// The fields the deliveries of 
+/// a webhook can be sorted by.
+pub const WEBHOOK_DELIVERY_SORT_FIELDS: [&str; 2] = [
+    "created_at", "updated_at"
+];
+
+/// The number of characters of
+/// the secrets deliveries are signed
+/// with.
+pub const WEBHOOK_SECRET_LENGTH: usize = 32;
+
+/// This function checks whether
+/// the supplied events can be subscribed
+/// to. At least one event is required. If
+/// the events are not valid, an error is 
+/// returned.
+pub fn validate_webhook_events(events: &Vec<String>) -> Result<(), CleoErr> {
+    if events.is_empty() {
+        let e: &str = "A webhook must subscribe to at least one event.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    for event in events {
+        if !WEBHOOK_EVENTS.contains(&event.as_str()) {
+            let e: String = format!(
+                "\"{}\" is not a valid event. Valid options are: {}.", 
+                event,
+                WEBHOOK_EVENTS.join(", ")
+            );
+            return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// This function checks whether
+/// the supplied URL can receive
+/// deliveries. Only absolute HTTP
+/// and HTTPS URLs are accepted. URLs
+/// pointing to loopback, private, or
+/// link-local hosts are only accepted
+/// if the host is on the supplied 
+/// allowlist of the instance. If the
+/// URL is not valid, an error is
+/// returned.
+pub fn validate_webhook_url(target_url: &str, allowlist: &[String]) -> Result<(), CleoErr> {
+    check_webhook_target(target_url, allowlist)
+}
+
+/// This function attempts
+/// to register a webhook for a user
+/// that receives the supplied events at
+/// the supplied URL. A secret to verify
+/// deliveries with is generated. If the
+/// operation is successful, an instance of
+/// the "Webhook" structure is returned. If
+/// this operation fails, an error is returned.
+pub async fn create_webhook(
+    user_obj: &CleoUser,
+    target_url: &str,
+    events: &Vec<String>,
+    pool: &Pool<Postgres>
+) -> Result<Webhook, CleoErr> {
+    let info: InstanceInformation = match get_instance_info(pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_webhook_url(target_url, &info.webhook_allowlist) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_webhook_events(events) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let secret: String = match new_secret(&WEBHOOK_SECRET_LENGTH) {
+        Ok(secret) => secret,
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let webhook_id: String = match new_id() {
+        Ok(webhook_id) => webhook_id,
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut events: Vec<String> = events.to_owned();
+    events.sort();
+    events.dedup();
+    match query!(
+        "INSERT INTO webhooks (webhook_id, user_id, target_url, secret, events, is_active, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        webhook_id,
+        user_obj.user_id,
+        target_url.trim(),
+        secret,
+        events.as_slice(),
+        true,
+        Utc::now(),
+        Utc::now()
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let webhook: Webhook = match get_webhook_by_id(&webhook_id, pool).await {
+        Ok(webhook) => webhook,
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(webhook)
+}
+
+/// This function attempts
+/// to retrieve a webhook given
+/// its ID. If the operation is 
+/// successful, an instance of the
+/// "Webhook" structure is returned.
+/// If this operation fails, an error 
+/// is returned.
+pub async fn get_webhook_by_id(
+    webhook_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Webhook, CleoErr> {
+    let webhook: Webhook = match query_as!(
+        Webhook,
+        "SELECT * FROM webhooks WHERE webhook_id = $1", 
+        webhook_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(webhook) => webhook,
+        Err(_e) => {
+            let e: String = format!("The webhook \"{}\" does not exist.", webhook_id);
+            return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    Ok(webhook)
+}
+
+/// This function attempts
+/// to retrieve a webhook and 
+/// checks that the user owns it.
+/// If the user does not own it or 
+/// the operation fails, an error is
+/// returned.
+pub async fn get_owned_webhook(
+    user_id: &String,
+    webhook_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Webhook, CleoErr> {
+    let webhook: Webhook = match get_webhook_by_id(webhook_id, pool).await {
+        Ok(webhook) => webhook,
+        Err(e) => return Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if &webhook.user_id == user_id {
+        Ok(webhook)
+    }
+    else {
+        let e: String = "Could not verify ownership of the webhook.".to_string();
+        Err::<Webhook, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// This function attempts
+/// to retrieve all webhooks of
+/// a user. If the operation fails,
+/// an error is returned.
+pub async fn get_user_webhooks(
+    user_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<Vec<Webhook>, CleoErr> {
+    let webhooks: Vec<Webhook> = match query_as!(
+        Webhook,
+        "SELECT * FROM webhooks WHERE user_id = $1 ORDER BY created_at, webhook_id", 
+        user_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(webhooks) => webhooks,
+        Err(e) => return Err::<Vec<Webhook>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(webhooks)
+}
+
+/// This function attempts
+/// to change the URL, the events,
+/// or whether a webhook is active.
+/// Only the values that are supplied
+/// are changed. If the operation is 
+/// successful, an empty function is 
+/// returned. If this operation fails, an
+/// error is returned.
+pub async fn edit_webhook(
+    user_obj: &CleoUser,
+    webhook_id: &String,
+    target_url: &Option<String>,
+    events: &Option<Vec<String>>,
+    is_active: &Option<bool>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let webhook: Webhook = match get_owned_webhook(&user_obj.user_id, webhook_id, pool).await {
+        Ok(webhook) => webhook,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let new_url: String = match target_url {
+        Some(target_url) => target_url.trim().to_string(),
+        None => webhook.target_url
+    };
+    let info: InstanceInformation = match get_instance_info(pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let _valid: () = match validate_webhook_url(&new_url, &info.webhook_allowlist) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut new_events: Vec<String> = match events {
+        Some(events) => events.to_owned(),
+        None => webhook.events
+    };
+    let _valid: () = match validate_webhook_events(&new_events) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    new_events.sort();
+    new_events.dedup();
+    let update_op: () = match query!(
+        "UPDATE webhooks SET target_url = $1, events = $2, is_active = $3, updated_at = $4 WHERE webhook_id = $5",
+        new_url,
+        new_events.as_slice(),
+        is_active.unwrap_or(webhook.is_active),
+        Utc::now(),
+        webhook.webhook_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(update_op)
+}
+
+/// This function attempts
+/// to delete a webhook together
+/// with its deliveries. If the 
+/// operation is successful, an empty
+/// function is returned. If this 
+/// operation fails, an error is returned.
+pub async fn delete_webhook(
+    user_obj: &CleoUser,
+    webhook_id: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let webhook: Webhook = match get_owned_webhook(&user_obj.user_id, webhook_id, pool).await {
+        Ok(webhook) => webhook,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let del_op: () = match query!(
+        "DELETE FROM webhooks WHERE webhook_id = $1",
+        webhook.webhook_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(del_op)
+}
+
+/// This function attempts to queue
+/// a delivery of an event for every
+/// active webhook of a user that subscribed
+/// to it. The deliveries are queued in the
+/// supplied transaction and sent in the
+/// background once it is committed. If the
+/// operation is successful, an empty function
+/// is returned. If this operation fails, an
+/// error is returned.
+pub async fn enqueue_webhook_event(
+    user_id: &String,
+    event: &String,
+    data: &Value,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), CleoErr> {
+    let webhook_ids: Vec<String> = match query_scalar!(
+        "SELECT webhook_id FROM webhooks WHERE user_id = $1 AND is_active AND $2 = ANY(events)",
+        user_id,
+        event
+    )
+        .fetch_all(&mut **tx)
+        .await
+    {
+        Ok(webhook_ids) => webhook_ids,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let created_at: DateTime<Utc> = Utc::now();
+    for webhook_id in webhook_ids {
+        let delivery_id: String = match new_id() {
+            Ok(delivery_id) => delivery_id,
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let payload: String = json!({
+            "delivery_id": delivery_id,
+            "event": event,
+            "created_at": created_at.to_rfc3339(),
+            "data": data
+        }).to_string();
+        match query!(
+            "INSERT INTO webhook_deliveries (delivery_id, webhook_id, event, payload, delivery_status, attempts, next_attempt_at, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            delivery_id,
+            webhook_id,
+            event,
+            payload,
+            "pending",
+            0,
+            created_at,
+            created_at,
+            created_at
+        )
+            .execute(&mut **tx)
+            .await
+        {
+            Ok(_feedback) => {},
+            Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+        };
+    }
+    Ok(())
+}
+
+/// This function attempts to claim
+/// up to the supplied number of pending
+/// deliveries of active webhooks that are
+/// due. Claimed deliveries are hidden from
+/// other dispatchers for a while so that
+/// they are not sent twice. If the operation
+/// fails, an error is returned.
+pub async fn claim_due_deliveries(
+    limit: &i64,
+    pool: &Pool<Postgres>
+) -> Result<Vec<WebhookDelivery>, CleoErr> {
+    let deliveries: Vec<WebhookDelivery> = match query_as!(
+        WebhookDelivery,
+        "UPDATE webhook_deliveries SET next_attempt_at = $1 
+        WHERE delivery_id IN (
+            SELECT webhook_deliveries.delivery_id FROM webhook_deliveries 
+            JOIN webhooks ON webhooks.webhook_id = webhook_deliveries.webhook_id 
+            WHERE webhook_deliveries.delivery_status = 'pending' 
+            AND webhook_deliveries.next_attempt_at <= $2 
+            AND webhooks.is_active 
+            ORDER BY webhook_deliveries.next_attempt_at 
+            LIMIT $3 
+            FOR UPDATE OF webhook_deliveries SKIP LOCKED
+        ) 
+        RETURNING *",
+        Utc::now() + TimeDelta::seconds(WEBHOOK_LEASE),
+        Utc::now(),
+        limit
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(deliveries) => deliveries,
+        Err(e) => return Err::<Vec<WebhookDelivery>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(deliveries)
+}
+
+/// This function attempts to record
+/// the outcome of an attempt at a delivery.
+/// Deliveries without an error are marked as
+/// delivered. Deliveries with an error are
+/// retried at the supplied time or marked as
+/// failed if no time is supplied. If the 
+/// operation is successful, an empty function
+/// is returned. If this operation fails, an 
+/// error is returned.
+pub async fn record_delivery_attempt(
+    delivery_id: &String,
+    attempts: &i32,
+    status_code: &Option<i32>,
+    error: &Option<String>,
+    next_attempt_at: &Option<DateTime<Utc>>,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let delivery_status: &str = match (error, next_attempt_at) {
+        (None, _) => "delivered",
+        (Some(_error), Some(_next_attempt_at)) => "pending",
+        (Some(_error), None) => "failed"
+    };
+    let update_op: () = match query!(
+        "UPDATE webhook_deliveries SET delivery_status = $1, attempts = $2, last_status_code = $3, last_error = $4, next_attempt_at = $5, updated_at = $6 WHERE delivery_id = $7",
+        delivery_status,
+        attempts,
+        status_code.to_owned(),
+        error.to_owned(),
+        next_attempt_at.unwrap_or(Utc::now()),
+        Utc::now(),
+        delivery_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(update_op)
+}
+
+/// This function attempts to fetch
+/// a page of the deliveries of a webhook
+/// the user owns. If the operation is 
+/// successful, a vector of instances of
+/// the "WebhookDelivery" structure and the
+/// total number of deliveries are returned.
+/// If the operation fails, an error is returned.
+pub async fn get_webhook_deliveries(
+    user_obj: &CleoUser,
+    webhook_id: &String,
+    options: &ListOptions,
+    pool: &Pool<Postgres>
+) -> Result<(Vec<WebhookDelivery>, i64), CleoErr> {
+    let webhook: Webhook = match get_owned_webhook(&user_obj.user_id, webhook_id, pool).await {
+        Ok(webhook) => webhook,
+        Err(e) => return Err::<(Vec<WebhookDelivery>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let deliveries: Vec<WebhookDelivery> = match query_as!(
+        WebhookDelivery,
+        "SELECT * FROM webhook_deliveries WHERE webhook_id = $1 
+        ORDER BY 
+        CASE WHEN $2 = 'created_at' AND $3 = 'asc' THEN created_at END ASC, 
+        CASE WHEN $2 = 'created_at' AND $3 = 'desc' THEN created_at END DESC, 
+        CASE WHEN $2 = 'updated_at' AND $3 = 'asc' THEN updated_at END ASC, 
+        CASE WHEN $2 = 'updated_at' AND $3 = 'desc' THEN updated_at END DESC, 
+        delivery_id ASC 
+        LIMIT $4 OFFSET $5",
+        webhook.webhook_id,
+        options.sort_by,
+        options.sort_order,
+        options.per_page,
+        options.offset()
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(deliveries) => deliveries,
+        Err(e) => return Err::<(Vec<WebhookDelivery>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match query_scalar!(
+        "SELECT COUNT(*) AS \"total!\" FROM webhook_deliveries WHERE webhook_id = $1",
+        webhook.webhook_id
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<(Vec<WebhookDelivery>, i64), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok((deliveries, total))
+}
@@ -8,6 +8,21 @@ Licensed under the FSL v1.
 /// to derive it.
 use serde::Deserialize;
 
+/// Importing the "Json"
+/// structure to read the
+/// bodies of requests.
+use actix_web::web::Json;
+
+/// Importing the "Query" structure
+/// to read the query strings of
+/// requests.
+use actix_web::web::Query;
+
+/// Importing the "CleoErr"
+/// structure to catch and
+/// handle errors.
+use crate::modules::err::CleoErr;
+
 /// A data structure
 /// to extract the filename
 /// from file request URLs.
@@ -23,3 +38,163 @@ pub struct FileExtractor{
 pub struct TokenExtractor{
     pub token: String
 }
+
+/// A data structure
+/// to extract a username
+/// from request URLs.
+#[derive(Deserialize)]
+pub struct UsernameExtractor{
+    pub username: String
+}
+
+/// A data structure
+/// to extract the ID of
+/// a post from request URLs.
+#[derive(Deserialize)]
+pub struct ContentIdExtractor{
+    pub content_id: String
+}
+
+/// A data structure
+/// to extract the username,
+/// the content type, and the
+/// slug of a post from request 
+/// URLs.
+#[derive(Deserialize)]
+pub struct SlugExtractor{
+    pub username: String,
+    pub content_type: String,
+    pub slug: String
+}
+
+/// A data structure
+/// to extract the pagination
+/// and sorting options from
+/// the query string of request
+/// URLs.
+#[derive(Deserialize)]
+pub struct ListQueryExtractor{
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>
+}
+
+/// A data structure
+/// to extract a search query,
+/// its filters and the pagination
+/// options from the query string
+/// of request URLs.
+#[derive(Deserialize)]
+pub struct SearchQueryExtractor{
+    pub q: String,
+    pub author: Option<String>,
+    pub content_type: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>
+}
+
+/// A data structure
+/// to extract the name of
+/// a taxonomy from request 
+/// URLs.
+#[derive(Deserialize)]
+pub struct TaxonomyExtractor{
+    pub taxonomy: String
+}
+
+/// A data structure
+/// to extract the name of
+/// a taxonomy and the slug of
+/// one of its terms from request 
+/// URLs.
+#[derive(Deserialize)]
+pub struct TermExtractor{
+    pub taxonomy: String,
+    pub slug: String
+}
+
+/// A data structure
+/// to extract a username
+/// and the slug of one of
+/// their menus from request 
+/// URLs.
+#[derive(Deserialize)]
+pub struct MenuExtractor{
+    pub username: String,
+    pub slug: String
+}
+
+/// A data structure
+/// to extract the format
+/// of a feed from request 
+/// URLs.
+#[derive(Deserialize)]
+pub struct FeedFormatExtractor{
+    pub format: String
+}
+
+/// A data structure
+/// to extract a username
+/// and the format of their
+/// feed from request URLs.
+#[derive(Deserialize)]
+pub struct UserFeedExtractor{
+    pub username: String,
+    pub format: String
+}
+
+/// A data structure
+/// to extract the type of
+/// content a feed is limited
+/// to from the query string of
+/// request URLs.
+#[derive(Deserialize)]
+pub struct FeedQueryExtractor{
+    pub content_type: Option<String>
+}
+
+/// A data structure
+/// to extract the number
+/// of a sitemap from request 
+/// URLs.
+#[derive(Deserialize)]
+pub struct SitemapExtractor{
+    pub page: i64
+}
+
+/// A data structure
+/// to extract the API token
+/// and the events a stream of 
+/// changes is limited to from
+/// the query string of request
+/// URLs.
+#[derive(Deserialize)]
+pub struct ChangeStreamExtractor{
+    pub api_token: Option<String>,
+    pub events: Option<String>
+}
+
+/// Returns the parameters of a
+/// service that reads data. "GET"
+/// requests supply them in the query
+/// string and the deprecated "POST"
+/// requests in a JSON body. The body
+/// is preferred if both can be read.
+/// If neither can be read, an error
+/// is returned.
+pub fn read_params<T>(
+    query: Option<Query<T>>,
+    body: Option<Json<T>>
+) -> Result<T, CleoErr> {
+    match (body, query) {
+        (Some(body), _) => Ok(body.into_inner()),
+        (None, Some(query)) => Ok(query.into_inner()),
+        (None, None) => {
+            let e: &str = "The parameters of the request are missing or invalid.";
+            Err::<T, CleoErr>(CleoErr::new(e))
+        }
+    }
+}
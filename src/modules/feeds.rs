@@ -0,0 +1,293 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "json"
+/// macro to build JSON
+/// Feed documents.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to build JSON
+/// Feed items.
+use serde_json::Value;
+
+/// Importing the "SecondsFormat"
+/// enum to format the dates in
+/// sitemaps.
+use chrono::SecondsFormat;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the structure
+/// holding the details of a
+/// feed.
+use crate::modules::units::FeedMeta;
+
+/// Importing the structure
+/// holding the details of an
+/// entry of a feed.
+use crate::modules::units::FeedEntry;
+
+/// Importing the structure
+/// holding the details of an
+/// entry of a sitemap.
+use crate::modules::units::SitemapEntry;
+
+/// The largest number of
+/// links a single sitemap
+/// can contain.
+pub const SITEMAP_SIZE: i64 = 50000;
+
+/// The formats feeds
+/// can be requested in.
+pub const FEED_FORMATS: [&str; 3] = ["rss", "atom", "json"];
+
+/// Checks whether the supplied
+/// format is one feeds can be
+/// requested in. If it is not, an
+/// error is returned.
+pub fn validate_feed_format(format: &String) -> Result<(), CleoErr> {
+    if FEED_FORMATS.contains(&format.as_str()) {
+        Ok(())
+    }
+    else {
+        let e: String = format!(
+            "\"{}\" is not a valid feed format. Valid options are: {}.", 
+            format,
+            FEED_FORMATS.join(", ")
+        );
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// Escapes the characters in
+/// the supplied string that have
+/// a special meaning in XML.
+pub fn escape_xml(subject: &str) -> String {
+    subject
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Turns the hostname of an
+/// instance into the base of
+/// absolute links. Hostnames
+/// without a scheme are assumed
+/// to use HTTPS and trailing
+/// slashes are removed.
+pub fn base_url(hostname: &str) -> String {
+    let trimmed: &str = hostname.trim().trim_end_matches('/');
+    if trimmed.contains("://") {
+        trimmed.to_string()
+    }
+    else {
+        format!("https://{}", trimmed)
+    }
+}
+
+/// Picks the base of the links to
+/// published posts. If the URL of the
+/// public website of the instance is set,
+/// links point there. Otherwise the website
+/// is assumed to be served from the hostname
+/// of the instance.
+pub fn site_base_url(hostname: &str, site_url: &str) -> String {
+    if site_url.trim().is_empty() {
+        base_url(hostname)
+    }
+    else {
+        base_url(site_url)
+    }
+}
+
+/// Percent-encodes the supplied string
+/// so that it can be used as one segment
+/// of the path of a URL. Only unreserved
+/// characters are left as they are.
+pub fn encode_path_segment(segment: &str) -> String {
+    let mut encoded: String = String::new();
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        }
+        else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Builds the absolute link of the
+/// public page of a published post from
+/// the base URL of the public website, the
+/// username of its author, its type of content,
+/// and its slug. Each segment of the path
+/// is percent-encoded.
+pub fn post_url(
+    base: &str,
+    username: &str,
+    content_type: &str,
+    slug: &str
+) -> String {
+    format!(
+        "{}/{}/{}/{}",
+        base,
+        encode_path_segment(username),
+        encode_path_segment(content_type),
+        encode_path_segment(slug)
+    )
+}
+
+/// Renders the supplied feed
+/// and entries as an RSS 2.0
+/// document.
+pub fn render_rss(meta: &FeedMeta, entries: &Vec<FeedEntry>) -> String {
+    let mut items: String = String::new();
+    for entry in entries {
+        let description: &String = if entry.summary.is_empty() {
+            &entry.content_html
+        }
+        else {
+            &entry.summary
+        };
+        items.push_str(
+            &format!(
+                "<item><title>{}</title><link>{}</link><guid isPermaLink=\"true\">{}</guid><dc:creator>{}</dc:creator><pubDate>{}</pubDate><description>{}</description><content:encoded>{}</content:encoded></item>",
+                escape_xml(&entry.title),
+                escape_xml(&entry.url),
+                escape_xml(&entry.id),
+                escape_xml(&entry.author),
+                entry.published.to_rfc2822(),
+                escape_xml(description),
+                escape_xml(&entry.content_html)
+            )
+        );
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><channel><title>{}</title><link>{}</link><description>{}</description><atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/><lastBuildDate>{}</lastBuildDate>{}</channel></rss>\n",
+        escape_xml(&meta.title),
+        escape_xml(&meta.home_url),
+        escape_xml(&meta.description),
+        escape_xml(&meta.feed_url),
+        meta.updated.to_rfc2822(),
+        items
+    )
+}
+
+/// Renders the supplied feed
+/// and entries as an Atom
+/// document.
+pub fn render_atom(meta: &FeedMeta, entries: &Vec<FeedEntry>) -> String {
+    let mut items: String = String::new();
+    for entry in entries {
+        let summary: String = if entry.summary.is_empty() {
+            String::new()
+        }
+        else {
+            format!("<summary>{}</summary>", escape_xml(&entry.summary))
+        };
+        items.push_str(
+            &format!(
+                "<entry><id>{}</id><title>{}</title><link rel=\"alternate\" href=\"{}\"/><author><name>{}</name></author><published>{}</published><updated>{}</updated>{}<content type=\"html\">{}</content></entry>",
+                escape_xml(&entry.id),
+                escape_xml(&entry.title),
+                escape_xml(&entry.url),
+                escape_xml(&entry.author),
+                entry.published.to_rfc3339(),
+                entry.updated.to_rfc3339(),
+                summary,
+                escape_xml(&entry.content_html)
+            )
+        );
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\"><id>{}</id><title>{}</title><subtitle>{}</subtitle><link rel=\"alternate\" href=\"{}\"/><link rel=\"self\" href=\"{}\"/><updated>{}</updated>{}</feed>\n",
+        escape_xml(&meta.feed_url),
+        escape_xml(&meta.title),
+        escape_xml(&meta.description),
+        escape_xml(&meta.home_url),
+        escape_xml(&meta.feed_url),
+        meta.updated.to_rfc3339(),
+        items
+    )
+}
+
+/// Renders the supplied feed
+/// and entries as a JSON Feed
+/// 1.1 document.
+pub fn render_json_feed(meta: &FeedMeta, entries: &[FeedEntry]) -> String {
+    let items: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            let mut item: Value = json!({
+                "id": entry.id,
+                "url": entry.url,
+                "title": entry.title,
+                "content_html": entry.content_html,
+                "date_published": entry.published.to_rfc3339(),
+                "date_modified": entry.updated.to_rfc3339(),
+                "authors": [{ "name": entry.author }]
+            });
+            if !entry.summary.is_empty() {
+                item["summary"] = json!(entry.summary);
+            }
+            item
+        })
+        .collect();
+    json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": meta.title,
+        "description": meta.description,
+        "home_page_url": meta.home_url,
+        "feed_url": meta.feed_url,
+        "items": items
+    }).to_string()
+}
+
+/// Renders the supplied entries
+/// as a sitemap with absolute links
+/// built from the supplied base URL.
+pub fn render_sitemap(base: &str, entries: &Vec<SitemapEntry>) -> String {
+    let mut urls: String = String::new();
+    for entry in entries {
+        urls.push_str(
+            &format!(
+                "<url><loc>{}</loc><lastmod>{}</lastmod></url>",
+                escape_xml(&post_url(base, &entry.username, &entry.content_type, &entry.slug)),
+                entry.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+            )
+        );
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">{}</urlset>\n",
+        urls
+    )
+}
+
+/// Renders a sitemap index linking
+/// to the supplied number of sitemaps
+/// with absolute links built from the
+/// supplied base URL.
+pub fn render_sitemap_index(base: &String, sitemap_count: i64) -> String {
+    let mut sitemaps: String = String::new();
+    for page in 1..=sitemap_count {
+        sitemaps.push_str(
+            &format!(
+                "<sitemap><loc>{}</loc></sitemap>",
+                escape_xml(&format!("{}/sitemaps/{}", base, page))
+            )
+        );
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">{}</sitemapindex>\n",
+        sitemaps
+    )
+}
@@ -0,0 +1,567 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "RefCell"
+/// structure to mutate the
+/// state of the sanitizer.
+use std::cell::RefCell;
+
+/// Importing the "Parser"
+/// structure to parse
+/// Markdown.
+use pulldown_cmark::Parser;
+
+/// Importing the "Options"
+/// structure to enable
+/// Markdown extensions.
+use pulldown_cmark::Options;
+
+/// Importing the "Tag"
+/// structure to read tags
+/// found by the tokenizer.
+use html5ever::tokenizer::Tag;
+
+/// Importing the "Token"
+/// enum to match on tokens
+/// found by the tokenizer.
+use html5ever::tokenizer::Token;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "TagKind"
+/// enum to tell opening and
+/// closing tags apart.
+use html5ever::tokenizer::TagKind;
+
+/// Importing the "StrTendril"
+/// structure to feed text to
+/// the tokenizer.
+use html5ever::tendril::StrTendril;
+
+/// Importing the function
+/// to render Markdown to
+/// HTML.
+use pulldown_cmark::html::push_html;
+
+/// Importing the "Tokenizer"
+/// structure to tokenize HTML.
+use html5ever::tokenizer::Tokenizer;
+
+/// Importing the "TokenSink"
+/// trait to receive tokens
+/// from the tokenizer.
+use html5ever::tokenizer::TokenSink;
+
+/// Importing the "BufferQueue"
+/// structure to feed text to
+/// the tokenizer.
+use html5ever::tokenizer::BufferQueue;
+
+/// Importing the "TokenizerOpts"
+/// structure to configure the
+/// tokenizer.
+use html5ever::tokenizer::TokenizerOpts;
+
+/// Importing the "RawKind"
+/// enum to read the content of
+/// some tags as text.
+use html5ever::tokenizer::states::RawKind;
+
+/// Importing the "TokenSinkResult"
+/// enum to tell the tokenizer to
+/// continue.
+use html5ever::tokenizer::TokenSinkResult;
+
+/// The tags that are 
+/// allowed in the content
+/// of posts.
+pub const ALLOWED_TAGS: [&str; 47] = [
+    "a", "abbr", "b", "blockquote", "br", "caption", "cite", "code", 
+    "dd", "del", "details", "div", "dl", "dt", "em", "figcaption", 
+    "figure", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i", "img", 
+    "ins", "kbd", "li", "mark", "ol", "p", "pre", "s", "span", "strong", 
+    "sub", "summary", "sup", "table", "tbody", "td", "tfoot", "th", 
+    "thead", "tr", "ul"
+];
+
+/// The tags that are
+/// removed together
+/// with their content.
+pub const DROPPED_TAGS: [&str; 11] = [
+    "script", "style", "iframe", "object", "embed", 
+    "noscript", "template", "textarea", "select",
+    "svg", "math"
+];
+
+/// The tags whose content
+/// is read as text up to 
+/// their closing tag.
+pub const RAW_TEXT_TAGS: [&str; 6] = [
+    "style", "iframe", "noembed", "noframes", 
+    "noscript", "xmp"
+];
+
+/// The tags whose content
+/// is read as text with
+/// character references up 
+/// to their closing tag.
+pub const ESCAPABLE_RAW_TEXT_TAGS: [&str; 2] = ["textarea", "title"];
+
+/// The tags that never
+/// have a closing tag.
+pub const VOID_TAGS: [&str; 11] = [
+    "area", "base", "br", "col", "embed", "hr", 
+    "img", "input", "link", "meta", "wbr"
+];
+
+/// The tags whose closing
+/// tag may be left out.
+pub const OPTIONAL_END_TAGS: [&str; 10] = [
+    "p", "li", "dt", "dd", "tr", "td", 
+    "th", "thead", "tbody", "tfoot"
+];
+
+/// The tags that close an
+/// open paragraph when they
+/// are opened.
+pub const PARAGRAPH_CLOSERS: [&str; 20] = [
+    "p", "div", "ul", "ol", "dl", "pre", "blockquote", "table", 
+    "figure", "details", "hr", "h1", "h2", "h3", "h4", "h5", "h6",
+    "thead", "tbody", "tfoot"
+];
+
+/// The attributes that are
+/// allowed on all allowed
+/// tags.
+pub const ALLOWED_ATTRIBUTES: [&str; 3] = [
+    "title", "lang", "dir"
+];
+
+/// The attributes that are
+/// only allowed on certain
+/// tags.
+pub const ALLOWED_TAG_ATTRIBUTES: [(&str, &str); 12] = [
+    ("a", "href"),
+    ("a", "rel"),
+    ("img", "src"),
+    ("img", "alt"),
+    ("img", "width"),
+    ("img", "height"),
+    ("td", "colspan"),
+    ("td", "rowspan"),
+    ("th", "colspan"),
+    ("th", "rowspan"),
+    ("ol", "start"),
+    ("code", "class")
+];
+
+/// The attributes whose
+/// values are URLs.
+pub const URL_ATTRIBUTES: [&str; 2] = ["href", "src"];
+
+/// The URL schemes that
+/// are allowed in URL
+/// attributes.
+pub const ALLOWED_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// A structure holding
+/// an open tag and whether
+/// it was written to the
+/// sanitized output.
+pub struct OpenTag{
+    pub name: String,
+    pub kept: bool
+}
+
+/// A structure that receives
+/// tokens from the HTML tokenizer,
+/// checks that the tags of the HTML 
+/// are balanced, and writes an allowed
+/// subset of it to a string.
+pub struct HtmlSanitizer{
+    pub output: RefCell<String>,
+    pub open_tags: RefCell<Vec<OpenTag>>,
+    pub dropped: RefCell<Vec<String>>,
+    pub errors: RefCell<Vec<String>>
+}
+
+impl HtmlSanitizer {
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "HtmlSanitizer"
+    /// structure.
+    pub fn new() -> HtmlSanitizer {
+        HtmlSanitizer {
+            output: RefCell::new(String::new()),
+            open_tags: RefCell::new(Vec::new()),
+            dropped: RefCell::new(Vec::new()),
+            errors: RefCell::new(Vec::new())
+        }
+    }
+
+    /// Implementing a method
+    /// to close the innermost open
+    /// tags whose closing tags are
+    /// implied by the supplied
+    /// opening tag.
+    pub fn close_implied(&self, name: &String) {
+        let mut open_tags = self.open_tags.borrow_mut();
+        while let Some(open) = open_tags.last() {
+            if !is_implied_end(&open.name, name) {
+                break;
+            }
+            if open.kept {
+                self.output.borrow_mut().push_str(&format!("</{}>", open.name));
+            }
+            open_tags.pop();
+        }
+    }
+
+    /// Implementing a method
+    /// to handle an opening tag.
+    pub fn open_tag(&self, tag: &Tag) {
+        let name: String = tag.name.to_string();
+        if !self.dropped.borrow().is_empty() || DROPPED_TAGS.contains(&name.as_str()) {
+            if !VOID_TAGS.contains(&name.as_str()) && !tag.self_closing {
+                self.dropped.borrow_mut().push(name);
+            }
+            return;
+        }
+        self.close_implied(&name);
+        let kept: bool = ALLOWED_TAGS.contains(&name.as_str());
+        if kept {
+            let mut output = self.output.borrow_mut();
+            output.push('<');
+            output.push_str(&name);
+            for attr in &tag.attrs {
+                let attr_name: String = attr.name.local.to_string();
+                let attr_value: String = attr.value.to_string();
+                if is_allowed_attribute(&name, &attr_name, &attr_value) {
+                    output.push_str(&format!(" {}=\"{}\"", attr_name, escape_html(&attr_value)));
+                }
+            }
+            output.push('>');
+        }
+        if !VOID_TAGS.contains(&name.as_str()) && !tag.self_closing {
+            self.open_tags.borrow_mut().push(OpenTag{ name, kept });
+        }
+    }
+
+    /// Implementing a method
+    /// to handle a closing tag.
+    pub fn close_tag(&self, tag: &Tag, line_number: u64) {
+        let name: String = tag.name.to_string();
+        if !self.dropped.borrow().is_empty() {
+            if self.dropped.borrow().last() == Some(&name) {
+                self.dropped.borrow_mut().pop();
+            }
+            return;
+        }
+        if VOID_TAGS.contains(&name.as_str()) {
+            return;
+        }
+        let position: Option<usize> = self.open_tags
+            .borrow()
+            .iter()
+            .rposition(|open| open.name == name);
+        let index: usize = match position {
+            Some(index) => index,
+            None => {
+                self.errors.borrow_mut().push(
+                    format!("Unexpected closing tag \"</{}>\" on line {}.", name, line_number)
+                );
+                return;
+            }
+        };
+        let mut open_tags = self.open_tags.borrow_mut();
+        let implied: bool = open_tags[index + 1..]
+            .iter()
+            .all(|open| OPTIONAL_END_TAGS.contains(&open.name.as_str()));
+        if !implied {
+            let unclosed: String = open_tags[open_tags.len() - 1].name.clone();
+            self.errors.borrow_mut().push(
+                format!("The tag \"<{}>\" is not closed before line {}.", unclosed, line_number)
+            );
+            return;
+        }
+        while open_tags.len() > index {
+            let open: OpenTag = match open_tags.pop() {
+                Some(open) => open,
+                None => break
+            };
+            if open.kept {
+                self.output.borrow_mut().push_str(&format!("</{}>", open.name));
+            }
+        }
+    }
+
+    /// Implementing a method
+    /// to close all tags that are
+    /// still open once the end of
+    /// the text has been reached.
+    pub fn finish(&self) {
+        if let Some(dropped) = self.dropped.borrow().last() {
+            self.errors.borrow_mut().push(
+                format!("The tag \"<{}>\" is never closed.", dropped)
+            );
+        }
+        let mut open_tags = self.open_tags.borrow_mut();
+        while let Some(open) = open_tags.pop() {
+            if !OPTIONAL_END_TAGS.contains(&open.name.as_str()) {
+                self.errors.borrow_mut().push(
+                    format!("The tag \"<{}>\" is never closed.", open.name)
+                );
+                return;
+            }
+            if open.kept {
+                self.output.borrow_mut().push_str(&format!("</{}>", open.name));
+            }
+        }
+    }
+
+}
+
+impl Default for HtmlSanitizer {
+
+    /// Implementing the method
+    /// to create an empty sanitizer.
+    fn default() -> HtmlSanitizer {
+        HtmlSanitizer::new()
+    }
+
+}
+
+impl TokenSink for HtmlSanitizer {
+    type Handle = ();
+
+    /// Implementing the method
+    /// to receive tokens from
+    /// the tokenizer.
+    fn process_token(&self, token: Token, line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) => match tag.kind {
+                TagKind::StartTag => {
+                    self.open_tag(&tag);
+                    if !tag.self_closing {
+                        if let Some(kind) = raw_text_kind(&tag.name.to_string()) {
+                            return TokenSinkResult::RawData(kind);
+                        }
+                    }
+                },
+                TagKind::EndTag => self.close_tag(&tag, line_number)
+            },
+            Token::CharacterTokens(text) if self.dropped.borrow().is_empty() => {
+                self.output.borrow_mut().push_str(&escape_html(&text));
+            },
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// Returns how the tokenizer must
+/// read the content of the tag with
+/// the supplied name if it is not
+/// made up of tags.
+pub fn raw_text_kind(name: &String) -> Option<RawKind> {
+    if name == "script" {
+        Some(RawKind::ScriptData)
+    }
+    else if RAW_TEXT_TAGS.contains(&name.as_str()) {
+        Some(RawKind::Rawtext)
+    }
+    else if ESCAPABLE_RAW_TEXT_TAGS.contains(&name.as_str()) {
+        Some(RawKind::Rcdata)
+    }
+    else {
+        None
+    }
+}
+
+/// Checks whether opening the
+/// tag with the supplied name 
+/// implies the closing tag of
+/// the supplied open tag.
+pub fn is_implied_end(open: &str, name: &String) -> bool {
+    match open {
+        "p" => PARAGRAPH_CLOSERS.contains(&name.as_str()),
+        "li" => name == "li",
+        "dt" | "dd" => name == "dt" || name == "dd",
+        "td" | "th" => name == "td" || name == "th" || name == "tr",
+        "tr" => name == "tr",
+        _ => false
+    }
+}
+
+/// Escapes the characters in
+/// the supplied string that have
+/// a special meaning in HTML.
+pub fn escape_html(subject: &str) -> String {
+    subject
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns the text of the supplied
+/// HTML without its tags. The HTML
+/// must have been sanitized so that
+/// all special characters in text and
+/// attribute values are escaped.
+pub fn strip_tags(subject: &str) -> String {
+    let mut result: String = String::new();
+    let mut in_tag: bool = false;
+    for character in subject.chars() {
+        if character == '<' {
+            in_tag = true;
+        }
+        else if character == '>' {
+            in_tag = false;
+            result.push(' ');
+        }
+        else if !in_tag {
+            result.push(character);
+        }
+    }
+    result
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+/// Checks whether the supplied
+/// URL is relative or uses one
+/// of the allowed schemes.
+pub fn is_allowed_url(url: &str) -> bool {
+    let cleaned: String = url
+        .chars()
+        .filter(|character| !character.is_whitespace() && !character.is_control())
+        .collect::<String>()
+        .to_lowercase();
+    let scheme_end: Option<usize> = cleaned.find(':');
+    let path_start: Option<usize> = cleaned.find(['/', '?', '#']);
+    match (scheme_end, path_start) {
+        (None, _) => true,
+        (Some(scheme_end), Some(path_start)) if path_start < scheme_end => true,
+        (Some(scheme_end), _) => ALLOWED_SCHEMES.contains(&&cleaned[..scheme_end])
+    }
+}
+
+/// Checks whether the supplied
+/// attribute is allowed on the
+/// supplied tag with the supplied
+/// value.
+pub fn is_allowed_attribute(tag: &str, name: &str, value: &str) -> bool {
+    let allowed: bool = ALLOWED_ATTRIBUTES.contains(&name) ||
+        ALLOWED_TAG_ATTRIBUTES.contains(&(tag, name));
+    if !allowed {
+        false
+    }
+    else if URL_ATTRIBUTES.contains(&name) {
+        is_allowed_url(value)
+    }
+    else {
+        true
+    }
+}
+
+/// Attempts to parse the supplied HTML
+/// and returns a sanitized version of it
+/// that only contains allowed tags and
+/// attributes. Stray characters that do
+/// not form tags are kept as text. If tags
+/// are not closed or closed without being
+/// opened, an error describing the first
+/// problem is returned.
+pub fn sanitize_html(subject: &str) -> Result<String, CleoErr> {
+    let tokenizer: Tokenizer<HtmlSanitizer> = Tokenizer::new(
+        HtmlSanitizer::new(), 
+        TokenizerOpts::default()
+    );
+    let queue: BufferQueue = BufferQueue::default();
+    queue.push_back(StrTendril::from(subject));
+    let _result = tokenizer.feed(&queue);
+    tokenizer.end();
+    tokenizer.sink.finish();
+    let errors: Vec<String> = tokenizer.sink.errors.take();
+    match errors.first() {
+        Some(e) => Err::<String, CleoErr>(CleoErr::new(&e.to_string())),
+        None => Ok(tokenizer.sink.output.take())
+    }
+}
+
+/// Attempts to render the supplied
+/// Markdown to HTML and sanitizes
+/// the result. If the Markdown contains
+/// malformed HTML, an error is returned.
+pub fn render_markdown(subject: &str) -> Result<String, CleoErr> {
+    let mut options: Options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    let parser: Parser = Parser::new_ext(subject, options);
+    let mut rendered: String = String::new();
+    push_html(&mut rendered, parser);
+    sanitize_html(&rendered)
+}
+
+/// Attempts to return the sanitized
+/// HTML of a post given the format 
+/// its text was written in. If this
+/// operation fails, an error is returned.
+pub fn render_post_html(
+    source_format: &str, 
+    content_text: &str
+) -> Result<String, CleoErr> {
+    match source_format {
+        "markdown" => render_markdown(content_text),
+        "html" => sanitize_html(content_text),
+        _ => {
+            let e: String = format!("\"{}\" is not a valid source format.", source_format);
+            Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+        }
+    }
+}
+
+/// Attempts to return the plain text
+/// of a post given the format its text
+/// was written in. Markdown syntax and
+/// HTML tags are removed. If this
+/// operation fails, an error is returned.
+pub fn render_post_text(
+    source_format: &str, 
+    content_text: &str
+) -> Result<String, CleoErr> {
+    match render_post_html(source_format, content_text) {
+        Ok(rendered) => Ok(strip_tags(&rendered)),
+        Err(e) => Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// Attempts to prepare the text of
+/// a post for saving. HTML is sanitized
+/// and Markdown is stored as written after
+/// checking that it renders. If this
+/// operation fails, an error is returned.
+pub fn prepare_post_source(
+    source_format: &str, 
+    content_text: &str
+) -> Result<String, CleoErr> {
+    let rendered: String = match render_post_html(source_format, content_text) {
+        Ok(rendered) => rendered,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if source_format == "markdown" {
+        Ok(content_text.to_string())
+    }
+    else {
+        Ok(rendered)
+    }
+}
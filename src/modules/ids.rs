@@ -0,0 +1,161 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Mutex"
+/// structure to guard the
+/// clock of the generator.
+use std::sync::Mutex;
+
+/// Importing the "TryRngCore"
+/// trait to fill buffers with
+/// random bytes.
+use rand::TryRngCore;
+
+/// Importing the "OsRng"
+/// structure to draw bytes
+/// from the random number
+/// generator of the operating
+/// system.
+use rand::rngs::OsRng;
+
+/// Importing the "UNIX_EPOCH"
+/// constant to measure the
+/// current time.
+use std::time::UNIX_EPOCH;
+
+/// Importing the "SystemTime"
+/// structure to read the
+/// current time.
+use std::time::SystemTime;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// The characters secrets
+/// are made up of.
+pub const SECRET_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890";
+
+/// The largest value the
+/// counter of identifiers
+/// generated in the same
+/// millisecond can reach.
+pub const MAX_ID_COUNTER: u16 = 0x0FFF;
+
+/// The last millisecond an
+/// identifier was generated in
+/// and the counter used for it.
+/// Identifiers generated in the
+/// same millisecond increment the
+/// counter so that they never collide
+/// within this process. If the counter
+/// runs out, the millisecond is advanced
+/// instead.
+static ID_CLOCK: Mutex<(u64, u16)> = Mutex::new((0, 0));
+
+/// Attempts to draw the supplied
+/// number of bytes from the random
+/// number generator of the operating
+/// system. If this operation fails,
+/// an error is returned.
+pub fn random_bytes(len: &usize) -> Result<Vec<u8>, CleoErr> {
+    let mut bytes: Vec<u8> = vec![0; *len];
+    match OsRng.try_fill_bytes(&mut bytes) {
+        Ok(_filled) => Ok(bytes),
+        Err(e) => Err::<Vec<u8>, CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// Attempts to generate a new
+/// identifier for a row. Identifiers
+/// are version 7 UUIDs: they start with
+/// the current time in milliseconds so
+/// that they sort by creation, followed by
+/// a counter for identifiers generated in the
+/// same millisecond and 62 random bits. 
+/// Within a process, identifiers never 
+/// collide. Identifiers from different
+/// processes can only collide if they share
+/// the millisecond, the counter, and all 62 
+/// random bits. For "n" identifiers generated
+/// in the same millisecond, this happens with
+/// a probability below "n * n / 2^63", which is 
+/// about one in eight million even for a million
+/// identifiers. Inserts therefore do not retry
+/// on a violated unique constraint. If this 
+/// operation fails, an error is returned.
+pub fn new_id() -> Result<String, CleoErr> {
+    let random: Vec<u8> = match random_bytes(&10) {
+        Ok(random) => random,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let now: u64 = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(now) => now.as_millis() as u64,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (millis, counter): (u64, u16) = {
+        let mut clock = match ID_CLOCK.lock() {
+            Ok(clock) => clock,
+            Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        if now > clock.0 {
+            clock.0 = now;
+            clock.1 = u16::from_be_bytes([random[0], random[1]]) & (MAX_ID_COUNTER >> 1);
+        }
+        else if clock.1 < MAX_ID_COUNTER {
+            clock.1 += 1;
+        }
+        else {
+            clock.0 += 1;
+            clock.1 = 0;
+        }
+        (clock.0, clock.1)
+    };
+    let mut bytes: [u8; 16] = [0; 16];
+    bytes[0..6].copy_from_slice(&millis.to_be_bytes()[2..8]);
+    bytes[6] = 0x70 | ((counter >> 8) as u8 & 0x0F);
+    bytes[7] = counter as u8;
+    bytes[8..16].copy_from_slice(&random[2..10]);
+    bytes[8] = 0x80 | (bytes[8] & 0x3F);
+    let hex: String = bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    Ok(
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    )
+}
+
+/// Attempts to generate a random
+/// secret of the supplied length
+/// from the characters in "SECRET_ALPHABET".
+/// Bytes that would favour some characters
+/// over others are discarded. If this
+/// operation fails, an error is returned.
+pub fn new_secret(len: &usize) -> Result<String, CleoErr> {
+    let alpha: Vec<char> = SECRET_ALPHABET.chars().collect::<Vec<char>>();
+    let limit: usize = 256 - (256 % alpha.len());
+    let mut secret: String = String::new();
+    while secret.len() < *len {
+        let bytes: Vec<u8> = match random_bytes(len) {
+            Ok(bytes) => bytes,
+            Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        for byte in bytes {
+            if (byte as usize) < limit && secret.len() < *len {
+                secret.push(alpha[(byte as usize) % alpha.len()]);
+            }
+        }
+    }
+    Ok(secret)
+}
@@ -21,6 +21,37 @@ pub mod db;
 /// structure.
 pub mod err;
 
+/// Exporting the
+/// module for validating
+/// and sanitizing HTML.
+pub mod html;
+
+/// Exporting the
+/// module for rendering
+/// feeds and sitemaps of
+/// posts.
+pub mod feeds;
+
+/// Exporting the
+/// module for signing
+/// and sending webhooks.
+pub mod webhooks;
+
+/// Exporting the
+/// module for streaming
+/// changes to content.
+pub mod changes;
+
+/// Exporting the
+/// module for authenticating
+/// requests.
+pub mod auth;
+
+/// Exporting the
+/// module for generating
+/// identifiers and secrets.
+pub mod ids;
+
 /// Exporting 
 /// a module
 /// containing
@@ -3,6 +3,12 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+/// Importing the "Utc"
+/// structure to specify
+/// the timezone of stored
+/// timestamps.
+use chrono::Utc;
+
 /// Importing the
 /// "FromRow" derive
 /// macro to
@@ -11,6 +17,11 @@ Licensed under the FSL v1.
 /// database.
 use sqlx::FromRow;
 
+/// Importing the "DateTime"
+/// structure to read and write
+/// timestamps.
+use chrono::DateTime;
+
 /// A structure
 /// for creating tables
 /// for holding info
@@ -23,14 +34,17 @@ pub struct InstanceInformation{
     pub smtp_server: String,
     pub smtp_username: String,
     pub smtp_pass: String,
-    pub file_dir: String
+    pub file_dir: String,
+    pub webhook_allowlist: Vec<String>,
+    pub password_reset_url: String,
+    pub site_url: String
 }
 
 /// A structure
 /// for creating tables
 /// for holding info
 /// on Cleo users.
-#[derive(FromRow)]
+#[derive(FromRow, Clone)]
 pub struct CleoUser{
     pub user_id: String,
     pub display_name: String,
@@ -40,6 +54,8 @@ pub struct CleoUser{
     pub email_addr: String,
     pub pfp_url: String,
     pub is_admin: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 /// A structure
@@ -52,7 +68,9 @@ pub struct UserFile{
     pub file_id: String,
     pub user_id: String,
     pub file_path: String,
-    pub file_url: String
+    pub file_url: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
 }
 
 /// A structure
@@ -66,6 +84,18 @@ pub struct UserPost{
     pub user_id: String,
     pub content_type: String,
     pub content_text: String,
+    pub post_status: String,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub slug: String,
+    pub title: String,
+    pub excerpt: String,
+    pub cover_file_id: Option<String>,
+    pub author_name: String,
+    pub source_format: String,
+    pub parent_id: Option<String>,
+    pub page_position: i32,
 }
 
 /// A structure
@@ -79,7 +109,10 @@ pub struct ExtraContentField{
     pub field_id: String,
     pub content_id: String,
     pub field_key: String,
-    pub field_value: String
+    pub field_value: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub field_type: String
 }
 
 /// A structure
@@ -87,11 +120,19 @@ pub struct ExtraContentField{
 /// for holding info
 /// on API tokens belonging
 /// to a certain Cleo user.
-#[derive(FromRow)]
+/// Only a hash of the token
+/// is stored.
+#[derive(FromRow, Clone)]
 pub struct UserAPIToken {
     pub token_id: String,
     pub user_id: String,
-    pub token: String
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub label: Option<String>,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>
 }
 
 /// A structure
@@ -123,3 +164,187 @@ pub struct EmailToken{
   pub email_token: String,
   pub user_id: String
 }
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on saved versions
+/// of a post. The extra
+/// content fields of the
+/// post at the time are
+/// stored as JSON.
+#[derive(FromRow)]
+pub struct PostRevision{
+    pub revision_id: String,
+    pub content_id: String,
+    pub user_id: String,
+    pub content_text: String,
+    pub extra_fields: String,
+    pub created_at: DateTime<Utc>,
+    pub title: String,
+    pub excerpt: String,
+    pub cover_file_id: Option<String>,
+    pub author_name: String
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on slugs a post was
+/// previously reachable
+/// under.
+#[derive(FromRow)]
+pub struct SlugRedirect{
+    pub user_id: String,
+    pub content_type: String,
+    pub old_slug: String,
+    pub content_id: String,
+    pub created_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the types of content
+/// administrators have
+/// defined.
+#[derive(FromRow)]
+pub struct ContentTypeDefinition{
+    pub type_name: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the extra content
+/// fields a type of content
+/// accepts.
+#[derive(FromRow)]
+pub struct ContentTypeField{
+    pub type_name: String,
+    pub field_key: String,
+    pub is_required: bool,
+    pub created_at: DateTime<Utc>,
+    pub field_type: String
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the tags and categories
+/// of an instance.
+#[derive(FromRow)]
+pub struct TaxonomyTerm{
+    pub term_id: String,
+    pub taxonomy: String,
+    pub term_name: String,
+    pub term_slug: String,
+    pub parent_id: Option<String>,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the tags and categories
+/// assigned to posts.
+#[derive(FromRow)]
+pub struct PostTerm{
+    pub content_id: String,
+    pub term_id: String,
+    pub created_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the navigation menus
+/// of a user.
+#[derive(FromRow)]
+pub struct NavMenu{
+    pub menu_id: String,
+    pub user_id: String,
+    pub menu_name: String,
+    pub menu_slug: String,
+    pub max_depth: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the items of a
+/// navigation menu.
+#[derive(FromRow)]
+pub struct MenuItem{
+    pub item_id: String,
+    pub menu_id: String,
+    pub parent_id: Option<String>,
+    pub label: String,
+    pub target_type: String,
+    pub content_id: Option<String>,
+    pub file_id: Option<String>,
+    pub target_url: Option<String>,
+    pub item_position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the webhooks a user
+/// registered.
+#[derive(FromRow, Clone)]
+pub struct Webhook{
+    pub webhook_id: String,
+    pub user_id: String,
+    pub target_url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on the deliveries of
+/// a webhook.
+#[derive(FromRow)]
+pub struct WebhookDelivery{
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub event: String,
+    pub payload: String,
+    pub delivery_status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+/// A structure
+/// for creating tables
+/// for holding info
+/// on requests to reset
+/// the password of a user.
+/// Only a hash of the token
+/// sent by email is stored.
+#[derive(FromRow)]
+pub struct PasswordReset{
+    pub reset_id: String,
+    pub user_id: String,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>
+}
@@ -8,27 +8,41 @@ Licensed under the FSL v1.
 /// to derive it.
 use serde::Deserialize;
 
+/// Importing the "HashMap"
+/// structure to accept maps
+/// of extra content fields.
+use std::collections::HashMap;
+
 /// Importing the "Json" structure
 /// to accept JSON data with uploaded
 /// files.
 use actix_multipart::form::json::Json;
 
+/// Importing the structure holding
+/// what an item of a menu points to.
+use crate::modules::units::MenuTarget;
+
 /// Importing the "MultipartForm" trait
 /// for explicit typing and to upload files.
 use actix_multipart::form::MultipartForm;
 
+/// Importing the structure
+/// describing a field a type
+/// of content accepts.
+use crate::modules::units::FieldDefinition;
+
 /// Importing the "TempFile" structure to
 /// upload files and save them temporarily.
 use actix_multipart::form::tempfile::TempFile;
 
 /// An enum to describe
-/// all possible types of
-/// content that can be
-/// submitted.
+/// all possible formats
+/// the text of a post
+/// can be written in.
 #[derive(Deserialize, PartialEq)]
-pub enum ContentType{
-    Page,
-    Post
+pub enum SourceFormat{
+    Html,
+    Markdown
 }
 
 /// A structure for a 
@@ -50,7 +64,7 @@ pub struct UserCreationPayload{
 /// user.
 #[derive(Deserialize)]
 pub struct UserChangePayload{
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub new_value: String,
 }
 
@@ -64,6 +78,20 @@ pub struct AuthActionPayload {
     pub password: String    
 }
 
+/// A structure for a 
+/// payload to create
+/// an API token with
+/// an optional label,
+/// scopes, and expiry.
+#[derive(Deserialize)]
+pub struct TokenCreationPayload {
+    pub username: String,
+    pub password: String,
+    pub label: Option<String>,
+    pub scopes: Option<Vec<String>>,
+    pub expires_at: Option<String>
+}
+
 /// A structure for a 
 /// payload to delete
 /// an API token.
@@ -74,24 +102,137 @@ pub struct DelTokenPayload {
     pub password: String    
 }
 
+/// A structure for a
+/// payload to request an
+/// email for resetting the
+/// password of a user.
+#[derive(Deserialize)]
+pub struct PasswordResetRequestPayload {
+    pub username: String
+}
+
+/// A structure for a
+/// payload to set a new
+/// password with a token
+/// sent by email.
+#[derive(Deserialize)]
+pub struct PasswordResetPayload {
+    pub new_password: String
+}
+
+/// A structure for a 
+/// payload to revoke an
+/// API token given its ID.
+#[derive(Deserialize)]
+pub struct TokenRevocationPayload {
+    pub api_token: Option<String>,
+    pub token_id: String
+}
+
 /// A structure for submitting
 /// a payload for creating a new
-/// user post.
+/// user post. If no slug is
+/// supplied, one is generated
+/// from the title. If no author
+/// name is supplied, the display
+/// name of the user is used. If 
+/// no source format is supplied,
+/// the text is treated as HTML.
+/// The extra content fields are
+/// checked against the definition
+/// of the content type.
 #[derive(Deserialize)]
 pub struct PostCreationPayload{
-    pub api_token: String,
-    pub content_type: ContentType,
+    pub api_token: Option<String>,
+    pub content_type: String,
     pub content_text: String,
+    pub source_format: Option<SourceFormat>,
+    pub slug: Option<String>,
+    pub title: Option<String>,
+    pub excerpt: Option<String>,
+    pub cover_file_id: Option<String>,
+    pub author_name: Option<String>,
+    pub extra_fields: Option<HashMap<String, String>>,
 }
 
 /// A structure for submitting
 /// a payload for editing a
-/// user post.
+/// user post. Fields that are
+/// not supplied are left as they
+/// are. An empty cover file ID
+/// removes the cover image.
 #[derive(Deserialize)]
 pub struct UpdatePostPayload{
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub content_id: String,
     pub text: String,
+    pub title: Option<String>,
+    pub excerpt: Option<String>,
+    pub cover_file_id: Option<String>,
+    pub author_name: Option<String>,
+}
+
+/// A structure for submitting
+/// a payload for changing the
+/// publication status of a
+/// user post.
+#[derive(Deserialize)]
+pub struct PostStatusPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+}
+
+/// A structure for submitting
+/// a payload for changing the
+/// slug of a user post.
+#[derive(Deserialize)]
+pub struct UpdateSlugPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+    pub slug: String,
+}
+
+/// A structure for submitting
+/// a payload for scheduling the
+/// publication of a user post.
+/// The time of publication must
+/// be supplied in the RFC 3339
+/// format.
+#[derive(Deserialize)]
+pub struct SchedulePostPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+    pub publish_at: String,
+}
+
+/// A structure for submitting
+/// a payload for listing all
+/// revisions of a user post.
+#[derive(Deserialize)]
+pub struct PostRevisionsPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+}
+
+/// A structure for submitting
+/// a payload for comparing two
+/// revisions of a user post.
+#[derive(Deserialize)]
+pub struct RevisionDiffPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+    pub from_revision: String,
+    pub to_revision: String,
+}
+
+/// A structure for submitting
+/// a payload for restoring a
+/// revision of a user post.
+#[derive(Deserialize)]
+pub struct RestoreRevisionPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+    pub revision_id: String,
 }
 
 /// A structure for a 
@@ -100,19 +241,24 @@ pub struct UpdatePostPayload{
 /// created. 
 #[derive(Deserialize)]
 pub struct DeletePostPayload {
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub content_id: String,
 }
 
 /// A structure for submitting a
 /// payload to create extra content
-/// fields for a user post.
+/// fields for a user post. If the
+/// content type of the post defines 
+/// the field, its type is used. 
+/// Otherwise the type defaults to
+/// "string".
 #[derive(Deserialize)]
 pub struct ExtraContentFieldCreationPayload{
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub content_id: String,
     pub field_key: String,
     pub field_value: String,
+    pub field_type: Option<String>,
 }
 
 /// A structure for submitting a
@@ -120,28 +266,84 @@ pub struct ExtraContentFieldCreationPayload{
 /// field for a user post.
 #[derive(Deserialize)]
 pub struct EditExtraContentFieldPayload{
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub content_id: String,
     pub field_id: String,
     pub new_value: String,
 }
 
+/// A structure for submitting a
+/// payload to list all extra content
+/// fields of a user post.
+#[derive(Deserialize)]
+pub struct PostFieldsPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+}
+
 /// A structure for submitting a
 /// payload to delete an extra content
 /// field for a user post.
 #[derive(Deserialize)]
 pub struct DeleteExtraContentFieldPayload{
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub content_id: String,
     pub field_id: String,
 }
 
+/// A structure for submitting a
+/// payload to define a new type
+/// of content. If no fields are
+/// supplied, posts of this type
+/// accept any extra content fields.
+#[derive(Deserialize)]
+pub struct ContentTypeCreationPayload{
+    pub api_token: Option<String>,
+    pub type_name: String,
+    pub description: Option<String>,
+    pub fields: Vec<FieldDefinition>,
+}
+
+/// A structure for submitting a
+/// payload to delete a type of
+/// content.
+#[derive(Deserialize)]
+pub struct ContentTypeDeletionPayload{
+    pub api_token: Option<String>,
+    pub type_name: String,
+}
+
+/// A structure for submitting a
+/// payload to add a field to the
+/// definition of a type of content
+/// or to change whether it is
+/// required.
+#[derive(Deserialize)]
+pub struct ContentTypeFieldPayload{
+    pub api_token: Option<String>,
+    pub type_name: String,
+    pub field_key: String,
+    pub field_type: String,
+    pub is_required: bool,
+}
+
+/// A structure for submitting a
+/// payload to remove a field from
+/// the definition of a type of 
+/// content.
+#[derive(Deserialize)]
+pub struct ContentTypeFieldDeletionPayload{
+    pub api_token: Option<String>,
+    pub type_name: String,
+    pub field_key: String,
+}
+
 /// A structure for submitting a
 /// payload to delete a file
 /// a user has uploaded.
 #[derive(Deserialize)]
 pub struct DeleteUserFilePayload{
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub file_id: String,
 }
 
@@ -151,7 +353,7 @@ pub struct DeleteUserFilePayload{
 #[derive(Deserialize)]
 pub struct UserKeyPayload{
     pub key_type: String,
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub username: String
 }
 
@@ -160,7 +362,240 @@ pub struct UserKeyPayload{
 /// require an API token.
 #[derive(Deserialize)]
 pub struct TokenOnlyPayload {
-    pub api_token: String,
+    pub api_token: Option<String>,
+}
+
+/// A structure for submitting a
+/// payload for listing a page of
+/// records a user can access.
+#[derive(Deserialize)]
+pub struct ListPayload {
+    pub api_token: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>
+}
+
+/// A structure for submitting a
+/// payload for listing a page of
+/// the posts of a user with optional
+/// filters.
+#[derive(Deserialize)]
+pub struct PostListPayload {
+    pub api_token: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+    pub content_type: Option<String>,
+    pub status: Option<String>,
+    pub field_key: Option<String>,
+    pub field_value: Option<String>
+}
+
+/// A structure for submitting a
+/// payload for creating a tag
+/// or category.
+#[derive(Deserialize)]
+pub struct TermCreationPayload{
+    pub api_token: Option<String>,
+    pub taxonomy: String,
+    pub name: String,
+    pub slug: Option<String>,
+    pub description: Option<String>,
+    pub parent_id: Option<String>
+}
+
+/// A structure for submitting a
+/// payload for editing a tag
+/// or category.
+#[derive(Deserialize)]
+pub struct TermEditPayload{
+    pub api_token: Option<String>,
+    pub term_id: String,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub description: Option<String>
+}
+
+/// A structure for submitting a
+/// payload for moving a category
+/// below another category.
+#[derive(Deserialize)]
+pub struct TermMovePayload{
+    pub api_token: Option<String>,
+    pub term_id: String,
+    pub parent_id: Option<String>
+}
+
+/// A structure for submitting a
+/// payload for deleting a tag
+/// or category.
+#[derive(Deserialize)]
+pub struct TermDeletionPayload{
+    pub api_token: Option<String>,
+    pub term_id: String
+}
+
+/// A structure for submitting a
+/// payload for assigning a tag
+/// or category to a post or removing
+/// it from a post.
+#[derive(Deserialize)]
+pub struct PostTermPayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+    pub term_id: String
+}
+
+/// A structure for submitting a
+/// payload for moving a page below
+/// another page.
+#[derive(Deserialize)]
+pub struct PageMovePayload{
+    pub api_token: Option<String>,
+    pub content_id: String,
+    pub parent_id: Option<String>,
+    pub position: Option<i32>
+}
+
+/// A structure for submitting a
+/// payload for changing the order
+/// of the pages below a parent page.
+#[derive(Deserialize)]
+pub struct PageReorderPayload{
+    pub api_token: Option<String>,
+    pub parent_id: Option<String>,
+    pub content_ids: Vec<String>
+}
+
+/// A structure for submitting a
+/// payload for creating a navigation
+/// menu.
+#[derive(Deserialize)]
+pub struct MenuCreationPayload{
+    pub api_token: Option<String>,
+    pub name: String,
+    pub slug: Option<String>,
+    pub max_depth: Option<i32>
+}
+
+/// A structure for submitting a
+/// payload for editing a navigation
+/// menu.
+#[derive(Deserialize)]
+pub struct MenuEditPayload{
+    pub api_token: Option<String>,
+    pub menu_id: String,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub max_depth: Option<i32>
+}
+
+/// A structure for submitting a
+/// payload for deleting a navigation
+/// menu.
+#[derive(Deserialize)]
+pub struct MenuDeletionPayload{
+    pub api_token: Option<String>,
+    pub menu_id: String
+}
+
+/// A structure for submitting a
+/// payload for adding an item to
+/// a navigation menu.
+#[derive(Deserialize)]
+pub struct MenuItemCreationPayload{
+    pub api_token: Option<String>,
+    pub menu_id: String,
+    pub parent_id: Option<String>,
+    pub label: String,
+    pub target: MenuTarget,
+    pub position: Option<i32>
+}
+
+/// A structure for submitting a
+/// payload for editing an item of
+/// a navigation menu.
+#[derive(Deserialize)]
+pub struct MenuItemEditPayload{
+    pub api_token: Option<String>,
+    pub item_id: String,
+    pub label: Option<String>,
+    pub target: Option<MenuTarget>
+}
+
+/// A structure for submitting a
+/// payload for moving an item of
+/// a navigation menu.
+#[derive(Deserialize)]
+pub struct MenuItemMovePayload{
+    pub api_token: Option<String>,
+    pub item_id: String,
+    pub parent_id: Option<String>,
+    pub position: Option<i32>
+}
+
+/// A structure for submitting a
+/// payload for deleting an item of
+/// a navigation menu.
+#[derive(Deserialize)]
+pub struct MenuItemDeletionPayload{
+    pub api_token: Option<String>,
+    pub item_id: String
+}
+
+/// A structure for submitting a
+/// payload for registering a
+/// webhook.
+#[derive(Deserialize)]
+pub struct WebhookCreationPayload{
+    pub api_token: Option<String>,
+    pub url: String,
+    pub events: Vec<String>
+}
+
+/// A structure for submitting a
+/// payload for editing a webhook.
+#[derive(Deserialize)]
+pub struct WebhookEditPayload{
+    pub api_token: Option<String>,
+    pub webhook_id: String,
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>
+}
+
+/// A structure for submitting a
+/// payload for deleting a webhook.
+#[derive(Deserialize)]
+pub struct WebhookDeletionPayload{
+    pub api_token: Option<String>,
+    pub webhook_id: String
+}
+
+/// A structure for submitting a
+/// payload for listing a page of
+/// the deliveries of a webhook.
+#[derive(Deserialize)]
+pub struct WebhookDeliveriesPayload{
+    pub api_token: Option<String>,
+    pub webhook_id: String,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>
+}
+
+/// A structure for submitting a
+/// payload for replacing the hosts
+/// webhooks may be delivered to even
+/// though they are not public.
+#[derive(Deserialize)]
+pub struct WebhookAllowlistPayload{
+    pub api_token: Option<String>,
+    pub hosts: Vec<String>
 }
 
 /// A structure for submitting a
@@ -168,7 +603,7 @@ pub struct TokenOnlyPayload {
 /// key.
 #[derive(Deserialize)]
 pub struct UserKeyDeletionPayload {
-    pub api_token: String,
+    pub api_token: Option<String>,
     pub key_id: String
 }
 
@@ -178,7 +613,7 @@ pub struct UserKeyDeletionPayload {
 #[derive(Debug, Deserialize)]
 pub struct UserFileMetadata{
     pub name: String,
-    pub api_token: String
+    pub api_token: Option<String>
 }
 
 /// A structure to simulate a 
@@ -8,6 +8,81 @@ Licensed under the FSL v1.
 /// to derive it.
 use serde::Serialize;
 
+/// Importing the "Value"
+/// enum to return typed
+/// JSON values.
+use serde_json::Value;
+
+/// Importing the "HashMap"
+/// structure to return maps
+/// of extra content fields.
+use std::collections::HashMap;
+
+/// Importing the structure
+/// describing a single line
+/// of a difference.
+use crate::modules::units::DiffLine;
+
+/// Importing the model for
+/// webhooks for explicit typing.
+use crate::modules::models::Webhook;
+
+/// Importing the model for
+/// user posts to create
+/// responses from it.
+use crate::modules::models::UserPost;
+
+/// Importing the function to escape
+/// text for use in HTML.
+use crate::modules::html::escape_html;
+
+/// Importing the structure
+/// holding the options used
+/// for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure holding
+/// a term together with the post it
+/// is assigned to.
+use crate::modules::units::AssignedTerm;
+
+/// Importing the model for
+/// API tokens for explicit typing.
+use crate::modules::models::UserAPIToken;
+
+/// Importing the model for tags
+/// and categories for explicit
+/// typing.
+use crate::modules::models::TaxonomyTerm;
+
+/// Importing the structure holding
+/// a snapshot of an extra content
+/// field.
+use crate::modules::units::FieldSnapshot;
+
+/// Importing the structure
+/// describing a field a type
+/// of content accepts.
+use crate::modules::units::FieldDefinition;
+
+/// Importing the function to render
+/// the text of a post as HTML.
+use crate::modules::html::render_post_html;
+
+/// Importing the model for
+/// deliveries of webhooks for
+/// explicit typing.
+use crate::modules::models::WebhookDelivery;
+
+/// Importing the model for
+/// extra content fields.
+use crate::modules::models::ExtraContentField;
+
+/// Importing the function to convert
+/// the value of an extra content field
+/// to JSON.
+use crate::modules::utils::field_value_to_json;
+
 /// A data structure
 /// to return info
 /// in JSON format
@@ -21,6 +96,8 @@ pub struct UserCreationResponse{
     pub email_addr: String,
     pub pfp_url: String,
     pub is_admin: bool,
+    pub created_at: String,
+    pub updated_at: String,
 }
 
 /// A data structure
@@ -38,7 +115,9 @@ pub struct UserCreationResponseOnly{
     pub email_addr: String,
     pub pfp_url: String,
     pub is_admin: bool,
-    pub key_status_updated: bool
+    pub key_status_updated: bool,
+    pub created_at: String,
+    pub updated_at: String,
 }
 /// A data structure
 /// to return info
@@ -54,11 +133,70 @@ pub struct StatusResponse {
 /// to return info
 /// in JSON format
 /// on a created API
-/// token.
+/// token. This is the only
+/// time the token itself is
+/// returned.
 #[derive(Serialize)]
 pub struct APITokenResponse {
     pub token_id: String,
     pub token: String,
+    pub label: Option<String>,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<String>,
+    pub created_at: String,
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on an existing API
+/// token. The token itself
+/// is never returned.
+#[derive(Serialize)]
+pub struct APITokenInfoResponse {
+    pub token_id: String,
+    pub label: Option<String>,
+    pub scopes: Vec<String>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub is_current: bool
+}
+
+/// Implementing generic
+/// methods for the "APITokenInfoResponse"
+/// structure.
+impl APITokenInfoResponse {
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "APITokenInfoResponse"
+    /// structure from an instance of
+    /// the "UserAPIToken" model. The
+    /// token the request authenticated
+    /// with is marked as current.
+    pub fn new(token: UserAPIToken, current_token_id: &String) -> APITokenInfoResponse {
+        APITokenInfoResponse {
+            is_current: &token.token_id == current_token_id,
+            token_id: token.token_id,
+            label: token.label,
+            scopes: token.scopes,
+            created_at: token.created_at.to_rfc3339(),
+            last_used_at: token.last_used_at.map(|last_used_at| last_used_at.to_rfc3339()),
+            expires_at: token.expires_at.map(|expires_at| expires_at.to_rfc3339())
+        }
+    }
+
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on all API tokens
+/// of a user.
+#[derive(Serialize)]
+pub struct APITokensResponse {
+    pub tokens: Vec<APITokenInfoResponse>
 }
 
 /// A data structure
@@ -72,6 +210,89 @@ pub struct UserPostResponse{
     pub content_type: String,
     pub user_id: String,
     pub content_text: String,
+    pub source_format: String,
+    pub rendered_html: String,
+    pub title: String,
+    pub excerpt: String,
+    pub cover_file_id: Option<String>,
+    pub author_name: String,
+    pub slug: String,
+    pub post_status: String,
+    pub publish_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub extra_fields: HashMap<String, Value>,
+    pub fields: Vec<ExtraContentFieldResponse>,
+    pub tags: Vec<PostTermResponse>,
+    pub categories: Vec<PostTermResponse>,
+    pub parent_id: Option<String>,
+    pub page_position: i32,
+}
+
+/// Implementing generic
+/// methods for the "UserPostResponse"
+/// structure.
+impl UserPostResponse {
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "UserPostResponse"
+    /// structure from an instance
+    /// of the "UserPost" model, the 
+    /// post's extra content fields, and 
+    /// the tags and categories assigned 
+    /// to it.
+    pub fn new(
+        post: UserPost, 
+        fields: Vec<ExtraContentField>, 
+        terms: Vec<AssignedTerm>
+    ) -> UserPostResponse {
+        let mut tags: Vec<PostTermResponse> = Vec::new();
+        let mut categories: Vec<PostTermResponse> = Vec::new();
+        for term in terms {
+            if term.taxonomy == "category" {
+                categories.push(PostTermResponse::new(term));
+            }
+            else {
+                tags.push(PostTermResponse::new(term));
+            }
+        }
+        let mut extra_fields: HashMap<String, Value> = HashMap::new();
+        let mut field_responses: Vec<ExtraContentFieldResponse> = Vec::new();
+        for field in fields {
+            let resp_field: ExtraContentFieldResponse = ExtraContentFieldResponse::new(field);
+            extra_fields.insert(resp_field.field_key.clone(), resp_field.field_value.clone());
+            field_responses.push(resp_field);
+        }
+        let rendered_html: String = match render_post_html(&post.source_format, &post.content_text) {
+            Ok(rendered_html) => rendered_html,
+            Err(_e) => escape_html(&post.content_text)
+        };
+        UserPostResponse {
+            content_id: post.content_id,
+            content_type: post.content_type,
+            user_id: post.user_id,
+            content_text: post.content_text,
+            source_format: post.source_format,
+            rendered_html,
+            title: post.title,
+            excerpt: post.excerpt,
+            cover_file_id: post.cover_file_id,
+            author_name: post.author_name,
+            slug: post.slug,
+            post_status: post.post_status,
+            publish_at: post.publish_at.map(|time| time.to_rfc3339()),
+            created_at: post.created_at.to_rfc3339(),
+            updated_at: post.updated_at.to_rfc3339(),
+            extra_fields,
+            fields: field_responses,
+            tags,
+            categories,
+            parent_id: post.parent_id,
+            page_position: post.page_position
+        }
+    }
+
 }
 
 /// A data structure
@@ -85,7 +306,31 @@ pub struct ExtraContentFieldResponse{
     pub field_id: String,
     pub content_id: String,
     pub field_key: String,
-    pub field_value: String
+    pub field_type: String,
+    pub field_value: Value,
+    pub created_at: String,
+    pub updated_at: String
+}
+
+impl ExtraContentFieldResponse {
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "ExtraContentFieldResponse"
+    /// structure from an instance
+    /// of the "ExtraContentField" model.
+    pub fn new(field: ExtraContentField) -> ExtraContentFieldResponse {
+        ExtraContentFieldResponse {
+            field_id: field.field_id,
+            content_id: field.content_id,
+            field_key: field.field_key,
+            field_value: field_value_to_json(&field.field_type, &field.field_value),
+            field_type: field.field_type,
+            created_at: field.created_at.to_rfc3339(),
+            updated_at: field.updated_at.to_rfc3339()
+        }
+    }
+
 }
 
 /// A data structure
@@ -98,7 +343,9 @@ pub struct UserFileResponse{
     pub user_id: String,
     pub file_url: String,
     pub file_name: String,
-    pub file_id: String
+    pub file_id: String,
+    pub created_at: String,
+    pub updated_at: String
 }
 
 /// A data structure
@@ -120,7 +367,8 @@ pub struct UserKeyCreationResponse {
 /// an instance.
 #[derive(Serialize)]
 pub struct InstanceUsersResponse{
-    pub users: Vec<UserCreationResponse>
+    pub users: Vec<UserCreationResponse>,
+    pub pagination: Pagination
 }
 
 /// A data structure
@@ -140,7 +388,8 @@ pub struct UserKeys{
 /// a user has made.
 #[derive(Serialize)]
 pub struct UserPosts{
-    pub posts: Vec<UserPostResponse>
+    pub posts: Vec<UserPostResponse>,
+    pub pagination: Pagination
 }
 
 /// A data structure
@@ -150,7 +399,8 @@ pub struct UserPosts{
 /// a user has uploaded.
 #[derive(Serialize)]
 pub struct UserFiles{
-    pub files: Vec<UserFileResponse>
+    pub files: Vec<UserFileResponse>,
+    pub pagination: Pagination
 }
 
 /// A data structure
@@ -169,5 +419,445 @@ pub struct InstanceResponse{
 /// as a JSON response.
 #[derive(Serialize)]
 pub struct UserKeysResponse{
-    pub keys: Vec<UserKeyCreationResponse>
+    pub keys: Vec<UserKeyCreationResponse>,
+    pub pagination: Pagination
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a saved revision
+/// of a user post.
+#[derive(Serialize)]
+pub struct PostRevisionResponse{
+    pub revision_id: String,
+    pub content_id: String,
+    pub user_id: String,
+    pub content_text: String,
+    pub extra_fields: Vec<FieldSnapshot>,
+    pub created_at: String,
+    pub title: String,
+    pub excerpt: String,
+    pub cover_file_id: Option<String>,
+    pub author_name: String
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on all revisions
+/// of a user post.
+#[derive(Serialize)]
+pub struct PostRevisions{
+    pub revisions: Vec<PostRevisionResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on the differences
+/// between two revisions
+/// of a user post.
+#[derive(Serialize)]
+pub struct RevisionDiffResponse{
+    pub from_revision: String,
+    pub to_revision: String,
+    pub text_changes: Vec<DiffLine>,
+    pub from_fields: Vec<FieldSnapshot>,
+    pub to_fields: Vec<FieldSnapshot>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a type of content
+/// and the fields it
+/// accepts.
+#[derive(Serialize)]
+pub struct ContentTypeResponse{
+    pub type_name: String,
+    pub description: String,
+    pub fields: Vec<FieldDefinition>,
+    pub created_at: String,
+    pub updated_at: String
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on all types of
+/// content.
+#[derive(Serialize)]
+pub struct ContentTypesResponse{
+    pub content_types: Vec<ContentTypeResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on all extra content
+/// fields of a post.
+#[derive(Serialize)]
+pub struct ExtraContentFields{
+    pub fields: Vec<ExtraContentFieldResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on the page of a
+/// list that was returned.
+#[derive(Serialize)]
+pub struct Pagination{
+    pub page: i64,
+    pub per_page: i64,
+    pub total_count: i64,
+    pub total_pages: i64,
+    pub sort_by: String,
+    pub sort_order: String
+}
+
+/// Implementing generic
+/// methods for the "Pagination"
+/// structure.
+impl Pagination{
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "Pagination"
+    /// structure from the options
+    /// used for listing and the total
+    /// number of matching records.
+    pub fn new(options: &ListOptions, total_count: i64) -> Pagination{
+        let total_pages: i64 = (total_count + options.per_page - 1) / options.per_page;
+        Pagination{
+            page: options.page,
+            per_page: options.per_page,
+            total_count,
+            total_pages,
+            sort_by: options.sort_by.clone(),
+            sort_order: options.sort_order.clone()
+        }
+    }
+
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a post matching
+/// a search.
+#[derive(Serialize)]
+pub struct SearchResultResponse{
+    pub rank: f32,
+    pub snippet: String,
+    pub post: UserPostResponse
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a page of posts
+/// matching a search.
+#[derive(Serialize)]
+pub struct SearchResults{
+    pub query: String,
+    pub results: Vec<SearchResultResponse>,
+    pub pagination: Pagination
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a tag or category
+/// assigned to a post.
+#[derive(Serialize)]
+pub struct PostTermResponse{
+    pub term_id: String,
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<String>
+}
+
+/// Implementing generic
+/// methods for the "PostTermResponse"
+/// structure.
+impl PostTermResponse{
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "PostTermResponse"
+    /// structure from a term 
+    /// assigned to a post.
+    pub fn new(term: AssignedTerm) -> PostTermResponse{
+        PostTermResponse{
+            term_id: term.term_id,
+            name: term.term_name,
+            slug: term.term_slug,
+            parent_id: term.parent_id
+        }
+    }
+
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a tag or category.
+#[derive(Serialize)]
+pub struct TaxonomyTermResponse{
+    pub term_id: String,
+    pub taxonomy: String,
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<String>,
+    pub description: String,
+    pub created_at: String,
+    pub updated_at: String
+}
+
+/// Implementing generic
+/// methods for the "TaxonomyTermResponse"
+/// structure.
+impl TaxonomyTermResponse{
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "TaxonomyTermResponse"
+    /// structure from an instance of
+    /// the "TaxonomyTerm" model.
+    pub fn new(term: TaxonomyTerm) -> TaxonomyTermResponse{
+        TaxonomyTermResponse{
+            term_id: term.term_id,
+            taxonomy: term.taxonomy,
+            name: term.term_name,
+            slug: term.term_slug,
+            parent_id: term.parent_id,
+            description: term.description,
+            created_at: term.created_at.to_rfc3339(),
+            updated_at: term.updated_at.to_rfc3339()
+        }
+    }
+
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on all tags or all
+/// categories.
+#[derive(Serialize)]
+pub struct TaxonomyTermsResponse{
+    pub terms: Vec<TaxonomyTermResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a tag or category
+/// and a page of the posts
+/// that have it.
+#[derive(Serialize)]
+pub struct TermPostsResponse{
+    pub term: TaxonomyTermResponse,
+    pub posts: Vec<UserPostResponse>,
+    pub pagination: Pagination
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a page and the
+/// pages below it.
+#[derive(Serialize)]
+pub struct PageTreeNode{
+    pub content_id: String,
+    pub title: String,
+    pub slug: String,
+    pub post_status: String,
+    pub page_position: i32,
+    pub children: Vec<PageTreeNode>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on the hierarchy of
+/// all pages of a user.
+#[derive(Serialize)]
+pub struct PageTreeResponse{
+    pub pages: Vec<PageTreeNode>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on an item of a 
+/// navigation menu and
+/// the items below it.
+#[derive(Serialize)]
+pub struct MenuItemResponse{
+    pub item_id: String,
+    pub label: String,
+    pub target_type: String,
+    pub content_id: Option<String>,
+    pub content_type: Option<String>,
+    pub slug: Option<String>,
+    pub file_id: Option<String>,
+    pub url: Option<String>,
+    pub item_position: i32,
+    pub children: Vec<MenuItemResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a navigation menu
+/// and all its items.
+#[derive(Serialize)]
+pub struct MenuResponse{
+    pub menu_id: String,
+    pub name: String,
+    pub slug: String,
+    pub max_depth: i32,
+    pub created_at: String,
+    pub updated_at: String,
+    pub items: Vec<MenuItemResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on all navigation 
+/// menus of a user.
+#[derive(Serialize)]
+pub struct MenusResponse{
+    pub menus: Vec<MenuResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a webhook.
+#[derive(Serialize)]
+pub struct WebhookResponse{
+    pub webhook_id: String,
+    pub url: String,
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String
+}
+
+/// Implementing generic
+/// methods for the "WebhookResponse"
+/// structure.
+impl WebhookResponse{
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "WebhookResponse"
+    /// structure from an instance of
+    /// the "Webhook" model. The secret
+    /// of the webhook is left out.
+    pub fn new(webhook: Webhook) -> WebhookResponse{
+        WebhookResponse{
+            webhook_id: webhook.webhook_id,
+            url: webhook.target_url,
+            events: webhook.events,
+            is_active: webhook.is_active,
+            created_at: webhook.created_at.to_rfc3339(),
+            updated_at: webhook.updated_at.to_rfc3339()
+        }
+    }
+
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a webhook that
+/// was just registered
+/// together with the secret
+/// its deliveries are signed
+/// with.
+#[derive(Serialize)]
+pub struct WebhookCreationResponse{
+    pub webhook: WebhookResponse,
+    pub secret: String
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on all webhooks of
+/// a user.
+#[derive(Serialize)]
+pub struct WebhooksResponse{
+    pub webhooks: Vec<WebhookResponse>
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a delivery of a
+/// webhook.
+#[derive(Serialize)]
+pub struct WebhookDeliveryResponse{
+    pub delivery_id: String,
+    pub event: String,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<String>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String
+}
+
+/// Implementing generic
+/// methods for the "WebhookDeliveryResponse"
+/// structure.
+impl WebhookDeliveryResponse{
+
+    /// Implementing a method
+    /// to create a new instance
+    /// of the "WebhookDeliveryResponse"
+    /// structure from an instance of
+    /// the "WebhookDelivery" model. The
+    /// time of the next attempt is only
+    /// returned for pending deliveries.
+    pub fn new(delivery: WebhookDelivery) -> WebhookDeliveryResponse{
+        let next_attempt_at: Option<String> = if delivery.delivery_status == "pending" {
+            Some(delivery.next_attempt_at.to_rfc3339())
+        }
+        else {
+            None
+        };
+        WebhookDeliveryResponse{
+            delivery_id: delivery.delivery_id,
+            event: delivery.event,
+            payload: serde_json::from_str(&delivery.payload).unwrap_or(Value::String(delivery.payload)),
+            status: delivery.delivery_status,
+            attempts: delivery.attempts,
+            next_attempt_at,
+            last_status_code: delivery.last_status_code,
+            last_error: delivery.last_error,
+            created_at: delivery.created_at.to_rfc3339(),
+            updated_at: delivery.updated_at.to_rfc3339()
+        }
+    }
+
+}
+
+/// A data structure
+/// to return info
+/// in JSON format
+/// on a page of the 
+/// deliveries of a webhook.
+#[derive(Serialize)]
+pub struct WebhookDeliveriesResponse{
+    pub deliveries: Vec<WebhookDeliveryResponse>,
+    pub pagination: Pagination
 }
@@ -3,6 +3,13 @@ Mocha Backend by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+/// Importing the
+/// "Pool" structure
+/// to pass a pool of
+/// database connections
+/// to background tasks.
+use sqlx::Pool;
+
 /// Importing the "App"
 /// structure to create a new
 /// Actix Web app.
@@ -13,6 +20,16 @@ use actix_web::App;
 /// rules.
 use actix_cors::Cors;
 
+/// Importing the "sleep"
+/// function to wait before
+/// listening for changes again.
+use tokio::time::sleep;
+
+/// Importing the "Duration"
+/// structure to specify how
+/// often background tasks run.
+use std::time::Duration;
+
 /// Importing the "Data"
 /// structure to register
 /// persistent app data.
@@ -22,6 +39,11 @@ use actix_web::web::Data;
 /// error structure.
 use super::err::CleoErr;
 
+/// Importing the function
+/// to spawn background tasks
+/// on the Actix Web runtime.
+use actix_web::rt::spawn;
+
 /// Importing the "HttpServer"
 /// structure to create an
 /// Actix Web app.
@@ -37,11 +59,32 @@ use super::units::AppData;
 /// typing.
 use super::units::Config;
 
+/// Importing the function
+/// to create a recurring timer
+/// for background tasks.
+use tokio::time::interval;
+
 /// Importing the "AadminInfo"
 /// structure for explicit
 /// typing.
 use super::units::AdminInfo;
 
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the "Sender"
+/// structure to send changes
+/// to content to open streams.
+use tokio::sync::broadcast::Sender;
+
+/// Importing the "from_fn"
+/// function to register 
+/// middleware written as
+/// functions.
+use actix_web::middleware::from_fn;
+
 /// Importing all service
 /// functions to create
 /// extra content fields.
@@ -74,14 +117,43 @@ use crate::modules::services::admin::*;
 /// user posts.
 use crate::modules::services::posts::*;
 
+/// Importing all service functions for
+/// arranging pages in a hierarchy.
+use crate::modules::services::pages::*;
+
+/// Importing all service functions for
+/// managing navigation menus.
+use crate::modules::services::menus::*;
+
+/// Importing all service functions for
+/// publishing feeds and sitemaps of posts.
+use crate::modules::services::feeds::*;
+
+/// Importing the structure
+/// describing a change to 
+/// content.
+use crate::modules::units::ChangeEvent;
+
+/// Importing the middleware
+/// to authenticate requests.
+use crate::modules::auth::authenticate;
+
 /// Importing all service functions for
 /// creating and deleting API tokens.
 use crate::modules::services::tokens::*;
 
+/// Importing the service function for
+/// searching published posts.
+use crate::modules::services::search::*;
+
 /// Importing all service functions for
 /// retrieving info on different things.
 use crate::modules::services::general::*;
 
+/// Importing all service functions for
+/// streaming changes to content.
+use crate::modules::services::changes::*;
+
 /// Importing the "DefaultHeaders" structure
 /// to set custom headers.
 use actix_web::middleware::DefaultHeaders;
@@ -90,10 +162,100 @@ use actix_web::middleware::DefaultHeaders;
 /// neccessary data from the enviroment.
 use crate::modules::config::create_config;
 
+/// Importing all service functions for
+/// managing webhooks.
+use crate::modules::services::webhooks::*;
+
+/// Importing all service functions for
+/// managing tags and categories.
+use crate::modules::services::taxonomy::*;
+
+/// Importing the number of seconds to
+/// wait before listening for changes again.
+use crate::modules::changes::CHANGE_RETRY;
+
+/// Importing all service functions for
+/// listing, comparing, and restoring
+/// post revisions.
+use crate::modules::services::revisions::*;
+
 /// Importing the function to create the
 /// neccessary entities for the app.
 use crate::modules::config::create_admin_info;
 
+/// Importing all service functions for
+/// defining types of content.
+use crate::modules::services::content_types::*;
+
+/// Importing the function to pass changes
+/// to content on to open streams.
+use crate::modules::changes::listen_for_changes;
+
+/// Importing the function to send
+/// all deliveries of webhooks that
+/// are due.
+use crate::modules::webhooks::deliver_due_webhooks;
+
+/// Importing the function to publish
+/// all scheduled posts that are due.
+use crate::modules::db::posts::publish_scheduled_posts;
+
+/// The number of seconds to wait
+/// between checks for scheduled posts
+/// that are due to be published.
+pub const SCHEDULER_INTERVAL: u64 = 60;
+
+/// Runs a loop that publishes all
+/// scheduled posts whose time of
+/// publication has passed. Errors
+/// are reported but do not stop
+/// the loop.
+pub async fn run_post_scheduler(pool: Pool<Postgres>) {
+    let mut timer = interval(Duration::from_secs(SCHEDULER_INTERVAL));
+    loop {
+        timer.tick().await;
+        match publish_scheduled_posts(&pool).await {
+            Ok(_published) => {},
+            Err(e) => eprintln!("{}", &e.to_string())
+        };
+    }
+}
+
+/// The number of seconds to wait
+/// between checks for deliveries of
+/// webhooks that are due.
+pub const WEBHOOK_INTERVAL: u64 = 10;
+
+/// Runs a loop that sends all
+/// deliveries of webhooks that are
+/// due. Errors are reported but do 
+/// not stop the loop.
+pub async fn run_webhook_dispatcher(pool: Pool<Postgres>) {
+    let mut timer = interval(Duration::from_secs(WEBHOOK_INTERVAL));
+    loop {
+        timer.tick().await;
+        match deliver_due_webhooks(&pool).await {
+            Ok(_attempted) => {},
+            Err(e) => eprintln!("{}", &e.to_string())
+        };
+    }
+}
+
+/// Runs a loop that passes changes
+/// to content announced in the database
+/// on to all open streams. If the connection
+/// is lost, the error is reported and the
+/// loop listens again after a short wait.
+pub async fn run_change_listener(pool: Pool<Postgres>, sender: Sender<ChangeEvent>) {
+    loop {
+        match listen_for_changes(&pool, &sender).await {
+            Ok(_listened) => {},
+            Err(e) => eprintln!("{}", &e.to_string())
+        };
+        sleep(Duration::from_secs(CHANGE_RETRY)).await;
+    }
+}
+
 /// Attempts to run the app with some environment
 /// variables set. If this operations fails,
 /// an error is returned.
@@ -108,17 +270,21 @@ pub async fn run_app() -> Result<(), CleoErr> {
     };
     if admin_info.admin.username == config.admin_username && admin_info.instance == 0 {
         let data: Data<AppData> = Data::new(AppData::new(&admin_info.pool));
+        spawn(run_post_scheduler(admin_info.pool.clone()));
+        spawn(run_webhook_dispatcher(admin_info.pool.clone()));
+        spawn(run_change_listener(admin_info.pool.clone(), data.changes.clone()));
         let server = match HttpServer::new(
         move || {
             let cors = Cors::permissive()
                 .allow_any_origin()
                 .allowed_methods(vec!["GET", "POST"]);
             App::new()
+                .wrap(from_fn(authenticate))
                 .wrap(cors)
                 .wrap(DefaultHeaders::new()
                     .add(("Access-Control-Allow-Origin", "*"))
                     .add(("Access-Control-Allow-Methods", "GET,POST"))
-                    .add(("Access-Control-Allow-Headers", "Origin, X-Requested-With, Content-Type, Accept"))
+                    .add(("Access-Control-Allow-Headers", "Origin, X-Requested-With, Content-Type, Accept, Authorization"))
                 )
                 .app_data(data.clone())
                 .service(create_user_service)
@@ -132,11 +298,25 @@ pub async fn run_app() -> Result<(), CleoErr> {
                 .service(delete_api_token_service)
                 .service(create_user_post_service)
                 .service(update_user_post_service)
+                .service(publish_user_post_service)
+                .service(unpublish_user_post_service)
+                .service(archive_user_post_service)
+                .service(schedule_user_post_service)
                 .service(delete_user_post_service)
+                .service(update_user_post_slug_service)
+                .service(get_post_revisions_service)
+                .service(diff_post_revisions_service)
+                .service(restore_post_revision_service)
+                .service(create_content_type_service)
+                .service(get_content_types_service)
+                .service(delete_content_type_service)
+                .service(set_content_type_field_service)
+                .service(delete_content_type_field_service)
                 .service(create_extra_content_field_service)
                 .service(edit_extra_content_field_key_service)
                 .service(edit_extra_content_field_value_service)
                 .service(delete_extra_content_field_service)
+                .service(get_extra_content_fields_service)
                 .service(delete_user_file_service)
                 .service(create_user_key_service)
                 .service(delete_user_key_service)
@@ -145,11 +325,55 @@ pub async fn run_app() -> Result<(), CleoErr> {
                 .service(get_user_keys_service)
                 .service(get_user_files_service)
                 .service(get_user_posts_service)
+                .service(get_public_posts_service)
+                .service(get_public_pages_service)
+                .service(get_public_post_service)
+                .service(get_public_post_by_slug_service)
+                .service(search_posts_service)
+                .service(create_term_service)
+                .service(edit_term_service)
+                .service(move_term_service)
+                .service(delete_term_service)
+                .service(assign_term_service)
+                .service(unassign_term_service)
+                .service(get_terms_service)
+                .service(get_term_posts_service)
+                .service(move_page_service)
+                .service(reorder_pages_service)
+                .service(get_page_tree_service)
+                .service(get_public_page_tree_service)
+                .service(create_menu_service)
+                .service(edit_menu_service)
+                .service(delete_menu_service)
+                .service(get_menus_service)
+                .service(add_menu_item_service)
+                .service(edit_menu_item_service)
+                .service(move_menu_item_service)
+                .service(delete_menu_item_service)
+                .service(get_public_menu_service)
+                .service(get_instance_feed_service)
+                .service(get_user_feed_service)
+                .service(get_sitemap_service)
+                .service(get_sitemap_page_service)
+                .service(create_webhook_service)
+                .service(edit_webhook_service)
+                .service(delete_webhook_service)
+                .service(get_webhooks_service)
+                .service(get_webhook_deliveries_service)
+                .service(stream_changes_service)
+                .service(get_api_tokens_service)
+                .service(revoke_api_token_service)
+                .service(revoke_other_api_tokens_service)
+                .service(request_password_reset_service)
+                .service(reset_password_service)
                 .service(edit_instance_name_service)
                 .service(edit_instance_hostname_service)
+                .service(edit_password_reset_url_service)
+                .service(edit_site_url_service)
                 .service(edit_smtp_server_service)
                 .service(edit_smtp_username_service)
                 .service(edit_smtp_password_service)
+                .service(edit_webhook_allowlist_service)
                 .service(verify_email_service)
                 .service(create_user_file_service)
                 .service(static_file_service)
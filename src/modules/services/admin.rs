@@ -8,6 +8,12 @@ Licensed under the FSL v1.
 /// that accepts "POST" requests.
 use actix_web::post;
 
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
 /// Importing the "Result"
 /// enum for Actix Web services.
 use actix_web::Result;
@@ -22,6 +28,11 @@ use actix_web::web::Data;
 /// responses.
 use actix_web::web::Json;
 
+/// Importing the "Query" structure
+/// to read the parameters of requests
+/// from their query strings.
+use actix_web::web::Query;
+
 /// Importing the function
 /// to return a HTTP response.
 use actix_web::HttpResponse;
@@ -39,17 +50,46 @@ use crate::modules::units::AppData;
 /// users for explicit typing.
 use crate::modules::models::CleoUser;
 
+/// Importing the structure holding
+/// the options for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the data structure for
+/// returning information on the page
+/// of a list.
+use crate::modules::responses::Pagination;
+
+/// Importing the data structure for
+/// submitting a payload for listing
+/// records.
+use crate::modules::payloads::ListPayload;
+
+/// Importing the function to read
+/// the parameters of a request from
+/// its query string or its body.
+use crate::modules::extractors::read_params;
+
+/// Importing the scope for
+/// administering the
+/// instance.
+use crate::modules::db::tokens::SCOPE_ADMIN;
+
+/// Importing the function to edit
+/// the URL of the public website
+/// of the instance.
+use crate::modules::db::admin::edit_site_url;
+
 /// Importing the data structure
 /// for returning info on whether
 /// a write operation was successful
 /// or not.
 use crate::modules::responses::StatusResponse;
 
-/// Importing the structure for 
-/// submitting payloads for actions
-/// that only require an API token.
-use crate::modules::payloads::TokenOnlyPayload;
-
 /// Importing the function
 /// to edit the password of
 /// the user for the instance's
@@ -62,6 +102,14 @@ use crate::modules::db::admin::edit_smtp_pass;
 /// user.
 use crate::modules::payloads::UserChangePayload;
 
+/// Importing the fields the users
+/// of an instance can be sorted by.
+use crate::modules::db::admin::USER_SORT_FIELDS;
+
+/// Importing the function to check
+/// the options for listing records.
+use crate::modules::utils::resolve_list_options;
+
 /// Importing the function to get
 /// a list of instance users.
 use crate::modules::db::admin::get_instance_users;
@@ -92,6 +140,21 @@ use crate::modules::responses::InstanceUsersResponse;
 /// the hostname of an instance.
 use crate::modules::db::admin::edit_instance_hostname;
 
+/// Importing the function to edit
+/// the hosts webhooks may reach even
+/// though they are not public.
+use crate::modules::db::admin::edit_webhook_allowlist;
+
+/// Importing the structure for
+/// the payload to edit the hosts
+/// webhooks may reach.
+use crate::modules::payloads::WebhookAllowlistPayload;
+
+/// Importing the function to edit
+/// the URL of the page users choose
+/// a new password on.
+use crate::modules::db::admin::edit_password_reset_url;
+
 /// Importing the function to update
 /// the address of the instance's SMTP
 /// server.
@@ -105,13 +168,40 @@ use crate::modules::db::admin::edit_instance_smtp_server;
 /// instances of the "UserCreationResponse" structure
 /// as a JSON response is returned. In any other
 /// case an error is returned.
-#[post("/instance/admins")]
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/instance/admins", method = "GET", method = "POST")]
 pub async fn get_instance_admins_service(
-    payload: Json<TokenOnlyPayload>,
+    query: Option<Query<ListPayload>>,
+    body: Option<Json<ListPayload>>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr>{
-    let admins: Vec<CleoUser> = match get_instance_admins(&payload.api_token, &data.pool).await {
-        Ok(admins) => admins,
+    let payload: ListPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let options: ListOptions = match resolve_list_options(
+        &payload.page, 
+        &payload.per_page, 
+        &payload.sort_by, 
+        &payload.sort_order, 
+        &USER_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (admins, total): (Vec<CleoUser>, i64) = match get_instance_admins(
+        &user, 
+        &options, 
+        &data.pool
+    ).await {
+        Ok(page) => page,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
     let mut result: Vec<UserCreationResponse> = Vec::new();
@@ -123,11 +213,16 @@ pub async fn get_instance_admins_service(
             username: admin.username,
             email_addr: admin.email_addr,
             pfp_url: admin.pfp_url,
-            is_admin: admin.is_admin
+            is_admin: admin.is_admin,
+            created_at: admin.created_at.to_rfc3339(),
+            updated_at: admin.updated_at.to_rfc3339()
         };
         result.push(user);
     }
-    Ok(HttpResponse::Ok().json(InstanceUsersResponse{ users: result }))    
+    Ok(HttpResponse::Ok().json(InstanceUsersResponse{ 
+        users: result, 
+        pagination: Pagination::new(&options, total) 
+    }))    
 }
 
 /// This function is the API service
@@ -138,13 +233,40 @@ pub async fn get_instance_admins_service(
 /// instances of the "UserCreationResponse" structure
 /// as a JSON response is returned. In any other
 /// case an error is returned.
-#[post("/instance/users")]
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/instance/users", method = "GET", method = "POST")]
 pub async fn get_instance_users_service(
-    payload: Json<TokenOnlyPayload>,
+    query: Option<Query<ListPayload>>,
+    body: Option<Json<ListPayload>>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr>{
-    let admins: Vec<CleoUser> = match get_instance_users(&payload.api_token, &data.pool).await {
-        Ok(admins) => admins,
+    let payload: ListPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let options: ListOptions = match resolve_list_options(
+        &payload.page, 
+        &payload.per_page, 
+        &payload.sort_by, 
+        &payload.sort_order, 
+        &USER_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (admins, total): (Vec<CleoUser>, i64) = match get_instance_users(
+        &user, 
+        &options, 
+        &data.pool
+    ).await {
+        Ok(page) => page,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
     let mut result: Vec<UserCreationResponse> = Vec::new();
@@ -156,11 +278,16 @@ pub async fn get_instance_users_service(
             username: admin.username,
             email_addr: admin.email_addr,
             pfp_url: admin.pfp_url,
-            is_admin: admin.is_admin
+            is_admin: admin.is_admin,
+            created_at: admin.created_at.to_rfc3339(),
+            updated_at: admin.updated_at.to_rfc3339()
         };
         result.push(user);
     }
-    Ok(HttpResponse::Ok().json(InstanceUsersResponse{ users: result }))    
+    Ok(HttpResponse::Ok().json(InstanceUsersResponse{ 
+        users: result, 
+        pagination: Pagination::new(&options, total) 
+    }))    
 }
 
 /// This function is the API service
@@ -173,10 +300,15 @@ pub async fn get_instance_users_service(
 #[post("/instance/edit/name")]
 pub async fn edit_instance_name_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match edit_instance_name(
-        &payload.api_token, 
+        &user, 
         &payload.new_value,
         &data.pool
     ).await {
@@ -196,10 +328,74 @@ pub async fn edit_instance_name_service(
 #[post("/instance/edit/hostname")]
 pub async fn edit_instance_hostname_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match edit_instance_hostname(
-        &payload.api_token, 
+        &user, 
+        &payload.new_value,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
+
+/// This function is the API service
+/// function for editing the URL of the
+/// page users of the current Cleo instance
+/// choose a new password on. If the 
+/// received request and resulting operation
+/// are both valid, an instance of the 
+/// "StatusResponse" as a JSON response 
+/// is returned. 
+#[post("/instance/edit/password/reset")]
+pub async fn edit_password_reset_url_service(
+    payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let update_op: bool = match edit_password_reset_url(
+        &user, 
+        &payload.new_value,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
+
+/// This function is the API service
+/// function for editing the URL of the
+/// public website that shows the posts
+/// of the current Cleo instance. Links
+/// in feeds and sitemaps point to this
+/// website. If the received request and
+/// resulting operation are both valid, an
+/// instance of the "StatusResponse" as a
+/// JSON response is returned. 
+#[post("/instance/edit/site")]
+pub async fn edit_site_url_service(
+    payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let update_op: bool = match edit_site_url(
+        &user, 
         &payload.new_value,
         &data.pool
     ).await {
@@ -220,10 +416,15 @@ pub async fn edit_instance_hostname_service(
 #[post("/instance/edit/smtp/server")]
 pub async fn edit_smtp_server_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse { 
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match edit_instance_smtp_server(
-        &payload.api_token, 
+        &user, 
         &payload.new_value,
         &data.pool
     ).await {
@@ -244,10 +445,15 @@ pub async fn edit_smtp_server_service(
 #[post("/instance/edit/smtp/username")]
 pub async fn edit_smtp_username_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match edit_smtp_username(
-        &payload.api_token, 
+        &user, 
         &payload.new_value,
         &data.pool
     ).await {
@@ -268,10 +474,15 @@ pub async fn edit_smtp_username_service(
 #[post("/instance/edit/smtp/pass")]
 pub async fn edit_smtp_password_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse { 
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match edit_smtp_pass(
-        &payload.api_token, 
+        &user, 
         &payload.new_value,
         &data.pool
     ).await {
@@ -280,3 +491,32 @@ pub async fn edit_smtp_password_service(
     };
     HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
 }
+
+/// This function is the API service
+/// function for replacing the hosts
+/// webhooks on the current Cleo instance
+/// may be delivered to even though they
+/// are not public. If the received request
+/// and resulting operation are both valid,
+/// an instance of the "StatusResponse" as
+/// a JSON response is returned. 
+#[post("/instance/edit/webhooks/allowlist")]
+pub async fn edit_webhook_allowlist_service(
+    payload: Json<WebhookAllowlistPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse { 
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let update_op: bool = match edit_webhook_allowlist(
+        &user, 
+        &payload.hosts,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
@@ -0,0 +1,122 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "get"
+/// macro to make a service
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Query"
+/// structure to extract the
+/// query string of requests.
+use actix_web::web::Query;
+
+/// Importing the "HttpResponse"
+/// structure to return an HTTP
+/// response.
+use actix_web::HttpResponse;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the structure for the
+/// body of a stream of changes.
+use crate::modules::changes::ChangeStream;
+
+/// Importing the function to open
+/// a stream of the changes to the
+/// content of a user.
+use crate::modules::changes::stream_user_changes;
+
+/// Importing the scope for
+/// reading private
+/// content.
+use crate::modules::db::tokens::SCOPE_CONTENT_READ;
+
+/// Importing the structure to extract
+/// the API token and the events from
+/// the query string.
+use crate::modules::extractors::ChangeStreamExtractor;
+
+/// Importing the function to check that
+/// all supplied events are known.
+use crate::modules::db::webhooks::validate_webhook_events;
+
+/// This function is the API
+/// service function for streaming
+/// the changes to the posts, fields,
+/// and files of a user as Server-Sent
+/// Events. Browsers cannot send headers
+/// with an "EventSource", so the API token
+/// can also be supplied in the query string
+/// as a deprecated fallback. The events can be
+/// limited with a comma-separated list.
+/// If the received request is valid, the
+/// stream stays open until the client 
+/// disconnects. In any other case an error
+/// is returned.
+#[get("/changes/stream")]
+pub async fn stream_changes_service(
+    query: Query<ChangeStreamExtractor>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&query.api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let events: Option<Vec<String>> = match &query.events {
+        Some(events) => {
+            let events: Vec<String> = events
+                .split(',')
+                .map(|event| event.trim().to_string())
+                .filter(|event| !event.is_empty())
+                .collect();
+            match validate_webhook_events(&events){
+                Ok(_valid) => {},
+                Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+            };
+            Some(events)
+        },
+        None => None
+    };
+    let stream: ChangeStream = stream_user_changes(
+        &user.user_id, 
+        &events, 
+        data.changes.subscribe()
+    );
+    Ok(
+        HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header(("Cache-Control", "no-cache"))
+            .insert_header(("X-Accel-Buffering", "no"))
+            .body(stream)
+    )
+}
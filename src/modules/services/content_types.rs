@@ -0,0 +1,331 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Pool"
+/// structure for explicit
+/// typing.
+use sqlx::Pool;
+
+/// Importing the "get"
+/// decorator to make a service
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "post"
+/// decorator to make a service
+/// that accepts "POST" requests.
+use actix_web::post;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Json"
+/// structure to return JSON
+/// responses.
+use actix_web::web::Json;
+
+/// Importing the function
+/// to return a HTTP response.
+use actix_web::HttpResponse;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing this crate's
+/// error structure.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to register
+/// persistent app data.
+use crate::modules::units::AppData;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the structure
+/// describing a field a type
+/// of content accepts.
+use crate::modules::units::FieldDefinition;
+
+/// Importing the scope for
+/// administering the
+/// instance.
+use crate::modules::db::tokens::SCOPE_ADMIN;
+
+/// Importing the model for
+/// the fields of a type of
+/// content for explicit typing.
+use crate::modules::models::ContentTypeField;
+
+/// Importing the data structure
+/// for returning info on whether
+/// a write operation was successful
+/// or not.
+use crate::modules::responses::StatusResponse;
+
+/// Importing the model for
+/// types of content for explicit
+/// typing.
+use crate::modules::models::ContentTypeDefinition;
+
+/// Importing the data structure
+/// for returning information on
+/// a type of content.
+use crate::modules::responses::ContentTypeResponse;
+
+/// Importing the data structure
+/// for returning information on
+/// all types of content.
+use crate::modules::responses::ContentTypesResponse;
+
+/// Importing the data structure for
+/// submitting a payload for changing
+/// a field of a type of content.
+use crate::modules::payloads::ContentTypeFieldPayload;
+
+/// Importing the function to
+/// retrieve all types of content.
+use crate::modules::db::content_types::get_content_types;
+
+/// Importing the data structure for
+/// submitting a payload for defining
+/// a new type of content.
+use crate::modules::payloads::ContentTypeCreationPayload;
+
+/// Importing the data structure for
+/// submitting a payload for deleting
+/// a type of content.
+use crate::modules::payloads::ContentTypeDeletionPayload;
+
+/// Importing the function to
+/// define a new type of content.
+use crate::modules::db::content_types::create_content_type;
+
+/// Importing the function to
+/// delete a type of content.
+use crate::modules::db::content_types::delete_content_type;
+
+/// Importing the function to add or
+/// change a field of a type of content.
+use crate::modules::db::content_types::set_content_type_field;
+
+/// Importing the data structure for
+/// submitting a payload for removing
+/// a field from a type of content.
+use crate::modules::payloads::ContentTypeFieldDeletionPayload;
+
+/// Importing the function to retrieve
+/// the fields of a type of content.
+use crate::modules::db::content_types::get_content_type_fields;
+
+/// Importing the function to remove
+/// a field from a type of content.
+use crate::modules::db::content_types::delete_content_type_field;
+
+/// Attempts to create an instance of the
+/// "ContentTypeResponse" structure for a type
+/// of content, including the fields it accepts.
+/// If this operation fails, an error is returned.
+pub async fn content_type_to_response(
+    content_type: ContentTypeDefinition,
+    pool: &Pool<Postgres>
+) -> Result<ContentTypeResponse, CleoErr> {
+    let fields: Vec<ContentTypeField> = match get_content_type_fields(&content_type.type_name, pool).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<ContentTypeResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut definitions: Vec<FieldDefinition> = Vec::new();
+    for field in fields {
+        definitions.push(
+            FieldDefinition{
+                field_key: field.field_key,
+                field_type: field.field_type,
+                is_required: field.is_required
+            }
+        );
+    }
+    let resp: ContentTypeResponse = ContentTypeResponse{
+        type_name: content_type.type_name,
+        description: content_type.description,
+        fields: definitions,
+        created_at: content_type.created_at.to_rfc3339(),
+        updated_at: content_type.updated_at.to_rfc3339()
+    };
+    Ok(resp)
+}
+
+/// This function is the API
+/// service function for defining
+/// a new type of content. Only 
+/// administrators can do this. If the
+/// received request and resulting operation 
+/// are both valid, an instance of the 
+/// "ContentTypeResponse" structure is 
+/// returned. In any other case an error is
+/// returned.
+#[post("/content-types/create")]
+pub async fn create_content_type_service(
+    payload: Json<ContentTypeCreationPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let content_type: ContentTypeDefinition = match create_content_type(
+        &user, 
+        &payload.type_name, 
+        &payload.description.clone().unwrap_or_default(),
+        &payload.fields,
+        &data.pool
+    ).await {
+        Ok(content_type) => content_type,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: ContentTypeResponse = match content_type_to_response(content_type, &data.pool).await {
+        Ok(resp) => resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// all types of content and the fields
+/// they accept. No credentials are required.
+/// If the operation is successful, an instance
+/// of the "ContentTypesResponse" structure is 
+/// returned. In any other case an error is
+/// returned.
+#[get("/content-types")]
+pub async fn get_content_types_service(
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let mut sanitized: Vec<ContentTypeResponse> = Vec::new();
+    let content_types: Vec<ContentTypeDefinition> = match get_content_types(&data.pool).await {
+        Ok(content_types) => content_types,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    for content_type in content_types {
+        let resp_type: ContentTypeResponse = match content_type_to_response(content_type, &data.pool).await {
+            Ok(resp_type) => resp_type,
+            Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        sanitized.push(resp_type);
+    }
+    let resp: ContentTypesResponse = ContentTypesResponse{ content_types: sanitized };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API service
+/// function for deleting a type of
+/// content. Only administrators can do
+/// this. If the received request and 
+/// resulting operation are both valid, 
+/// an instance of the "StatusResponse" with 
+/// a boolean flag is returned as a JSON 
+/// response. 
+#[post("/content-types/delete")]
+pub async fn delete_content_type_service(
+    payload: Json<ContentTypeDeletionPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let del_op: bool = match delete_content_type(
+        &user, 
+        &payload.type_name,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
+}
+
+/// This function is the API service
+/// function for adding a field to a
+/// type of content or for changing 
+/// whether a field is required. Only
+/// administrators can do this. If the 
+/// received request and resulting operation
+/// are both valid, an instance of the 
+/// "StatusResponse" with a boolean flag 
+/// is returned as a JSON response. 
+#[post("/content-types/fields/set")]
+pub async fn set_content_type_field_service(
+    payload: Json<ContentTypeFieldPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let field: FieldDefinition = FieldDefinition{
+        field_key: payload.field_key.clone(),
+        field_type: payload.field_type.clone(),
+        is_required: payload.is_required
+    };
+    let update_op: bool = match set_content_type_field(
+        &user, 
+        &payload.type_name,
+        &field,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
+
+/// This function is the API service
+/// function for removing a field from 
+/// a type of content. Only administrators
+/// can do this. If the received request 
+/// and resulting operation are both valid, 
+/// an instance of the "StatusResponse" with 
+/// a boolean flag is returned as a JSON 
+/// response. 
+#[post("/content-types/fields/delete")]
+pub async fn delete_content_type_field_service(
+    payload: Json<ContentTypeFieldDeletionPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let del_op: bool = match delete_content_type_field(
+        &user, 
+        &payload.type_name,
+        &payload.field_key,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
+}
@@ -3,11 +3,22 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+/// Importing the "Pool"
+/// structure for explicit
+/// typing.
+use sqlx::Pool;
+
 /// Importing the "post"
 /// decorator to make a service
 /// that accepts "POST" requests.
 use actix_web::post;
 
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
 /// Importing the "Result"
 /// enum for Actix Web services.
 use actix_web::Result;
@@ -22,10 +33,25 @@ use actix_web::web::Data;
 /// responses.
 use actix_web::web::Json;
 
+/// Importing the "Query" structure
+/// to read the parameters of requests
+/// from their query strings.
+use actix_web::web::Query;
+
 /// Importing the function
 /// to return a HTTP response.
 use actix_web::HttpResponse;
 
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the "HashMap"
+/// structure to group extra
+/// content fields by post.
+use std::collections::HashMap;
+
 /// Importing this crate's
 /// error structure.
 use crate::modules::err::CleoErr;
@@ -35,6 +61,30 @@ use crate::modules::err::CleoErr;
 /// persistent app data.
 use crate::modules::units::AppData;
 
+/// Importing the model for
+/// user posts for explicit
+/// typing.
+use crate::modules::models::UserPost;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure holding
+/// a term together with the post it
+/// is assigned to.
+use crate::modules::units::AssignedTerm;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the function to read
+/// the parameters of a request from
+/// its query string or its body.
+use crate::modules::extractors::read_params;
+
 /// Importing the strcuture for modelling
 /// extra content fields in the database.
 use crate::modules::models::ExtraContentField;
@@ -45,6 +95,43 @@ use crate::modules::models::ExtraContentField;
 /// or not.
 use crate::modules::responses::StatusResponse;
 
+/// Importing the data structure for
+/// submitting a payload for listing
+/// the extra content fields of a post.
+use crate::modules::payloads::PostFieldsPayload;
+
+/// Importing the data structure for
+/// returning information on posts.
+use crate::modules::responses::UserPostResponse;
+
+/// Importing the data structure for
+/// returning all extra content fields
+/// of a post.
+use crate::modules::responses::ExtraContentFields;
+
+/// Importing the scope for
+/// reading private
+/// content.
+use crate::modules::db::tokens::SCOPE_CONTENT_READ;
+
+/// Importing the scope for
+/// writing content.
+use crate::modules::db::tokens::SCOPE_CONTENT_WRITE;
+
+/// Importing the function to retrieve
+/// the tags and categories of several
+/// posts at once.
+use crate::modules::db::taxonomy::get_terms_for_posts;
+
+/// Importing the function to retrieve
+/// all extra content fields of a post.
+use crate::modules::db::ecf::get_extra_fields_for_post;
+
+/// Importing the function to retrieve
+/// the extra content fields of several
+/// posts at once.
+use crate::modules::db::ecf::get_extra_fields_for_posts;
+
 /// Importing the data structure for returning
 /// information on an extra content field that
 /// has been created.
@@ -67,6 +154,11 @@ use crate::modules::db::ecf::edit_extra_field_key_for_post;
 /// field.
 use crate::modules::payloads::EditExtraContentFieldPayload;
 
+/// Importing the function to retrieve
+/// all extra content fields of a post
+/// a user has made.
+use crate::modules::db::ecf::get_user_extra_fields_for_post;
+
 /// Importing the function to edit the value
 /// of an extra content field of a post.
 use crate::modules::db::ecf::edit_extra_field_value_for_post;
@@ -81,6 +173,70 @@ use crate::modules::payloads::DeleteExtraContentFieldPayload;
 /// field.
 use crate::modules::payloads::ExtraContentFieldCreationPayload;
 
+/// Attempts to create an instance of the
+/// "UserPostResponse" structure for a post
+/// with its extra content fields, tags and
+/// categories embedded. If this operation 
+/// fails, an error is returned.
+pub async fn post_to_response(
+    post: UserPost,
+    pool: &Pool<Postgres>
+) -> Result<UserPostResponse, CleoErr> {
+    let fields: Vec<ExtraContentField> = match get_extra_fields_for_post(&post.content_id, pool).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<UserPostResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let terms: Vec<AssignedTerm> = match get_terms_for_posts(&vec![post.content_id.clone()], pool).await {
+        Ok(terms) => terms,
+        Err(e) => return Err::<UserPostResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(UserPostResponse::new(post, fields, terms))
+}
+
+/// Attempts to create instances of the
+/// "UserPostResponse" structure for several
+/// posts with their extra content fields, 
+/// tags and categories embedded. The fields 
+/// and the terms of all posts are each fetched
+/// with a single query. If this operation fails,
+/// an error is returned.
+pub async fn posts_to_responses(
+    posts: Vec<UserPost>,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserPostResponse>, CleoErr> {
+    let content_ids: Vec<String> = posts
+        .iter()
+        .map(|post| post.content_id.clone())
+        .collect();
+    let fields: Vec<ExtraContentField> = match get_extra_fields_for_posts(&content_ids, pool).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<Vec<UserPostResponse>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let terms: Vec<AssignedTerm> = match get_terms_for_posts(&content_ids, pool).await {
+        Ok(terms) => terms,
+        Err(e) => return Err::<Vec<UserPostResponse>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut grouped: HashMap<String, Vec<ExtraContentField>> = HashMap::new();
+    for field in fields {
+        grouped.entry(field.content_id.clone()).or_default().push(field);
+    }
+    let mut grouped_terms: HashMap<String, Vec<AssignedTerm>> = HashMap::new();
+    for term in terms {
+        grouped_terms.entry(term.content_id.clone()).or_default().push(term);
+    }
+    let mut result: Vec<UserPostResponse> = Vec::new();
+    for post in posts {
+        let post_fields: Vec<ExtraContentField> = grouped
+            .remove(&post.content_id)
+            .unwrap_or_default();
+        let post_terms: Vec<AssignedTerm> = grouped_terms
+            .remove(&post.content_id)
+            .unwrap_or_default();
+        result.push(UserPostResponse::new(post, post_fields, post_terms));
+    }
+    Ok(result)
+}
+
 /// This function is the API service
 /// function for creating an extra
 /// content field on a post of a user.
@@ -92,24 +248,25 @@ use crate::modules::payloads::ExtraContentFieldCreationPayload;
 #[post("/ecf/create")]
 pub async fn create_extra_content_field_service(
     payload: Json<ExtraContentFieldCreationPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let field: ExtraContentField = match create_extra_field_for_post(
-        &payload.api_token, 
+        &user, 
         &payload.content_id, 
         &payload.field_key, 
         &payload.field_value, 
+        &payload.field_type, 
         &data.pool
     ).await {
         Ok(field) => field,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let resp: ExtraContentFieldResponse = ExtraContentFieldResponse {
-        field_id: field.field_id,
-        content_id: field.content_id,
-        field_key: field.field_key,
-        field_value: field.field_value
-    };
+    let resp: ExtraContentFieldResponse = ExtraContentFieldResponse::new(field);
     Ok(HttpResponse::Ok().json(resp))
 }
 
@@ -123,10 +280,15 @@ pub async fn create_extra_content_field_service(
 #[post("/ecf/edit/key")]
 pub async fn edit_extra_content_field_key_service(
     payload: Json<EditExtraContentFieldPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match edit_extra_field_key_for_post(
-        &payload.api_token, 
+        &user, 
         &payload.content_id,
         &payload.field_id,
         &payload.new_value,
@@ -148,10 +310,15 @@ pub async fn edit_extra_content_field_key_service(
 #[post("/ecf/edit/value")]
 pub async fn edit_extra_content_field_value_service(
     payload: Json<EditExtraContentFieldPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let del_op: bool = match edit_extra_field_value_for_post(
-        &payload.api_token, 
+        &user, 
         &payload.content_id,
         &payload.field_id,
         &payload.new_value,
@@ -173,10 +340,15 @@ pub async fn edit_extra_content_field_value_service(
 #[post("/ecf/delete")]
 pub async fn delete_extra_content_field_service(
     payload: Json<DeleteExtraContentFieldPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let del_op: bool = match delete_extra_field_for_post(
-        &payload.api_token, 
+        &user, 
         &payload.content_id,
         &data.pool,
         &payload.field_id
@@ -186,3 +358,45 @@ pub async fn delete_extra_content_field_service(
     };
     HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
 }
+
+/// This function is the API service
+/// function for listing all extra
+/// content fields on a post of a user.
+/// If the received request and resulting
+/// operation are both valid, an instance of
+/// the "ExtraContentFields" structure is 
+/// returned. In any other case an error 
+/// is returned.
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/ecf/all", method = "GET", method = "POST")]
+pub async fn get_extra_content_fields_service(
+    query: Option<Query<PostFieldsPayload>>,
+    body: Option<Json<PostFieldsPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let payload: PostFieldsPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut sanitized: Vec<ExtraContentFieldResponse> = Vec::new();
+    let fields: Vec<ExtraContentField> = match get_user_extra_fields_for_post(
+        &user, 
+        &payload.content_id, 
+        &data.pool
+    ).await {
+        Ok(fields) => fields,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    for field in fields {
+        sanitized.push(ExtraContentFieldResponse::new(field));
+    }
+    let resp: ExtraContentFields = ExtraContentFields{ fields: sanitized };
+    Ok(HttpResponse::Ok().json(resp))
+}
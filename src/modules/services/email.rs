@@ -3,11 +3,21 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
 /// Importing the "get"
 /// decorator to make a service
 /// that accepts "GET" requests.
 use actix_web::get;
 
+/// Importing the "post"
+/// decorator to make a service
+/// that accepts "POST" requests.
+use actix_web::post;
+
 /// Importing the "Result"
 /// enum for Actix Web services.
 use actix_web::Result;
@@ -22,10 +32,25 @@ use actix_web::web::Data;
 /// to extract data.
 use actix_web::web::Path;
 
+/// Importing the "spawn"
+/// function to send emails
+/// in the background.
+use actix_web::rt::spawn;
+
+/// Importing the "Json"
+/// structure to accept
+/// JSON payloads.
+use actix_web::web::Json;
+
 /// Importing the function
 /// to return a HTTP response.
 use actix_web::HttpResponse;
 
+/// Importing the
+/// "Postgres" structure
+/// for explicit typing.
+use sqlx::postgres::Postgres;
+
 /// Importing this crate's
 /// error structure.
 use crate::modules::err::CleoErr;
@@ -35,6 +60,14 @@ use crate::modules::err::CleoErr;
 /// persistent app data.
 use crate::modules::units::AppData;
 
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the function
+/// to send an email.
+use crate::modules::utils::send_email;
+
 /// Importing the model for
 /// email tokens for explicit
 /// typing.
@@ -54,14 +87,48 @@ use crate::modules::responses::StatusResponse;
 /// data from an URL.
 use crate::modules::extractors::TokenExtractor;
 
+/// Importing the model for
+/// instance information for
+/// explicit typing.
+use crate::modules::models::InstanceInformation;
+
 /// Importing the function to delete an email token
 /// object from the database.
 use crate::modules::db::email::delete_email_token;
 
+/// Importing the function to
+/// retrieve instance information
+/// from the database.
+use crate::modules::db::general::get_instance_info;
+
+/// Importing the payload for
+/// setting a new password.
+use crate::modules::payloads::PasswordResetPayload;
+
+/// Importing the function to retrieve
+/// a user given their username.
+use crate::modules::db::users::get_user_by_username;
+
 /// Importing the function to retrieve an email
 /// token object from the database given the token.
 use crate::modules::db::email::get_object_from_token;
 
+/// Importing the function to create
+/// a token for resetting a password.
+use crate::modules::db::email::create_password_reset;
+
+/// Importing the function to set a new
+/// password with a password reset token.
+use crate::modules::db::email::redeem_password_reset;
+
+/// Importing the number of seconds
+/// a password reset token stays valid for.
+use crate::modules::db::email::PASSWORD_RESET_LIFETIME;
+
+/// Importing the payload for requesting
+/// an email to reset a password.
+use crate::modules::payloads::PasswordResetRequestPayload;
+
 
 /// This function is the API service
 /// function for verifying an email token.
@@ -97,3 +164,108 @@ pub async fn verify_email_service(
     }
 }
 
+
+/// Attempts to send the user with
+/// the supplied username an email with
+/// a link for choosing a new password.
+/// The link leads to the password reset
+/// URL of the instance with the token
+/// appended. Unknown users are skipped
+/// without an error. If the instance has
+/// no password reset URL or the operation
+/// fails, an error is returned.
+pub async fn send_password_reset(
+    username: &String,
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let user: CleoUser = match get_user_by_username(username, pool).await {
+        Ok(user) => user,
+        Err(_e) => return Ok(())
+    };
+    let instance_info: InstanceInformation = match get_instance_info(pool).await {
+        Ok(instance_info) => instance_info,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if instance_info.password_reset_url.is_empty() {
+        let e: &str = "No password reset URL is set for this instance.";
+        return Err::<(), CleoErr>(CleoErr::new(e));
+    }
+    let token: String = match create_password_reset(&user, pool).await {
+        Ok(token) => token,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let body: String = format!(
+        "Someone asked to reset your password. To choose a new one, open this link within {} minutes: {}{}\nIf this was not you, you can ignore this email.",
+        PASSWORD_RESET_LIFETIME / 60,
+        instance_info.password_reset_url,
+        &token
+    );
+    let send_mail: bool = match send_email(
+        &instance_info.smtp_username, 
+        &instance_info.smtp_pass, 
+        &format!("Password reset for {}", &instance_info.instance_name), 
+        &body, 
+        &user.email_addr, 
+        &instance_info.smtp_server
+    ).await {
+        Ok(send_mail) => send_mail,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if send_mail {
+        Ok(())
+    }
+    else {
+        let e: String = format!("The password reset email for \"{}\" could not be sent.", username);
+        Err::<(), CleoErr>(CleoErr::new(&e))
+    }
+}
+
+/// This function is the API service
+/// function for requesting an email to
+/// reset the password of a user. The 
+/// email is sent in the background and
+/// failures are only logged, so the
+/// response is the same whether the user
+/// exists or not. An instance of the 
+/// "StatusResponse" structure as JSON is
+/// always returned.
+#[post("/user/password/forgot")]
+pub async fn request_password_reset_service(
+    payload: Json<PasswordResetRequestPayload>,
+    data: Data<AppData>
+) -> HttpResponse {
+    let username: String = payload.username.clone();
+    let pool: Pool<Postgres> = data.pool.clone();
+    spawn(async move {
+        match send_password_reset(&username, &pool).await {
+            Ok(_sent) => {},
+            Err(e) => eprintln!("{}", &e.to_string())
+        };
+    });
+    HttpResponse::Ok().json(StatusResponse{ is_ok: true })
+}
+
+/// This function is the API service
+/// function for setting a new password
+/// with a token sent by email. The page
+/// at the password reset URL of the 
+/// instance submits the new password
+/// here. All API tokens of the user are
+/// revoked. If the
+/// received request and resulting operation
+/// are both valid, an instance of the 
+/// "StatusResponse" structure as JSON is
+/// returned with a boolean flag. In any
+/// other case an error is returned.
+#[post("/user/password/reset/{token}")]
+pub async fn reset_password_service(
+    token: Path<TokenExtractor>,
+    payload: Json<PasswordResetPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let _revoked: u64 = match redeem_password_reset(&token.token, &payload.new_password, &data.pool).await {
+        Ok(revoked) => revoked,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(StatusResponse{ is_ok: true }))
+}
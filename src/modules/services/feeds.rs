@@ -0,0 +1,494 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Pool"
+/// structure to accept multiple
+/// connections to a database.
+use sqlx::Pool;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "get"
+/// macro to make a service
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing.
+use chrono::DateTime;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Path"
+/// structure to extract
+/// parameters from request
+/// URLs.
+use actix_web::web::Path;
+
+/// Importing the "Query"
+/// structure to extract
+/// parameters from the query
+/// string of request URLs.
+use actix_web::web::Query;
+
+/// Importing the "HttpResponse"
+/// structure to return an HTTP
+/// response.
+use actix_web::HttpResponse;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "HashMap"
+/// structure to look up the
+/// authors of posts.
+use std::collections::HashMap;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the function to turn
+/// a hostname into the base of 
+/// absolute links.
+use crate::modules::feeds::base_url;
+
+/// Importing the function to build
+/// the absolute link of a post.
+use crate::modules::feeds::post_url;
+
+/// Importing the structure holding
+/// the details of a feed.
+use crate::modules::units::FeedMeta;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// user posts for explicit
+/// typing.
+use crate::modules::models::UserPost;
+
+/// Importing the structure holding
+/// the details of an entry of a feed.
+use crate::modules::units::FeedEntry;
+
+/// Importing the function to render
+/// a feed as an RSS document.
+use crate::modules::feeds::render_rss;
+
+/// Importing the function to render
+/// a feed as an Atom document.
+use crate::modules::feeds::render_atom;
+
+/// Importing the largest number of
+/// links a sitemap can contain.
+use crate::modules::feeds::SITEMAP_SIZE;
+
+/// Importing the structure holding
+/// the details of an entry of a sitemap.
+use crate::modules::units::SitemapEntry;
+
+/// Importing the function to pick
+/// the base of the links to posts.
+use crate::modules::feeds::site_base_url;
+
+/// Importing the function to render
+/// a sitemap.
+use crate::modules::feeds::render_sitemap;
+
+/// Importing the function to render
+/// the HTML of a post.
+use crate::modules::html::render_post_html;
+
+/// Importing the function to render
+/// a feed as a JSON Feed document.
+use crate::modules::feeds::render_json_feed;
+
+/// Importing the function to retrieve
+/// the posts that make up a feed.
+use crate::modules::db::feeds::get_feed_posts;
+
+/// Importing the function to encode
+/// a segment of the path of a URL.
+use crate::modules::feeds::encode_path_segment;
+
+/// Importing the function to render
+/// a sitemap index.
+use crate::modules::feeds::render_sitemap_index;
+
+/// Importing the function to check
+/// the format of a feed.
+use crate::modules::feeds::validate_feed_format;
+
+/// Importing the function to retrieve
+/// the users with the given IDs.
+use crate::modules::db::users::get_users_by_ids;
+
+/// Importing the model for
+/// information about the instance
+/// for explicit typing.
+use crate::modules::models::InstanceInformation;
+
+/// Importing the structure to extract
+/// the number of a sitemap from request
+/// URLs.
+use crate::modules::extractors::SitemapExtractor;
+
+/// Importing the structure to extract
+/// a username and the format of their feed
+/// from request URLs.
+use crate::modules::extractors::UserFeedExtractor;
+
+/// Importing the function to retrieve
+/// a page of the entries of a sitemap.
+use crate::modules::db::feeds::get_sitemap_entries;
+
+/// Importing the function to retrieve
+/// the information on the instance.
+use crate::modules::db::general::get_instance_info;
+
+/// Importing the structure to extract
+/// the type of content a feed is limited
+/// to from request URLs.
+use crate::modules::extractors::FeedQueryExtractor;
+
+/// Importing the structure to extract
+/// the format of a feed from request URLs.
+use crate::modules::extractors::FeedFormatExtractor;
+
+/// Importing the function to retrieve
+/// a user given their username.
+use crate::modules::db::users::get_user_by_username;
+
+/// Importing the function to count
+/// the entries of all sitemaps.
+use crate::modules::db::feeds::count_sitemap_entries;
+
+/// Importing the function to check that
+/// a type of content exists.
+use crate::modules::db::content_types::get_content_type;
+
+/// This function attempts to turn
+/// the supplied posts into entries of
+/// a feed with absolute links built from
+/// the supplied base URL. The authors of
+/// all posts are fetched at once. If the
+/// operation fails, an error is returned.
+pub async fn posts_to_feed_entries(
+    posts: Vec<UserPost>,
+    base: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<FeedEntry>, CleoErr> {
+    let user_ids: Vec<String> = posts
+        .iter()
+        .map(|post| post.user_id.clone())
+        .collect();
+    let users: Vec<CleoUser> = match get_users_by_ids(&user_ids, pool).await {
+        Ok(users) => users,
+        Err(e) => return Err::<Vec<FeedEntry>, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let users: HashMap<String, CleoUser> = users
+        .into_iter()
+        .map(|user| (user.user_id.clone(), user))
+        .collect();
+    let mut entries: Vec<FeedEntry> = Vec::new();
+    for post in posts {
+        let user: &CleoUser = match users.get(&post.user_id) {
+            Some(user) => user,
+            None => {
+                let e: String = format!("The author of the post \"{}\" does not exist.", post.content_id);
+                return Err::<Vec<FeedEntry>, CleoErr>(CleoErr::new(&e.to_string()));
+            }
+        };
+        let content_html: String = match render_post_html(&post.source_format, &post.content_text) {
+            Ok(content_html) => content_html,
+            Err(e) => return Err::<Vec<FeedEntry>, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        let url: String = post_url(
+            base,
+            &user.username,
+            &post.content_type,
+            &post.slug
+        );
+        let author: String = if post.author_name.is_empty() {
+            user.display_name.clone()
+        }
+        else {
+            post.author_name
+        };
+        entries.push(
+            FeedEntry{
+                id: url.clone(),
+                title: post.title,
+                url,
+                author,
+                summary: post.excerpt,
+                content_html,
+                published: post.publish_at.unwrap_or(post.created_at),
+                updated: post.updated_at
+            }
+        );
+    }
+    Ok(entries)
+}
+
+/// Renders the supplied feed and
+/// entries in the requested format
+/// and returns them with the matching
+/// content type. The time the feed was
+/// last updated is taken from the newest
+/// entry.
+pub fn render_feed(
+    format: &str,
+    mut meta: FeedMeta,
+    entries: &Vec<FeedEntry>
+) -> HttpResponse {
+    if let Some(updated) = entries.iter().map(|entry| entry.updated).max() {
+        meta.updated = updated;
+    }
+    match format {
+        "atom" => HttpResponse::Ok()
+            .content_type("application/atom+xml; charset=utf-8")
+            .body(render_atom(&meta, entries)),
+        "json" => HttpResponse::Ok()
+            .content_type("application/feed+json; charset=utf-8")
+            .body(render_json_feed(&meta, entries)),
+        _ => HttpResponse::Ok()
+            .content_type("application/rss+xml; charset=utf-8")
+            .body(render_rss(&meta, entries))
+    }
+}
+
+/// Builds the absolute link of a 
+/// feed, including the type of content
+/// it is limited to if one is supplied.
+fn feed_url(base: &String, path: &String, content_type: &Option<String>) -> String {
+    match content_type {
+        Some(content_type) => format!("{}{}?content_type={}", base, path, content_type),
+        None => format!("{}{}", base, path)
+    }
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a feed of the latest published posts
+/// on the instance. The feed can be limited
+/// to one type of content with the "content_type"
+/// parameter. No credentials are required.
+/// If the received request and resulting 
+/// operation are both valid, the feed is
+/// returned as RSS, Atom, or JSON Feed. In 
+/// any other case an error is returned.
+#[get("/public/feeds/{format}")]
+pub async fn get_instance_feed_service(
+    feed: Path<FeedFormatExtractor>,
+    filters: Query<FeedQueryExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let _valid: () = match validate_feed_format(&feed.format) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if let Some(content_type) = &filters.content_type {
+        if let Err(e) = get_content_type(content_type, &data.pool).await {
+            return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    }
+    let info: InstanceInformation = match get_instance_info(&data.pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let posts: Vec<UserPost> = match get_feed_posts(&None, &filters.content_type, &data.pool).await {
+        Ok(posts) => posts,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let base: String = base_url(&info.hostname);
+    let site: String = site_base_url(&info.hostname, &info.site_url);
+    let entries: Vec<FeedEntry> = match posts_to_feed_entries(posts, &site, &data.pool).await {
+        Ok(entries) => entries,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let title: String = match &filters.content_type {
+        Some(content_type) => format!("{}: {}", info.instance_name, content_type),
+        None => info.instance_name.clone()
+    };
+    let now: DateTime<Utc> = Utc::now();
+    let meta: FeedMeta = FeedMeta{
+        title,
+        description: format!("The latest posts on {}.", info.instance_name),
+        home_url: site,
+        feed_url: feed_url(&base, &format!("/public/feeds/{}", feed.format), &filters.content_type),
+        updated: now
+    };
+    Ok(render_feed(&feed.format, meta, &entries))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a feed of the latest published posts
+/// of a user. The feed can be limited to
+/// one type of content with the "content_type"
+/// parameter. No credentials are required.
+/// If the received request and resulting 
+/// operation are both valid, the feed is
+/// returned as RSS, Atom, or JSON Feed. In 
+/// any other case an error is returned.
+#[get("/public/feeds/{username}/{format}")]
+pub async fn get_user_feed_service(
+    feed: Path<UserFeedExtractor>,
+    filters: Query<FeedQueryExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let _valid: () = match validate_feed_format(&feed.format) {
+        Ok(valid) => valid,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if let Some(content_type) = &filters.content_type {
+        if let Err(e) = get_content_type(content_type, &data.pool).await {
+            return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    }
+    let user: CleoUser = match get_user_by_username(&feed.username, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let info: InstanceInformation = match get_instance_info(&data.pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let posts: Vec<UserPost> = match get_feed_posts(
+        &Some(user.user_id.clone()), 
+        &filters.content_type, 
+        &data.pool
+    ).await {
+        Ok(posts) => posts,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let base: String = base_url(&info.hostname);
+    let site: String = site_base_url(&info.hostname, &info.site_url);
+    let entries: Vec<FeedEntry> = match posts_to_feed_entries(posts, &site, &data.pool).await {
+        Ok(entries) => entries,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let title: String = match &filters.content_type {
+        Some(content_type) => format!("{}: {} on {}", user.display_name, content_type, info.instance_name),
+        None => format!("{} on {}", user.display_name, info.instance_name)
+    };
+    let now: DateTime<Utc> = Utc::now();
+    let meta: FeedMeta = FeedMeta{
+        title,
+        description: format!("The latest posts by {} on {}.", user.display_name, info.instance_name),
+        home_url: format!("{}/{}", site, encode_path_segment(&user.username)),
+        feed_url: feed_url(
+            &base, 
+            &format!("/public/feeds/{}/{}", encode_path_segment(&user.username), feed.format), 
+            &filters.content_type
+        ),
+        updated: now
+    };
+    Ok(render_feed(&feed.format, meta, &entries))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// the sitemap of the instance with
+/// links to all published posts and 
+/// pages. If there are more links than
+/// fit into one sitemap, a sitemap index
+/// linking to the numbered sitemaps is
+/// returned instead. No credentials are
+/// required. In any other case an error
+/// is returned.
+#[get("/sitemap.xml")]
+pub async fn get_sitemap_service(
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let info: InstanceInformation = match get_instance_info(&data.pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match count_sitemap_entries(&data.pool).await {
+        Ok(total) => total,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let base: String = base_url(&info.hostname);
+    if total > SITEMAP_SIZE {
+        let sitemap_count: i64 = (total + SITEMAP_SIZE - 1) / SITEMAP_SIZE;
+        return Ok(
+            HttpResponse::Ok()
+                .content_type("application/xml; charset=utf-8")
+                .body(render_sitemap_index(&base, sitemap_count))
+        );
+    }
+    let entries: Vec<SitemapEntry> = match get_sitemap_entries(&1, &SITEMAP_SIZE, &data.pool).await {
+        Ok(entries) => entries,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(
+        HttpResponse::Ok()
+            .content_type("application/xml; charset=utf-8")
+            .body(render_sitemap(&site_base_url(&info.hostname, &info.site_url), &entries))
+    )
+}
+
+/// This function is the API
+/// service function for retrieving
+/// one of the numbered sitemaps listed
+/// in the sitemap index. Sitemaps are
+/// numbered from one. No credentials are
+/// required. If the received request and
+/// resulting operation are both valid, the
+/// sitemap is returned. In any other case
+/// an error is returned.
+#[get("/sitemaps/{page}")]
+pub async fn get_sitemap_page_service(
+    sitemap: Path<SitemapExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let info: InstanceInformation = match get_instance_info(&data.pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let total: i64 = match count_sitemap_entries(&data.pool).await {
+        Ok(total) => total,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let sitemap_count: i64 = ((total + SITEMAP_SIZE - 1) / SITEMAP_SIZE).max(1);
+    if sitemap.page < 1 || sitemap.page > sitemap_count {
+        let e: String = format!("The sitemap \"{}\" does not exist.", sitemap.page);
+        return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let entries: Vec<SitemapEntry> = match get_sitemap_entries(&sitemap.page, &SITEMAP_SIZE, &data.pool).await {
+        Ok(entries) => entries,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(
+        HttpResponse::Ok()
+            .content_type("application/xml; charset=utf-8")
+            .body(render_sitemap(&site_base_url(&info.hostname, &info.site_url), &entries))
+    )
+}
@@ -69,10 +69,19 @@ use crate::modules::models::CleoUser;
 /// user files for explicit typing.
 use crate::modules::models::UserFile;
 
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
 /// Importing the "MultipartForm" trait
 /// for explicit typing and to upload files.
 use actix_multipart::form::MultipartForm;
 
+/// Importing the scope for
+/// managing files.
+use crate::modules::db::tokens::SCOPE_FILES;
+
 /// Importing the structure to upload
 /// files via POST request.
 use crate::modules::payloads::UserFileUpload;
@@ -114,10 +123,6 @@ use crate::modules::db::files::create_user_file;
 /// instance information.
 use crate::modules::db::general::get_instance_info;
 
-/// Importing the function to retrieve a record
-/// for a user given one of their API tokens.
-use crate::modules::db::tokens::get_user_from_token;
-
 /// Importing the data structure for submitting
 /// a payload for deleting a file a user has
 /// uploaded.
@@ -133,8 +138,13 @@ use crate::modules::payloads::DeleteUserFilePayload;
 #[post("/files/create")]
 pub async fn create_user_file_service(
     MultipartForm(form): MultipartForm<UserFileUpload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&form.json.api_token, SCOPE_FILES, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let info: InstanceInformation = match get_instance_info(&data.pool).await{
         Ok(info) => info,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
@@ -154,7 +164,7 @@ pub async fn create_user_file_service(
         Err(e) =>return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     }; 
     let created_file: UserFile = match create_user_file(
-        &form.json.api_token, 
+        &user, 
         &file_path, 
         &file_url,
         &data.pool
@@ -163,19 +173,13 @@ pub async fn create_user_file_service(
         Ok(created_file) => created_file,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let user: CleoUser = match get_user_from_token(
-        &form.json.api_token, 
-        &data.pool
-    ).await 
-    {
-        Ok(user) => user,
-        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
-    };
     let resp: UserFileResponse = UserFileResponse{
         file_url: file_url,
         file_name: file_path,
         file_id: created_file.file_id,
-        user_id: user.user_id
+        user_id: user.user_id,
+        created_at: created_file.created_at.to_rfc3339(),
+        updated_at: created_file.updated_at.to_rfc3339()
     };
     Ok(HttpResponse::Ok().json(resp))
 }
@@ -190,10 +194,15 @@ pub async fn create_user_file_service(
 #[post("/files/delete")]
 pub async fn delete_user_file_service(
     payload: Json<DeleteUserFilePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_FILES, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let del_op: bool = match delete_user_file(
-        &payload.api_token, 
+        &user, 
         &payload.file_id,
         &data.pool
     ).await {
@@ -3,10 +3,16 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
-/// Importing the "post"
+/// Importing the "get"
 /// decorator to make a service
-/// that accepts "POST" requests.
-use actix_web::post;
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
 
 /// Importing the "Result"
 /// enum for Actix Web services.
@@ -22,6 +28,16 @@ use actix_web::web::Data;
 /// responses.
 use actix_web::web::Json;
 
+/// Importing the "Path"
+/// structure to extract
+/// data from URLs.
+use actix_web::web::Path;
+
+/// Importing the "Query" structure
+/// to extract options from the query
+/// string of request URLs.
+use actix_web::web::Query;
+
 /// Importing the function
 /// to return a HTTP response.
 use actix_web::HttpResponse;
@@ -45,6 +61,24 @@ use crate::modules::models::UserPost;
 /// in the database.
 use crate::modules::models::UserFile;
 
+/// Importing the model for
+/// Cleo users for explicit
+/// typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure holding
+/// the filters for listing posts.
+use crate::modules::units::PostFilters;
+
+/// Importing the structure holding
+/// the options for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
 /// Importing the data structure
 /// for returning data on all files
 /// a user has created.
@@ -55,25 +89,44 @@ use crate::modules::responses::UserFiles;
 /// a user has created.
 use crate::modules::responses::UserPosts;
 
-/// Importing the function to
-/// retrieve all the files 
-/// a user has uploaded.
-use crate::modules::db::files::get_user_files;
+/// Importing the model for
+/// redirects of changed slugs
+/// for explicit typing.
+use crate::modules::models::SlugRedirect;
 
-/// Importing the function to
-/// retrieve all the posts a 
-/// user has made.
-use crate::modules::db::posts::get_user_posts;
+/// Importing the data structure for
+/// returning information on the page
+/// of a list.
+use crate::modules::responses::Pagination;
+
+/// Importing the data structure for
+/// submitting a payload for listing
+/// records.
+use crate::modules::payloads::ListPayload;
+
+/// Importing the function to read
+/// the parameters of a request from
+/// its query string or its body.
+use crate::modules::extractors::read_params;
+
+/// Importing the scope for
+/// managing files.
+use crate::modules::db::tokens::SCOPE_FILES;
+
+/// Importing the data structure for
+/// submitting a payload for listing
+/// posts.
+use crate::modules::payloads::PostListPayload;
+
+/// Importing the structure to
+/// extract the slug of a post
+/// from URLs.
+use crate::modules::extractors::SlugExtractor;
 
 /// Importing the structure to
 /// return info on user files.
 use crate::modules::responses::UserFileResponse;
 
-/// Importing the structure for 
-/// submitting payloads for actions
-/// that only require an API token.
-use crate::modules::payloads::TokenOnlyPayload;
-
 /// Importing the structure for modelling instance
 /// information in the database for explicit
 /// typing.
@@ -84,55 +137,185 @@ use crate::modules::models::InstanceInformation;
 /// posts.
 use crate::modules::responses::UserPostResponse;
 
+/// Importing the function to check
+/// the options for listing records.
+use crate::modules::utils::resolve_list_options;
+
+/// Importing the function to
+/// retrieve all the files 
+/// a user has uploaded.
+use crate::modules::db::general::get_user_files;
+
+/// Importing the function to
+/// retrieve all the posts a 
+/// user has made.
+use crate::modules::db::general::get_user_posts;
+
+/// Importing the function to retrieve
+/// the redirect for a changed slug.
+use crate::modules::db::slugs::get_slug_redirect;
+
+/// Importing the fields the posts
+/// of a user can be sorted by.
+use crate::modules::db::general::POST_SORT_FIELDS;
+
+/// Importing the fields the files
+/// of a user can be sorted by.
+use crate::modules::db::general::FILE_SORT_FIELDS;
+
+/// Importing the data structure to extract
+/// a username from a request URL.
+use crate::modules::extractors::UsernameExtractor;
+
 /// Importing the function to
 /// retrieve instance information
 /// from the database.
 use crate::modules::db::general::get_instance_info;
 
+/// Importing the structure to extract
+/// options for listing from request URLs.
+use crate::modules::extractors::ListQueryExtractor;
+
+/// Importing the data structure to extract
+/// the ID of a post from a request URL.
+use crate::modules::extractors::ContentIdExtractor;
+
+/// Importing the scope for
+/// reading private
+/// content.
+use crate::modules::db::tokens::SCOPE_CONTENT_READ;
+
+/// Importing the function to retrieve
+/// a Cleo user given their username.
+use crate::modules::db::users::get_user_by_username;
+
+/// Importing the function to create
+/// a response for a post with its
+/// extra content fields.
+use crate::modules::services::ecf::post_to_response;
+
+/// Importing the function to retrieve
+/// a public post given the post's ID.
+use crate::modules::db::posts::get_public_post_by_id;
+
+/// Importing the function to create
+/// responses for several posts with
+/// their extra content fields.
+use crate::modules::services::ecf::posts_to_responses;
+
+/// Importing the function to
+/// retrieve all public posts
+/// of a user.
+use crate::modules::db::general::get_public_user_posts;
+
+/// Importing the function to retrieve
+/// a published post given its slug.
+use crate::modules::db::slugs::get_public_post_by_slug;
+
 /// This function is the API
 /// service function for retrieving
-/// a vector of all posts made by a
-/// user.If the received request and resulting
+/// a page of the posts made by a
+/// user. The posts can be filtered by
+/// content type, status and extra content
+/// fields. If the received request and resulting
 /// operation are both valid, a vector with
 /// instances of the "UserPostResponse" structure
-/// is returned. In any other case an error is
-/// returned.
-#[post("/posts/all")]
+/// and the pagination details are returned. 
+/// In any other case an error is returned.
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/posts/all", method = "GET", method = "POST")]
 pub async fn get_user_posts_service(
-    payload: Json<TokenOnlyPayload>,
+    query: Option<Query<PostListPayload>>,
+    body: Option<Json<PostListPayload>>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr> {
-    let mut sanitized: Vec<UserPostResponse> = Vec::new();
-    let posts: Vec<UserPost> = match get_user_posts(&payload.api_token, &data.pool).await {
-        Ok(users) => users,
+    let payload: PostListPayload = match read_params(query, body) {
+        Ok(payload) => payload,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    for post in posts {
-        let resp_post: UserPostResponse = UserPostResponse{
-            content_id: post.content_id,
-            content_type: post.content_type,
-            user_id: post.user_id,
-            content_text: post.content_text
-        };
-        sanitized.push(resp_post);
-    }
-    let resp: UserPosts = UserPosts{ posts: sanitized };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let options: ListOptions = match resolve_list_options(
+        &payload.page, 
+        &payload.per_page, 
+        &payload.sort_by, 
+        &payload.sort_order, 
+        &POST_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let filters: PostFilters = PostFilters{
+        content_type: payload.content_type.clone(),
+        post_status: payload.status.clone(),
+        field_key: payload.field_key.clone(),
+        field_value: payload.field_value.clone()
+    };
+    let (posts, total): (Vec<UserPost>, i64) = match get_user_posts(
+        &user, 
+        &filters, 
+        &options, 
+        &data.pool
+    ).await {
+        Ok(page) => page,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let sanitized: Vec<UserPostResponse> = match posts_to_responses(posts, &data.pool).await {
+        Ok(sanitized) => sanitized,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: UserPosts = UserPosts{ 
+        posts: sanitized, 
+        pagination: Pagination::new(&options, total) 
+    };
     Ok(HttpResponse::Ok().json(resp))
 }
 
 /// This function contains the
 /// service functions that fetches
-/// information on all files a user
-/// has uploaded. If this operation fails,
-/// an error is returned.
-#[post("/files/all")]
+/// information on a page of the files 
+/// a user has uploaded. If this operation 
+/// fails, an error is returned.
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/files/all", method = "GET", method = "POST")]
 pub async fn get_user_files_service(
-    payload: Json<TokenOnlyPayload>,
+    query: Option<Query<ListPayload>>,
+    body: Option<Json<ListPayload>>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr> {
+    let payload: ListPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_FILES, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     let mut sanitized: Vec<UserFileResponse> = Vec::new();
-    let files: Vec<UserFile> = match get_user_files(&payload.api_token, &data.pool).await {
-        Ok(files) => files,
+    let options: ListOptions = match resolve_list_options(
+        &payload.page, 
+        &payload.per_page, 
+        &payload.sort_by, 
+        &payload.sort_order, 
+        &FILE_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (files, total): (Vec<UserFile>, i64) = match get_user_files(
+        &user, 
+        &options, 
+        &data.pool
+    ).await {
+        Ok(page) => page,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
     let info: InstanceInformation = match get_instance_info(&data.pool).await {
@@ -144,10 +327,188 @@ pub async fn get_user_files_service(
            user_id: file.user_id,
            file_id: file.file_id,
            file_url: format!("{}{}", &info.hostname, &file.file_path),
-           file_name: file.file_path
+           file_name: file.file_path,
+           created_at: file.created_at.to_rfc3339(),
+           updated_at: file.updated_at.to_rfc3339()
         };
         sanitized.push(resp_file);
     }
-    let resp: UserFiles = UserFiles{ files: sanitized };
+    let resp: UserFiles = UserFiles{ 
+        files: sanitized, 
+        pagination: Pagination::new(&options, total) 
+    };
     Ok(HttpResponse::Ok().json(resp))
 }
+
+/// This function is the API
+/// service function for retrieving
+/// a page of the published posts made
+/// by a user. No credentials are required.
+/// If the received request and resulting
+/// operation are both valid, a vector with
+/// instances of the "UserPostResponse" structure
+/// and the pagination details are returned. 
+/// In any other case an error is returned.
+#[get("/public/posts/{username}")]
+pub async fn get_public_posts_service(
+    user: Path<UsernameExtractor>,
+    list: Query<ListQueryExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let options: ListOptions = match resolve_list_options(
+        &list.page, 
+        &list.per_page, 
+        &list.sort_by, 
+        &list.sort_order, 
+        &POST_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (posts, total): (Vec<UserPost>, i64) = match get_public_user_posts(
+        &user.username, 
+        &"post".to_string(), 
+        &options,
+        &data.pool
+    ).await {
+        Ok(page) => page,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let sanitized: Vec<UserPostResponse> = match posts_to_responses(posts, &data.pool).await {
+        Ok(sanitized) => sanitized,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: UserPosts = UserPosts{ 
+        posts: sanitized, 
+        pagination: Pagination::new(&options, total) 
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a page of the published pages made
+/// by a user. No credentials are required.
+/// If the received request and resulting
+/// operation are both valid, a vector with
+/// instances of the "UserPostResponse" structure
+/// and the pagination details are returned. 
+/// In any other case an error is returned.
+#[get("/public/pages/{username}")]
+pub async fn get_public_pages_service(
+    user: Path<UsernameExtractor>,
+    list: Query<ListQueryExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let options: ListOptions = match resolve_list_options(
+        &list.page, 
+        &list.per_page, 
+        &list.sort_by, 
+        &list.sort_order, 
+        &POST_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (pages, total): (Vec<UserPost>, i64) = match get_public_user_posts(
+        &user.username, 
+        &"page".to_string(), 
+        &options,
+        &data.pool
+    ).await {
+        Ok(page) => page,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let sanitized: Vec<UserPostResponse> = match posts_to_responses(pages, &data.pool).await {
+        Ok(sanitized) => sanitized,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: UserPosts = UserPosts{ 
+        posts: sanitized, 
+        pagination: Pagination::new(&options, total) 
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a single published post or page given
+/// its ID. No credentials are required.
+/// If the received request and resulting
+/// operation are both valid, an instance
+/// of the "UserPostResponse" structure
+/// is returned. In any other case an error is
+/// returned.
+#[get("/public/post/{content_id}")]
+pub async fn get_public_post_service(
+    post: Path<ContentIdExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let post_obj: UserPost = match get_public_post_by_id(&post.content_id, &data.pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: UserPostResponse = match post_to_response(post_obj, &data.pool).await {
+        Ok(resp) => resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a single published post or page given
+/// the username of its author, its content
+/// type, and its slug. No credentials are 
+/// required. If the slug was changed, a
+/// permanent redirect to the current slug 
+/// is returned. If the received request and 
+/// resulting operation are both valid, an 
+/// instance of the "UserPostResponse" structure
+/// is returned. In any other case an error is
+/// returned.
+#[get("/public/slug/{username}/{content_type}/{slug}")]
+pub async fn get_public_post_by_slug_service(
+    post: Path<SlugExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match get_user_by_username(&post.username, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if let Ok(post_obj) = get_public_post_by_slug(
+        &user.user_id, 
+        &post.content_type, 
+        &post.slug, 
+        &data.pool
+    ).await {
+        let resp: UserPostResponse = match post_to_response(post_obj, &data.pool).await {
+            Ok(resp) => resp,
+            Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        return Ok(HttpResponse::Ok().json(resp));
+    }
+    let redirect: SlugRedirect = match get_slug_redirect(
+        &user.user_id, 
+        &post.content_type, 
+        &post.slug, 
+        &data.pool
+    ).await {
+        Ok(redirect) => redirect,
+        Err(_e) => {
+            let e: String = format!("The post \"{}\" could not be found.", &post.slug);
+            return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    let post_obj: UserPost = match get_public_post_by_id(&redirect.content_id, &data.pool).await {
+        Ok(post_obj) => post_obj,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let location: String = format!(
+        "/public/slug/{}/{}/{}", 
+        &post.username, 
+        &post_obj.content_type, 
+        &post_obj.slug
+    );
+    Ok(HttpResponse::MovedPermanently().insert_header(("Location", location)).finish())
+}
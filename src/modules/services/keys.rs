@@ -8,6 +8,12 @@ Licensed under the FSL v1.
 /// that accepts "POST" requests.
 use actix_web::post;
 
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
 /// Importing the "Result"
 /// enum for Actix Web services.
 use actix_web::Result;
@@ -22,6 +28,11 @@ use actix_web::web::Data;
 /// responses.
 use actix_web::web::Json;
 
+/// Importing the "Query" structure
+/// to read the parameters of requests
+/// from their query strings.
+use actix_web::web::Query;
+
 /// Importing the function
 /// to return a HTTP response.
 use actix_web::HttpResponse;
@@ -40,11 +51,44 @@ use crate::modules::models::UserKey;
 /// persistent app data.
 use crate::modules::units::AppData;
 
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure holding
+/// the options for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the data structure for
+/// returning information on the page
+/// of a list.
+use crate::modules::responses::Pagination;
+
+/// Importing the data structure for
+/// submitting a payload for listing
+/// records.
+use crate::modules::payloads::ListPayload;
+
 /// Importing the function to retrieve
 /// all user keys an administrator has
 /// created.
 use crate::modules::db::keys::get_user_keys;
 
+/// Importing the function to read
+/// the parameters of a request from
+/// its query string or its body.
+use crate::modules::extractors::read_params;
+
+/// Importing the scope for
+/// administering the
+/// instance.
+use crate::modules::db::tokens::SCOPE_ADMIN;
+
 /// Importing the structure for submitting
 /// a payload in a POST request for creating
 /// a user key.
@@ -64,16 +108,19 @@ use crate::modules::db::keys::create_user_key;
 /// a user key from the database.
 use crate::modules::db::keys::delete_user_key;
 
-/// Importing the structure for 
-/// submitting payloads for actions
-/// that only require an API token.
-use crate::modules::payloads::TokenOnlyPayload;
+/// Importing the fields the keys
+/// of an administrator can be sorted by.
+use crate::modules::db::keys::KEY_SORT_FIELDS;
 
 /// Importing the structure for returning
 /// all user keys an administrator has created
 /// as a JSON response.
 use crate::modules::responses::UserKeysResponse;
 
+/// Importing the function to check
+/// the options for listing records.
+use crate::modules::utils::resolve_list_options;
+
 /// Importing the structure for submitting
 /// a payload in a POST request for deleting
 /// a user key.
@@ -93,10 +140,15 @@ use crate::modules::responses::UserKeyCreationResponse;
 #[post("/keys/create")]
 pub async fn create_user_key_service(
     payload: Json<UserKeyPayload>,
+    auth: AuthExtractor,
     data: Data<AppData> 
 ) -> Result<HttpResponse, CleoErr>{
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
     if payload.key_type == "admin"{
-        let created: UserKey = match create_user_key(&payload.username, &("admin".to_string()), &payload.api_token, &data.pool).await {
+        let created: UserKey = match create_user_key(&payload.username, &("admin".to_string()), &user, &data.pool).await {
             Ok(created) => created,
             Err(e) =>return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
         };
@@ -107,7 +159,7 @@ pub async fn create_user_key_service(
         Ok(HttpResponse::Ok().json(resp))
     }
     else if payload.key_type == "normal"{
-        let created: UserKey = match create_user_key(&payload.username, &("normal".to_string()), &payload.api_token, &data.pool).await {
+        let created: UserKey = match create_user_key(&payload.username, &("normal".to_string()), &user, &data.pool).await {
             Ok(created) => created,
             Err(e) =>return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
         };
@@ -133,10 +185,15 @@ pub async fn create_user_key_service(
 #[post("/keys/delete")]
 pub async fn delete_user_key_service(
     payload: Json<UserKeyDeletionPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let del_op: bool = match delete_user_key(
-        &payload.api_token, 
+        &user, 
         &payload.key_id,
         &data.pool,
     ).await {
@@ -154,13 +211,40 @@ pub async fn delete_user_key_service(
 /// of the "UserKeysResponse" structure as
 /// a JSON response is returned. In any other 
 /// case an error is returned.
-#[post("/keys/all")]
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/keys/all", method = "GET", method = "POST")]
 pub async fn get_user_keys_service(
-    payload: Json<TokenOnlyPayload>,
+    query: Option<Query<ListPayload>>,
+    body: Option<Json<ListPayload>>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr>{
-    let keys: Vec<UserKey> = match get_user_keys(&payload.api_token, &data.pool).await {
-        Ok(keys) => keys,
+    let payload: ListPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let options: ListOptions = match resolve_list_options(
+        &payload.page, 
+        &payload.per_page, 
+        &payload.sort_by, 
+        &payload.sort_order, 
+        &KEY_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (keys, total): (Vec<UserKey>, i64) = match get_user_keys(
+        &user, 
+        &options, 
+        &data.pool
+    ).await {
+        Ok(page) => page,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
     let mut result: Vec<UserKeyCreationResponse> = Vec::new();
@@ -171,5 +255,8 @@ pub async fn get_user_keys_service(
         };
         result.push(created);
     }
-    Ok(HttpResponse::Ok().json(UserKeysResponse{ keys: result }))
+    Ok(HttpResponse::Ok().json(UserKeysResponse{ 
+        keys: result, 
+        pagination: Pagination::new(&options, total) 
+    }))
 }
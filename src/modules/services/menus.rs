@@ -0,0 +1,669 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Pool"
+/// structure to accept multiple
+/// connections to a database.
+use sqlx::Pool;
+
+/// Importing the "get"
+/// macro to make a service
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "post"
+/// macro to make a service
+/// that accepts "POST" requests.
+use actix_web::post;
+
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Json"
+/// structure to return JSON
+/// responses.
+use actix_web::web::Json;
+
+/// Importing the "Path"
+/// structure to extract
+/// parameters from request
+/// URLs.
+use actix_web::web::Path;
+
+/// Importing the "HttpResponse"
+/// structure to return an HTTP
+/// response.
+use actix_web::HttpResponse;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "HashSet"
+/// structure to keep track of
+/// items already in the tree.
+use std::collections::HashSet;
+
+/// Importing the "HashMap"
+/// structure to group items
+/// by their parent.
+use std::collections::HashMap;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the model for
+/// navigation menus for explicit
+/// typing.
+use crate::modules::models::NavMenu;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// user posts for explicit
+/// typing.
+use crate::modules::models::UserPost;
+
+/// Importing the model for
+/// user files for explicit
+/// typing.
+use crate::modules::models::UserFile;
+
+/// Importing the model for
+/// the items of navigation menus
+/// for explicit typing.
+use crate::modules::models::MenuItem;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the function to edit
+/// a navigation menu.
+use crate::modules::db::menus::edit_menu;
+
+/// Importing the function to create
+/// a navigation menu.
+use crate::modules::db::menus::create_menu;
+
+/// Importing the function to delete
+/// a navigation menu.
+use crate::modules::db::menus::delete_menu;
+
+/// Importing the data structure for
+/// returning a navigation menu.
+use crate::modules::responses::MenuResponse;
+
+/// Importing the function to add an
+/// item to a navigation menu.
+use crate::modules::db::menus::add_menu_item;
+
+/// Importing the data structure for
+/// returning all navigation menus of
+/// a user.
+use crate::modules::responses::MenusResponse;
+
+/// Importing the function to retrieve
+/// all items of a navigation menu.
+use crate::modules::db::menus::get_menu_items;
+
+/// Importing the function to edit an
+/// item of a navigation menu.
+use crate::modules::db::menus::edit_menu_item;
+
+/// Importing the function to move an
+/// item of a navigation menu.
+use crate::modules::db::menus::move_menu_item;
+
+/// Importing the function to retrieve
+/// all navigation menus of a user.
+use crate::modules::db::menus::get_user_menus;
+
+/// Importing the structure to extract
+/// a username and the slug of a menu
+/// from request URLs.
+use crate::modules::extractors::MenuExtractor;
+
+/// Importing the function to retrieve
+/// a navigation menu given its ID.
+use crate::modules::db::menus::get_menu_by_id;
+
+/// Importing the data structure for
+/// submitting a payload for editing
+/// a navigation menu.
+use crate::modules::payloads::MenuEditPayload;
+
+/// Importing the data structure
+/// for returning info on whether
+/// a write operation was successful
+/// or not.
+use crate::modules::responses::StatusResponse;
+
+/// Importing the data structure for
+/// submitting a payload for actions
+/// that only require an API token.
+use crate::modules::payloads::TokenOnlyPayload;
+
+/// Importing the function to retrieve
+/// the files with the given IDs.
+use crate::modules::db::files::get_files_by_ids;
+
+/// Importing the function to delete an
+/// item of a navigation menu.
+use crate::modules::db::menus::delete_menu_item;
+
+/// Importing the function to retrieve
+/// a navigation menu given its slug.
+use crate::modules::db::menus::get_menu_by_slug;
+
+/// Importing the data structure for
+/// returning an item of a navigation
+/// menu.
+use crate::modules::responses::MenuItemResponse;
+
+/// Importing the function to retrieve
+/// the posts with the given IDs.
+use crate::modules::db::search::get_posts_by_ids;
+
+/// Importing the data structure for
+/// submitting a payload for creating
+/// a navigation menu.
+use crate::modules::payloads::MenuCreationPayload;
+
+/// Importing the data structure for
+/// submitting a payload for deleting
+/// a navigation menu.
+use crate::modules::payloads::MenuDeletionPayload;
+
+/// Importing the data structure for
+/// submitting a payload for editing
+/// an item of a navigation menu.
+use crate::modules::payloads::MenuItemEditPayload;
+
+/// Importing the data structure for
+/// submitting a payload for moving
+/// an item of a navigation menu.
+use crate::modules::payloads::MenuItemMovePayload;
+
+/// Importing the scope for
+/// reading private
+/// content.
+use crate::modules::db::tokens::SCOPE_CONTENT_READ;
+
+/// Importing the function to retrieve
+/// a user given their username.
+use crate::modules::db::users::get_user_by_username;
+
+/// Importing the scope for
+/// writing content.
+use crate::modules::db::tokens::SCOPE_CONTENT_WRITE;
+
+/// Importing the data structure for
+/// submitting a payload for adding
+/// an item to a navigation menu.
+use crate::modules::payloads::MenuItemCreationPayload;
+
+/// Importing the data structure for
+/// submitting a payload for deleting
+/// an item of a navigation menu.
+use crate::modules::payloads::MenuItemDeletionPayload;
+
+/// Builds the nodes for the items
+/// below the given parent, or the 
+/// top-level items if no parent is 
+/// given, together with all items below
+/// them. If only public items are 
+/// requested, items pointing to posts
+/// or files that are missing or not 
+/// published are left out together with
+/// the items below them.
+fn build_menu_nodes(
+    parent_id: &Option<String>,
+    children: &mut HashMap<Option<String>, Vec<MenuItem>>,
+    posts: &HashMap<String, UserPost>,
+    files: &HashMap<String, UserFile>,
+    public: bool,
+    visited: &mut HashSet<String>
+) -> Vec<MenuItemResponse> {
+    let mut nodes: Vec<MenuItemResponse> = Vec::new();
+    let items: Vec<MenuItem> = children.remove(parent_id).unwrap_or_default();
+    for item in items {
+        if !visited.insert(item.item_id.clone()) {
+            continue;
+        }
+        let post: Option<&UserPost> = item.content_id
+            .as_ref()
+            .and_then(|content_id| posts.get(content_id));
+        let file: Option<&UserFile> = item.file_id
+            .as_ref()
+            .and_then(|file_id| files.get(file_id));
+        if public {
+            let available: bool = match item.target_type.as_str() {
+                "post" | "page" => post
+                    .map(|post| post.post_status == "published")
+                    .unwrap_or(false),
+                "file" => file.is_some(),
+                _ => item.target_url.is_some()
+            };
+            if !available {
+                continue;
+            }
+        }
+        let url: Option<String> = match file {
+            Some(file) => Some(file.file_url.clone()),
+            None => item.target_url.clone()
+        };
+        let item_children: Vec<MenuItemResponse> = build_menu_nodes(
+            &Some(item.item_id.clone()), 
+            children, 
+            posts,
+            files,
+            public,
+            visited
+        );
+        nodes.push(
+            MenuItemResponse{
+                item_id: item.item_id,
+                label: item.label,
+                target_type: item.target_type,
+                content_id: item.content_id,
+                content_type: post.map(|post| post.content_type.clone()),
+                slug: post.map(|post| post.slug.clone()),
+                file_id: item.file_id,
+                url,
+                item_position: item.item_position,
+                children: item_children
+            }
+        );
+    }
+    nodes
+}
+
+/// This function attempts to build
+/// the response for a navigation menu
+/// with all its items arranged in a tree.
+/// The posts and files the items point to
+/// are fetched at once. If only public items
+/// are requested, items pointing to posts
+/// or files that are missing or not published
+/// are left out. If the operation fails, an
+/// error is returned.
+pub async fn menu_to_response(
+    menu: NavMenu,
+    public: bool,
+    pool: &Pool<Postgres>
+) -> Result<MenuResponse, CleoErr> {
+    let items: Vec<MenuItem> = match get_menu_items(&menu.menu_id, pool).await {
+        Ok(items) => items,
+        Err(e) => return Err::<MenuResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let content_ids: Vec<String> = items
+        .iter()
+        .filter_map(|item| item.content_id.clone())
+        .collect();
+    let file_ids: Vec<String> = items
+        .iter()
+        .filter_map(|item| item.file_id.clone())
+        .collect();
+    let posts: Vec<UserPost> = match get_posts_by_ids(&content_ids, pool).await {
+        Ok(posts) => posts,
+        Err(e) => return Err::<MenuResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let files: Vec<UserFile> = match get_files_by_ids(&file_ids, pool).await {
+        Ok(files) => files,
+        Err(e) => return Err::<MenuResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let posts: HashMap<String, UserPost> = posts
+        .into_iter()
+        .map(|post| (post.content_id.clone(), post))
+        .collect();
+    let files: HashMap<String, UserFile> = files
+        .into_iter()
+        .map(|file| (file.file_id.clone(), file))
+        .collect();
+    let mut children: HashMap<Option<String>, Vec<MenuItem>> = HashMap::new();
+    for item in items {
+        children.entry(item.parent_id.clone()).or_default().push(item);
+    }
+    let mut visited: HashSet<String> = HashSet::new();
+    let nodes: Vec<MenuItemResponse> = build_menu_nodes(
+        &None, 
+        &mut children, 
+        &posts, 
+        &files, 
+        public, 
+        &mut visited
+    );
+    Ok(
+        MenuResponse{
+            menu_id: menu.menu_id,
+            name: menu.menu_name,
+            slug: menu.menu_slug,
+            max_depth: menu.max_depth,
+            created_at: menu.created_at.to_rfc3339(),
+            updated_at: menu.updated_at.to_rfc3339(),
+            items: nodes
+        }
+    )
+}
+
+/// This function is the API
+/// service function for creating
+/// a navigation menu. If the received
+/// request and resulting operation are
+/// both valid, an instance of the 
+/// "MenuResponse" structure is returned.
+/// In any other case an error is returned.
+#[post("/menus/create")]
+pub async fn create_menu_service(
+    payload: Json<MenuCreationPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let menu: NavMenu = match create_menu(
+        &user, 
+        &payload.name,
+        &payload.slug,
+        &payload.max_depth,
+        &data.pool
+    ).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: MenuResponse = match menu_to_response(menu, false, &data.pool).await {
+        Ok(resp) => resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API service
+/// function for changing the name, slug,
+/// or depth of a navigation menu. If 
+/// the received request and resulting 
+/// operation are both valid, an instance 
+/// of the "StatusResponse" with a boolean 
+/// flag is returned as a JSON response. 
+#[post("/menus/edit")]
+pub async fn edit_menu_service(
+    payload: Json<MenuEditPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let edit_op: bool = match edit_menu(
+        &user, 
+        &payload.menu_id,
+        &payload.name,
+        &payload.slug,
+        &payload.max_depth,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: edit_op })
+}
+
+/// This function is the API service
+/// function for deleting a navigation
+/// menu and all its items. If the 
+/// received request and resulting 
+/// operation are both valid, an instance 
+/// of the "StatusResponse" with a boolean 
+/// flag is returned as a JSON response. 
+#[post("/menus/delete")]
+pub async fn delete_menu_service(
+    payload: Json<MenuDeletionPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let del_op: bool = match delete_menu(
+        &user, 
+        &payload.menu_id,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
+}
+
+/// This function is the API
+/// service function for retrieving
+/// all navigation menus of a user with
+/// all their items, including items that
+/// point to posts that are not published.
+/// Clients that send a bearer token can
+/// use a "GET" request without a body.
+/// If the received request and resulting 
+/// operation are both valid, an instance 
+/// of the "MenusResponse" structure is 
+/// returned. In any other case an error
+/// is returned.
+#[route("/menus/all", method = "GET", method = "POST")]
+pub async fn get_menus_service(
+    payload: Option<Json<TokenOnlyPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let api_token: Option<String> = payload.and_then(|payload| payload.into_inner().api_token);
+    let user: CleoUser = match auth.resolve(&api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let menus: Vec<NavMenu> = match get_user_menus(&user.user_id, &data.pool).await {
+        Ok(menus) => menus,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut resp: Vec<MenuResponse> = Vec::new();
+    for menu in menus {
+        let menu_resp: MenuResponse = match menu_to_response(menu, false, &data.pool).await {
+            Ok(menu_resp) => menu_resp,
+            Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        resp.push(menu_resp);
+    }
+    Ok(HttpResponse::Ok().json(MenusResponse{ menus: resp }))
+}
+
+/// This function is the API
+/// service function for adding an
+/// item to a navigation menu. If the
+/// received request and resulting 
+/// operation are both valid, the updated
+/// menu is returned as an instance of the
+/// "MenuResponse" structure. In any other
+/// case an error is returned.
+#[post("/menus/items/add")]
+pub async fn add_menu_item_service(
+    payload: Json<MenuItemCreationPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let item: MenuItem = match add_menu_item(
+        &user, 
+        &payload.menu_id,
+        &payload.parent_id,
+        &payload.label,
+        &payload.target,
+        &payload.position,
+        &data.pool
+    ).await {
+        Ok(item) => item,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let menu: NavMenu = match get_menu_by_id(&item.menu_id, &data.pool).await {
+        Ok(menu) => menu,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: MenuResponse = match menu_to_response(menu, false, &data.pool).await {
+        Ok(resp) => resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API service
+/// function for changing the label or
+/// target of an item of a navigation
+/// menu. If the received request and 
+/// resulting operation are both valid, 
+/// an instance of the "StatusResponse" 
+/// with a boolean flag is returned as a
+/// JSON response. 
+#[post("/menus/items/edit")]
+pub async fn edit_menu_item_service(
+    payload: Json<MenuItemEditPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let edit_op: bool = match edit_menu_item(
+        &user, 
+        &payload.item_id,
+        &payload.label,
+        &payload.target,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: edit_op })
+}
+
+/// This function is the API service
+/// function for moving an item of a 
+/// navigation menu below another item
+/// or to the top level. If the received
+/// request and resulting operation are
+/// both valid, an instance of the 
+/// "StatusResponse" with a boolean flag
+/// is returned as a JSON response. 
+#[post("/menus/items/move")]
+pub async fn move_menu_item_service(
+    payload: Json<MenuItemMovePayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let move_op: bool = match move_menu_item(
+        &user, 
+        &payload.item_id,
+        &payload.parent_id,
+        &payload.position,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: move_op })
+}
+
+/// This function is the API service
+/// function for deleting an item of
+/// a navigation menu together with the
+/// items below it. If the received
+/// request and resulting operation are
+/// both valid, an instance of the 
+/// "StatusResponse" with a boolean flag
+/// is returned as a JSON response. 
+#[post("/menus/items/delete")]
+pub async fn delete_menu_item_service(
+    payload: Json<MenuItemDeletionPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let del_op: bool = match delete_menu_item(
+        &user, 
+        &payload.item_id,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a navigation menu of a user with all
+/// its items in one call. Items pointing
+/// to posts or files that are missing or
+/// not published are left out together with
+/// the items below them. No credentials are
+/// required. If the received request and 
+/// resulting operation are both valid, an
+/// instance of the "MenuResponse" structure
+/// is returned. In any other case an error
+/// is returned.
+#[get("/public/menus/{username}/{slug}")]
+pub async fn get_public_menu_service(
+    menu: Path<MenuExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user_obj: CleoUser = match get_user_by_username(&menu.username, &data.pool).await {
+        Ok(user_obj) => user_obj,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let menu_obj: NavMenu = match get_menu_by_slug(&user_obj.user_id, &menu.slug, &data.pool).await {
+        Ok(menu_obj) => menu_obj,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: MenuResponse = match menu_to_response(menu_obj, true, &data.pool).await {
+        Ok(resp) => resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
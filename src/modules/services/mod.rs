@@ -23,6 +23,60 @@ pub mod keys;
 /// actions for posts.
 pub mod posts;
 
+/// Exporting the
+/// service functions
+/// for performing
+/// actions on post
+/// revisions.
+pub mod revisions;
+
+/// Exporting the
+/// service functions
+/// for searching
+/// published posts.
+pub mod search;
+
+/// Exporting the
+/// service functions
+/// for managing tags
+/// and categories.
+pub mod taxonomy;
+
+/// Exporting the
+/// service functions
+/// for arranging pages
+/// in a hierarchy.
+pub mod pages;
+
+/// Exporting the
+/// service functions
+/// for managing navigation
+/// menus.
+pub mod menus;
+
+/// Exporting the
+/// service functions
+/// for publishing feeds
+/// and sitemaps of posts.
+pub mod feeds;
+
+/// Exporting the
+/// service functions
+/// for managing webhooks.
+pub mod webhooks;
+
+/// Exporting the
+/// service functions
+/// for streaming changes
+/// to content.
+pub mod changes;
+
+/// Exporting the
+/// service functions
+/// for defining types
+/// of content.
+pub mod content_types;
+
 /// Exporting the
 /// service functions
 /// for performing
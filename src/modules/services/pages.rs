@@ -0,0 +1,303 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "get"
+/// macro to make a service
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "post"
+/// macro to make a service
+/// that accepts "POST" requests.
+use actix_web::post;
+
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Json"
+/// structure to return JSON
+/// responses.
+use actix_web::web::Json;
+
+/// Importing the "Path"
+/// structure to extract
+/// parameters from request
+/// URLs.
+use actix_web::web::Path;
+
+/// Importing the "HttpResponse"
+/// structure to return an HTTP
+/// response.
+use actix_web::HttpResponse;
+
+/// Importing the "HashSet"
+/// structure to keep track of
+/// pages already in the tree.
+use std::collections::HashSet;
+
+/// Importing the "HashMap"
+/// structure to group pages
+/// by their parent.
+use std::collections::HashMap;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// user posts for explicit
+/// typing.
+use crate::modules::models::UserPost;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the function to move
+/// a page below another page.
+use crate::modules::db::pages::move_page;
+
+/// Importing the data structure for
+/// returning a page and the pages
+/// below it.
+use crate::modules::responses::PageTreeNode;
+
+/// Importing the function to change
+/// the order of pages below a parent.
+use crate::modules::db::pages::reorder_pages;
+
+/// Importing the function to retrieve
+/// all pages of a user.
+use crate::modules::db::pages::get_user_pages;
+
+/// Importing the data structure for
+/// submitting a payload for moving
+/// a page.
+use crate::modules::payloads::PageMovePayload;
+
+/// Importing the data structure
+/// for returning info on whether
+/// a write operation was successful
+/// or not.
+use crate::modules::responses::StatusResponse;
+
+/// Importing the data structure for
+/// submitting a payload for actions
+/// that only require an API token.
+use crate::modules::payloads::TokenOnlyPayload;
+
+/// Importing the data structure for
+/// returning the hierarchy of pages.
+use crate::modules::responses::PageTreeResponse;
+
+/// Importing the data structure for
+/// submitting a payload for changing
+/// the order of pages.
+use crate::modules::payloads::PageReorderPayload;
+
+/// Importing the structure to extract
+/// a username from request URLs.
+use crate::modules::extractors::UsernameExtractor;
+
+/// Importing the scope for
+/// reading private
+/// content.
+use crate::modules::db::tokens::SCOPE_CONTENT_READ;
+
+/// Importing the function to retrieve
+/// a user given their username.
+use crate::modules::db::users::get_user_by_username;
+
+/// Importing the scope for
+/// writing content.
+use crate::modules::db::tokens::SCOPE_CONTENT_WRITE;
+
+/// Builds the nodes for the pages
+/// below the given parent, or the 
+/// top-level pages if no parent is 
+/// given, together with all pages below
+/// them. Pages that were already added
+/// are skipped.
+fn build_page_nodes(
+    parent_id: &Option<String>,
+    children: &mut HashMap<Option<String>, Vec<UserPost>>,
+    visited: &mut HashSet<String>
+) -> Vec<PageTreeNode> {
+    let mut nodes: Vec<PageTreeNode> = Vec::new();
+    let pages: Vec<UserPost> = children.remove(parent_id).unwrap_or_default();
+    for page in pages {
+        if !visited.insert(page.content_id.clone()) {
+            continue;
+        }
+        let page_children: Vec<PageTreeNode> = build_page_nodes(
+            &Some(page.content_id.clone()), 
+            children, 
+            visited
+        );
+        nodes.push(
+            PageTreeNode{
+                content_id: page.content_id,
+                title: page.title,
+                slug: page.slug,
+                post_status: page.post_status,
+                page_position: page.page_position,
+                children: page_children
+            }
+        );
+    }
+    nodes
+}
+
+/// Arranges the supplied pages into
+/// a tree. The pages must be in the order
+/// they appear among their siblings. Pages
+/// whose parent is not among the supplied
+/// pages are left out together with the pages
+/// below them.
+pub fn build_page_tree(pages: Vec<UserPost>) -> Vec<PageTreeNode> {
+    let mut children: HashMap<Option<String>, Vec<UserPost>> = HashMap::new();
+    for page in pages {
+        children.entry(page.parent_id.clone()).or_default().push(page);
+    }
+    let mut visited: HashSet<String> = HashSet::new();
+    build_page_nodes(&None, &mut children, &mut visited)
+}
+
+/// This function is the API service
+/// function for moving a page below
+/// another page or to the top level.
+/// If the received request and resulting 
+/// operation are both valid, an instance 
+/// of the "StatusResponse" with a boolean 
+/// flag is returned as a JSON response. 
+#[post("/pages/move")]
+pub async fn move_page_service(
+    payload: Json<PageMovePayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let move_op: bool = match move_page(
+        &user, 
+        &payload.content_id,
+        &payload.parent_id,
+        &payload.position,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: move_op })
+}
+
+/// This function is the API service
+/// function for changing the order of
+/// the pages below a parent page. If 
+/// the received request and resulting 
+/// operation are both valid, an instance 
+/// of the "StatusResponse" with a boolean 
+/// flag is returned as a JSON response. 
+#[post("/pages/reorder")]
+pub async fn reorder_pages_service(
+    payload: Json<PageReorderPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let reorder_op: bool = match reorder_pages(
+        &user, 
+        &payload.parent_id,
+        &payload.content_ids,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: reorder_op })
+}
+
+/// This function is the API
+/// service function for retrieving
+/// the hierarchy of all pages of a 
+/// user, including pages that are 
+/// not published. Clients that send a
+/// bearer token can use a "GET" request
+/// without a body. If the received request
+/// and resulting operation are both valid,
+/// an instance of the "PageTreeResponse" 
+/// structure is returned. In any other case
+/// an error is returned.
+#[route("/pages/tree", method = "GET", method = "POST")]
+pub async fn get_page_tree_service(
+    payload: Option<Json<TokenOnlyPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let api_token: Option<String> = payload.and_then(|payload| payload.into_inner().api_token);
+    let user: CleoUser = match auth.resolve(&api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let pages: Vec<UserPost> = match get_user_pages(&user.user_id, false, &data.pool).await {
+        Ok(pages) => pages,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(PageTreeResponse{ pages: build_page_tree(pages) }))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// the hierarchy of the published pages
+/// of a user. Published pages below a page
+/// that is not published are left out. No
+/// credentials are required. If the received 
+/// request and resulting operation are both 
+/// valid, an instance of the "PageTreeResponse" 
+/// structure is returned. In any other case an 
+/// error is returned.
+#[get("/public/pages/{username}/tree")]
+pub async fn get_public_page_tree_service(
+    user: Path<UsernameExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user_obj: CleoUser = match get_user_by_username(&user.username, &data.pool).await {
+        Ok(user_obj) => user_obj,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let pages: Vec<UserPost> = match get_user_pages(&user_obj.user_id, true, &data.pool).await {
+        Ok(pages) => pages,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(PageTreeResponse{ pages: build_page_tree(pages) }))
+}
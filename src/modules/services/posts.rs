@@ -3,11 +3,21 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
 /// Importing the "post"
 /// decorator to make a service
 /// that accepts "POST" requests.
 use actix_web::post;
 
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing of timestamps.
+use chrono::DateTime;
+
 /// Importing the "Result"
 /// enum for Actix Web services.
 use actix_web::Result;
@@ -26,6 +36,11 @@ use actix_web::web::Json;
 /// to return a HTTP response.
 use actix_web::HttpResponse;
 
+/// Importing the "HashMap"
+/// structure to pass maps
+/// of extra content fields.
+use std::collections::HashMap;
+
 /// Importing this crate's
 /// error structure.
 use crate::modules::err::CleoErr;
@@ -35,20 +50,39 @@ use crate::modules::err::CleoErr;
 /// persistent app data.
 use crate::modules::units::AppData;
 
+/// Importing the structure
+/// holding everything a new post
+/// is created from.
+use crate::modules::units::NewPost;
+
 /// Importing the model for
 /// user posts for explicit
 /// typing.
 use crate::modules::models::UserPost;
 
-/// Importing the enum to describe
-/// all possible types of content.
-use crate::modules::payloads::ContentType;
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure holding
+/// the descriptive fields of a post.
+use crate::modules::units::PostDetails;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
 
 /// Importing the function
 /// to delete a post a user
 /// has created.
 use crate::modules::db::posts::delete_post;
 
+/// Importing the enum to describe
+/// all possible formats of the text
+/// of a post.
+use crate::modules::payloads::SourceFormat;
+
 /// Importing the data structure
 /// for returning info on whether
 /// a write operation was successful
@@ -78,11 +112,48 @@ use crate::modules::db::posts::update_post_text;
 /// posts.
 use crate::modules::responses::UserPostResponse;
 
+/// Importing the data structure for
+/// submitting a payload for changing
+/// the publication status of a post.
+use crate::modules::payloads::PostStatusPayload;
+
+/// Importing the data structure for
+/// submitting a payload for changing
+/// the slug of a post.
+use crate::modules::payloads::UpdateSlugPayload;
+
+/// Importing the function to
+/// change the slug of a post.
+use crate::modules::db::slugs::update_post_slug;
+
 /// Importing the data structure for
 /// submitting a payload for creating
 /// a new post.
 use crate::modules::payloads::PostCreationPayload;
 
+/// Importing the function to
+/// change the publication status
+/// of a post.
+use crate::modules::db::posts::update_post_status;
+
+/// Importing the data structure for
+/// submitting a payload for scheduling
+/// the publication of a post.
+use crate::modules::payloads::SchedulePostPayload;
+
+/// Importing the function to update
+/// the descriptive fields of a post.
+use crate::modules::db::posts::update_post_details;
+
+/// Importing the function to create
+/// a response for a post with its
+/// extra content fields.
+use crate::modules::services::ecf::post_to_response;
+
+/// Importing the scope for
+/// writing content.
+use crate::modules::db::tokens::SCOPE_CONTENT_WRITE;
+
 /// This function is the API service
 /// function for creating a post.
 /// If the received request and resulting
@@ -93,46 +164,85 @@ use crate::modules::payloads::PostCreationPayload;
 #[post("/posts/create")]
 pub async fn create_user_post_service(
     payload: Json<PostCreationPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr> {
-    let content_type: String;
-    if &payload.content_type == &ContentType::Page{
-        content_type = "page".to_string();
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let content_type: String = payload.content_type.to_lowercase();
+    let extra_fields: HashMap<String, String> = payload.extra_fields.clone().unwrap_or_default();
+    let source_format: String = if payload.source_format == Some(SourceFormat::Markdown){
+        "markdown".to_string()
     }
     else {
-        content_type = "post".to_string();
-    }
-    let post: UserPost = match create_user_post(
-        &payload.api_token, 
-        &content_type, 
-        &payload.content_text, 
-        &data.pool
-    ).await {
+        "html".to_string()
+    };
+    let details: PostDetails = PostDetails{
+        title: payload.title.clone(),
+        excerpt: payload.excerpt.clone(),
+        cover_file_id: payload.cover_file_id.clone(),
+        author_name: payload.author_name.clone()
+    };
+    let new_post: NewPost = NewPost{
+        content_type,
+        content_text: payload.content_text.clone(),
+        source_format,
+        slug: payload.slug.clone(),
+        details,
+        extra_fields
+    };
+    let post: UserPost = match create_user_post(&user, &new_post, &data.pool).await {
         Ok(post) => post,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
-    let resp: UserPostResponse = UserPostResponse{
-        content_id: post.content_id,
-        content_type: post.content_type,
-        user_id: post.user_id,
-        content_text: post.content_text
+    let resp: UserPostResponse = match post_to_response(post, &data.pool).await {
+        Ok(resp) => resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
     Ok(HttpResponse::Ok().json(resp))
 }
 
 /// This function is the API service
 /// function for editing a post of a user.
-/// If the received request and resulting
-/// operation are both valid, an instance of
-/// the "StatusResponse" with a boolean flag
-/// is returned as a JSON response. 
+/// The title, excerpt, cover image, and 
+/// author name are only changed if they 
+/// are supplied. If the received request 
+/// and resulting operation are both valid, 
+/// an instance of the "StatusResponse" with 
+/// a boolean flag is returned as a JSON 
+/// response. 
 #[post("/posts/update")]
 pub async fn update_user_post_service(
     payload: Json<UpdatePostPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let details: PostDetails = PostDetails{
+        title: payload.title.clone(),
+        excerpt: payload.excerpt.clone(),
+        cover_file_id: payload.cover_file_id.clone(),
+        author_name: payload.author_name.clone()
+    };
+    let details_op: bool = match update_post_details(
+        &user, 
+        &payload.content_id,
+        &details, 
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    if !details_op {
+        return HttpResponse::Ok().json(StatusResponse{ is_ok: details_op });
+    }
     let update_op: bool = match update_post_text(
-        &payload.api_token, 
+        &user, 
         &payload.content_id,
         &payload.text, 
         &data.pool
@@ -143,6 +253,165 @@ pub async fn update_user_post_service(
     HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
 }
 
+/// This function is the API service
+/// function for changing the slug of 
+/// a post of a user. The old slug keeps
+/// redirecting to the post. If the received
+/// request and resulting operation are both 
+/// valid, an instance of the "StatusResponse" 
+/// with a boolean flag is returned as a JSON 
+/// response. 
+#[post("/posts/slug")]
+pub async fn update_user_post_slug_service(
+    payload: Json<UpdateSlugPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let update_op: bool = match update_post_slug(
+        &user, 
+        &payload.content_id,
+        &payload.slug, 
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
+
+/// This function is the API service
+/// function for publishing a post of
+/// a user. If the received request 
+/// and resulting operation are both valid, 
+/// an instance of the "StatusResponse" with 
+/// a boolean flag is returned as a JSON 
+/// response. 
+#[post("/posts/publish")]
+pub async fn publish_user_post_service(
+    payload: Json<PostStatusPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let update_op: bool = match update_post_status(
+        &user, 
+        &payload.content_id,
+        &"published".to_string(), 
+        &Some(Utc::now()),
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
+
+/// This function is the API service
+/// function for turning a post of
+/// a user back into a draft. If the received 
+/// request and resulting operation are both 
+/// valid, an instance of the "StatusResponse" 
+/// with a boolean flag is returned as a JSON 
+/// response. 
+#[post("/posts/unpublish")]
+pub async fn unpublish_user_post_service(
+    payload: Json<PostStatusPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let update_op: bool = match update_post_status(
+        &user, 
+        &payload.content_id,
+        &"draft".to_string(), 
+        &None,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
+
+/// This function is the API service
+/// function for archiving a post of
+/// a user. If the received request 
+/// and resulting operation are both valid, 
+/// an instance of the "StatusResponse" with 
+/// a boolean flag is returned as a JSON 
+/// response. 
+#[post("/posts/archive")]
+pub async fn archive_user_post_service(
+    payload: Json<PostStatusPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let update_op: bool = match update_post_status(
+        &user, 
+        &payload.content_id,
+        &"archived".to_string(), 
+        &None,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: update_op })
+}
+
+/// This function is the API service
+/// function for scheduling the publication
+/// of a post of a user at a future time.
+/// If the received request and resulting
+/// operation are both valid, an instance of
+/// the "StatusResponse" with a boolean flag
+/// is returned as a JSON response. In any
+/// other case an error is returned.
+#[post("/posts/schedule")]
+pub async fn schedule_user_post_service(
+    payload: Json<SchedulePostPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let publish_at: DateTime<Utc> = match DateTime::parse_from_rfc3339(&payload.publish_at) {
+        Ok(publish_at) => publish_at.with_timezone(&Utc),
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if publish_at <= Utc::now() {
+        let e: String = "The time of publication must be in the future.".to_string();
+        return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let update_op: bool = match update_post_status(
+        &user, 
+        &payload.content_id,
+        &"scheduled".to_string(), 
+        &Some(publish_at),
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    Ok(HttpResponse::Ok().json(StatusResponse{ is_ok: update_op }))
+}
+
 /// This function is the API service
 /// function for deleting a  post of a user.
 /// If the received request and resulting
@@ -152,10 +421,15 @@ pub async fn update_user_post_service(
 #[post("/posts/delete")]
 pub async fn delete_user_post_service(
     payload: Json<DeletePostPayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let del_op: bool = match delete_post(
-        &payload.api_token, 
+        &user, 
         &payload.content_id,
         &data.pool
     ).await {
@@ -0,0 +1,301 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "post"
+/// decorator to make a service
+/// that accepts "POST" requests.
+use actix_web::post;
+
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Json"
+/// structure to return JSON
+/// responses.
+use actix_web::web::Json;
+
+/// Importing the function to
+/// deserialize JSON strings.
+use serde_json::from_str;
+
+/// Importing the "Query" structure
+/// to read the parameters of requests
+/// from their query strings.
+use actix_web::web::Query;
+
+/// Importing the function
+/// to return a HTTP response.
+use actix_web::HttpResponse;
+
+/// Importing this crate's
+/// error structure.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to register
+/// persistent app data.
+use crate::modules::units::AppData;
+
+/// Importing the function to compute
+/// the differences between two texts.
+use crate::modules::utils::diff_text;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the structure holding
+/// a snapshot of an extra content
+/// field.
+use crate::modules::units::FieldSnapshot;
+
+/// Importing the model for
+/// post revisions for explicit
+/// typing.
+use crate::modules::models::PostRevision;
+
+/// Importing the function to read
+/// the parameters of a request from
+/// its query string or its body.
+use crate::modules::extractors::read_params;
+
+/// Importing the data structure
+/// for returning data on all revisions
+/// of a post.
+use crate::modules::responses::PostRevisions;
+
+/// Importing the data structure for
+/// submitting a payload for comparing
+/// two revisions of a post.
+use crate::modules::payloads::RevisionDiffPayload;
+
+/// Importing the data structure for
+/// submitting a payload for listing
+/// the revisions of a post.
+use crate::modules::payloads::PostRevisionsPayload;
+
+/// Importing the scope for
+/// reading private
+/// content.
+use crate::modules::db::tokens::SCOPE_CONTENT_READ;
+
+/// Importing the data structure for
+/// returning the differences between
+/// two revisions of a post.
+use crate::modules::responses::RevisionDiffResponse;
+
+/// Importing the data structure for
+/// returning information on a revision
+/// of a post.
+use crate::modules::responses::PostRevisionResponse;
+
+/// Importing the scope for
+/// writing content.
+use crate::modules::db::tokens::SCOPE_CONTENT_WRITE;
+
+/// Importing the data structure for
+/// submitting a payload for restoring
+/// a revision of a post.
+use crate::modules::payloads::RestoreRevisionPayload;
+
+/// Importing the function to restore
+/// a revision of a post.
+use crate::modules::db::revisions::restore_post_revision;
+
+/// Importing the function to retrieve
+/// a single revision of a post.
+use crate::modules::db::revisions::get_user_post_revision;
+
+/// Importing the function to retrieve
+/// all revisions of a post.
+use crate::modules::db::revisions::get_user_post_revisions;
+
+/// Attempts to create an instance of the
+/// "PostRevisionResponse" structure from an
+/// instance of the "PostRevision" model. If
+/// this operation fails, an error is returned.
+pub fn revision_to_response(
+    revision: PostRevision
+) -> Result<PostRevisionResponse, CleoErr> {
+    let extra_fields: Vec<FieldSnapshot> = match from_str(&revision.extra_fields) {
+        Ok(extra_fields) => extra_fields,
+        Err(e) => return Err::<PostRevisionResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: PostRevisionResponse = PostRevisionResponse{
+        revision_id: revision.revision_id,
+        content_id: revision.content_id,
+        user_id: revision.user_id,
+        content_text: revision.content_text,
+        extra_fields,
+        created_at: revision.created_at.to_rfc3339(),
+        title: revision.title,
+        excerpt: revision.excerpt,
+        cover_file_id: revision.cover_file_id,
+        author_name: revision.author_name
+    };
+    Ok(resp)
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a vector of all revisions of a post.
+/// If the received request and resulting
+/// operation are both valid, an instance
+/// of the "PostRevisions" structure
+/// is returned. In any other case an error is
+/// returned.
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/posts/revisions", method = "GET", method = "POST")]
+pub async fn get_post_revisions_service(
+    query: Option<Query<PostRevisionsPayload>>,
+    body: Option<Json<PostRevisionsPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let payload: PostRevisionsPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut sanitized: Vec<PostRevisionResponse> = Vec::new();
+    let revisions: Vec<PostRevision> = match get_user_post_revisions(
+        &user, 
+        &payload.content_id, 
+        &data.pool
+    ).await {
+        Ok(revisions) => revisions,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    for revision in revisions {
+        let resp_revision: PostRevisionResponse = match revision_to_response(revision) {
+            Ok(resp_revision) => resp_revision,
+            Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+        };
+        sanitized.push(resp_revision);
+    }
+    let resp: PostRevisions = PostRevisions{ revisions: sanitized };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API
+/// service function for comparing
+/// two revisions of a post. If the 
+/// received request and resulting
+/// operation are both valid, an instance
+/// of the "RevisionDiffResponse" structure
+/// is returned. In any other case an error is
+/// returned.
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/posts/revisions/diff", method = "GET", method = "POST")]
+pub async fn diff_post_revisions_service(
+    query: Option<Query<RevisionDiffPayload>>,
+    body: Option<Json<RevisionDiffPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let payload: RevisionDiffPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let from: PostRevision = match get_user_post_revision(
+        &user, 
+        &payload.content_id, 
+        &payload.from_revision,
+        &data.pool
+    ).await {
+        Ok(from) => from,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let to: PostRevision = match get_user_post_revision(
+        &user, 
+        &payload.content_id, 
+        &payload.to_revision,
+        &data.pool
+    ).await {
+        Ok(to) => to,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let text_changes = diff_text(&from.content_text, &to.content_text);
+    let from_resp: PostRevisionResponse = match revision_to_response(from) {
+        Ok(from_resp) => from_resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let to_resp: PostRevisionResponse = match revision_to_response(to) {
+        Ok(to_resp) => to_resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: RevisionDiffResponse = RevisionDiffResponse{
+        from_revision: from_resp.revision_id,
+        to_revision: to_resp.revision_id,
+        text_changes,
+        from_fields: from_resp.extra_fields,
+        to_fields: to_resp.extra_fields
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API
+/// service function for restoring
+/// a revision of a post as the current
+/// version of the post. If the received
+/// request and resulting operation are
+/// both valid, the newly-saved revision
+/// is returned as an instance of the
+/// "PostRevisionResponse" structure.
+/// In any other case an error is
+/// returned.
+#[post("/posts/revisions/restore")]
+pub async fn restore_post_revision_service(
+    payload: Json<RestoreRevisionPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let restored: PostRevision = match restore_post_revision(
+        &user, 
+        &payload.content_id, 
+        &payload.revision_id,
+        &data.pool
+    ).await {
+        Ok(restored) => restored,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: PostRevisionResponse = match revision_to_response(restored) {
+        Ok(resp) => resp,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
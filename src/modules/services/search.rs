@@ -0,0 +1,156 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "get"
+/// macro to make a service
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Query" structure
+/// to extract options from the query
+/// string of request URLs.
+use actix_web::web::Query;
+
+/// Importing the "HttpResponse"
+/// structure to return an HTTP
+/// response.
+use actix_web::HttpResponse;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the structure
+/// holding a single match of
+/// a search.
+use crate::modules::units::SearchHit;
+
+/// Importing the model for
+/// user posts for explicit
+/// typing.
+use crate::modules::models::UserPost;
+
+/// Importing the structure holding
+/// the options for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure holding
+/// the filters for searching posts.
+use crate::modules::units::SearchFilters;
+
+/// Importing the data structure for
+/// returning information on the page
+/// of a list.
+use crate::modules::responses::Pagination;
+
+/// Importing the data structure for
+/// returning a page of search results.
+use crate::modules::responses::SearchResults;
+
+/// Importing the function to search
+/// the text of published posts.
+use crate::modules::db::search::search_posts;
+
+/// Importing the data structure for
+/// returning information on posts.
+use crate::modules::responses::UserPostResponse;
+
+/// Importing the function to check
+/// the options for listing records.
+use crate::modules::utils::resolve_list_options;
+
+/// Importing the fields the results
+/// of a search can be sorted by.
+use crate::modules::db::search::SEARCH_SORT_FIELDS;
+
+/// Importing the data structure for
+/// returning information on a post
+/// matching a search.
+use crate::modules::responses::SearchResultResponse;
+
+/// Importing the structure to extract a
+/// search query from request URLs.
+use crate::modules::extractors::SearchQueryExtractor;
+
+/// Importing the function to create
+/// responses for several posts with
+/// their extra content fields.
+use crate::modules::services::ecf::posts_to_responses;
+
+/// This function is the API
+/// service function for searching
+/// the published posts on the instance.
+/// The results can be filtered by the
+/// username of their author and their
+/// content type. No credentials are 
+/// required. If the received request and
+/// resulting operation are both valid, 
+/// an instance of the "SearchResults" 
+/// structure is returned. In any other 
+/// case an error is returned.
+#[get("/public/search")]
+pub async fn search_posts_service(
+    search: Query<SearchQueryExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let options: ListOptions = match resolve_list_options(
+        &search.page, 
+        &search.per_page, 
+        &search.sort_by, 
+        &search.sort_order, 
+        &SEARCH_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let filters: SearchFilters = SearchFilters{
+        author: search.author.clone(),
+        content_type: search.content_type.clone()
+    };
+    let (hits, posts, total): (Vec<SearchHit>, Vec<UserPost>, i64) = match search_posts(
+        &search.q, 
+        &filters, 
+        &options, 
+        &data.pool
+    ).await {
+        Ok(found) => found,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let sanitized: Vec<UserPostResponse> = match posts_to_responses(posts, &data.pool).await {
+        Ok(sanitized) => sanitized,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut results: Vec<SearchResultResponse> = Vec::new();
+    for (hit, post) in hits.into_iter().zip(sanitized) {
+        results.push(
+            SearchResultResponse{
+                rank: hit.rank,
+                snippet: hit.snippet,
+                post
+            }
+        );
+    }
+    let resp: SearchResults = SearchResults{
+        query: search.q.trim().to_string(),
+        results,
+        pagination: Pagination::new(&options, total)
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
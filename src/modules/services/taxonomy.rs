@@ -0,0 +1,462 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "get"
+/// macro to make a service
+/// that accepts "GET" requests.
+use actix_web::get;
+
+/// Importing the "post"
+/// macro to make a service
+/// that accepts "POST" requests.
+use actix_web::post;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Json"
+/// structure to return JSON
+/// responses.
+use actix_web::web::Json;
+
+/// Importing the "Path"
+/// structure to extract
+/// parameters from request
+/// URLs.
+use actix_web::web::Path;
+
+/// Importing the "Query" structure
+/// to extract options from the query
+/// string of request URLs.
+use actix_web::web::Query;
+
+/// Importing the "HttpResponse"
+/// structure to return an HTTP
+/// response.
+use actix_web::HttpResponse;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the model for
+/// user posts for explicit
+/// typing.
+use crate::modules::models::UserPost;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure holding
+/// the options for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the model for tags
+/// and categories for explicit
+/// typing.
+use crate::modules::models::TaxonomyTerm;
+
+/// Importing the data structure for
+/// returning information on the page
+/// of a list.
+use crate::modules::responses::Pagination;
+
+/// Importing the function to edit
+/// a tag or category.
+use crate::modules::db::taxonomy::edit_term;
+
+/// Importing the function to move
+/// a category.
+use crate::modules::db::taxonomy::move_term;
+
+/// Importing the function to retrieve
+/// all tags or all categories.
+use crate::modules::db::taxonomy::get_terms;
+
+/// Importing the scope for
+/// administering the
+/// instance.
+use crate::modules::db::tokens::SCOPE_ADMIN;
+
+/// Importing the data structure for
+/// submitting a payload for editing
+/// a tag or category.
+use crate::modules::payloads::TermEditPayload;
+
+/// Importing the data structure for
+/// submitting a payload for moving
+/// a category.
+use crate::modules::payloads::TermMovePayload;
+
+/// Importing the function to create
+/// a tag or category.
+use crate::modules::db::taxonomy::create_term;
+
+/// Importing the function to delete
+/// a tag or category.
+use crate::modules::db::taxonomy::delete_term;
+
+/// Importing the function to assign
+/// a tag or category to a post.
+use crate::modules::db::taxonomy::assign_term;
+
+/// Importing the data structure for
+/// submitting a payload for assigning
+/// or removing a term on a post.
+use crate::modules::payloads::PostTermPayload;
+
+/// Importing the structure to extract
+/// the slug of a term from request URLs.
+use crate::modules::extractors::TermExtractor;
+
+/// Importing the data structure
+/// for returning info on whether
+/// a write operation was successful
+/// or not.
+use crate::modules::responses::StatusResponse;
+
+/// Importing the function to remove
+/// a tag or category from a post.
+use crate::modules::db::taxonomy::unassign_term;
+
+/// Importing the data structure for
+/// returning information on posts.
+use crate::modules::responses::UserPostResponse;
+
+/// Importing the function to check
+/// the options for listing records.
+use crate::modules::utils::resolve_list_options;
+
+/// Importing the data structure for
+/// returning a term and the posts
+/// that have it.
+use crate::modules::responses::TermPostsResponse;
+
+/// Importing the fields the posts
+/// of a user can be sorted by.
+use crate::modules::db::general::POST_SORT_FIELDS;
+
+/// Importing the data structure for
+/// submitting a payload for creating
+/// a tag or category.
+use crate::modules::payloads::TermCreationPayload;
+
+/// Importing the data structure for
+/// submitting a payload for deleting
+/// a tag or category.
+use crate::modules::payloads::TermDeletionPayload;
+
+/// Importing the structure to extract
+/// the name of a taxonomy from request URLs.
+use crate::modules::extractors::TaxonomyExtractor;
+
+/// Importing the function to retrieve
+/// a tag or category given its slug.
+use crate::modules::db::taxonomy::get_term_by_slug;
+
+/// Importing the structure to extract
+/// options for listing from request URLs.
+use crate::modules::extractors::ListQueryExtractor;
+
+/// Importing the data structure for
+/// returning information on a tag
+/// or category.
+use crate::modules::responses::TaxonomyTermResponse;
+
+/// Importing the scope for
+/// writing content.
+use crate::modules::db::tokens::SCOPE_CONTENT_WRITE;
+
+/// Importing the data structure for
+/// returning information on all tags
+/// or all categories.
+use crate::modules::responses::TaxonomyTermsResponse;
+
+/// Importing the function to create
+/// responses for several posts with
+/// their extra content fields.
+use crate::modules::services::ecf::posts_to_responses;
+
+/// Importing the function to retrieve
+/// the published posts that have a tag
+/// or category.
+use crate::modules::db::taxonomy::get_public_posts_by_term;
+
+/// This function is the API
+/// service function for creating
+/// a tag or category. Only 
+/// administrators can do this. If the
+/// received request and resulting operation 
+/// are both valid, an instance of the 
+/// "TaxonomyTermResponse" structure is 
+/// returned. In any other case an error is
+/// returned.
+#[post("/taxonomy/create")]
+pub async fn create_term_service(
+    payload: Json<TermCreationPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let term: TaxonomyTerm = match create_term(
+        &user, 
+        &payload.taxonomy.to_lowercase(), 
+        &payload.name,
+        &payload.slug,
+        &payload.description.clone().unwrap_or_default(),
+        &payload.parent_id,
+        &data.pool
+    ).await {
+        Ok(term) => term,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(HttpResponse::Ok().json(TaxonomyTermResponse::new(term)))
+}
+
+/// This function is the API service
+/// function for editing the name, slug,
+/// or description of a tag or category.
+/// Only administrators can do this. If 
+/// the received request and resulting 
+/// operation are both valid, an instance 
+/// of the "StatusResponse" with a boolean 
+/// flag is returned as a JSON response. 
+#[post("/taxonomy/edit")]
+pub async fn edit_term_service(
+    payload: Json<TermEditPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let edit_op: bool = match edit_term(
+        &user, 
+        &payload.term_id,
+        &payload.name,
+        &payload.slug,
+        &payload.description,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: edit_op })
+}
+
+/// This function is the API service
+/// function for moving a category below
+/// another category or to the top level.
+/// Only administrators can do this. If 
+/// the received request and resulting 
+/// operation are both valid, an instance 
+/// of the "StatusResponse" with a boolean 
+/// flag is returned as a JSON response. 
+#[post("/taxonomy/move")]
+pub async fn move_term_service(
+    payload: Json<TermMovePayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let move_op: bool = match move_term(
+        &user, 
+        &payload.term_id,
+        &payload.parent_id,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: move_op })
+}
+
+/// This function is the API service
+/// function for deleting a tag or 
+/// category. Only administrators can 
+/// do this. If the received request and 
+/// resulting operation are both valid, 
+/// an instance of the "StatusResponse" with 
+/// a boolean flag is returned as a JSON 
+/// response. 
+#[post("/taxonomy/delete")]
+pub async fn delete_term_service(
+    payload: Json<TermDeletionPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ADMIN, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let del_op: bool = match delete_term(
+        &user, 
+        &payload.term_id,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
+}
+
+/// This function is the API service
+/// function for assigning a tag or 
+/// category to a post. Only the owner
+/// of the post can do this. If the received
+/// request and resulting operation are both
+/// valid, an instance of the "StatusResponse"
+/// with a boolean flag is returned as a JSON 
+/// response. 
+#[post("/taxonomy/assign")]
+pub async fn assign_term_service(
+    payload: Json<PostTermPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let assign_op: bool = match assign_term(
+        &user, 
+        &payload.content_id,
+        &payload.term_id,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: assign_op })
+}
+
+/// This function is the API service
+/// function for removing a tag or 
+/// category from a post. Only the owner
+/// of the post can do this. If the received
+/// request and resulting operation are both
+/// valid, an instance of the "StatusResponse"
+/// with a boolean flag is returned as a JSON 
+/// response. 
+#[post("/taxonomy/unassign")]
+pub async fn unassign_term_service(
+    payload: Json<PostTermPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let unassign_op: bool = match unassign_term(
+        &user, 
+        &payload.content_id,
+        &payload.term_id,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: unassign_op })
+}
+
+/// This function is the API
+/// service function for retrieving
+/// all tags or all categories of the
+/// instance. No credentials are required.
+/// If the operation is successful, an instance
+/// of the "TaxonomyTermsResponse" structure is 
+/// returned. In any other case an error is
+/// returned.
+#[get("/public/terms/{taxonomy}")]
+pub async fn get_terms_service(
+    taxonomy: Path<TaxonomyExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let terms: Vec<TaxonomyTerm> = match get_terms(&taxonomy.taxonomy, &data.pool).await {
+        Ok(terms) => terms,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut sanitized: Vec<TaxonomyTermResponse> = Vec::new();
+    for term in terms {
+        sanitized.push(TaxonomyTermResponse::new(term));
+    }
+    Ok(HttpResponse::Ok().json(TaxonomyTermsResponse{ terms: sanitized }))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a page of the published posts that
+/// have a tag or category. Posts in the
+/// subcategories of a category are included.
+/// No credentials are required. If the 
+/// received request and resulting operation 
+/// are both valid, an instance of the 
+/// "TermPostsResponse" structure is returned. 
+/// In any other case an error is returned.
+#[get("/public/terms/{taxonomy}/{slug}")]
+pub async fn get_term_posts_service(
+    term: Path<TermExtractor>,
+    list: Query<ListQueryExtractor>,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let options: ListOptions = match resolve_list_options(
+        &list.page, 
+        &list.per_page, 
+        &list.sort_by, 
+        &list.sort_order, 
+        &POST_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let term_obj: TaxonomyTerm = match get_term_by_slug(&term.taxonomy, &term.slug, &data.pool).await {
+        Ok(term_obj) => term_obj,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (posts, total): (Vec<UserPost>, i64) = match get_public_posts_by_term(
+        &term_obj.term_id, 
+        &options, 
+        &data.pool
+    ).await {
+        Ok(page) => page,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let sanitized: Vec<UserPostResponse> = match posts_to_responses(posts, &data.pool).await {
+        Ok(sanitized) => sanitized,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: TermPostsResponse = TermPostsResponse{
+        term: TaxonomyTermResponse::new(term_obj),
+        posts: sanitized,
+        pagination: Pagination::new(&options, total)
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
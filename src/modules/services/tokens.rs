@@ -3,11 +3,27 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+/// Importing the "Utc"
+/// structure for timestamps
+/// in UTC.
+use chrono::Utc;
+
 /// Importing the "post"
 /// decorator to make a service
 /// that accepts "POST" requests.
 use actix_web::post;
 
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
+/// Importing the "DateTime"
+/// structure to parse the
+/// expiry of a token.
+use chrono::DateTime;
+
 /// Importing the "Result"
 /// enum for Actix Web services.
 use actix_web::Result;
@@ -35,6 +51,15 @@ use crate::modules::err::CleoErr;
 /// persistent app data.
 use crate::modules::units::AppData;
 
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
 /// Importing the model for
 /// user API keys for explicit
 /// typing.
@@ -44,6 +69,10 @@ use crate::modules::models::UserAPIToken;
 /// delete an API token.
 use crate::modules::db::tokens::delete_token;
 
+/// Importing the function to revoke
+/// an API token given its ID.
+use crate::modules::db::tokens::revoke_token;
+
 /// Importing the data structure
 /// for returning info on whether
 /// a write operation was successful
@@ -55,15 +84,44 @@ use crate::modules::responses::StatusResponse;
 /// info for deleting an API token.
 use crate::modules::payloads::DelTokenPayload;
 
+/// Importing the scope for
+/// changing one's own account.
+use crate::modules::db::tokens::SCOPE_ACCOUNT;
+
+/// Importing the data structure for
+/// submitting a payload for actions
+/// that only require an API token.
+use crate::modules::payloads::TokenOnlyPayload;
+
 /// Importing the data structure for
 /// returning information a created 
 /// API token.
 use crate::modules::responses::APITokenResponse;
 
+/// Importing the function to retrieve
+/// all API tokens of a user.
+use crate::modules::db::tokens::get_user_tokens;
+
+/// Importing the data structure for
+/// returning all API tokens of a user.
+use crate::modules::responses::APITokensResponse;
+
 /// Importing the structure for a 
-/// payload to carry out actions
-/// requiring higher privileges.
-use crate::modules::payloads::AuthActionPayload;
+/// payload to create an API token.
+use crate::modules::payloads::TokenCreationPayload;
+
+/// Importing the function to revoke
+/// all API tokens of a user but one.
+use crate::modules::db::tokens::revoke_other_tokens;
+
+/// Importing the data structure for
+/// returning an existing API token.
+use crate::modules::responses::APITokenInfoResponse;
+
+/// Importing the data structure for
+/// submitting a payload for revoking
+/// an API token.
+use crate::modules::payloads::TokenRevocationPayload;
 
 /// Importing the function to create
 /// an API token for a Cleo user.
@@ -71,28 +129,44 @@ use crate::modules::db::tokens::create_api_token_for_user;
 
 /// This function is the API service
 /// function for creating an API token
-/// for a user.If the received request 
-/// and resulting operation are both valid, 
-/// an instance of "APITokenResponse" 
-/// structure as a JSON response is
-/// returned. In any other case an 
-/// error is returned.
+/// for a user. The token can carry a
+/// label, a set of scopes, and a time
+/// it expires at. If the received request 
+/// and resulting operation are both valid,
+/// an instance of the "APITokenResponse"
+/// structure is returned. The token is not
+/// shown again. In any other case an error 
+/// is returned.
 #[post("/token/create")]
 pub async fn create_api_token_service(
-    payload: Json<AuthActionPayload>,
+    payload: Json<TokenCreationPayload>,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr> {
-    let token: UserAPIToken = match create_api_token_for_user(
+    let expires_at: Option<DateTime<Utc>> = match &payload.expires_at {
+        Some(expires_at) => match DateTime::parse_from_rfc3339(expires_at) {
+            Ok(expires_at) => Some(expires_at.with_timezone(&Utc)),
+            Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+        },
+        None => None
+    };
+    let (token_obj, token): (UserAPIToken, String) = match create_api_token_for_user(
         &payload.username, 
         &payload.password, 
+        &payload.label,
+        &payload.scopes,
+        &expires_at,
         &data.pool
     ).await {
-        Ok(token) => token,
+        Ok(created) => created,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
     let resp: APITokenResponse = APITokenResponse{
-        token_id: token.token_id,
-        token: token.token
+        token_id: token_obj.token_id,
+        token,
+        label: token_obj.label,
+        scopes: token_obj.scopes,
+        expires_at: token_obj.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+        created_at: token_obj.created_at.to_rfc3339()
     };
     Ok(HttpResponse::Ok().json(resp))
 }
@@ -120,3 +194,86 @@ pub async fn delete_api_token_service(
     };
     HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
 }
+
+/// This function is the API
+/// service function for listing
+/// all API tokens of a user without
+/// the tokens themselves. Clients that
+/// send a bearer token can use a "GET"
+/// request without a body. If the received
+/// request and resulting operation are both
+/// valid, an instance of the "APITokensResponse"
+/// structure is returned. In any other case
+/// an error is returned.
+#[route("/token/all", method = "GET", method = "POST")]
+pub async fn get_api_tokens_service(
+    payload: Option<Json<TokenOnlyPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let api_token: Option<String> = payload.and_then(|payload| payload.into_inner().api_token);
+    let (user, current): (CleoUser, UserAPIToken) = match auth.resolve_token(&api_token, SCOPE_ACCOUNT, &data.pool).await {
+        Ok(authenticated) => authenticated,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let tokens: Vec<UserAPIToken> = match get_user_tokens(&user.user_id, &data.pool).await {
+        Ok(tokens) => tokens,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: APITokensResponse = APITokensResponse{
+        tokens: tokens
+            .into_iter()
+            .map(|token| APITokenInfoResponse::new(token, &current.token_id))
+            .collect()
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API service
+/// function for revoking one of a user's
+/// API tokens given its ID. If the received 
+/// request and resulting operation are both
+/// valid, an instance of the "StatusResponse" 
+/// with a boolean flag is returned as a 
+/// JSON response. 
+#[post("/token/revoke")]
+pub async fn revoke_api_token_service(
+    payload: Json<TokenRevocationPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ACCOUNT, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let revoke_op: bool = match revoke_token(&user, &payload.token_id, &data.pool).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: revoke_op })
+}
+
+/// This function is the API service
+/// function for revoking all of a user's
+/// API tokens except the one the request
+/// authenticated with. If the received 
+/// request and resulting operation are both
+/// valid, an instance of the "StatusResponse" 
+/// with a boolean flag is returned as a 
+/// JSON response. 
+#[post("/token/revoke/others")]
+pub async fn revoke_other_api_tokens_service(
+    payload: Json<TokenOnlyPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let (user, current): (CleoUser, UserAPIToken) = match auth.resolve_token(&payload.api_token, SCOPE_ACCOUNT, &data.pool).await {
+        Ok(authenticated) => authenticated,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let revoke_op: bool = match revoke_other_tokens(&user, &current.token_id, &data.pool).await {
+        Ok(_revoked) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: revoke_op })
+}
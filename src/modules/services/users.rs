@@ -48,6 +48,11 @@ use crate::modules::utils::send_email;
 /// typing.
 use crate::modules::models::EmailToken;
 
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
 /// Importing the function
 /// to update a user's
 /// profile picture.
@@ -68,6 +73,11 @@ use crate::modules::db::users::update_email;
 /// or not.
 use crate::modules::responses::StatusResponse;
 
+/// Importing the scope for
+/// changing one's
+/// own account.
+use crate::modules::db::tokens::SCOPE_ACCOUNT;
+
 /// Importing the function to
 /// update the username of a user.
 use crate::modules::db::users::update_username;
@@ -100,11 +110,6 @@ use crate::modules::db::email::create_email_token;
 /// new users.
 use crate::modules::payloads::UserCreationPayload;
 
-/// Importing the "get_user_from_token" 
-/// function to retrieve an entry for a Cleo
-/// user given their API token.
-use crate::modules::db::users::get_user_from_token;
-
 /// Importing the function to delete
 /// a user from the database.
 use crate::modules::db::users::delete_user_from_db;
@@ -189,7 +194,9 @@ pub async fn create_user_service(
             email_addr: user.email_addr,
             pfp_url: user.pfp_url,
             is_admin: user.is_admin,
-            key_status_updated: key_updated
+            key_status_updated: key_updated,
+            created_at: user.created_at.to_rfc3339(),
+            updated_at: user.updated_at.to_rfc3339()
         };
         Ok(HttpResponse::Ok().json(user_obj))
     }
@@ -209,9 +216,10 @@ pub async fn create_user_service(
 #[post("/user/update/email")]
 pub async fn update_email_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> Result<HttpResponse, CleoErr> {
-    let user: CleoUser = match get_user_from_token(&payload.api_token, &data.pool).await {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ACCOUNT, &data.pool).await {
         Ok(user) => user,
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
@@ -240,7 +248,7 @@ pub async fn update_email_service(
         Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
     };
     if send_mail{
-        let update_op: bool = match update_email(&payload.api_token, &payload.new_value, &data.pool).await {
+        let update_op: bool = match update_email(&user, &payload.new_value, &data.pool).await {
             Ok(_op) => true,
             Err(_e) => false
         };
@@ -260,10 +268,15 @@ pub async fn update_email_service(
 #[post("/user/update/username")]
 pub async fn update_username_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse { 
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ACCOUNT, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match update_username(
-        &payload.api_token, 
+        &user, 
         &payload.new_value, 
         &data.pool
     ).await {
@@ -282,10 +295,15 @@ pub async fn update_username_service(
 #[post("/user/update/name")]
 pub async fn update_name_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ACCOUNT, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match update_display_name(
-        &payload.api_token, 
+        &user, 
         &payload.new_value, 
         &data.pool
     ).await {
@@ -304,10 +322,15 @@ pub async fn update_name_service(
 #[post("/user/update/picture")]
 pub async fn update_pfp_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ACCOUNT, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match update_pfp(
-        &payload.api_token, 
+        &user, 
         &payload.new_value, 
         &data.pool
     ).await {
@@ -326,10 +349,15 @@ pub async fn update_pfp_service(
 #[post("/user/update/password")]
 pub async fn update_password_service(
     payload: Json<UserChangePayload>,
+    auth: AuthExtractor,
     data: Data<AppData>
 ) -> HttpResponse { 
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_ACCOUNT, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
     let update_op: bool = match update_password(
-        &payload.api_token, 
+        &user, 
         &payload.new_value, 
         &data.pool
     ).await {
@@ -0,0 +1,351 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "post"
+/// macro to make a service
+/// that accepts "POST" requests.
+use actix_web::post;
+
+/// Importing the "route"
+/// macro to make a service
+/// that accepts both "GET"
+/// and "POST" requests.
+use actix_web::route;
+
+/// Importing the "Result"
+/// enum for Actix Web services.
+use actix_web::Result;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "Json"
+/// structure to return JSON
+/// responses.
+use actix_web::web::Json;
+
+/// Importing the "Query" structure
+/// to read the parameters of requests
+/// from their query strings.
+use actix_web::web::Query;
+
+/// Importing the "HttpResponse"
+/// structure to return an HTTP
+/// response.
+use actix_web::HttpResponse;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "AppData"
+/// structure to use persistent
+/// app data.
+use crate::modules::units::AppData;
+
+/// Importing the model for
+/// webhooks for explicit typing.
+use crate::modules::models::Webhook;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure holding
+/// the options for listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the extractor for
+/// the user a request authenticated
+/// as.
+use crate::modules::auth::AuthExtractor;
+
+/// Importing the data structure for
+/// returning the details of a page
+/// of a list.
+use crate::modules::responses::Pagination;
+
+/// Importing the function to read
+/// the parameters of a request from
+/// its query string or its body.
+use crate::modules::extractors::read_params;
+
+/// Importing the model for
+/// deliveries of webhooks for
+/// explicit typing.
+use crate::modules::models::WebhookDelivery;
+
+/// Importing the data structure
+/// for returning info on whether
+/// a write operation was successful
+/// or not.
+use crate::modules::responses::StatusResponse;
+
+/// Importing the function to edit
+/// a webhook.
+use crate::modules::db::webhooks::edit_webhook;
+
+/// Importing the data structure for
+/// returning a webhook.
+use crate::modules::responses::WebhookResponse;
+
+/// Importing the data structure for
+/// submitting a payload for actions
+/// that only require an API token.
+use crate::modules::payloads::TokenOnlyPayload;
+
+/// Importing the function to resolve
+/// the options for listing records.
+use crate::modules::utils::resolve_list_options;
+
+/// Importing the data structure for
+/// returning all webhooks of a user.
+use crate::modules::responses::WebhooksResponse;
+
+/// Importing the function to register
+/// a webhook.
+use crate::modules::db::webhooks::create_webhook;
+
+/// Importing the function to delete
+/// a webhook.
+use crate::modules::db::webhooks::delete_webhook;
+
+/// Importing the data structure for
+/// submitting a payload for editing
+/// a webhook.
+use crate::modules::payloads::WebhookEditPayload;
+
+/// Importing the scope for
+/// reading private
+/// content.
+use crate::modules::db::tokens::SCOPE_CONTENT_READ;
+
+/// Importing the function to retrieve
+/// all webhooks of a user.
+use crate::modules::db::webhooks::get_user_webhooks;
+
+/// Importing the scope for
+/// writing content.
+use crate::modules::db::tokens::SCOPE_CONTENT_WRITE;
+
+/// Importing the data structure for
+/// submitting a payload for registering
+/// a webhook.
+use crate::modules::payloads::WebhookCreationPayload;
+
+/// Importing the data structure for
+/// submitting a payload for deleting
+/// a webhook.
+use crate::modules::payloads::WebhookDeletionPayload;
+
+/// Importing the data structure for
+/// returning a delivery of a webhook.
+use crate::modules::responses::WebhookDeliveryResponse;
+
+/// Importing the data structure for
+/// returning a webhook that was just
+/// registered with its secret.
+use crate::modules::responses::WebhookCreationResponse;
+
+/// Importing the data structure for
+/// submitting a payload for listing
+/// the deliveries of a webhook.
+use crate::modules::payloads::WebhookDeliveriesPayload;
+
+/// Importing the function to retrieve
+/// a page of the deliveries of a webhook.
+use crate::modules::db::webhooks::get_webhook_deliveries;
+
+/// Importing the data structure for
+/// returning a page of the deliveries
+/// of a webhook.
+use crate::modules::responses::WebhookDeliveriesResponse;
+
+/// Importing the fields the deliveries
+/// of a webhook can be sorted by.
+use crate::modules::db::webhooks::WEBHOOK_DELIVERY_SORT_FIELDS;
+
+/// This function is the API
+/// service function for registering
+/// a webhook. If the received request
+/// and resulting operation are both 
+/// valid, an instance of the 
+/// "WebhookCreationResponse" structure
+/// with the secret deliveries are signed
+/// with is returned. The secret is not
+/// returned again. In any other case an 
+/// error is returned.
+#[post("/webhooks/create")]
+pub async fn create_webhook_service(
+    payload: Json<WebhookCreationPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let webhook: Webhook = match create_webhook(
+        &user, 
+        &payload.url,
+        &payload.events,
+        &data.pool
+    ).await {
+        Ok(webhook) => webhook,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let secret: String = webhook.secret.clone();
+    let resp: WebhookCreationResponse = WebhookCreationResponse{
+        webhook: WebhookResponse::new(webhook),
+        secret
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API service
+/// function for changing the URL, the
+/// events, or whether a webhook is active.
+/// If the received request and resulting 
+/// operation are both valid, an instance 
+/// of the "StatusResponse" with a boolean 
+/// flag is returned as a JSON response. 
+#[post("/webhooks/edit")]
+pub async fn edit_webhook_service(
+    payload: Json<WebhookEditPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let edit_op: bool = match edit_webhook(
+        &user, 
+        &payload.webhook_id,
+        &payload.url,
+        &payload.events,
+        &payload.is_active,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: edit_op })
+}
+
+/// This function is the API service
+/// function for deleting a webhook and
+/// its deliveries. If the received request
+/// and resulting operation are both valid,
+/// an instance of the "StatusResponse" with
+/// a boolean flag is returned as a JSON 
+/// response. 
+#[post("/webhooks/delete")]
+pub async fn delete_webhook_service(
+    payload: Json<WebhookDeletionPayload>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> HttpResponse {
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_WRITE, &data.pool).await {
+        Ok(user) => user,
+        Err(_e) => return HttpResponse::Ok().json(StatusResponse{ is_ok: false })
+    };
+    let del_op: bool = match delete_webhook(
+        &user, 
+        &payload.webhook_id,
+        &data.pool
+    ).await {
+        Ok(_op) => true,
+        Err(_e) => false
+    };
+    HttpResponse::Ok().json(StatusResponse{ is_ok: del_op })
+}
+
+/// This function is the API
+/// service function for retrieving
+/// all webhooks of a user. Clients that
+/// send a bearer token can use a "GET"
+/// request without a body. If the 
+/// received request and resulting 
+/// operation are both valid, an instance
+/// of the "WebhooksResponse" structure is
+/// returned. In any other case an error
+/// is returned.
+#[route("/webhooks/all", method = "GET", method = "POST")]
+pub async fn get_webhooks_service(
+    payload: Option<Json<TokenOnlyPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let api_token: Option<String> = payload.and_then(|payload| payload.into_inner().api_token);
+    let user: CleoUser = match auth.resolve(&api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let webhooks: Vec<Webhook> = match get_user_webhooks(&user.user_id, &data.pool).await {
+        Ok(webhooks) => webhooks,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: WebhooksResponse = WebhooksResponse{
+        webhooks: webhooks.into_iter().map(WebhookResponse::new).collect()
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// This function is the API
+/// service function for retrieving
+/// a page of the log of deliveries of
+/// a webhook with the outcome of their
+/// latest attempt. If the received request
+/// and resulting operation are both valid,
+/// an instance of the "WebhookDeliveriesResponse"
+/// structure is returned. In any other case
+/// an error is returned.
+/// Clients that send a bearer token
+/// can use a "GET" request with the
+/// parameters in the query string.
+#[route("/webhooks/deliveries", method = "GET", method = "POST")]
+pub async fn get_webhook_deliveries_service(
+    query: Option<Query<WebhookDeliveriesPayload>>,
+    body: Option<Json<WebhookDeliveriesPayload>>,
+    auth: AuthExtractor,
+    data: Data<AppData>
+) -> Result<HttpResponse, CleoErr> {
+    let payload: WebhookDeliveriesPayload = match read_params(query, body) {
+        Ok(payload) => payload,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let user: CleoUser = match auth.resolve(&payload.api_token, SCOPE_CONTENT_READ, &data.pool).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let options: ListOptions = match resolve_list_options(
+        &payload.page, 
+        &payload.per_page, 
+        &payload.sort_by, 
+        &payload.sort_order, 
+        &WEBHOOK_DELIVERY_SORT_FIELDS
+    ) {
+        Ok(options) => options,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (deliveries, total): (Vec<WebhookDelivery>, i64) = match get_webhook_deliveries(
+        &user, 
+        &payload.webhook_id,
+        &options,
+        &data.pool
+    ).await {
+        Ok(page) => page,
+        Err(e) => return Err::<HttpResponse, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let resp: WebhookDeliveriesResponse = WebhookDeliveriesResponse{
+        deliveries: deliveries.into_iter().map(WebhookDeliveryResponse::new).collect(),
+        pagination: Pagination::new(&options, total)
+    };
+    Ok(HttpResponse::Ok().json(resp))
+}
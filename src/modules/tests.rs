@@ -1,5 +0,0 @@
-
-/*
-Cleo by Alyx Shang.
-Licensed under the FSL v1.
-*/
@@ -3,24 +3,32 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
-/// Importing the "App"
-/// structure to create a new
-/// Actix Web app.
-use actix_web::App;
+/// Importing the "json"
+/// macro to build payloads.
+use serde_json::json;
 
-/// Importing all data structures
-/// for sending payloads.
-use crate::modules::payloads::*:
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
 
-/// Importing all data structures
-/// for checking responses.
-use crate::modules::responses::*;
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the password
+/// every test user has.
+use super::helpers::TEST_PASSWORD;
 
 /// Importing all service functions
 /// for the ecf-related
 /// services.
 use crate::modules::services::ecf::*;
 
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
 /// Importing all service functions
 /// for the key-related
 /// services.
@@ -36,21 +44,11 @@ use crate::modules::services::admin::*;
 /// services.
 use crate::modules::services::posts::*;
 
-/// Importing all service functions
-/// for the email-related
-/// services.
-use crate::modules::services::email::*;
-
 /// Importing all service functions
 /// for the user-related
 /// services.
 use crate::modules::services::users::*;
 
-/// Importing all service functions
-/// for the file-related
-/// services.
-use crate::modules::services::files::*;
-
 /// Importing all service functions
 /// for the token-related
 /// services.
@@ -60,6 +58,10 @@ use crate::modules::services::tokens::*;
 /// for general services.
 use crate::modules::services::general::*;
 
+/// Importing the function to
+/// save the instance information.
+use super::helpers::ensure_instance_info;
+
 /*
 ## TODO
 - Add tests for email verif.
@@ -68,496 +70,321 @@ use crate::modules::services::general::*;
 
 /// The function to test the
 /// user service functions.
-#[actix_test::test]
+#[actix_web::test]
 pub async fn test_user_service_functions(){
-    use actix_web::test;
-    let app = test::init_service(
-        App::new()
-            .service(create_user_service)
-            .service(update_email_service)
-            .service(update_username_service)
-            .service(update_name_service)
-            .service(update_pfp_service)
-            .service(update_password_service)
-            .service(delete_user_service)
-        )
-        .await;
-    let req_create_user = test::TestRequest::post().uri("/user/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserCreationPayload{ 
-                    username: "alyshang",
-                    display: "Aly Shang",
-                    password: "12345678",
-                    email_addr: "example@example.com",
-                    pfp_url: "https://avatars.githubusercontent.com/u/6471485?s=200&v=4",
-                    user_key: "1234567890",
-                }
-            )
-            .to_request();
-    let resp_create_user = test::call_service(&app, req_create_user).await;
-    let req_create_token = test::TestRequest::post().uri("/token/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                AuthActionPayload{ 
-                    username: "alyshang", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-    let resp_create_token = test::call_service(&app, req_create_token).await;
-    let token: String resp_create_token.token;
-    let req_u_email = test::TestRequest::post().uri("/user/update/email")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token,
-                    new_value: "ziggy@stardust.com",
-                }
-            )
-            .to_request();
-    let resp_u_email = test::call_service(&app, req_u_email).await;
-    let req_u_uname = test::TestRequest::post().uri("/user/update/username")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token,
-                    new_value: "alyxshang",
-                }
-            )
-            .to_request();
-    let resp_u_uname = test::call_service(&app, req_u_uname).await;
-    let req_u_name = test::TestRequest::post().uri("/user/update/name")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token,
-                    new_value: "Alyx Shang",
-                }
-            )
-            .to_request();
-    let resp_u_name = test::call_service(&app, req_u_name).await;
-    let req_u_password = test::TestRequest::post().uri("/user/update/password")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token,
-                    new_value: "12340987",
-                }
-            )
-            .to_request();
-    let resp_u_password = test::call_service(&app, req_u_password).await;
-    let req_u_pic = test::TestRequest::post().uri("/user/update/picture")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token,
-                    new_value: "https://avatars.githubusercontent.com/u/179976644?v=4",
-                }
-            )
-            .to_request();
-    let resp_u_pic = test::call_service(&app, req_u_pic).await;
-    let req_del_user = test::TestRequest::post().uri("/user/delete")
-            .insert_header(ContentType::json())
-            .set_json(
-                AuthActionPayload{ 
-                    username: "alyxshang",
-                    password: "12340987"
-                }
-            )
-            .to_request();
-    let resp_del_user = test::call_service(&app, req_del_user).await;
-    assert!(resp_create_user.status().is_success());
-    assert!(resp_u_uname.status().is_success());
-    assert!(resp_u_name.status().is_success());
-    assert!(resp_u_pic.status().is_success());
-    assert!(resp_u_email.status().is_success());
-    assert!(resp_del_user.status().is_success());
+    let pool = test_pool().await;
+    let (user, token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        update_username_service,
+        update_name_service,
+        update_pfp_service,
+        update_password_service,
+        delete_user_service
+    );
+    let new_username: String = format!("{}x", &user.username);
+    let resp_u_uname: Value = post_json!(
+        app,
+        "/user/update/username",
+        json!({ "api_token": token, "new_value": new_username })
+    );
+    let resp_u_name: Value = post_json!(
+        app,
+        "/user/update/name",
+        json!({ "api_token": token, "new_value": "Alyx Shang" })
+    );
+    let resp_u_pic: Value = post_json!(
+        app,
+        "/user/update/picture",
+        json!({ "api_token": token, "new_value": "https://avatars.githubusercontent.com/u/179976644?v=4" })
+    );
+    let resp_u_password: Value = post_json!(
+        app,
+        "/user/update/password",
+        json!({ "api_token": token, "new_value": "12340987" })
+    );
+    let resp_del_user: Value = post_json!(
+        app,
+        "/user/delete",
+        json!({ "username": new_username, "password": "12340987" })
+    );
+    assert_eq!(resp_u_uname["is_ok"], true);
+    assert_eq!(resp_u_name["is_ok"], true);
+    assert_eq!(resp_u_pic["is_ok"], true);
+    assert_eq!(resp_u_password["is_ok"], true);
+    assert_eq!(resp_del_user["is_ok"], true);
 }
 
 /// The function to test the
 /// API token service functions.
 #[actix_web::test]
 pub async fn test_api_token_services() {
-    use actix_web::test;
-    let app = test::init_service(
-        App::new()
-            .service(create_api_token_service)
-            .service(delete_api_token_service)
-        )
-        .await;
-        let req_create = test::TestRequest::post().uri("/token/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                AuthActionPayload{ 
-                    username: "alyx", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-        let resp_create = test::call_service(&app, req_create).await;
-        let token: String = resp_create.token;
-        let req_delete = test::TestRequest::post().uri("/token/delete")
-            .insert_header(ContentType::json())
-            .set_json(
-                DelTokenPayload{ 
-                    token: token,
-                    username: "alyx", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-        assert!(resp_create.status().is_success());
-        assert!(resp_delete.status().is_success());
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        create_api_token_service,
+        delete_api_token_service
+    );
+    let resp_create: Value = post_json!(
+        app,
+        "/token/create",
+        json!({ "username": user.username, "password": TEST_PASSWORD })
+    );
+    let token: &str = resp_create["token"].as_str().unwrap();
+    let resp_delete: Value = post_json!(
+        app,
+        "/token/delete",
+        json!({ "token": token, "username": user.username, "password": TEST_PASSWORD })
+    );
+    assert_eq!(resp_delete["is_ok"], true);
 }
 
-/// The function to tet the
-/// user-posts service functions.
+/// The function to test the
+/// post service functions.
 #[actix_web::test]
 pub async fn test_posts_services(){
-    use actix_web::test;
-    let app = test::init_service(
-        App::new()
-            .service(create_user_post_service)
-            .service(update_user_post.service)
-    ).await;
-    let req_token_create = test::TestRequest::post().uri("/token/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                AuthActionPayload{ 
-                    username: "alyx", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-    let resp_token_create = test::call_service(&app, req_token_create).await;
-    let token: String = resp_token_create.token;
-    let req_post_create = test::TestRequest::post().uri("/posts/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                PostCreationPayload{ 
-                    api_token: token, 
-                    content_type: "page",
-                    content_text: "<h1>Hello World</h1>"
-                }
-            )
-            .to_request();
-    let resp_post_create = test::call_service(&app, req_post_create).await;
-    let id: String = resp_post_create.content_id;
-    let req_post_update = test::TestRequest::post().uri("/posts/update")
-            .insert_header(ContentType::json())
-            .set_json(
-                UpdatePostPayload{ 
-                    api_token: token, 
-                    content__id: id,
-                    text: "<h1>Hello World haha</h1>"
-                }
-            )
-            .to_request();
-    let resp_post_update = test::call_service(&app, req_post_update).await;
-    let req_post_delete = test::TestRequest::post().uri("/posts/delete")
-            .insert_header(ContentType::json())
-            .set_json(
-                DeletePostPayload{ 
-                    api_token: token, 
-                    content__id: id,
-                }
-            )
-            .to_request();
-    let resp_post_delete = test::call_service(&app, req_post_delete).await;
-    assert!(resp_post_create.status().is_success());
-    assert!(resp_post_update.status().is_success());
-    assert!(resp_post_delete.status().is_success());
+    let pool = test_pool().await;
+    let (_user, token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        create_user_post_service,
+        update_user_post_service,
+        delete_user_post_service
+    );
+    let resp_post_create: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": token, "content_type": "post", "content_text": "Hello world!", "title": "Hello" })
+    );
+    let content_id: &str = resp_post_create["content_id"].as_str().unwrap();
+    let resp_post_update: Value = post_json!(
+        app,
+        "/posts/update",
+        json!({ "api_token": token, "content_id": content_id, "text": "Hello there!" })
+    );
+    let resp_post_delete: Value = post_json!(
+        app,
+        "/posts/delete",
+        json!({ "api_token": token, "content_id": content_id })
+    );
+    assert_eq!(resp_post_create["post_status"], "draft");
+    assert_eq!(resp_post_update["is_ok"], true);
+    assert_eq!(resp_post_delete["is_ok"], true);
 }
 
-/// A function to test the 
-/// service functions for
-/// user keys.
+/// The function to test the
+/// user key service functions.
 #[actix_web::test]
 pub async fn test_user_key_services(){
-   let app = test::init_service(
-        App::new()
-            .service(create_user_post_service)
-            .service(update_user_post.service)
-    ).await;
-    let req_token_create = test::TestRequest::post().uri("/token/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                userKeyPayload{ 
-                    username: "alyx", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-    let resp_token_create = test::call_service(&app, req_token_create).await;
-    let token: String = resp_token_create.token;
-    let req_key_create = test::TestRequest::post().uri("/keys/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserKeyPayload{ 
-                    key_type: "admin",
-                    api_token: token,
-                    username: "alyx",
-                }
-            )
-            .to_request();
-    let resp_key_create = test::call_service(&app, req_key_create).await;
-    let id: String = resp_key_create.key_id;
-    let req_key_delete = test::TestRequest::post().uri("/keys/delete")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserKeyDeletionPayload{ 
-                    api_token: token,
-                    key_id: id,
-                }
-            )
-            .to_request();
-    let resp_key_delete = test::call_service(&app, req_key_delete).await;
-    let req_keys_get = test::TestRequest::post().uri("/keys/all")
-            .insert_header(ContentType::json())
-            .set_json(
-                TokenOnlyPayload{ 
-                    api_token: token,
-                }
-            )
-            .to_request();
-    let resp_keys_get = test::call_service(&app, req_keys_get).await;
-    assert!(resp_key_create.status().is_success());
-    assert!(resp_key_delete.status().is_success());
-    assert!(resp_keys_get.status().is_success());
+    let pool = test_pool().await;
+    let (admin, token): (_, String) = create_test_user(true, &pool).await;
+    let app = test_app!(
+        pool,
+        create_user_key_service,
+        delete_user_key_service,
+        get_user_keys_service
+    );
+    let resp_key_create: Value = post_json!(
+        app,
+        "/keys/create",
+        json!({ "api_token": token, "key_type": "normal", "username": "newcomer" })
+    );
+    let resp_keys_get: Value = get_json!(app, "/keys/all", token);
+    let key_id: String = sqlx::query_scalar("SELECT key_id FROM user_keys WHERE user_key = $1 AND user_id = $2")
+        .bind(resp_key_create["user_key"].as_str().unwrap())
+        .bind(&admin.user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let resp_key_delete: Value = post_json!(
+        app,
+        "/keys/delete",
+        json!({ "api_token": token, "key_id": key_id })
+    );
+    assert_eq!(resp_key_create["key_type"], "normal");
+    assert_eq!(resp_keys_get["keys"].as_array().unwrap().len(), 1);
+    assert_eq!(resp_key_delete["is_ok"], true);
 }
 
-/// A function to test general
-/// API service functions.
-#[actix::test]
+/// The function to test the
+/// general service functions.
+#[actix_web::test]
 pub async fn test_general_services(){
-    let app = test::init_service(
-        App::new()
-            .service(get_user_posts_service)
-            .service(get_user_files_service)
-        ).await;
-    let req_token_create = test::TestRequest::post().uri("/token/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                userKeyPayload{ 
-                    username: "alyx", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-    let resp_token_create = test::call_service(&app, req_token_create).await;
-    let token: String = resp_token_create.token;
-    let req_posts_get = test::TestRequest::post().uri("/posts/all")
-            .insert_header(ContentType::json())
-            .set_json(
-                TokenOnlyPayload{ 
-                    api_token: token,
-                }
-            )
-            .to_request();
-    let resp_posts_get = test::call_service(&app, req_posts_get).await;
-    let req_files_get = test::TestRequest::post().uri("/files/all")
-            .insert_header(ContentType::json())
-            .set_json(
-                TokenOnlyPayload{ 
-                    api_token: token,
-                }
-            )
-            .to_request();
-    let resp_files_get = test::call_service(&app, req_files_get).await;
-    assert!(resp_posts_get.status().is_success());
-    assert!(resp_files_get.status().is_success());
+    let pool = test_pool().await;
+    let (user, token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        create_user_post_service,
+        publish_user_post_service,
+        get_user_posts_service,
+        get_user_files_service,
+        get_public_post_service,
+        get_public_posts_service
+    );
+    let resp_post_create: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": token, "content_type": "post", "content_text": "Hello world!", "title": "Hello" })
+    );
+    let content_id: String = resp_post_create["content_id"].as_str().unwrap().to_string();
+    let public_post_uri: String = format!("/public/post/{}", &content_id);
+    let public_posts_uri: String = format!("/public/posts/{}", &user.username);
+    let resp_draft: Value = get_json!(app, &public_post_uri);
+    let resp_drafts: Value = get_json!(app, &public_posts_uri);
+    let resp_publish: Value = post_json!(
+        app,
+        "/posts/publish",
+        json!({ "api_token": token, "content_id": content_id })
+    );
+    let resp_published: Value = get_json!(app, &public_post_uri);
+    let resp_public_posts: Value = get_json!(app, &public_posts_uri);
+    let resp_posts_get: Value = get_json!(app, "/posts/all", token);
+    let resp_files_get: Value = get_json!(app, "/files/all", token);
+    assert!(resp_draft.get("error").is_some());
+    assert_eq!(resp_drafts["posts"].as_array().unwrap().len(), 0);
+    assert_eq!(resp_publish["is_ok"], true);
+    assert_eq!(resp_published["content_id"], content_id.as_str());
+    assert_eq!(resp_public_posts["posts"].as_array().unwrap().len(), 1);
+    assert_eq!(resp_posts_get["posts"].as_array().unwrap().len(), 1);
+    assert_eq!(resp_files_get["files"].as_array().unwrap().len(), 0);
 }
 
-/// Testing the service functions for 
-/// working with extra content fields
-/// on user posts.
+/// The function to test the
+/// ecf service functions.
 #[actix_web::test]
 pub async fn test_ecf_service_functions(){
-    use actix_web::test;
-    let app = test::init_service(
-        App::new()
-            .service(create_user_post_service)
-            .service(update_user_post.service)
-    ).await;
-    let req_token_create = test::TestRequest::post().uri("/token/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                AuthActionPayload{ 
-                    username: "alyx", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-    let resp_token_create = test::call_service(&app, req_token_create).await;
-    let token: String = resp_token_create.token;
-    let req_post_create = test::TestRequest::post().uri("/posts/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                PostCreationPayload{ 
-                    api_token: token, 
-                    content_type: "page",
-                    content_text: "<h1>Hello World</h1>"
-                }
-            )
-            .to_request();
-    let resp_post_create = test::call_service(&app, req_post_create).await;
-    let id: String = resp_post_create.content_id;
-    let req_ecf_create = test::TestRequest::post().uri("/ecf/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                ExtraContentFieldCreationPayload{ 
-                    api_token: token, 
-                    content_id: id,
-                    field_key: "name",
-                    field_value: "Hello World!"
-                }
-            )
-            .to_request();
-    let resp_ecf_create = test::call_service(&app, req_ecf_create).await;
-    let req_ecf_k_update = test::TestRequest::post().uri("/ecf/edit/key")
-            .insert_header(ContentType::json())
-            .set_json(
-                EditExtraContentFieldPayload{ 
-                    api_token: token, 
-                    content_id: id,
-                    field_id: resp_ecf_create.field_id,
-                    new_key: "title"
-                }
-            )
-            .to_request();
-    let resp_ecf_k_update = test::call_service(&app, req_ecf_k_update).await;
-    let req_ecf_v_update = test::TestRequest::post().uri("/ecf/edit/value")
-            .insert_header(ContentType::json())
-            .set_json(
-                EditExtraContentFieldPayload{ 
-                    api_token: token, 
-                    content_id: id,
-                    field_id: resp_ecf_create.field_id,
-                    new_value: "Hello"
-                }
-            )
-            .to_request();
-    let resp_ecf_v_update = test::call_service(&app, req_ecf_v_update).await;
-    let req_ecf_delete = test::TestRequest::post().uri("/ecf/delete")
-            .insert_header(ContentType::json())
-            .set_json(
-                DeleteExtraContentFieldPayload{ 
-                    api_token: token, 
-                    content_id: id,
-                    field_id: resp_ecf_create.field_id,
-                }
-            )
-            .to_request();
-    let resp_ecf_delete = test::call_service(&app, req_ecf_delete).await;
-    assert!(resp_ecf_create.status().is_success());
-    assert!(resp_ecf_v_update.status().is_success());
-    assert!(resp_ecf_k_update.status().is_success());
-    assert(resp_ecf_delete.status().is_success());
+    let pool = test_pool().await;
+    let (_user, token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        create_user_post_service,
+        create_extra_content_field_service,
+        edit_extra_content_field_key_service,
+        edit_extra_content_field_value_service,
+        delete_extra_content_field_service
+    );
+    let resp_post_create: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": token, "content_type": "post", "content_text": "Hello world!", "title": "Hello" })
+    );
+    let content_id: &str = resp_post_create["content_id"].as_str().unwrap();
+    let resp_ecf_create: Value = post_json!(
+        app,
+        "/ecf/create",
+        json!({ "api_token": token, "content_id": content_id, "field_key": "mood", "field_value": "happy" })
+    );
+    let field_id: &str = resp_ecf_create["field_id"].as_str().unwrap();
+    let resp_ecf_k_update: Value = post_json!(
+        app,
+        "/ecf/edit/key",
+        json!({ "api_token": token, "content_id": content_id, "field_id": field_id, "new_value": "feeling" })
+    );
+    let resp_ecf_v_update: Value = post_json!(
+        app,
+        "/ecf/edit/value",
+        json!({ "api_token": token, "content_id": content_id, "field_id": field_id, "new_value": "content" })
+    );
+    let resp_ecf_delete: Value = post_json!(
+        app,
+        "/ecf/delete",
+        json!({ "api_token": token, "content_id": content_id, "field_id": field_id })
+    );
+    assert_eq!(resp_ecf_create["field_key"], "mood");
+    assert_eq!(resp_ecf_k_update["is_ok"], true);
+    assert_eq!(resp_ecf_v_update["is_ok"], true);
+    assert_eq!(resp_ecf_delete["is_ok"], true);
 }
 
-/// Testing the service functions
-/// for the administrators.
+/// The function to test the
+/// administrator service functions.
 #[actix_web::test]
 pub async fn test_admin_service_functions(){
-    use actix_web::test;
-    let app = test::init_service(
-        App::new()
-            .service(create_user_post_service)
-            .service(update_user_post.service)
-    ).await;
-    let req_token_create = test::TestRequest::post().uri("/token/create")
-            .insert_header(ContentType::json())
-            .set_json(
-                AuthActionPayload{ 
-                    username: "alyx", 
-                    password: "12345678" 
-                }
-            )
-            .to_request();
-    let resp_token_create = test::call_service(&app, req_token_create).await;
-    let token: String = resp_token_create.token;
-    let req_admins_get = test::TestRequest::post().uri("/instance/admins")
-            .insert_header(ContentType::json())
-            .set_json(
-                TokenOnlyPayload{ 
-                    api_token: token, 
-                }
-            )
-            .to_request();
-    
-    let resp_admins_get = test::call_service(&app, req_admins_get).await;
-    let req_users_get = test::TestRequest::post().uri("/instance/users")
-            .insert_header(ContentType::json())
-            .set_json(
-                TokenOnlyPayload{ 
-                    api_token: token, 
-                }
-            )
-            .to_request();
-    
-    let resp_users_get = test::call_service(&app, req_users_get).await;
-    let req_i_name = test::TestRequest::post().uri("/instance/edit/name")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token, 
-                    new_value: "KitsuClub"
-                }
-            )
-            .to_request();
-    
-    let resp_i_name = test::call_service(&app, req_i_name).await;
-    let req_i_hname = test::TestRequest::post().uri("/instance/edit/hostname")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token, 
-                    new_value: "kitsublog.blog"
-                }
-            )
-            .to_request();
-    
-    let resp_i_hname = test::call_service(&app, req_i_hname).await;
-    let req_i_ss = test::TestRequest::post().uri("/instance/edit/smtp/server")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token, 
-                    new_value: "kitsublog.blog"
-                }
-            )
-            .to_request();
-    
-    let resp_i_ss = test::call_service(&app, req_i_ss).await;
-    let req_i_su = test::TestRequest::post().uri("/instance/edit/smtp/username")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token, 
-                    new_value: "kitsuadmin"
-                }
-            )
-            .to_request();
-    
-    let resp_i_su = test::call_service(&app, req_i_su).await;
-    let req_i_sp = test::TestRequest::post().uri("/instance/edit/smtp/pass")
-            .insert_header(ContentType::json())
-            .set_json(
-                UserChangePayload{ 
-                    api_token: token, 
-                    new_value: "kitsuadmin"
-                }
-            )
-            .to_request();
-    
-    let resp_i_sp = test::call_service(&app, req_i_sp).await;
-    assert!(resp_admins_get.status().is_success());
-    assert!(resp_users_get.status().is_success());
-    assert!(resp_i_name.status().is_success());
-    assert!(resp_i_hname.status().is_success());
-    assert!(resp_i_name.status().is_success());
-    assert!(resp_i_ss.status().is_success());
-    assert!(resp_i_su.status().is_success());
-    assert!(resp_i_sp.status().is_success());
+    let pool = test_pool().await;
+    ensure_instance_info(&pool).await;
+    let (_admin, token): (_, String) = create_test_user(true, &pool).await;
+    let (_user, user_token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        get_instance_admins_service,
+        get_instance_users_service,
+        edit_instance_name_service,
+        edit_instance_hostname_service,
+        edit_smtp_server_service,
+        edit_smtp_username_service
+    );
+    let resp_admins_get: Value = get_json!(app, "/instance/admins", token);
+    let resp_users_get: Value = get_json!(app, "/instance/users", token);
+    let resp_i_name: Value = post_json!(
+        app,
+        "/instance/edit/name",
+        json!({ "api_token": token, "new_value": "Cleo" })
+    );
+    let resp_i_hname: Value = post_json!(
+        app,
+        "/instance/edit/hostname",
+        json!({ "api_token": token, "new_value": "http://localhost:8000" })
+    );
+    let resp_i_ss: Value = post_json!(
+        app,
+        "/instance/edit/smtp/server",
+        json!({ "api_token": token, "new_value": "localhost" })
+    );
+    let resp_i_su: Value = post_json!(
+        app,
+        "/instance/edit/smtp/username",
+        json!({ "api_token": token, "new_value": "cleo@localhost" })
+    );
+    let resp_forbidden: Value = post_json!(
+        app,
+        "/instance/edit/name",
+        json!({ "api_token": user_token, "new_value": "Not Cleo" })
+    );
+    assert!(!resp_admins_get["users"].as_array().unwrap().is_empty());
+    assert!(!resp_users_get["users"].as_array().unwrap().is_empty());
+    assert_eq!(resp_i_name["is_ok"], true);
+    assert_eq!(resp_i_hname["is_ok"], true);
+    assert_eq!(resp_i_ss["is_ok"], true);
+    assert_eq!(resp_i_su["is_ok"], true);
+    assert_eq!(resp_forbidden["is_ok"], false);
+}
+
+/// The function to test that a
+/// bearer token authenticates requests
+/// and that a stale one only fails the
+/// services that need a user.
+#[actix_web::test]
+pub async fn test_bearer_token_services(){
+    let pool = test_pool().await;
+    let (user, token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        get_user_posts_service,
+        get_public_posts_service
+    );
+    let resp_valid: Value = get_json!(app, "/posts/all?per_page=5&status=draft", token);
+    let resp_deprecated: Value = post_json!(
+        app,
+        "/posts/all",
+        json!({ "api_token": token, "per_page": 5 })
+    );
+    let resp_anonymous: Value = get_json!(app, "/posts/all");
+    let req = actix_web::test::TestRequest::post()
+        .uri("/posts/all")
+        .insert_header(("Authorization", "Bearer stale"))
+        .set_json(json!({ "api_token": token }))
+        .to_request();
+    let resp_stale: Value = actix_web::test::call_and_read_body_json(&app, req).await;
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("/public/posts/{}", &user.username))
+        .insert_header(("Authorization", "Bearer stale"))
+        .to_request();
+    let resp_public: Value = actix_web::test::call_and_read_body_json(&app, req).await;
+    assert!(resp_valid["posts"].is_array());
+    assert_eq!(resp_valid["pagination"]["per_page"], 5);
+    assert_eq!(resp_deprecated["pagination"]["per_page"], 5);
+    assert!(resp_anonymous.get("error").is_some());
+    assert!(resp_stale.get("error").is_some());
+    assert!(resp_public["posts"].is_array());
 }
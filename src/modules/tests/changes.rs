@@ -0,0 +1,362 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "Pin"
+/// structure to poll the
+/// body of a response.
+use std::pin::Pin;
+
+/// Importing the "json"
+/// macro to build the data
+/// of events.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the "Duration"
+/// structure to limit waits.
+use std::time::Duration;
+
+/// Importing the "timeout"
+/// function to stop waiting
+/// for changes.
+use tokio::time::timeout;
+
+/// Importing the "Data"
+/// structure to register
+/// persistent app data.
+use actix_web::web::Data;
+
+/// Importing the "poll_fn"
+/// function to read the body
+/// of a response frame by frame.
+use std::future::poll_fn;
+
+/// Importing the "Bytes"
+/// structure for the frames
+/// of streams.
+use actix_web::web::Bytes;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the "PgListener"
+/// structure to listen for
+/// announced changes.
+use sqlx::postgres::PgListener;
+
+/// Importing the function
+/// to make names no other
+/// test uses.
+use super::helpers::unique_name;
+
+/// Importing the "MessageBody"
+/// trait to read the body of a
+/// streamed response.
+use actix_web::body::MessageBody;
+
+/// Importing the "Sender"
+/// structure for explicit
+/// typing.
+use tokio::sync::broadcast::Sender;
+
+/// Importing the "AppData"
+/// structure to share a channel
+/// for changes with an app.
+use crate::modules::units::AppData;
+
+/// Importing the model for
+/// webhooks for explicit typing.
+use crate::modules::models::Webhook;
+
+/// Importing the "channel"
+/// function to make a channel
+/// for changes.
+use tokio::sync::broadcast::channel;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure
+/// describing a change to
+/// content.
+use crate::modules::units::ChangeEvent;
+
+/// Importing the function to make
+/// sure the instance has its
+/// information saved.
+use super::helpers::ensure_instance_info;
+
+/// Importing the structure for the
+/// body of a stream of changes.
+use crate::modules::changes::ChangeStream;
+
+/// Importing the function to
+/// delete a post.
+use crate::modules::db::posts::delete_post;
+
+/// Importing the number of changes
+/// held for open streams.
+use crate::modules::changes::CHANGE_BUFFER;
+
+/// Importing the name of the
+/// channel changes are announced
+/// on.
+use crate::modules::db::changes::CHANGE_CHANNEL;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Importing the function to
+/// create a webhook.
+use crate::modules::db::webhooks::create_webhook;
+
+/// Importing the function to open
+/// a stream of the changes to the
+/// content of a user.
+use crate::modules::changes::stream_user_changes;
+
+/// Importing the function to
+/// announce a change.
+use crate::modules::db::changes::publish_change_event;
+
+/// Importing the function to edit
+/// the hosts webhooks may reach
+/// though they are not public.
+use crate::modules::db::admin::edit_webhook_allowlist;
+
+/// Importing the function to list
+/// the deliveries of a webhook.
+use crate::modules::db::webhooks::get_webhook_deliveries;
+
+/// Importing the service function
+/// for streaming changes.
+use crate::modules::services::changes::stream_changes_service;
+
+/// A shorthand to describe a
+/// change to the content of the
+/// supplied user.
+fn change(user_id: &str, event: &str) -> ChangeEvent {
+    ChangeEvent{
+        event_id: unique_name("event"),
+        user_id: user_id.to_string(),
+        event: event.to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        data: json!({ "content_id": "post" })
+    }
+}
+
+/// Waits for the next frame of a
+/// body that is not a keep-alive
+/// comment. If no frame arrives in
+/// time, nothing is returned. If the
+/// body ended, an empty frame is
+/// returned.
+async fn next_frame<B: MessageBody + Unpin>(body: &mut B) -> Option<String> {
+    loop {
+        let polled = timeout(
+            Duration::from_secs(5),
+            poll_fn(|cx| Pin::new(&mut *body).poll_next(cx))
+        ).await;
+        let frame: Bytes = match polled {
+            Ok(Some(Ok(frame))) => frame,
+            Ok(Some(Err(_failed))) => return None,
+            Ok(None) => return Some(String::new()),
+            Err(_elapsed) => return None
+        };
+        let frame: String = String::from_utf8(frame.to_vec()).unwrap();
+        if !frame.starts_with(": keep-alive") {
+            return Some(frame);
+        }
+    }
+}
+
+/// Waits for the next change of
+/// the supplied user announced on the
+/// channel for changes. Changes of other
+/// users are skipped. If no change arrives
+/// in time, nothing is returned.
+async fn next_change(
+    listener: &mut PgListener,
+    user_id: &String,
+    wait_ms: u64
+) -> Option<ChangeEvent> {
+    loop {
+        let notification = match timeout(Duration::from_millis(wait_ms), listener.recv()).await {
+            Ok(notification) => notification.unwrap(),
+            Err(_elapsed) => return None
+        };
+        let change: ChangeEvent = serde_json::from_str(notification.payload()).unwrap();
+        if &change.user_id == user_id {
+            return Some(change);
+        }
+    }
+}
+
+/// A shorthand to count the
+/// deliveries queued for a
+/// webhook.
+async fn delivery_count(
+    user: &CleoUser,
+    webhook: &Webhook,
+    pool: &Pool<Postgres>
+) -> i64 {
+    let options: ListOptions = ListOptions{
+        page: 1,
+        per_page: 50,
+        sort_by: "created_at".to_string(),
+        sort_order: "desc".to_string()
+    };
+    get_webhook_deliveries(user, &webhook.webhook_id, &options, pool).await.unwrap().1
+}
+
+/// The function to test that
+/// changes are announced and queued
+/// for webhooks only once the writes
+/// they describe are committed.
+#[tokio::test]
+pub async fn test_change_events(){
+    let pool = test_pool().await;
+    ensure_instance_info(&pool).await;
+    let (admin, _admin_token): (_, String) = create_test_user(true, &pool).await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    edit_webhook_allowlist(&admin, &vec!["127.0.0.1".to_string()], &pool).await.unwrap();
+    let webhook: Webhook = create_webhook(
+        &user,
+        "http://127.0.0.1:9/hook",
+        &vec!["post.created".to_string(), "post.updated".to_string()],
+        &pool
+    ).await.unwrap();
+    let mut listener: PgListener = PgListener::connect_with(&pool).await.unwrap();
+    listener.listen(CHANGE_CHANNEL).await.unwrap();
+    let post: UserPost = create_user_post(&user, &test_post("post", "Text.", "markdown", None), &pool).await.unwrap();
+    let created: ChangeEvent = next_change(&mut listener, &user.user_id, 5000).await.unwrap();
+    assert_eq!(created.event, "post.created");
+    assert_eq!(created.data["content_id"], post.content_id.as_str());
+    assert_eq!(created.data["post_status"], "draft");
+    assert_eq!(delivery_count(&user, &webhook, &pool).await, 1);
+    let data = json!({ "content_id": post.content_id });
+    let mut rolled_back = pool.begin().await.unwrap();
+    publish_change_event(&user.user_id, &"post.updated".to_string(), &data, &mut rolled_back).await.unwrap();
+    assert!(next_change(&mut listener, &user.user_id, 300).await.is_none());
+    assert_eq!(delivery_count(&user, &webhook, &pool).await, 1);
+    rolled_back.rollback().await.unwrap();
+    assert!(next_change(&mut listener, &user.user_id, 300).await.is_none());
+    assert_eq!(delivery_count(&user, &webhook, &pool).await, 1);
+    let mut committed = pool.begin().await.unwrap();
+    publish_change_event(&user.user_id, &"post.updated".to_string(), &data, &mut committed).await.unwrap();
+    committed.commit().await.unwrap();
+    let updated: ChangeEvent = next_change(&mut listener, &user.user_id, 5000).await.unwrap();
+    assert_eq!(updated.event, "post.updated");
+    assert_eq!(delivery_count(&user, &webhook, &pool).await, 2);
+    delete_post(&user, &post.content_id, &pool).await.unwrap();
+    let deleted: ChangeEvent = next_change(&mut listener, &user.user_id, 5000).await.unwrap();
+    assert_eq!(deleted.event, "post.deleted");
+    assert_eq!(delivery_count(&user, &webhook, &pool).await, 2);
+}
+
+/// The function to test that a
+/// stream of changes only carries
+/// the changes of its user that it
+/// asked for and tells slow clients
+/// what they missed.
+#[actix_web::test]
+pub async fn test_change_stream(){
+    let (sender, _receiver): (Sender<ChangeEvent>, _) = channel(CHANGE_BUFFER);
+    let mut stream: ChangeStream = stream_user_changes(
+        "alyx",
+        &Some(vec!["post.created".to_string()]),
+        sender.subscribe()
+    );
+    let own: ChangeEvent = change("alyx", "post.created");
+    assert!(sender.send(change("other", "post.created")).is_ok());
+    assert!(sender.send(change("alyx", "post.updated")).is_ok());
+    assert!(sender.send(own.clone()).is_ok());
+    let frame: String = next_frame(&mut stream).await.unwrap();
+    assert!(frame.starts_with(&format!("id: {}\nevent: post.created\ndata: ", own.event_id)));
+    assert!(frame.ends_with("\n\n"));
+    let data: ChangeEvent = serde_json::from_str(frame.lines().nth(2).unwrap().trim_start_matches("data: ")).unwrap();
+    assert_eq!(data.user_id, "alyx");
+    drop(sender);
+    assert_eq!(next_frame(&mut stream).await.unwrap(), "");
+    let (small, _small_receiver): (Sender<ChangeEvent>, _) = channel(2);
+    let mut lagging: ChangeStream = stream_user_changes("alyx", &None, small.subscribe());
+    for _sent in 0..5 {
+        assert!(small.send(change("alyx", "post.updated")).is_ok());
+    }
+    assert_eq!(next_frame(&mut lagging).await.unwrap(), "event: lagged\ndata: 3\n\n");
+    assert!(next_frame(&mut lagging).await.unwrap().contains("event: post.updated"));
+}
+
+/// The function to test the
+/// service function for streaming
+/// changes.
+#[actix_web::test]
+pub async fn test_change_stream_service(){
+    let pool = test_pool().await;
+    let (user, token): (_, String) = create_test_user(false, &pool).await;
+    let (sender, _receiver): (Sender<ChangeEvent>, _) = channel(CHANGE_BUFFER);
+    let app = actix_web::test::init_service(
+        actix_web::App::new()
+            .app_data(Data::new(AppData{ pool: pool.clone(), changes: sender.clone() }))
+            .wrap(actix_web::middleware::from_fn(crate::modules::auth::authenticate))
+            .service(stream_changes_service)
+    ).await;
+    let resp_anonymous: Value = get_json!(app, "/changes/stream");
+    let resp_invalid: Value = get_json!(app, "/changes/stream?events=post.moved", token);
+    assert!(resp_anonymous.get("error").is_some());
+    assert!(resp_invalid.get("error").is_some());
+    let req = actix_web::test::TestRequest::get()
+        .uri("/changes/stream?events=post.created,post.deleted")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("content-type").unwrap(), "text/event-stream");
+    assert_eq!(resp.headers().get("cache-control").unwrap(), "no-cache");
+    let mut body = resp.into_body();
+    assert!(sender.send(change(&user.user_id, "post.updated")).is_ok());
+    let deleted: ChangeEvent = change(&user.user_id, "post.deleted");
+    assert!(sender.send(deleted.clone()).is_ok());
+    let frame: String = next_frame(&mut body).await.unwrap();
+    assert!(frame.starts_with(&format!("id: {}\nevent: post.deleted\n", deleted.event_id)));
+}
@@ -0,0 +1,206 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "json"
+/// macro to build payloads.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function
+/// to make names no other
+/// test uses.
+use super::helpers::unique_name;
+
+/// Importing the structure
+/// holding everything a new post
+/// is created from.
+use crate::modules::units::NewPost;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing all service functions
+/// for the post-related
+/// services.
+use crate::modules::services::posts::*;
+
+/// Importing all database functions
+/// for types of content.
+use crate::modules::db::content_types::*;
+
+/// Importing the structure describing
+/// a field a type of content accepts.
+use crate::modules::units::FieldDefinition;
+
+/// Importing all service functions
+/// for types of content.
+use crate::modules::services::content_types::*;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Importing the function to retrieve
+/// the extra fields of a post.
+use crate::modules::db::ecf::get_extra_fields_for_post;
+
+/// A shorthand to describe
+/// a field of a type of content.
+fn field(field_key: &str, field_type: &str, is_required: bool) -> FieldDefinition {
+    FieldDefinition{
+        field_key: field_key.to_string(),
+        field_type: field_type.to_string(),
+        is_required
+    }
+}
+
+/// A shorthand to build a new post
+/// of the supplied type with the
+/// supplied extra fields.
+fn post_with_fields(content_type: &str, fields: &[(&str, &str)]) -> NewPost {
+    let mut new_post: NewPost = test_post(content_type, "Text.", "markdown", Some("Event"));
+    for (field_key, field_value) in fields {
+        new_post.extra_fields.insert(field_key.to_string(), field_value.to_string());
+    }
+    new_post
+}
+
+/// The function to test that
+/// only administrators define
+/// types of content and that the
+/// built-in types stay.
+#[tokio::test]
+pub async fn test_content_type_definitions(){
+    let pool = test_pool().await;
+    let (admin, _token): (_, String) = create_test_user(true, &pool).await;
+    let (user, _user_token): (_, String) = create_test_user(false, &pool).await;
+    let type_name: String = unique_name("event");
+    let fields: Vec<FieldDefinition> = vec![field("starts_on", "date", true)];
+    assert!(create_content_type(&user, &type_name, &String::new(), &fields, &pool).await.is_err());
+    assert!(create_content_type(&admin, &"Not A Slug".to_string(), &String::new(), &fields, &pool).await.is_err());
+    assert!(create_content_type(&admin, &type_name, &String::new(), &vec![field("size", "colour", false)], &pool).await.is_err());
+    assert!(get_content_type(&type_name, &pool).await.is_err());
+    create_content_type(&admin, &type_name, &"Events.".to_string(), &fields, &pool).await.unwrap();
+    assert!(create_content_type(&admin, &type_name, &String::new(), &fields, &pool).await.is_err());
+    assert!(set_content_type_field(&user, &type_name, &field("venue", "string", false), &pool).await.is_err());
+    set_content_type_field(&admin, &type_name, &field("venue", "string", false), &pool).await.unwrap();
+    let defined: Vec<String> = get_content_type_fields(&type_name, &pool).await.unwrap()
+        .into_iter()
+        .map(|field| field.field_key)
+        .collect();
+    assert_eq!(defined, vec!["starts_on".to_string(), "venue".to_string()]);
+    assert!(delete_content_type(&admin, &"post".to_string(), &pool).await.is_err());
+    create_user_post(&user, &post_with_fields(&type_name, &[("starts_on", "2024-05-01")]), &pool).await.unwrap();
+    assert!(delete_content_type(&admin, &type_name, &pool).await.is_err());
+    let unused: String = unique_name("listing");
+    create_content_type(&admin, &unused, &String::new(), &Vec::new(), &pool).await.unwrap();
+    assert!(delete_content_type(&user, &unused, &pool).await.is_err());
+    delete_content_type(&admin, &unused, &pool).await.unwrap();
+    assert!(get_content_type(&unused, &pool).await.is_err());
+}
+
+/// The function to test that
+/// posts are checked against the
+/// schema of their type of content.
+#[tokio::test]
+pub async fn test_content_type_validation(){
+    let pool = test_pool().await;
+    let (admin, _token): (_, String) = create_test_user(true, &pool).await;
+    let (user, _user_token): (_, String) = create_test_user(false, &pool).await;
+    let type_name: String = unique_name("event");
+    let fields: Vec<FieldDefinition> = vec![
+        field("starts_on", "date", true),
+        field("seats", "integer", false)
+    ];
+    create_content_type(&admin, &type_name, &String::new(), &fields, &pool).await.unwrap();
+    let rejected: [NewPost; 5] = [
+        post_with_fields("missing", &[]),
+        post_with_fields(&type_name, &[("seats", "10")]),
+        post_with_fields(&type_name, &[("starts_on", "next friday")]),
+        post_with_fields(&type_name, &[("starts_on", "2024-05-01"), ("seats", "ten")]),
+        post_with_fields(&type_name, &[("starts_on", "2024-05-01"), ("venue", "Hall")])
+    ];
+    for new_post in &rejected {
+        assert!(create_user_post(&user, new_post, &pool).await.is_err());
+    }
+    let post: UserPost = create_user_post(
+        &user,
+        &post_with_fields(&type_name, &[("starts_on", "2024-05-01"), ("seats", "10")]),
+        &pool
+    ).await.unwrap();
+    assert_eq!(post.content_type, type_name);
+    assert_eq!(get_extra_fields_for_post(&post.content_id, &pool).await.unwrap().len(), 2);
+    let free: UserPost = create_user_post(&user, &post_with_fields("post", &[("mood", "happy")]), &pool).await.unwrap();
+    assert_eq!(get_extra_fields_for_post(&free.content_id, &pool).await.unwrap().len(), 1);
+    assert!(validate_field_key(&type_name, &"venue".to_string(), &pool).await.is_err());
+    assert!(validate_field_removal(&type_name, &"starts_on".to_string(), &pool).await.is_err());
+    assert!(validate_field_removal(&type_name, &"seats".to_string(), &pool).await.is_ok());
+    assert!(resolve_field_type(&type_name, &"seats".to_string(), &Some("string".to_string()), &pool).await.is_err());
+    assert_eq!(resolve_field_type(&type_name, &"seats".to_string(), &None, &pool).await.unwrap(), "integer");
+}
+
+/// The function to test the
+/// service functions for types
+/// of content.
+#[actix_web::test]
+pub async fn test_content_type_services(){
+    let pool = test_pool().await;
+    let (_admin, token): (_, String) = create_test_user(true, &pool).await;
+    let (_user, user_token): (_, String) = create_test_user(false, &pool).await;
+    let type_name: String = unique_name("event");
+    let app = test_app!(
+        pool,
+        create_content_type_service,
+        get_content_types_service,
+        create_user_post_service
+    );
+    let resp_forbidden: Value = post_json!(
+        app,
+        "/content-types/create",
+        json!({ "api_token": user_token, "type_name": type_name, "fields": [] })
+    );
+    let resp_create: Value = post_json!(
+        app,
+        "/content-types/create",
+        json!({
+            "api_token": token,
+            "type_name": type_name,
+            "fields": [{ "field_key": "starts_on", "field_type": "date", "is_required": true }]
+        })
+    );
+    let resp_types: Value = get_json!(app, "/content-types");
+    let resp_invalid: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": user_token, "content_type": type_name, "content_text": "Text.", "extra_fields": { "starts_on": "soon" } })
+    );
+    let resp_valid: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": user_token, "content_type": type_name, "content_text": "Text.", "extra_fields": { "starts_on": "2024-05-01" } })
+    );
+    assert!(resp_forbidden.get("error").is_some());
+    assert_eq!(resp_create["type_name"], type_name.as_str());
+    assert!(resp_types["content_types"].as_array().unwrap().iter().any(|content_type| content_type["type_name"] == type_name.as_str()));
+    assert!(resp_invalid.get("error").is_some());
+    assert_eq!(resp_valid["content_type"], type_name.as_str());
+}
@@ -3,56 +3,114 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
-/// Importing "tokio"
-/// to do asynchronous
-/// tests.
-use tokio;
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
 
-/// Importing all data structures
-/// for working with all the defined
-/// database models.
-use crate::modules::models::*;
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
 
 /// Importing all database functions
 /// for the ecf-related
 /// services.
 use crate::modules::db::ecf::*;
 
-/// Importing all database functions
-/// for the key-related
-/// services.
-use crate::modules::db::keys::*;
-
-/// Importing all database functions
-/// for the administrator-related
-/// services.
-use crate::modules::db::admin::*;
-
 /// Importing all database functions
 /// for the post-related
 /// services.
 use crate::modules::db::posts::*;
 
 /// Importing all database functions
-/// for the email-related
+/// for the revision-related
 /// services.
-use crate::modules::db::email::*;
+use crate::modules::db::revisions::*;
 
-/// Importing all database functions
-/// for the user-related
-/// services.
-use crate::modules::db::users::*;
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
 
-/// Importing all database functions
-/// for the file-related
-/// services.
-use crate::modules::db::files::*;
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
 
-/// Importing all database functions
-/// for the token-related
-/// services.
-use crate::modules::db::tokens::*;
+/// Importing the structure
+/// for the details of a post.
+use crate::modules::units::PostDetails;
 
-/// Importing all database functions
-/// for general services.
-use crate::modules::db::general::*;
+/// Importing the model for
+/// revisions for explicit typing.
+use crate::modules::models::PostRevision;
+
+/// The function to test that
+/// posts start as drafts and only
+/// become public once published.
+#[tokio::test]
+pub async fn test_post_publication(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let post: UserPost = create_user_post(
+        &user,
+        &test_post("post", "Hello world!", "markdown", Some("Hello")),
+        &pool
+    ).await.unwrap();
+    assert_eq!(post.post_status, "draft");
+    assert!(get_public_post_by_id(&post.content_id, &pool).await.is_err());
+    update_post_status(&user, &post.content_id, &"published".to_string(), &Some(Utc::now()), &pool).await.unwrap();
+    let public: UserPost = get_public_post_by_id(&post.content_id, &pool).await.unwrap();
+    assert!(public.publish_at.is_some());
+    update_post_status(&user, &post.content_id, &"archived".to_string(), &None, &pool).await.unwrap();
+    assert!(get_public_post_by_id(&post.content_id, &pool).await.is_err());
+    assert!(update_post_status(&user, &post.content_id, &"scheduled".to_string(), &None, &pool).await.is_err());
+}
+
+/// The function to test that
+/// every change to a post or its
+/// extra content fields saves a
+/// revision and that restoring one
+/// brings back the saved state.
+#[tokio::test]
+pub async fn test_post_revisions(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let post: UserPost = create_user_post(
+        &user,
+        &test_post("post", "First text.", "markdown", Some("Hello")),
+        &pool
+    ).await.unwrap();
+    let first: Vec<PostRevision> = get_revisions_for_post(&post.content_id, &pool).await.unwrap();
+    assert_eq!(first.len(), 1);
+    update_post_text(&user, &post.content_id, &"Second text.".to_string(), &pool).await.unwrap();
+    let changed: PostDetails = PostDetails{
+        title: Some("Hello again".to_string()),
+        excerpt: None,
+        cover_file_id: None,
+        author_name: None
+    };
+    update_post_details(&user, &post.content_id, &changed, &pool).await.unwrap();
+    create_extra_field_for_post(
+        &user,
+        &post.content_id,
+        &"mood".to_string(),
+        &"happy".to_string(),
+        &None,
+        &pool
+    ).await.unwrap();
+    let revisions: Vec<PostRevision> = get_revisions_for_post(&post.content_id, &pool).await.unwrap();
+    assert_eq!(revisions.len(), 4);
+    assert!(revisions.iter().any(|revision| revision.extra_fields.contains("happy")));
+    assert_eq!(first[0].title, "Hello");
+    assert_eq!(revisions[2].title, "Hello again");
+    restore_post_revision(&user, &post.content_id, &first[0].revision_id, &pool).await.unwrap();
+    let restored: UserPost = get_post_by_id(&post.content_id, &pool).await.unwrap();
+    assert_eq!(restored.content_text, "First text.");
+    assert_eq!(restored.title, "Hello");
+    assert!(get_extra_fields_for_post(&post.content_id, &pool).await.unwrap().is_empty());
+    assert_eq!(get_revisions_for_post(&post.content_id, &pool).await.unwrap().len(), 5);
+}
@@ -0,0 +1,246 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing.
+use chrono::DateTime;
+
+/// Importing the "TimeZone"
+/// trait to build fixed
+/// dates.
+use chrono::TimeZone;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing all functions
+/// for rendering feeds and
+/// sitemaps.
+use crate::modules::feeds::*;
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the structure
+/// holding the details of a
+/// feed.
+use crate::modules::units::FeedMeta;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the structure
+/// holding the details of an
+/// entry of a feed.
+use crate::modules::units::FeedEntry;
+
+/// Importing all service functions
+/// for feeds and sitemaps.
+use crate::modules::services::feeds::*;
+
+/// Importing the structure
+/// holding the details of an
+/// entry of a sitemap.
+use crate::modules::units::SitemapEntry;
+
+/// Importing the function to make
+/// sure the instance has its
+/// information saved.
+use super::helpers::ensure_instance_info;
+
+/// Importing the function to edit
+/// the URL of the public website
+/// of the instance.
+use crate::modules::db::admin::edit_site_url;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Importing the function to
+/// change the status of a post.
+use crate::modules::db::posts::update_post_status;
+
+/// The URL of the public website
+/// the feed tests link to.
+const TEST_SITE_URL: &str = "https://blog.example.com";
+
+/// A shorthand to build the
+/// details of a feed.
+fn meta(updated: DateTime<Utc>) -> FeedMeta {
+    FeedMeta{
+        title: "News & Notes".to_string(),
+        description: "The <latest> posts.".to_string(),
+        home_url: "https://blog.example.com".to_string(),
+        feed_url: "https://api.example.com/public/feeds/rss".to_string(),
+        updated
+    }
+}
+
+/// A shorthand to build an
+/// entry of a feed.
+fn entry(title: &str, summary: &str, updated: DateTime<Utc>) -> FeedEntry {
+    FeedEntry{
+        id: format!("https://blog.example.com/alyx/post/{}", title.to_lowercase()),
+        title: title.to_string(),
+        url: format!("https://blog.example.com/alyx/post/{}", title.to_lowercase()),
+        author: "Alyx".to_string(),
+        summary: summary.to_string(),
+        content_html: "<p>Tom & Jerry</p>".to_string(),
+        published: updated,
+        updated
+    }
+}
+
+/// A shorthand to send a "GET"
+/// request and read the content
+/// type and the body of the
+/// response.
+macro_rules! get_text {
+    ($app:expr, $uri:expr) => {{
+        let req = actix_web::test::TestRequest::get()
+            .uri($uri)
+            .to_request();
+        let resp = actix_web::test::call_service(&$app, req).await;
+        let content_type: String = resp.headers()
+            .get("content-type")
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default();
+        let body = actix_web::test::read_body(resp).await;
+        (content_type, String::from_utf8(body.to_vec()).unwrap())
+    }};
+}
+
+/// The function to test the
+/// absolute links of feeds and
+/// sitemaps.
+#[test]
+pub fn test_feed_links(){
+    assert_eq!(base_url("example.com/"), "https://example.com");
+    assert_eq!(base_url(" http://localhost:8000 "), "http://localhost:8000");
+    assert_eq!(site_base_url("api.example.com", ""), "https://api.example.com");
+    assert_eq!(site_base_url("api.example.com", "https://blog.example.com/"), "https://blog.example.com");
+    assert_eq!(encode_path_segment("hello-world_1.0~"), "hello-world_1.0~");
+    assert_eq!(encode_path_segment("a b/c?d#e"), "a%20b%2Fc%3Fd%23e");
+    assert_eq!(encode_path_segment("café"), "caf%C3%A9");
+    assert_eq!(
+        post_url("https://blog.example.com", "alyx shang", "post", "hello/world"),
+        "https://blog.example.com/alyx%20shang/post/hello%2Fworld"
+    );
+    assert!(validate_feed_format(&"atom".to_string()).is_ok());
+    assert!(validate_feed_format(&"yaml".to_string()).is_err());
+}
+
+/// The function to test rendering
+/// feeds and sitemaps in every
+/// format.
+#[test]
+pub fn test_feed_rendering(){
+    let updated: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let entries: Vec<FeedEntry> = vec![
+        entry("First", "A <short> summary.", updated),
+        entry("Second", "", updated)
+    ];
+    let rss: String = render_rss(&meta(updated), &entries);
+    assert!(rss.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    assert!(rss.contains("<title>News &amp; Notes</title>"));
+    assert!(rss.contains("<link>https://blog.example.com/alyx/post/first</link>"));
+    assert!(rss.contains("<description>A &lt;short&gt; summary.</description>"));
+    assert!(rss.contains("<description>&lt;p&gt;Tom &amp; Jerry&lt;/p&gt;</description>"));
+    assert!(rss.contains("<pubDate>Wed, 1 May 2024 12:00:00 +0000</pubDate>"));
+    let atom: String = render_atom(&meta(updated), &entries);
+    assert_eq!(atom.matches("<entry>").count(), 2);
+    assert_eq!(atom.matches("<summary>").count(), 1);
+    assert!(atom.contains("<link rel=\"alternate\" href=\"https://blog.example.com/alyx/post/second\"/>"));
+    assert!(atom.contains("<updated>2024-05-01T12:00:00+00:00</updated>"));
+    let json_feed: Value = serde_json::from_str(&render_json_feed(&meta(updated), &entries)).unwrap();
+    assert_eq!(json_feed["version"], "https://jsonfeed.org/version/1.1");
+    assert_eq!(json_feed["title"], "News & Notes");
+    assert_eq!(json_feed["items"][0]["summary"], "A <short> summary.");
+    assert!(json_feed["items"][1].get("summary").is_none());
+    assert_eq!(json_feed["items"][1]["authors"][0]["name"], "Alyx");
+    let sitemap_entries: Vec<SitemapEntry> = vec![
+        SitemapEntry{
+            username: "alyx".to_string(),
+            content_type: "page".to_string(),
+            slug: "tom&jerry".to_string(),
+            updated_at: updated
+        }
+    ];
+    let sitemap: String = render_sitemap("https://blog.example.com", &sitemap_entries);
+    assert!(sitemap.contains("<url><loc>https://blog.example.com/alyx/page/tom%26jerry</loc><lastmod>2024-05-01T12:00:00Z</lastmod></url>"));
+    let index: String = render_sitemap_index(&"https://api.example.com".to_string(), 2);
+    assert_eq!(index.matches("<sitemap>").count(), 2);
+    assert!(index.contains("<loc>https://api.example.com/sitemaps/2</loc>"));
+}
+
+/// The function to test the
+/// service functions for feeds
+/// and sitemaps.
+#[actix_web::test]
+pub async fn test_feed_services(){
+    let pool = test_pool().await;
+    ensure_instance_info(&pool).await;
+    let (admin, _token): (_, String) = create_test_user(true, &pool).await;
+    let (author, _author_token): (_, String) = create_test_user(false, &pool).await;
+    assert!(edit_site_url(&author, TEST_SITE_URL, &pool).await.is_err());
+    assert!(edit_site_url(&admin, "blog.example.com", &pool).await.is_err());
+    edit_site_url(&admin, TEST_SITE_URL, &pool).await.unwrap();
+    let post: UserPost = create_user_post(&author, &test_post("post", "Some text.", "markdown", Some("Hello")), &pool).await.unwrap();
+    let page: UserPost = create_user_post(&author, &test_post("page", "About me.", "markdown", Some("About")), &pool).await.unwrap();
+    let draft: UserPost = create_user_post(&author, &test_post("post", "Not yet.", "markdown", Some("Later")), &pool).await.unwrap();
+    for published in [&post, &page] {
+        update_post_status(&author, &published.content_id, &"published".to_string(), &Some(Utc::now()), &pool).await.unwrap();
+    }
+    let post_link: String = format!("{}/{}/post/{}", TEST_SITE_URL, author.username, post.slug);
+    let page_link: String = format!("{}/{}/page/{}", TEST_SITE_URL, author.username, page.slug);
+    let draft_link: String = format!("{}/{}/post/{}", TEST_SITE_URL, author.username, draft.slug);
+    let app = test_app!(
+        pool,
+        get_user_feed_service,
+        get_sitemap_service,
+        get_sitemap_page_service
+    );
+    let (rss_type, rss): (String, String) = get_text!(app, &format!("/public/feeds/{}/rss", author.username));
+    let (_atom_type, atom): (String, String) = get_text!(app, &format!("/public/feeds/{}/atom", author.username));
+    let (json_type, json_body): (String, String) = get_text!(app, &format!("/public/feeds/{}/json", author.username));
+    let (_invalid_type, invalid): (String, String) = get_text!(app, &format!("/public/feeds/{}/yaml", author.username));
+    let (sitemap_type, sitemap): (String, String) = get_text!(app, "/sitemap.xml");
+    let (_missing_type, missing): (String, String) = get_text!(app, "/sitemaps/0");
+    assert_eq!(rss_type, "application/rss+xml; charset=utf-8");
+    assert!(rss.contains(&format!("<link>{}</link>", post_link)));
+    assert!(!rss.contains(&page_link));
+    assert!(!rss.contains(&draft_link));
+    assert!(atom.contains(&format!("href=\"{}\"", post_link)));
+    assert_eq!(json_type, "application/feed+json; charset=utf-8");
+    let json_feed: Value = serde_json::from_str(&json_body).unwrap();
+    assert_eq!(json_feed["home_page_url"], format!("{}/{}", TEST_SITE_URL, author.username));
+    assert_eq!(json_feed["items"].as_array().unwrap().len(), 1);
+    assert_eq!(json_feed["items"][0]["url"], post_link.as_str());
+    assert!(serde_json::from_str::<Value>(&invalid).unwrap().get("error").is_some());
+    assert_eq!(sitemap_type, "application/xml; charset=utf-8");
+    assert!(sitemap.contains(&format!("<loc>{}</loc>", post_link)));
+    assert!(sitemap.contains(&format!("<loc>{}</loc>", page_link)));
+    assert!(!sitemap.contains(&draft_link));
+    assert!(serde_json::from_str::<Value>(&missing).unwrap().get("error").is_some());
+}
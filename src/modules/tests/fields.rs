@@ -0,0 +1,187 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "json"
+/// macro to build expected
+/// values.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing all service functions
+/// for the ecf-related
+/// services.
+use crate::modules::services::ecf::*;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing all service functions
+/// for the post-related
+/// services.
+use crate::modules::services::posts::*;
+
+/// Importing all service functions
+/// for general services.
+use crate::modules::services::general::*;
+
+/// Importing the function
+/// to check field types.
+use crate::modules::utils::validate_field_type;
+
+/// Importing the function to
+/// convert field values to JSON.
+use crate::modules::utils::field_value_to_json;
+
+/// Importing the function
+/// to check field values.
+use crate::modules::utils::validate_field_value;
+
+/// Importing the function to
+/// check field values that need
+/// the database.
+use crate::modules::db::ecf::validate_extra_field_value;
+
+/// A shorthand to check a
+/// value against a field type.
+fn is_valid(field_type: &str, value: &str) -> bool {
+    validate_field_value(&field_type.to_string(), &value.to_string()).is_ok()
+}
+
+/// The function to test
+/// checking field types.
+#[test]
+pub fn test_validate_field_type(){
+    for field_type in ["string", "integer", "float", "boolean", "date", "url", "file", "json"] {
+        assert!(validate_field_type(&field_type.to_string()).is_ok());
+    }
+    assert!(validate_field_type(&"".to_string()).is_err());
+    assert!(validate_field_type(&"String".to_string()).is_err());
+    assert!(validate_field_type(&"number".to_string()).is_err());
+}
+
+/// The function to test
+/// checking field values.
+#[test]
+pub fn test_validate_field_value(){
+    assert!(is_valid("string", ""));
+    assert!(is_valid("integer", "-42"));
+    assert!(!is_valid("integer", "4.2"));
+    assert!(!is_valid("integer", "99999999999999999999"));
+    assert!(is_valid("float", "4.2"));
+    assert!(!is_valid("float", "NaN"));
+    assert!(!is_valid("float", "inf"));
+    assert!(is_valid("boolean", "true"));
+    assert!(!is_valid("boolean", "True"));
+    assert!(is_valid("date", "2024-02-29"));
+    assert!(is_valid("date", "2024-02-29T10:00:00+01:00"));
+    assert!(!is_valid("date", "2023-02-29"));
+    assert!(!is_valid("date", "29.02.2024"));
+    assert!(is_valid("url", "https://example.com/a?b=c"));
+    assert!(!is_valid("url", "ftp://example.com"));
+    assert!(!is_valid("url", "https://example.com/a b"));
+    assert!(is_valid("file", "some-id"));
+    assert!(!is_valid("file", ""));
+    assert!(is_valid("json", "{\"a\": [1, 2]}"));
+    assert!(!is_valid("json", "{a: 1}"));
+    assert!(!is_valid("colour", "red"));
+}
+
+/// The function to test
+/// converting field values
+/// to JSON.
+#[test]
+pub fn test_field_value_to_json(){
+    assert_eq!(field_value_to_json(&"integer".to_string(), &"7".to_string()), json!(7));
+    assert_eq!(field_value_to_json(&"float".to_string(), &"0.5".to_string()), json!(0.5));
+    assert_eq!(field_value_to_json(&"boolean".to_string(), &"false".to_string()), json!(false));
+    assert_eq!(field_value_to_json(&"json".to_string(), &"[1]".to_string()), json!([1]));
+    assert_eq!(field_value_to_json(&"date".to_string(), &"2024-01-01".to_string()), json!("2024-01-01"));
+    assert_eq!(field_value_to_json(&"integer".to_string(), &"x".to_string()), json!("x"));
+}
+
+/// The function to test that
+/// file fields must name a file
+/// of the user.
+#[tokio::test]
+pub async fn test_validate_extra_field_value(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    assert!(validate_extra_field_value(&user.user_id, &"integer".to_string(), &"3".to_string(), &pool).await.is_ok());
+    assert!(validate_extra_field_value(&user.user_id, &"integer".to_string(), &"three".to_string(), &pool).await.is_err());
+    assert!(validate_extra_field_value(&user.user_id, &"file".to_string(), &"missing".to_string(), &pool).await.is_err());
+}
+
+/// The function to test that
+/// posts are returned with their
+/// extra fields and that the fields
+/// of a post can be listed.
+#[actix_web::test]
+pub async fn test_embedded_extra_fields(){
+    let pool = test_pool().await;
+    let (_user, token): (_, String) = create_test_user(false, &pool).await;
+    let (_other, other_token): (_, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        create_user_post_service,
+        create_extra_content_field_service,
+        get_extra_content_fields_service,
+        get_user_posts_service
+    );
+    let resp_post: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": token, "content_type": "post", "content_text": "Text.", "extra_fields": { "mood": "happy" } })
+    );
+    let resp_bare: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": token, "content_type": "post", "content_text": "Bare." })
+    );
+    let content_id: &str = resp_post["content_id"].as_str().unwrap();
+    let resp_seats: Value = post_json!(
+        app,
+        "/ecf/create",
+        json!({ "api_token": token, "content_id": content_id, "field_key": "seats", "field_value": "3", "field_type": "integer" })
+    );
+    let resp_invalid: Value = post_json!(
+        app,
+        "/ecf/create",
+        json!({ "api_token": token, "content_id": content_id, "field_key": "rows", "field_value": "three", "field_type": "integer" })
+    );
+    let resp_foreign: Value = post_json!(
+        app,
+        "/ecf/create",
+        json!({ "api_token": other_token, "content_id": content_id, "field_key": "mood", "field_value": "sad" })
+    );
+    let fields_uri: String = format!("/ecf/all?content_id={}", content_id);
+    let resp_fields: Value = get_json!(app, &fields_uri, token);
+    let resp_other_fields: Value = get_json!(app, &fields_uri, other_token);
+    let resp_no_post: Value = get_json!(app, "/ecf/all", token);
+    let resp_posts: Value = get_json!(app, "/posts/all?sort_by=created_at&sort_order=asc", token);
+    assert_eq!(resp_post["extra_fields"], json!({ "mood": "happy" }));
+    assert_eq!(resp_seats["field_value"], json!(3));
+    assert!(resp_invalid.get("error").is_some());
+    assert!(resp_foreign.get("error").is_some());
+    let fields: &Vec<Value> = resp_fields["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 2);
+    assert!(fields.iter().all(|field| field["content_id"] == content_id && field["field_id"].is_string()));
+    assert!(resp_other_fields.get("error").is_some());
+    assert!(resp_no_post.get("error").is_some());
+    let posts: &Vec<Value> = resp_posts["posts"].as_array().unwrap();
+    assert_eq!(posts.len(), 2);
+    assert_eq!(posts[0]["extra_fields"], json!({ "mood": "happy", "seats": 3 }));
+    assert_eq!(posts[0]["fields"].as_array().unwrap().len(), 2);
+    assert_eq!(posts[1]["content_id"], resp_bare["content_id"]);
+    assert_eq!(posts[1]["extra_fields"], json!({}));
+}
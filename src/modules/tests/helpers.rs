@@ -0,0 +1,218 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
+/// Importing the "hash"
+/// function to hash the
+/// passwords of test users.
+use bcrypt::hash;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the "HashMap"
+/// structure for the extra
+/// fields of posts.
+use std::collections::HashMap;
+
+/// Importing the function to
+/// generate identifiers for rows.
+use crate::modules::ids::new_id;
+
+/// Importing the "PgPoolOptions"
+/// structure to connect to the
+/// test database.
+use sqlx::postgres::PgPoolOptions;
+
+/// Importing the structure
+/// holding everything a new post
+/// is created from.
+use crate::modules::units::NewPost;
+
+/// Importing the function to
+/// generate random secrets.
+use crate::modules::ids::new_secret;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the structure
+/// for the details of a post.
+use crate::modules::units::PostDetails;
+
+/// Importing the function to retrieve
+/// a user given their username.
+use crate::modules::db::users::get_user_by_username;
+
+/// Importing the function to
+/// create an API token for a user.
+use crate::modules::db::tokens::create_api_token_for_user;
+
+/// The password every user
+/// created by the tests has.
+pub const TEST_PASSWORD: &str = "12345678";
+
+/// The cost used to hash the
+/// passwords of test users. It
+/// is kept low so that tests
+/// stay fast.
+pub const TEST_HASH_COST: u32 = 4;
+
+/// Builds an app with the persistent
+/// app data and the authentication
+/// middleware the runner uses and
+/// registers the supplied services.
+macro_rules! test_app {
+    ($pool:expr, $($service:expr),+ $(,)?) => {
+        actix_web::test::init_service(
+            actix_web::App::new()
+                .app_data(actix_web::web::Data::new(crate::modules::units::AppData::new(&$pool)))
+                .wrap(actix_web::middleware::from_fn(crate::modules::auth::authenticate))
+                $(.service($service))+
+        ).await
+    };
+}
+
+/// Sends a "POST" request with the
+/// supplied JSON payload to an app
+/// built with "test_app" and returns
+/// the response as JSON.
+macro_rules! post_json {
+    ($app:expr, $uri:expr, $payload:expr) => {{
+        let req = actix_web::test::TestRequest::post()
+            .uri($uri)
+            .set_json($payload)
+            .to_request();
+        let resp: serde_json::Value = actix_web::test::call_and_read_body_json(&$app, req).await;
+        resp
+    }};
+}
+
+/// Sends a "GET" request to an
+/// app built with "test_app" and
+/// returns the response as JSON. If
+/// a token is supplied, it is sent
+/// as a bearer token.
+macro_rules! get_json {
+    ($app:expr, $uri:expr) => {{
+        let req = actix_web::test::TestRequest::get()
+            .uri($uri)
+            .to_request();
+        let resp: serde_json::Value = actix_web::test::call_and_read_body_json(&$app, req).await;
+        resp
+    }};
+    ($app:expr, $uri:expr, $token:expr) => {{
+        let req = actix_web::test::TestRequest::get()
+            .uri($uri)
+            .insert_header(("Authorization", format!("Bearer {}", $token)))
+            .to_request();
+        let resp: serde_json::Value = actix_web::test::call_and_read_body_json(&$app, req).await;
+        resp
+    }};
+}
+
+/// Connects to the database in
+/// the "DATABASE_URL" environment
+/// variable. The database must have
+/// all migrations applied.
+pub async fn test_pool() -> Pool<Postgres> {
+    let url: String = std::env::var("DATABASE_URL")
+        .expect("\"DATABASE_URL\" must point to a migrated database.");
+    PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("Could not connect to the test database.")
+}
+
+/// Returns a name that no
+/// other test uses.
+pub fn unique_name(prefix: &str) -> String {
+    format!("{}{}", prefix, new_secret(&12).unwrap().to_lowercase())
+}
+
+/// Creates a user with the password
+/// in "TEST_PASSWORD" and an API token
+/// carrying all scopes the user may
+/// hold. The user and the token are
+/// returned.
+pub async fn create_test_user(is_admin: bool, pool: &Pool<Postgres>) -> (CleoUser, String) {
+    let username: String = unique_name("user");
+    sqlx::query(
+        "INSERT INTO cleo_users (user_id, display_name, is_verified, username, pwd, email_addr, pfp_url, is_admin) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+    )
+        .bind(new_id().unwrap())
+        .bind("Test User")
+        .bind(true)
+        .bind(&username)
+        .bind(hash(TEST_PASSWORD, TEST_HASH_COST).unwrap())
+        .bind(format!("{}@example.com", &username))
+        .bind("")
+        .bind(is_admin)
+        .execute(pool)
+        .await
+        .unwrap();
+    let (_token_obj, token): (_, String) = create_api_token_for_user(
+        &username,
+        &TEST_PASSWORD.to_string(),
+        &None,
+        &None,
+        &None,
+        pool
+    ).await.unwrap();
+    let user: CleoUser = get_user_by_username(&username, pool).await.unwrap();
+    (user, token)
+}
+
+/// Makes sure the instance has
+/// its information saved.
+pub async fn ensure_instance_info(pool: &Pool<Postgres>) {
+    sqlx::query(
+        "INSERT INTO instance_info (instance_id, hostname, instance_name, smtp_server, smtp_username, smtp_pass, file_dir) SELECT $1, $2, $3, $4, $5, $6, $7 WHERE NOT EXISTS (SELECT 1 FROM instance_info)"
+    )
+        .bind(new_id().unwrap())
+        .bind("http://localhost:8000")
+        .bind("Cleo")
+        .bind("localhost")
+        .bind("cleo@localhost")
+        .bind("")
+        .bind("files")
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+/// Builds the data for a new post
+/// of the supplied content type with
+/// the supplied text, source format,
+/// and title. No slug or extra fields
+/// are set.
+pub fn test_post(
+    content_type: &str,
+    content_text: &str,
+    source_format: &str,
+    title: Option<&str>
+) -> NewPost {
+    NewPost{
+        content_type: content_type.to_string(),
+        content_text: content_text.to_string(),
+        source_format: source_format.to_string(),
+        slug: None,
+        details: PostDetails{
+            title: title.map(|title| title.to_string()),
+            excerpt: None,
+            cover_file_id: None,
+            author_name: None
+        },
+        extra_fields: HashMap::new()
+    }
+}
@@ -0,0 +1,85 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the function
+/// to sanitize HTML.
+use crate::modules::html::sanitize_html;
+
+/// Importing the function
+/// to render Markdown.
+use crate::modules::html::render_markdown;
+
+/// A shorthand to sanitize
+/// a string slice.
+fn clean(subject: &str) -> Result<String, String> {
+    sanitize_html(&subject.to_string()).map_err(|e| e.to_string())
+}
+
+/// The function to test that
+/// allowed markup is kept as
+/// it was written.
+#[test]
+pub fn test_sanitize_keeps_allowed_markup(){
+    assert_eq!(clean("<p>Hello <em>world</em></p>").unwrap(), "<p>Hello <em>world</em></p>");
+    assert_eq!(
+        clean("<a href=\"https://example.com\" title=\"x\">link</a>").unwrap(),
+        "<a href=\"https://example.com\" title=\"x\">link</a>"
+    );
+    assert_eq!(clean("<ul><li>one<li>two</ul>").unwrap(), "<ul><li>one</li><li>two</li></ul>");
+    assert_eq!(clean("<p>one<p>two").unwrap(), "<p>one</p><p>two</p>");
+    assert_eq!(clean("a<br>b<img src=\"/x.png\" alt=\"x\">").unwrap(), "a<br>b<img src=\"/x.png\" alt=\"x\">");
+}
+
+/// The function to test that
+/// scripts, handlers, dangerous
+/// URLs, and foreign content are
+/// removed.
+#[test]
+pub fn test_sanitize_removes_dangerous_markup(){
+    assert_eq!(clean("a<script>alert(1)</script>b").unwrap(), "ab");
+    assert_eq!(clean("<script>if (a<b){}</script>ok").unwrap(), "ok");
+    assert_eq!(clean("<style>p > a { color: red }</style>ok").unwrap(), "ok");
+    assert_eq!(clean("<p onclick=\"alert(1)\">x</p>").unwrap(), "<p>x</p>");
+    assert_eq!(clean("<a href=\"javascript:alert(1)\">x</a>").unwrap(), "<a>x</a>");
+    assert_eq!(clean("<a href=\"JaVa\tScRiPt:alert(1)\">x</a>").unwrap(), "<a>x</a>");
+    assert_eq!(clean("<svg><script>alert(1)</script><a href=\"/\">x</a></svg>ok").unwrap(), "ok");
+    assert_eq!(clean("<svg/><p>ok</p>").unwrap(), "<p>ok</p>");
+    assert_eq!(clean("<math><mi>x</mi></math>ok").unwrap(), "ok");
+    assert_eq!(clean("<h2 id=\"top\">x</h2>").unwrap(), "<h2>x</h2>");
+    assert_eq!(clean("<form><input name=\"q\"></form>x").unwrap(), "x");
+}
+
+/// The function to test that
+/// stray characters are kept as
+/// escaped text instead of being
+/// rejected.
+#[test]
+pub fn test_sanitize_tolerates_stray_characters(){
+    assert_eq!(clean("x < y").unwrap(), "x &lt; y");
+    assert_eq!(clean("<p>1 < 2 & 3 > 2</p>").unwrap(), "<p>1 &lt; 2 &amp; 3 &gt; 2</p>");
+    assert_eq!(clean("fish &chips").unwrap(), "fish &amp;chips");
+    assert_eq!(clean("<p title='a\"b'>x</p>").unwrap(), "<p title=\"a&quot;b\">x</p>");
+}
+
+/// The function to test that
+/// unbalanced tags are rejected.
+#[test]
+pub fn test_sanitize_rejects_unbalanced_tags(){
+    assert!(clean("<div><p>x</div>").is_ok());
+    assert!(clean("<div>x").is_err());
+    assert!(clean("x</div>").is_err());
+    assert!(clean("<em><strong>x</em></strong>").is_err());
+    assert!(clean("<script>x").is_err());
+}
+
+/// The function to test that
+/// rendered Markdown is sanitized.
+#[test]
+pub fn test_render_markdown(){
+    let rendered: String = render_markdown(&"# Hi\n\n<script>x</script>\n\n*a* < b".to_string()).unwrap();
+    assert!(rendered.contains("<h1>Hi</h1>"));
+    assert!(!rendered.contains("script"));
+    assert!(rendered.contains("<em>a</em> &lt; b"));
+}
@@ -0,0 +1,56 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing all functions
+/// to generate identifiers
+/// and secrets.
+use crate::modules::ids::*;
+
+/// Importing the "HashSet"
+/// structure to find repeated
+/// values.
+use std::collections::HashSet;
+
+/// The function to test the
+/// format and the order of
+/// identifiers.
+#[test]
+pub fn test_new_id(){
+    let ids: Vec<String> = (0..10000)
+        .map(|_index| new_id().unwrap())
+        .collect::<Vec<String>>();
+    for id in &ids {
+        let groups: Vec<&str> = id.split('-').collect::<Vec<&str>>();
+        assert_eq!(id.len(), 36);
+        assert_eq!(
+            groups.iter().map(|group| group.len()).collect::<Vec<usize>>(),
+            vec![8, 4, 4, 4, 12]
+        );
+        assert!(id.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+        assert!(!id.chars().any(|c| c.is_ascii_uppercase()));
+        assert_eq!(&id[14..15], "7");
+        assert!("89ab".contains(&id[19..20]));
+    }
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(ids.iter().collect::<HashSet<&String>>().len(), ids.len());
+}
+
+/// The function to test the
+/// length and the characters
+/// of secrets.
+#[test]
+pub fn test_new_secret(){
+    assert_eq!(new_secret(&0).unwrap(), "");
+    let secrets: Vec<String> = (0..1000)
+        .map(|_index| new_secret(&48).unwrap())
+        .collect::<Vec<String>>();
+    for secret in &secrets {
+        assert_eq!(secret.len(), 48);
+        assert!(secret.chars().all(|c| SECRET_ALPHABET.contains(c)));
+    }
+    assert_eq!(secrets.iter().collect::<HashSet<&String>>().len(), secrets.len());
+    let joined: String = secrets.concat();
+    assert!(SECRET_ALPHABET.chars().all(|c| joined.contains(c)));
+}
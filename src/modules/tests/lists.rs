@@ -0,0 +1,154 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function to
+/// generate random secrets.
+use crate::modules::ids::new_secret;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure
+/// holding the filters for
+/// listing posts.
+use crate::modules::units::PostFilters;
+
+/// Importing the largest
+/// size of a page.
+use crate::modules::utils::MAX_PAGE_SIZE;
+
+/// Importing the size of
+/// a page if none is given.
+use crate::modules::utils::DEFAULT_PAGE_SIZE;
+
+/// Importing the function to
+/// list the posts of a user.
+use crate::modules::db::general::get_user_posts;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Importing the function to
+/// check options for listing
+/// records.
+use crate::modules::utils::resolve_list_options;
+
+/// Importing the function to add
+/// an extra field to a post.
+use crate::modules::db::ecf::create_extra_field_for_post;
+
+/// The fields the tests
+/// sort records by.
+const SORT_FIELDS: [&str; 2] = ["created_at", "title"];
+
+/// A shorthand to resolve
+/// the supplied options.
+fn resolve(
+    page: Option<i64>, 
+    per_page: Option<i64>, 
+    sort_by: Option<&str>, 
+    sort_order: Option<&str>
+) -> Option<ListOptions> {
+    resolve_list_options(
+        &page,
+        &per_page,
+        &sort_by.map(|sort_by| sort_by.to_string()),
+        &sort_order.map(|sort_order| sort_order.to_string()),
+        &SORT_FIELDS
+    ).ok()
+}
+
+/// The function to test
+/// the default options.
+#[test]
+pub fn test_list_option_defaults(){
+    let options: ListOptions = resolve(None, None, None, None).unwrap();
+    assert_eq!(options.page, 1);
+    assert_eq!(options.per_page, DEFAULT_PAGE_SIZE);
+    assert_eq!(options.sort_by, "created_at");
+    assert_eq!(options.sort_order, "desc");
+    assert_eq!(options.offset(), 0);
+}
+
+/// The function to test
+/// the bounds of the options.
+#[test]
+pub fn test_list_option_bounds(){
+    assert!(resolve(Some(0), None, None, None).is_none());
+    assert!(resolve(Some(-1), None, None, None).is_none());
+    assert!(resolve(None, Some(0), None, None).is_none());
+    assert!(resolve(None, Some(MAX_PAGE_SIZE + 1), None, None).is_none());
+    assert!(resolve(None, Some(MAX_PAGE_SIZE), None, None).is_some());
+    assert!(resolve(None, None, Some("pwd"), None).is_none());
+    assert!(resolve(None, None, None, Some("sideways")).is_none());
+    let options: ListOptions = resolve(Some(3), Some(10), Some("TITLE"), Some("ASC")).unwrap();
+    assert_eq!(options.offset(), 20);
+    assert_eq!(options.sort_by, "title");
+    assert_eq!(options.sort_order, "asc");
+}
+
+/// The function to test that
+/// page numbers whose offset does
+/// not fit are rejected.
+#[test]
+pub fn test_list_option_overflow(){
+    assert!(resolve(Some(i64::MAX), Some(MAX_PAGE_SIZE), None, None).is_none());
+    assert!(resolve(Some(i64::MAX), Some(1), None, None).is_some());
+    let largest: i64 = i64::MAX / MAX_PAGE_SIZE + 1;
+    let options: ListOptions = resolve(Some(largest), Some(MAX_PAGE_SIZE), None, None).unwrap();
+    assert!(options.offset() > 0);
+    assert!(resolve(Some(largest + 1), Some(MAX_PAGE_SIZE), None, None).is_none());
+}
+
+/// The function to test that
+/// posts can be filtered by long
+/// values of extra fields.
+#[tokio::test]
+pub async fn test_long_field_value_filter(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let post: UserPost = create_user_post(&user, &test_post("post", "Text.", "markdown", None), &pool).await.unwrap();
+    let long_value: String = (0..100).map(|_| new_secret(&64).unwrap()).collect();
+    create_extra_field_for_post(
+        &user,
+        &post.content_id,
+        &"notes".to_string(),
+        &long_value,
+        &None,
+        &pool
+    ).await.unwrap();
+    let options: ListOptions = resolve(None, None, None, None).unwrap();
+    let filters = |field_value: &String| PostFilters{
+        content_type: None,
+        post_status: None,
+        field_key: Some("notes".to_string()),
+        field_value: Some(field_value.clone())
+    };
+    let (found, total): (Vec<UserPost>, i64) = get_user_posts(&user, &filters(&long_value), &options, &pool).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(found[0].content_id, post.content_id);
+    let (_missed, none): (Vec<UserPost>, i64) = get_user_posts(&user, &filters(&"other".to_string()), &options, &pool).await.unwrap();
+    assert_eq!(none, 0);
+}
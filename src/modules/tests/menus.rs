@@ -0,0 +1,243 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
+/// Importing the "json"
+/// macro to build payloads.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function
+/// to make names no other
+/// test uses.
+use super::helpers::unique_name;
+
+/// Importing all database functions
+/// for navigation menus.
+use crate::modules::db::menus::*;
+
+/// Importing the model for
+/// menus for explicit typing.
+use crate::modules::models::NavMenu;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// the items of menus for
+/// explicit typing.
+use crate::modules::models::MenuItem;
+
+/// Importing the structure
+/// describing what a menu
+/// item points to.
+use crate::modules::units::MenuTarget;
+
+/// Importing all service functions
+/// for navigation menus.
+use crate::modules::services::menus::*;
+
+/// A shorthand to describe
+/// a link to a web address.
+fn link(url: &str) -> MenuTarget {
+    MenuTarget{
+        target_type: "url".to_string(),
+        target_id: None,
+        url: Some(url.to_string())
+    }
+}
+
+/// A shorthand to add a link
+/// to a menu at the supplied
+/// position.
+async fn add_link(
+    user: &CleoUser,
+    menu: &NavMenu,
+    parent_id: &Option<String>,
+    label: &str,
+    position: &Option<i32>,
+    pool: &Pool<Postgres>
+) -> MenuItem {
+    add_menu_item(user, &menu.menu_id, parent_id, label, &link("https://example.com"), position, pool).await.unwrap()
+}
+
+/// A shorthand to list the labels
+/// of the items below the supplied
+/// parent item in order.
+async fn labels(
+    menu: &NavMenu,
+    parent_id: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Vec<String> {
+    get_menu_items(&menu.menu_id, pool).await.unwrap()
+        .into_iter()
+        .filter(|item| &item.parent_id == parent_id)
+        .map(|item| item.label)
+        .collect()
+}
+
+/// The function to test creating
+/// menus and adding items to them.
+#[tokio::test]
+pub async fn test_menu_creation(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let (other, _other_token): (_, String) = create_test_user(false, &pool).await;
+    let name: String = unique_name("menu");
+    assert!(create_menu(&user, "   ", &None, &None, &pool).await.is_err());
+    assert!(create_menu(&user, &name, &Some("Not A Slug".to_string()), &None, &pool).await.is_err());
+    assert!(create_menu(&user, &name, &None, &Some(0), &pool).await.is_err());
+    assert!(create_menu(&user, &name, &None, &Some(MAX_MENU_DEPTH + 1), &pool).await.is_err());
+    let menu: NavMenu = create_menu(&user, &name, &None, &None, &pool).await.unwrap();
+    assert_eq!(menu.max_depth, DEFAULT_MENU_DEPTH);
+    assert!(create_menu(&user, &name, &None, &None, &pool).await.is_err());
+    assert_eq!(get_menu_by_slug(&user.user_id, &menu.menu_slug, &pool).await.unwrap().menu_id, menu.menu_id);
+    assert!(get_user_menus(&user.user_id, &pool).await.unwrap().iter().any(|owned| owned.menu_id == menu.menu_id));
+    assert!(add_menu_item(&other, &menu.menu_id, &None, "Home", &link("https://example.com"), &None, &pool).await.is_err());
+    assert!(add_menu_item(&user, &menu.menu_id, &None, "  ", &link("https://example.com"), &None, &pool).await.is_err());
+    assert!(add_menu_item(&user, &menu.menu_id, &None, "Home", &link("javascript:alert(1)"), &None, &pool).await.is_err());
+    assert!(add_menu_item(&user, &menu.menu_id, &None, "Home", &link("https://example.com"), &Some(-1), &pool).await.is_err());
+    let missing: MenuTarget = MenuTarget{ target_type: "post".to_string(), target_id: None, url: None };
+    assert!(add_menu_item(&user, &menu.menu_id, &None, "Post", &missing, &None, &pool).await.is_err());
+    add_link(&user, &menu, &None, "Home", &None, &pool).await;
+    add_link(&user, &menu, &None, "About", &None, &pool).await;
+    add_link(&user, &menu, &None, "Start", &Some(0), &pool).await;
+    add_link(&user, &menu, &None, "Blog", &Some(2), &pool).await;
+    assert_eq!(labels(&menu, &None, &pool).await, vec!["Start", "Home", "Blog", "About"]);
+    let positions: Vec<i32> = get_menu_items(&menu.menu_id, &pool).await.unwrap()
+        .into_iter()
+        .map(|item| item.item_position)
+        .collect();
+    assert_eq!(positions, vec![0, 1, 2, 3]);
+}
+
+/// The function to test nesting
+/// menu items and moving them within
+/// the depth of the menu.
+#[tokio::test]
+pub async fn test_menu_nesting(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let (other, _other_token): (_, String) = create_test_user(false, &pool).await;
+    let menu: NavMenu = create_menu(&user, &unique_name("menu"), &None, &Some(2), &pool).await.unwrap();
+    let other_menu: NavMenu = create_menu(&user, &unique_name("menu"), &None, &None, &pool).await.unwrap();
+    let docs: MenuItem = add_link(&user, &menu, &None, "Docs", &None, &pool).await;
+    let guide: MenuItem = add_link(&user, &menu, &Some(docs.item_id.clone()), "Guide", &None, &pool).await;
+    let news: MenuItem = add_link(&user, &menu, &None, "News", &None, &pool).await;
+    let stray: MenuItem = add_link(&user, &other_menu, &None, "Stray", &None, &pool).await;
+    assert!(add_menu_item(&user, &menu.menu_id, &Some(guide.item_id.clone()), "Deep", &link("https://example.com"), &None, &pool).await.is_err());
+    assert!(add_menu_item(&user, &menu.menu_id, &Some(stray.item_id.clone()), "Mixed", &link("https://example.com"), &None, &pool).await.is_err());
+    assert!(move_menu_item(&other, &news.item_id, &Some(docs.item_id.clone()), &None, &pool).await.is_err());
+    assert!(move_menu_item(&user, &docs.item_id, &Some(guide.item_id.clone()), &None, &pool).await.is_err());
+    assert!(move_menu_item(&user, &docs.item_id, &Some(news.item_id.clone()), &None, &pool).await.is_err());
+    move_menu_item(&user, &news.item_id, &Some(docs.item_id.clone()), &Some(0), &pool).await.unwrap();
+    assert_eq!(labels(&menu, &None, &pool).await, vec!["Docs"]);
+    assert_eq!(labels(&menu, &Some(docs.item_id.clone()), &pool).await, vec!["News", "Guide"]);
+    move_menu_item(&user, &guide.item_id, &None, &None, &pool).await.unwrap();
+    assert_eq!(labels(&menu, &None, &pool).await, vec!["Docs", "Guide"]);
+    assert_eq!(get_menu_item_by_id(&news.item_id, &pool).await.unwrap().item_position, 0);
+    delete_menu_item(&user, &docs.item_id, &pool).await.unwrap();
+    assert!(get_menu_item_by_id(&news.item_id, &pool).await.is_err());
+    assert_eq!(labels(&menu, &None, &pool).await, vec!["Guide"]);
+}
+
+/// The function to test that
+/// reordering menu items only touches
+/// the items whose position changes.
+#[tokio::test]
+pub async fn test_menu_reordering(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let menu: NavMenu = create_menu(&user, &unique_name("menu"), &None, &None, &pool).await.unwrap();
+    let first: MenuItem = add_link(&user, &menu, &None, "First", &None, &pool).await;
+    let second: MenuItem = add_link(&user, &menu, &None, "Second", &None, &pool).await;
+    let third: MenuItem = add_link(&user, &menu, &None, "Third", &None, &pool).await;
+    move_menu_item(&user, &third.item_id, &None, &Some(1), &pool).await.unwrap();
+    assert_eq!(labels(&menu, &None, &pool).await, vec!["First", "Third", "Second"]);
+    let unmoved: MenuItem = get_menu_item_by_id(&first.item_id, &pool).await.unwrap();
+    let shifted: MenuItem = get_menu_item_by_id(&second.item_id, &pool).await.unwrap();
+    assert_eq!(unmoved.updated_at, first.updated_at);
+    assert_eq!(shifted.item_position, 2);
+    assert!(shifted.updated_at > second.updated_at);
+    move_menu_item(&user, &first.item_id, &None, &Some(99), &pool).await.unwrap();
+    assert_eq!(labels(&menu, &None, &pool).await, vec!["Third", "Second", "First"]);
+    assert!(move_menu_item(&user, &first.item_id, &None, &Some(-1), &pool).await.is_err());
+}
+
+/// The function to test the
+/// service functions for navigation
+/// menus.
+#[actix_web::test]
+pub async fn test_menu_services(){
+    let pool = test_pool().await;
+    let (user, token): (_, String) = create_test_user(false, &pool).await;
+    let name: String = unique_name("menu");
+    let app = test_app!(
+        pool,
+        create_menu_service,
+        add_menu_item_service,
+        move_menu_item_service,
+        get_public_menu_service
+    );
+    let resp_menu: Value = post_json!(
+        app,
+        "/menus/create",
+        json!({ "api_token": token, "name": name })
+    );
+    let menu_id: &str = resp_menu["menu_id"].as_str().unwrap();
+    let slug: &str = resp_menu["slug"].as_str().unwrap();
+    let resp_parent: Value = post_json!(
+        app,
+        "/menus/items/add",
+        json!({ "api_token": token, "menu_id": menu_id, "label": "Docs", "target": { "target_type": "url", "url": "https://example.com/docs" } })
+    );
+    let parent_id: &str = resp_parent["items"][0]["item_id"].as_str().unwrap();
+    let resp_child: Value = post_json!(
+        app,
+        "/menus/items/add",
+        json!({ "api_token": token, "menu_id": menu_id, "label": "Guide", "target": { "target_type": "url", "url": "https://example.com/guide" } })
+    );
+    let child_id: &str = resp_child["items"][1]["item_id"].as_str().unwrap();
+    let resp_move: Value = post_json!(
+        app,
+        "/menus/items/move",
+        json!({ "api_token": token, "item_id": child_id, "parent_id": parent_id })
+    );
+    let resp_cycle: Value = post_json!(
+        app,
+        "/menus/items/move",
+        json!({ "api_token": token, "item_id": parent_id, "parent_id": child_id })
+    );
+    let resp_public: Value = get_json!(app, &format!("/public/menus/{}/{}", user.username, slug));
+    assert_eq!(resp_menu["max_depth"], DEFAULT_MENU_DEPTH);
+    assert_eq!(resp_move["is_ok"], true);
+    assert_eq!(resp_cycle["is_ok"], false);
+    assert_eq!(resp_public["items"].as_array().unwrap().len(), 1);
+    assert_eq!(resp_public["items"][0]["label"], "Docs");
+    assert_eq!(resp_public["items"][0]["children"][0]["item_id"], child_id);
+    assert_eq!(resp_public["items"][0]["children"][0]["url"], "https://example.com/guide");
+}
@@ -3,6 +3,11 @@ Cleo by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+/// Exporting the helpers
+/// shared by all tests.
+#[macro_use]
+pub mod helpers;
+
 /// Exporting the tests
 /// for the database
 /// functions.
@@ -12,3 +17,67 @@ pub mod db;
 /// for the API service
 /// functions.
 pub mod api;
+
+/// Exporting the tests
+/// for slugs.
+pub mod slugs;
+
+/// Exporting the tests
+/// for the sanitization
+/// of HTML.
+pub mod html;
+
+/// Exporting the tests
+/// for the validation of
+/// extra content fields.
+pub mod fields;
+
+/// Exporting the tests
+/// for types of content.
+pub mod content_types;
+
+/// Exporting the tests
+/// for the options of
+/// lists.
+pub mod lists;
+
+/// Exporting the tests
+/// for the tree of pages.
+pub mod pages;
+
+/// Exporting the tests
+/// for searching posts.
+pub mod search;
+
+/// Exporting the tests
+/// for navigation menus.
+pub mod menus;
+
+/// Exporting the tests
+/// for feeds and sitemaps.
+pub mod feeds;
+
+/// Exporting the tests
+/// for tags and categories.
+pub mod taxonomy;
+
+/// Exporting the tests
+/// for webhooks.
+pub mod webhooks;
+
+/// Exporting the tests
+/// for changes to content.
+pub mod changes;
+
+/// Exporting the tests
+/// for API tokens.
+pub mod tokens;
+
+/// Exporting the tests
+/// for identifiers and
+/// secrets.
+pub mod ids;
+
+/// Exporting the tests
+/// for resetting passwords.
+pub mod resets;
@@ -0,0 +1,124 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Pool"
+/// structure for explicit
+/// typing.
+use sqlx::Pool;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing all database functions
+/// for the page-related
+/// services.
+use crate::modules::db::pages::*;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the function to
+/// retrieve a post given its ID.
+use crate::modules::db::posts::get_post_by_id;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Creates a page with the
+/// supplied title for the
+/// supplied user.
+async fn create_page(user: &CleoUser, title: &str, pool: &Pool<Postgres>) -> UserPost {
+    create_user_post(
+        user,
+        &test_post("page", "Text.", "markdown", Some(title)),
+        pool
+    ).await.unwrap()
+}
+
+/// The function to test that
+/// pages cannot be moved below
+/// themselves or their descendants.
+#[tokio::test]
+pub async fn test_page_cycles_are_rejected(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let root: UserPost = create_page(&user, "Root", &pool).await;
+    let child: UserPost = create_page(&user, "Child", &pool).await;
+    let grandchild: UserPost = create_page(&user, "Grandchild", &pool).await;
+    move_page(&user, &child.content_id, &Some(root.content_id.clone()), &None, &pool).await.unwrap();
+    move_page(&user, &grandchild.content_id, &Some(child.content_id.clone()), &None, &pool).await.unwrap();
+    assert!(move_page(&user, &root.content_id, &Some(root.content_id.clone()), &None, &pool).await.is_err());
+    assert!(move_page(&user, &root.content_id, &Some(child.content_id.clone()), &None, &pool).await.is_err());
+    assert!(move_page(&user, &root.content_id, &Some(grandchild.content_id.clone()), &None, &pool).await.is_err());
+    assert!(move_page(&user, &grandchild.content_id, &None, &None, &pool).await.is_ok());
+    let moved: UserPost = get_post_by_id(&grandchild.content_id, &pool).await.unwrap();
+    assert_eq!(moved.parent_id, None);
+}
+
+/// The function to test that
+/// two pages moved below each other
+/// at the same time cannot form a
+/// loop.
+#[tokio::test]
+pub async fn test_concurrent_page_moves(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    for _round in 0..5 {
+        let first: UserPost = create_page(&user, "First", &pool).await;
+        let second: UserPost = create_page(&user, "Second", &pool).await;
+        let below_second: Option<String> = Some(second.content_id.clone());
+        let below_first: Option<String> = Some(first.content_id.clone());
+        let (first_moved, second_moved) = tokio::join!(
+            move_page(&user, &first.content_id, &below_second, &None, &pool),
+            move_page(&user, &second.content_id, &below_first, &None, &pool)
+        );
+        assert!(first_moved.is_ok() != second_moved.is_ok());
+        let first_parent: Option<String> = get_post_by_id(&first.content_id, &pool).await.unwrap().parent_id;
+        let second_parent: Option<String> = get_post_by_id(&second.content_id, &pool).await.unwrap().parent_id;
+        assert!(first_parent.is_none() || second_parent.is_none());
+    }
+}
+
+/// The function to test that
+/// reordering pages saves their
+/// positions and marks the moved
+/// pages as updated.
+#[tokio::test]
+pub async fn test_reorder_pages(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let first: UserPost = create_page(&user, "First", &pool).await;
+    let second: UserPost = create_page(&user, "Second", &pool).await;
+    assert_eq!(first.page_position, 0);
+    assert_eq!(second.page_position, 1);
+    let incomplete: Vec<String> = vec![second.content_id.clone()];
+    assert!(reorder_pages(&user, &None, &incomplete, &pool).await.is_err());
+    let order: Vec<String> = vec![second.content_id.clone(), first.content_id.clone()];
+    reorder_pages(&user, &None, &order, &pool).await.unwrap();
+    let moved: UserPost = get_post_by_id(&first.content_id, &pool).await.unwrap();
+    assert_eq!(moved.page_position, 1);
+    assert!(moved.updated_at > first.updated_at);
+}
@@ -0,0 +1,131 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "verify"
+/// function to check new
+/// passwords.
+use bcrypt::verify;
+
+/// Importing the "json"
+/// macro to build payloads.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the "TimeDelta"
+/// structure to move the expiry
+/// of tokens into the past.
+use chrono::TimeDelta;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing all service functions
+/// for the email-related
+/// services.
+use crate::modules::services::email::*;
+
+/// Importing the function to save
+/// the instance information.
+use super::helpers::ensure_instance_info;
+
+/// Importing the function to
+/// authenticate an API token.
+use crate::modules::db::tokens::authenticate_token;
+
+/// Importing the function to retrieve
+/// a user given their username.
+use crate::modules::db::users::get_user_by_username;
+
+/// Importing the function to create
+/// a token for resetting a password.
+use crate::modules::db::email::create_password_reset;
+
+/// Importing the function to set a new
+/// password with a password reset token.
+use crate::modules::db::email::redeem_password_reset;
+
+/// The function to test that
+/// a password reset token sets a
+/// new password, revokes all API
+/// tokens, and only works once.
+#[tokio::test]
+pub async fn test_password_reset_single_use(){
+    let pool = test_pool().await;
+    let (user, api_token): (CleoUser, String) = create_test_user(false, &pool).await;
+    let token: String = create_password_reset(&user, &pool).await.unwrap();
+    let new_password: String = "87654321".to_string();
+    assert_eq!(redeem_password_reset(&token, &new_password, &pool).await.unwrap(), 1);
+    let changed: CleoUser = get_user_by_username(&user.username, &pool).await.unwrap();
+    assert!(verify(&new_password, &changed.pwd).unwrap());
+    assert!(authenticate_token(&api_token, &pool).await.is_err());
+    assert!(redeem_password_reset(&token, &"11223344".to_string(), &pool).await.is_err());
+}
+
+/// The function to test that
+/// password reset tokens stop
+/// working once they expire or
+/// a newer one is requested.
+#[tokio::test]
+pub async fn test_password_reset_expiry(){
+    let pool = test_pool().await;
+    let (user, _api_token): (CleoUser, String) = create_test_user(false, &pool).await;
+    let replaced: String = create_password_reset(&user, &pool).await.unwrap();
+    let token: String = create_password_reset(&user, &pool).await.unwrap();
+    let new_password: String = "87654321".to_string();
+    assert!(redeem_password_reset(&replaced, &new_password, &pool).await.is_err());
+    sqlx::query("UPDATE password_resets SET expires_at = $1 WHERE user_id = $2")
+        .bind(Utc::now() - TimeDelta::seconds(1))
+        .bind(&user.user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(redeem_password_reset(&token, &new_password, &pool).await.is_err());
+    assert!(redeem_password_reset(&token, &new_password, &pool).await.is_err());
+}
+
+/// The function to test that
+/// requesting a password reset
+/// answers the same for known and
+/// unknown users.
+#[actix_web::test]
+pub async fn test_password_reset_request_service(){
+    let pool = test_pool().await;
+    ensure_instance_info(&pool).await;
+    let (user, _api_token): (CleoUser, String) = create_test_user(false, &pool).await;
+    let app = test_app!(
+        pool,
+        request_password_reset_service
+    );
+    let resp_known: Value = post_json!(
+        app,
+        "/user/password/forgot",
+        json!({ "username": user.username })
+    );
+    let resp_unknown: Value = post_json!(
+        app,
+        "/user/password/forgot",
+        json!({ "username": "nobody-has-this-name" })
+    );
+    assert_eq!(resp_known, json!({ "is_ok": true }));
+    assert_eq!(resp_known, resp_unknown);
+}
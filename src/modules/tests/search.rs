@@ -0,0 +1,204 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function
+/// to make names no other
+/// test uses.
+use super::helpers::unique_name;
+
+/// Importing all database functions
+/// for searching posts.
+use crate::modules::db::search::*;
+
+/// Importing the structure
+/// for a match of a search.
+use crate::modules::units::SearchHit;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure
+/// holding the filters for
+/// searches.
+use crate::modules::units::SearchFilters;
+
+/// Importing the function to
+/// check options for listing
+/// records.
+use crate::modules::utils::resolve_list_options;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Importing the function to
+/// change the status of a post.
+use crate::modules::db::posts::update_post_status;
+
+/// Importing the function to add
+/// an extra field to a post.
+use crate::modules::db::ecf::create_extra_field_for_post;
+
+/// Importing the service function
+/// for searching posts.
+use crate::modules::services::search::search_posts_service;
+
+/// Creates a post of the supplied
+/// type for the supplied user and
+/// publishes it if asked to.
+async fn create_post(
+    user: &CleoUser,
+    content_type: &str,
+    title: &str,
+    content_text: &str,
+    published: bool,
+    pool: &Pool<Postgres>
+) -> UserPost {
+    let post: UserPost = create_user_post(
+        user,
+        &test_post(content_type, content_text, "markdown", Some(title)),
+        pool
+    ).await.unwrap();
+    if published {
+        update_post_status(user, &post.content_id, &"published".to_string(), &Some(Utc::now()), pool).await.unwrap();
+    }
+    post
+}
+
+/// A shorthand to resolve
+/// the options of a search.
+fn options(sort_by: &str, sort_order: &str) -> ListOptions {
+    resolve_list_options(
+        &None,
+        &None,
+        &Some(sort_by.to_string()),
+        &Some(sort_order.to_string()),
+        &SEARCH_SORT_FIELDS
+    ).unwrap()
+}
+
+/// A shorthand to run a search
+/// and return the IDs of the
+/// posts found and the total.
+async fn search_ids(
+    search_query: &str,
+    filters: &SearchFilters,
+    options: &ListOptions,
+    pool: &Pool<Postgres>
+) -> (Vec<String>, i64) {
+    let (hits, posts, total): (Vec<SearchHit>, Vec<UserPost>, i64) = search_posts(
+        search_query,
+        filters,
+        options,
+        pool
+    ).await.unwrap();
+    assert_eq!(hits.len(), posts.len());
+    (posts.into_iter().map(|post| post.content_id).collect(), total)
+}
+
+/// The function to test that
+/// searches find published posts
+/// ranked by where the query matched
+/// and never drafts or archived posts.
+#[tokio::test]
+pub async fn test_search_posts(){
+    let pool = test_pool().await;
+    let (author, _token): (_, String) = create_test_user(false, &pool).await;
+    let (other, _other_token): (_, String) = create_test_user(false, &pool).await;
+    let word: String = unique_name("term");
+    let titled: UserPost = create_post(&author, "post", &format!("About {}", word), "Some text.", true, &pool).await;
+    let mentioned: UserPost = create_post(&author, "post", "Notes", &format!("It mentions {} once.", word), true, &pool).await;
+    let draft: UserPost = create_post(&author, "post", &word, &word, false, &pool).await;
+    let archived: UserPost = create_post(&author, "post", &word, &word, true, &pool).await;
+    update_post_status(&author, &archived.content_id, &"archived".to_string(), &None, &pool).await.unwrap();
+    let page: UserPost = create_post(&other, "page", "Elsewhere", &format!("Also {}.", word), true, &pool).await;
+    let _private: UserPost = create_post(&other, "post", &word, &word, false, &pool).await;
+    let everything: SearchFilters = SearchFilters{ author: None, content_type: None };
+    let (ranked, total): (Vec<String>, i64) = search_ids(&word, &everything, &options("rank", "desc"), &pool).await;
+    assert_eq!(total, 3);
+    assert_eq!(ranked.len(), 3);
+    assert_eq!(ranked[0], titled.content_id);
+    assert!(ranked.contains(&mentioned.content_id));
+    assert!(ranked.contains(&page.content_id));
+    assert!(!ranked.contains(&draft.content_id));
+    assert!(!ranked.contains(&archived.content_id));
+    let (oldest, _total): (Vec<String>, i64) = search_ids(&word, &everything, &options("created_at", "asc"), &pool).await;
+    assert_eq!(oldest, vec![titled.content_id.clone(), mentioned.content_id.clone(), page.content_id.clone()]);
+    let by_author: SearchFilters = SearchFilters{ author: Some(author.username.clone()), content_type: None };
+    let (authored, authored_total): (Vec<String>, i64) = search_ids(&word, &by_author, &options("rank", "desc"), &pool).await;
+    assert_eq!(authored_total, 2);
+    assert!(!authored.contains(&page.content_id));
+    let pages: SearchFilters = SearchFilters{ author: None, content_type: Some("Page".to_string()) };
+    assert_eq!(search_ids(&word, &pages, &options("rank", "desc"), &pool).await.0, vec![page.content_id.clone()]);
+    let field_word: String = unique_name("field");
+    create_extra_field_for_post(&author, &mentioned.content_id, &"mood".to_string(), &field_word, &None, &pool).await.unwrap();
+    assert_eq!(search_ids(&field_word, &everything, &options("rank", "desc"), &pool).await.0, vec![mentioned.content_id.clone()]);
+    assert!(search_posts("   ", &everything, &options("rank", "desc"), &pool).await.is_err());
+    let too_long: String = "a".repeat(MAX_SEARCH_QUERY_LENGTH + 1);
+    assert!(search_posts(&too_long, &everything, &options("rank", "desc"), &pool).await.is_err());
+}
+
+/// The function to test the
+/// service function for searching
+/// posts.
+#[actix_web::test]
+pub async fn test_search_service(){
+    let pool = test_pool().await;
+    let (author, _token): (_, String) = create_test_user(false, &pool).await;
+    let word: String = unique_name("term");
+    let post: UserPost = create_post(&author, "post", "Notes", &format!("It mentions {} once.", word), true, &pool).await;
+    let _draft: UserPost = create_post(&author, "post", &word, &word, false, &pool).await;
+    let app = test_app!(pool, search_posts_service);
+    let resp_search: Value = get_json!(app, &format!("/public/search?q={}", word));
+    let resp_empty: Value = get_json!(app, "/public/search?q=");
+    let results: &Vec<Value> = resp_search["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["post"]["content_id"], post.content_id.as_str());
+    assert!(results[0]["snippet"].as_str().unwrap().contains(&format!("<mark>{}</mark>", word)));
+    assert_eq!(resp_search["pagination"]["total_count"], 1);
+    assert!(resp_empty.get("error").is_some());
+}
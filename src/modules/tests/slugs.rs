@@ -0,0 +1,117 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function
+/// to turn text into a slug.
+use crate::modules::utils::slugify;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the function
+/// to check slugs.
+use crate::modules::db::slugs::validate_slug;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Importing the function to
+/// generate a slug that is
+/// not taken.
+use crate::modules::db::slugs::generate_unique_slug;
+
+/// The function to test
+/// turning text into slugs.
+#[test]
+pub fn test_slugify(){
+    assert_eq!(slugify(&"Hello World!".to_string()), "hello-world");
+    assert_eq!(slugify(&"  --Already--slugged--  ".to_string()), "already-slugged");
+    assert_eq!(slugify(&"x < y > z".to_string()), "x-y-z");
+    assert_eq!(slugify(&"Ünïcödé".to_string()), "n-c-d");
+    assert_eq!(slugify(&"!!!".to_string()), "");
+    assert_eq!(slugify(&"a".repeat(100)).len(), 64);
+    assert!(!slugify(&format!("{} b", "a".repeat(63))).ends_with('-'));
+}
+
+/// The function to test
+/// checking slugs.
+#[test]
+pub fn test_validate_slug(){
+    assert!(validate_slug(&"hello-world".to_string()).is_ok());
+    assert!(validate_slug(&"2024-recap".to_string()).is_ok());
+    assert!(validate_slug(&"".to_string()).is_err());
+    assert!(validate_slug(&"Hello".to_string()).is_err());
+    assert!(validate_slug(&"hello--world".to_string()).is_err());
+    assert!(validate_slug(&"-hello".to_string()).is_err());
+    assert!(validate_slug(&"hello world".to_string()).is_err());
+}
+
+/// The function to test that
+/// generated slugs are unique,
+/// fall back when no slug can be
+/// made, and ignore the markup of
+/// untitled posts.
+#[tokio::test]
+pub async fn test_generate_unique_slug(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let first: UserPost = create_user_post(
+        &user,
+        &test_post("post", "Some text.", "markdown", Some("Hello World")),
+        &pool
+    ).await.unwrap();
+    assert_eq!(first.slug, "hello-world");
+    let second: String = generate_unique_slug(
+        &user.user_id,
+        &"post".to_string(),
+        &"Hello, world.".to_string(),
+        &"fallback".to_string(),
+        &pool
+    ).await.unwrap();
+    assert_eq!(second, "hello-world-2");
+    let other_type: String = generate_unique_slug(
+        &user.user_id,
+        &"page".to_string(),
+        &"Hello World".to_string(),
+        &"fallback".to_string(),
+        &pool
+    ).await.unwrap();
+    assert_eq!(other_type, "hello-world");
+    let fallback: String = generate_unique_slug(
+        &user.user_id,
+        &"post".to_string(),
+        &"???".to_string(),
+        &"Fallback ID".to_string(),
+        &pool
+    ).await.unwrap();
+    assert_eq!(fallback, "fallback-id");
+    let markdown: UserPost = create_user_post(
+        &user,
+        &test_post("post", "**Bold** [move](https://example.com/link) & more", "markdown", None),
+        &pool
+    ).await.unwrap();
+    assert_eq!(markdown.slug, "bold-move-more");
+    let html: UserPost = create_user_post(
+        &user,
+        &test_post("post", "<p>Hello <em>there</em></p>", "html", None),
+        &pool
+    ).await.unwrap();
+    assert_eq!(html.slug, "hello-there");
+}
@@ -0,0 +1,270 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "json"
+/// macro to build payloads.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function to
+/// build the data for a new post.
+use super::helpers::test_post;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the function
+/// to make names no other
+/// test uses.
+use super::helpers::unique_name;
+
+/// Importing all database functions
+/// for tags and categories.
+use crate::modules::db::taxonomy::*;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the model for
+/// users for explicit typing.
+use crate::modules::models::CleoUser;
+
+/// Importing the model for
+/// posts for explicit typing.
+use crate::modules::models::UserPost;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the structure for
+/// a tag or category assigned
+/// to a post.
+use crate::modules::units::AssignedTerm;
+
+/// Importing the model for
+/// tags and categories for
+/// explicit typing.
+use crate::modules::models::TaxonomyTerm;
+
+/// Importing all service functions
+/// for tags and categories.
+use crate::modules::services::taxonomy::*;
+
+/// Importing the function to
+/// check options for listing
+/// records.
+use crate::modules::utils::resolve_list_options;
+
+/// Importing the function to
+/// create a post.
+use crate::modules::db::posts::create_user_post;
+
+/// Importing the fields posts
+/// can be sorted by.
+use crate::modules::db::general::POST_SORT_FIELDS;
+
+/// Importing the function to
+/// change the status of a post.
+use crate::modules::db::posts::update_post_status;
+
+/// Importing the function
+/// to check that a user is an
+/// administrator.
+use crate::modules::db::content_types::verify_admin;
+
+/// Creates a published post
+/// for the supplied user.
+async fn create_post(
+    user: &CleoUser,
+    title: &str,
+    pool: &Pool<Postgres>
+) -> UserPost {
+    let post: UserPost = create_user_post(
+        user,
+        &test_post("post", "Some text.", "markdown", Some(title)),
+        pool
+    ).await.unwrap();
+    update_post_status(user, &post.content_id, &"published".to_string(), &Some(Utc::now()), pool).await.unwrap();
+    post
+}
+
+/// A shorthand to list the IDs
+/// of the published posts that have
+/// a term, oldest first.
+async fn term_post_ids(
+    term_id: &String,
+    pool: &Pool<Postgres>
+) -> (Vec<String>, i64) {
+    let options: ListOptions = resolve_list_options(
+        &None,
+        &None,
+        &Some("created_at".to_string()),
+        &Some("asc".to_string()),
+        &POST_SORT_FIELDS
+    ).unwrap();
+    let (posts, total): (Vec<UserPost>, i64) = get_public_posts_by_term(term_id, &options, pool).await.unwrap();
+    (posts.into_iter().map(|post| post.content_id).collect(), total)
+}
+
+/// The function to test that
+/// only administrators create and
+/// change tags and categories.
+#[tokio::test]
+pub async fn test_term_definitions(){
+    let pool = test_pool().await;
+    let (admin, _token): (_, String) = create_test_user(true, &pool).await;
+    let (user, _user_token): (_, String) = create_test_user(false, &pool).await;
+    let name: String = unique_name("term");
+    let tag: String = "tag".to_string();
+    let category: String = "category".to_string();
+    assert!(verify_admin(&admin).is_ok());
+    assert!(verify_admin(&user).is_err());
+    assert!(create_term(&user, &tag, &name, &None, &String::new(), &None, &pool).await.is_err());
+    assert!(create_term(&admin, &"genre".to_string(), &name, &None, &String::new(), &None, &pool).await.is_err());
+    assert!(create_term(&admin, &tag, "   ", &None, &String::new(), &None, &pool).await.is_err());
+    assert!(create_term(&admin, &tag, &name, &Some("Not A Slug".to_string()), &String::new(), &None, &pool).await.is_err());
+    let created: TaxonomyTerm = create_term(&admin, &tag, &name, &None, &"Tagged.".to_string(), &None, &pool).await.unwrap();
+    assert_eq!(created.taxonomy, "tag");
+    assert_eq!(created.term_name, name);
+    assert_eq!(get_term_by_slug(&tag, &created.term_slug, &pool).await.unwrap().term_id, created.term_id);
+    assert!(create_term(&admin, &tag, &name, &None, &String::new(), &None, &pool).await.is_err());
+    assert!(get_terms(&tag, &pool).await.unwrap().iter().any(|term| term.term_id == created.term_id));
+    assert!(create_term(&admin, &tag, &unique_name("child"), &None, &String::new(), &Some(created.term_id.clone()), &pool).await.is_err());
+    let parent: TaxonomyTerm = create_term(&admin, &category, &unique_name("parent"), &None, &String::new(), &None, &pool).await.unwrap();
+    let child: TaxonomyTerm = create_term(
+        &admin,
+        &category,
+        &unique_name("child"),
+        &None,
+        &String::new(),
+        &Some(parent.term_id.clone()),
+        &pool
+    ).await.unwrap();
+    assert_eq!(child.parent_id, Some(parent.term_id.clone()));
+    assert!(move_term(&user, &parent.term_id, &Some(child.term_id.clone()), &pool).await.is_err());
+    assert!(move_term(&admin, &parent.term_id, &Some(child.term_id.clone()), &pool).await.is_err());
+    let renamed: String = unique_name("renamed");
+    assert!(edit_term(&user, &created.term_id, &Some(renamed.clone()), &None, &None, &pool).await.is_err());
+    edit_term(&admin, &created.term_id, &Some(renamed.clone()), &None, &None, &pool).await.unwrap();
+    assert_eq!(get_term_by_id(&created.term_id, &pool).await.unwrap().term_name, renamed);
+    assert!(delete_term(&user, &created.term_id, &pool).await.is_err());
+    delete_term(&admin, &created.term_id, &pool).await.unwrap();
+    assert!(get_term_by_id(&created.term_id, &pool).await.is_err());
+}
+
+/// The function to test assigning
+/// tags and categories to posts and
+/// listing the posts that have them.
+#[tokio::test]
+pub async fn test_term_assignments(){
+    let pool = test_pool().await;
+    let (admin, _token): (_, String) = create_test_user(true, &pool).await;
+    let (author, _author_token): (_, String) = create_test_user(false, &pool).await;
+    let (other, _other_token): (_, String) = create_test_user(false, &pool).await;
+    let category: String = "category".to_string();
+    let parent: TaxonomyTerm = create_term(&admin, &category, &unique_name("parent"), &None, &String::new(), &None, &pool).await.unwrap();
+    let child: TaxonomyTerm = create_term(
+        &admin,
+        &category,
+        &unique_name("child"),
+        &None,
+        &String::new(),
+        &Some(parent.term_id.clone()),
+        &pool
+    ).await.unwrap();
+    let first: UserPost = create_post(&author, "First", &pool).await;
+    let second: UserPost = create_post(&author, "Second", &pool).await;
+    let draft: UserPost = create_user_post(&author, &test_post("post", "Draft.", "markdown", None), &pool).await.unwrap();
+    assert!(assign_term(&other, &first.content_id, &parent.term_id, &pool).await.is_err());
+    assert!(assign_term(&author, &first.content_id, &"missing".to_string(), &pool).await.is_err());
+    assign_term(&author, &first.content_id, &parent.term_id, &pool).await.unwrap();
+    assign_term(&author, &first.content_id, &parent.term_id, &pool).await.unwrap();
+    assign_term(&author, &second.content_id, &child.term_id, &pool).await.unwrap();
+    assign_term(&author, &draft.content_id, &child.term_id, &pool).await.unwrap();
+    let assigned: Vec<AssignedTerm> = get_terms_for_posts(&vec![first.content_id.clone(), second.content_id.clone()], &pool).await.unwrap();
+    assert_eq!(assigned.len(), 2);
+    assert!(assigned.iter().any(|term| term.content_id == first.content_id && term.term_id == parent.term_id));
+    assert!(assigned.iter().any(|term| term.content_id == second.content_id && term.term_id == child.term_id));
+    let (in_parent, parent_total): (Vec<String>, i64) = term_post_ids(&parent.term_id, &pool).await;
+    assert_eq!(parent_total, 2);
+    assert_eq!(in_parent, vec![first.content_id.clone(), second.content_id.clone()]);
+    let (in_child, child_total): (Vec<String>, i64) = term_post_ids(&child.term_id, &pool).await;
+    assert_eq!(child_total, 1);
+    assert_eq!(in_child, vec![second.content_id.clone()]);
+    assert!(unassign_term(&other, &first.content_id, &parent.term_id, &pool).await.is_err());
+    unassign_term(&author, &first.content_id, &parent.term_id, &pool).await.unwrap();
+    assert_eq!(term_post_ids(&parent.term_id, &pool).await.0, vec![second.content_id.clone()]);
+}
+
+/// The function to test the
+/// service functions for tags
+/// and categories.
+#[actix_web::test]
+pub async fn test_taxonomy_services(){
+    let pool = test_pool().await;
+    let (_admin, token): (_, String) = create_test_user(true, &pool).await;
+    let (author, author_token): (_, String) = create_test_user(false, &pool).await;
+    let post: UserPost = create_post(&author, "Tagged", &pool).await;
+    let name: String = unique_name("term");
+    let app = test_app!(
+        pool,
+        create_term_service,
+        assign_term_service,
+        get_terms_service,
+        get_term_posts_service
+    );
+    let resp_forbidden: Value = post_json!(
+        app,
+        "/taxonomy/create",
+        json!({ "api_token": author_token, "taxonomy": "tag", "name": name })
+    );
+    let resp_create: Value = post_json!(
+        app,
+        "/taxonomy/create",
+        json!({ "api_token": token, "taxonomy": "Tag", "name": name })
+    );
+    let term_id: &str = resp_create["term_id"].as_str().unwrap();
+    let slug: &str = resp_create["slug"].as_str().unwrap();
+    let resp_assign: Value = post_json!(
+        app,
+        "/taxonomy/assign",
+        json!({ "api_token": author_token, "content_id": post.content_id, "term_id": term_id })
+    );
+    let resp_terms: Value = get_json!(app, "/public/terms/tag");
+    let resp_posts: Value = get_json!(app, &format!("/public/terms/tag/{}", slug));
+    let resp_invalid: Value = get_json!(app, "/public/terms/genre");
+    assert!(resp_forbidden.get("error").is_some());
+    assert_eq!(resp_create["name"], name.as_str());
+    assert_eq!(resp_assign["is_ok"], true);
+    assert!(resp_terms["terms"].as_array().unwrap().iter().any(|term| term["term_id"] == term_id));
+    assert_eq!(resp_posts["term"]["term_id"], term_id);
+    assert_eq!(resp_posts["posts"][0]["content_id"], post.content_id.as_str());
+    assert_eq!(resp_posts["posts"][0]["tags"][0]["term_id"], term_id);
+    assert_eq!(resp_posts["pagination"]["total_count"], 1);
+    assert!(resp_invalid.get("error").is_some());
+}
@@ -0,0 +1,258 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing.
+use chrono::DateTime;
+
+/// Importing the "json"
+/// macro to build payloads.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum to read responses.
+use serde_json::Value;
+
+/// Importing the "TimeDelta"
+/// structure to compute the
+/// expiry of tokens.
+use chrono::TimeDelta;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing the password
+/// every test user has.
+use super::helpers::TEST_PASSWORD;
+
+/// Importing all database functions
+/// for the token-related
+/// services.
+use crate::modules::db::tokens::*;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing all service functions
+/// for the post-related
+/// services.
+use crate::modules::services::posts::*;
+
+/// Importing all service functions
+/// for API tokens.
+use crate::modules::services::tokens::*;
+
+/// Importing all service functions
+/// for general services.
+use crate::modules::services::general::*;
+
+/// Importing the model for
+/// API tokens for explicit typing.
+use crate::modules::models::UserAPIToken;
+
+/// A shorthand to turn a
+/// list of scopes into the
+/// form tokens take them in.
+fn scopes(list: &[&str]) -> Option<Vec<String>> {
+    Some(list.iter().map(|scope| scope.to_string()).collect())
+}
+
+/// The function to test the
+/// validation of the scopes
+/// of tokens.
+#[test]
+pub fn test_token_scope_validation(){
+    let user_defaults: Vec<String> = validate_token_scopes(&None, false).unwrap();
+    let admin_defaults: Vec<String> = validate_token_scopes(&None, true).unwrap();
+    assert_eq!(user_defaults.len(), TOKEN_SCOPES.len() - 1);
+    assert!(!user_defaults.contains(&SCOPE_ADMIN.to_string()));
+    assert_eq!(admin_defaults.len(), TOKEN_SCOPES.len());
+    assert_eq!(
+        validate_token_scopes(&scopes(&[SCOPE_FILES, SCOPE_FILES]), false).unwrap(),
+        vec![SCOPE_FILES.to_string()]
+    );
+    assert!(validate_token_scopes(&scopes(&[]), false).is_err());
+    assert!(validate_token_scopes(&scopes(&["everything"]), true).is_err());
+    assert!(validate_token_scopes(&scopes(&[SCOPE_ADMIN]), false).is_err());
+    assert!(validate_token_scopes(&scopes(&[SCOPE_ADMIN]), true).is_ok());
+}
+
+/// The function to test that
+/// tokens are stored as hashes
+/// and stop working once they
+/// expire.
+#[tokio::test]
+pub async fn test_token_expiry(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let password: String = TEST_PASSWORD.to_string();
+    let expired: Option<DateTime<Utc>> = Some(Utc::now() - TimeDelta::hours(1));
+    assert!(create_api_token_for_user(&user.username, &password, &None, &None, &expired, &pool).await.is_err());
+    let expires_at: Option<DateTime<Utc>> = Some(Utc::now() + TimeDelta::hours(1));
+    let (token_obj, token): (UserAPIToken, String) = create_api_token_for_user(
+        &user.username,
+        &password,
+        &Some("Expiring".to_string()),
+        &scopes(&[SCOPE_CONTENT_READ]),
+        &expires_at,
+        &pool
+    ).await.unwrap();
+    assert_ne!(token_obj.token_hash, token);
+    let (owner, authenticated): (_, UserAPIToken) = authenticate_token(&token, &pool).await.unwrap();
+    assert_eq!(owner.user_id, user.user_id);
+    assert_eq!(authenticated.scopes, vec![SCOPE_CONTENT_READ.to_string()]);
+    sqlx::query("UPDATE user_api_tokens SET expires_at = $1 WHERE token_id = $2")
+        .bind(Utc::now() - TimeDelta::seconds(1))
+        .bind(&token_obj.token_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(authenticate_token(&token, &pool).await.is_err());
+    assert!(authenticate_token(&"not-a-token".to_string(), &pool).await.is_err());
+}
+
+/// The function to test that
+/// services refuse tokens that
+/// lack the scope they need.
+#[actix_web::test]
+pub async fn test_token_scope_services(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let (_token_obj, token): (UserAPIToken, String) = create_api_token_for_user(
+        &user.username,
+        &TEST_PASSWORD.to_string(),
+        &None,
+        &scopes(&[SCOPE_CONTENT_READ]),
+        &None,
+        &pool
+    ).await.unwrap();
+    let app = test_app!(
+        pool,
+        create_user_post_service,
+        get_user_posts_service
+    );
+    let resp_read: Value = get_json!(app, "/posts/all", token);
+    let resp_write: Value = post_json!(
+        app,
+        "/posts/create",
+        json!({ "api_token": token, "content_type": "post", "content_text": "Hello world!", "title": "Hello" })
+    );
+    assert!(resp_read["posts"].is_array());
+    assert!(resp_write["error"].as_str().unwrap().contains(SCOPE_CONTENT_WRITE));
+}
+
+/// A shorthand to create an API
+/// token with a label for the
+/// supplied user.
+async fn labelled_token(
+    username: &String,
+    label: &str,
+    pool: &Pool<Postgres>
+) -> (UserAPIToken, String) {
+    create_api_token_for_user(
+        username,
+        &TEST_PASSWORD.to_string(),
+        &Some(label.to_string()),
+        &None,
+        &None,
+        pool
+    ).await.unwrap()
+}
+
+/// The function to test listing
+/// and revoking the API tokens of
+/// a user.
+#[tokio::test]
+pub async fn test_token_revocation(){
+    let pool = test_pool().await;
+    let (user, _token): (_, String) = create_test_user(false, &pool).await;
+    let (other, _other_token): (_, String) = create_test_user(false, &pool).await;
+    let (first, first_token): (UserAPIToken, String) = labelled_token(&user.username, "First", &pool).await;
+    let (second, second_token): (UserAPIToken, String) = labelled_token(&user.username, "Second", &pool).await;
+    let listed: Vec<UserAPIToken> = get_user_tokens(&user.user_id, &pool).await.unwrap();
+    assert_eq!(listed.len(), 3);
+    assert_eq!(listed[0].token_id, second.token_id);
+    assert_eq!(listed[0].label, Some("Second".to_string()));
+    assert!(revoke_token(&other, &first.token_id, &pool).await.is_err());
+    revoke_token(&user, &first.token_id, &pool).await.unwrap();
+    assert!(revoke_token(&user, &first.token_id, &pool).await.is_err());
+    assert!(authenticate_token(&first_token, &pool).await.is_err());
+    assert_eq!(get_user_tokens(&user.user_id, &pool).await.unwrap().len(), 2);
+    assert_eq!(revoke_other_tokens(&user, &second.token_id, &pool).await.unwrap(), 1);
+    let remaining: Vec<UserAPIToken> = get_user_tokens(&user.user_id, &pool).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].token_id, second.token_id);
+    assert!(authenticate_token(&second_token, &pool).await.is_ok());
+    assert_eq!(get_user_tokens(&other.user_id, &pool).await.unwrap().len(), 1);
+}
+
+/// The function to test the
+/// service functions for listing
+/// and revoking API tokens and that
+/// revoked tokens are rejected.
+#[actix_web::test]
+pub async fn test_token_revocation_services(){
+    let pool = test_pool().await;
+    let (user, token): (_, String) = create_test_user(false, &pool).await;
+    let (revoked, revoked_token): (UserAPIToken, String) = labelled_token(&user.username, "Revoked", &pool).await;
+    let (_other, other_token): (UserAPIToken, String) = labelled_token(&user.username, "Other", &pool).await;
+    let app = test_app!(
+        pool,
+        get_api_tokens_service,
+        revoke_api_token_service,
+        revoke_other_api_tokens_service,
+        get_user_posts_service
+    );
+    let resp_tokens: Value = get_json!(app, "/token/all", token);
+    let tokens: &Vec<Value> = resp_tokens["tokens"].as_array().unwrap();
+    assert_eq!(tokens.len(), 3);
+    assert!(tokens.iter().all(|listed| listed.get("token").is_none() && listed.get("token_hash").is_none()));
+    assert_eq!(tokens.iter().filter(|listed| listed["is_current"] == true).count(), 1);
+    assert!(tokens.iter().any(|listed| listed["token_id"] == revoked.token_id.as_str() && listed["is_current"] == false));
+    assert!(get_json!(app, "/posts/all", revoked_token)["posts"].is_array());
+    let resp_revoke: Value = post_json!(
+        app,
+        "/token/revoke",
+        json!({ "api_token": token, "token_id": revoked.token_id })
+    );
+    let resp_again: Value = post_json!(
+        app,
+        "/token/revoke",
+        json!({ "api_token": token, "token_id": revoked.token_id })
+    );
+    assert_eq!(resp_revoke["is_ok"], true);
+    assert_eq!(resp_again["is_ok"], false);
+    assert!(get_json!(app, "/posts/all", revoked_token).get("error").is_some());
+    assert!(get_json!(app, "/token/all", revoked_token).get("error").is_some());
+    let resp_others: Value = post_json!(
+        app,
+        "/token/revoke/others",
+        json!({ "api_token": token })
+    );
+    assert_eq!(resp_others["is_ok"], true);
+    assert!(get_json!(app, "/posts/all", other_token).get("error").is_some());
+    let resp_remaining: Value = get_json!(app, "/token/all", token);
+    assert_eq!(resp_remaining["tokens"].as_array().unwrap().len(), 1);
+    assert_eq!(resp_remaining["tokens"][0]["is_current"], true);
+}
@@ -0,0 +1,311 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the
+/// "Pool" structure
+/// for explicit typing.
+use sqlx::Pool;
+
+/// Importing the "Utc"
+/// structure to read the
+/// current time.
+use chrono::Utc;
+
+/// Importing the "Arc"
+/// structure to share the
+/// state of the receiver.
+use std::sync::Arc;
+
+/// Importing the "Mutex"
+/// structure to record the
+/// requests of the receiver.
+use std::sync::Mutex;
+
+/// Importing the "json"
+/// macro to build the data
+/// of events.
+use serde_json::json;
+
+/// Importing the "Value"
+/// enum for the data of
+/// events.
+use serde_json::Value;
+
+/// Importing the "TimeDelta"
+/// structure to compare waits.
+use chrono::TimeDelta;
+
+/// Importing the "TcpListener"
+/// structure to run a local
+/// receiver.
+use tokio::net::TcpListener;
+
+/// Importing the "AsyncReadExt"
+/// trait to read requests.
+use tokio::io::AsyncReadExt;
+
+/// Importing the "Postgres"
+/// structure for explicit
+/// typing.
+use sqlx::postgres::Postgres;
+
+/// Importing the "AsyncWriteExt"
+/// trait to write responses.
+use tokio::io::AsyncWriteExt;
+
+/// Importing the function
+/// to connect to the test
+/// database.
+use super::helpers::test_pool;
+
+/// Importing all functions
+/// for delivering webhooks.
+use crate::modules::webhooks::*;
+
+/// Importing the "Ordering"
+/// enum to read and write the
+/// answer of the receiver.
+use std::sync::atomic::Ordering;
+
+/// Importing the "AtomicU16"
+/// structure to change the answer
+/// of the receiver.
+use std::sync::atomic::AtomicU16;
+
+/// Importing all database functions
+/// for the webhook-related
+/// services.
+use crate::modules::db::webhooks::*;
+
+/// Importing the model for
+/// webhooks for explicit typing.
+use crate::modules::models::Webhook;
+
+/// Importing the function
+/// to create a test user.
+use super::helpers::create_test_user;
+
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use crate::modules::units::ListOptions;
+
+/// Importing the function to save
+/// the instance information.
+use super::helpers::ensure_instance_info;
+
+/// Importing the model for
+/// deliveries for explicit typing.
+use crate::modules::models::WebhookDelivery;
+
+/// Importing the function to edit
+/// the hosts webhooks may reach even
+/// though they are not public.
+use crate::modules::db::admin::edit_webhook_allowlist;
+
+/// A local receiver of deliveries.
+/// Every request is recorded and
+/// answered with the current status
+/// code.
+struct StubReceiver {
+    port: u16,
+    status: Arc<AtomicU16>,
+    requests: Arc<Mutex<Vec<String>>>
+}
+
+/// Starts a receiver on a free
+/// local port that answers every
+/// request with the supplied status
+/// code until it is changed.
+async fn start_stub_receiver(status: u16) -> StubReceiver {
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port: u16 = listener.local_addr().unwrap().port();
+    let status: Arc<AtomicU16> = Arc::new(AtomicU16::new(status));
+    let requests: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let (answer, recorded) = (status.clone(), requests.clone());
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(_e) => break
+            };
+            let mut raw: Vec<u8> = Vec::new();
+            let mut buffer: [u8; 4096] = [0; 4096];
+            loop {
+                let read: usize = stream.read(&mut buffer).await.unwrap_or(0);
+                if read == 0 {
+                    break;
+                }
+                raw.extend_from_slice(&buffer[..read]);
+                let text: String = String::from_utf8_lossy(&raw).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    if body.len() >= header_value(head, "content-length").parse::<usize>().unwrap_or(0) {
+                        break;
+                    }
+                }
+            }
+            recorded.lock().unwrap().push(String::from_utf8_lossy(&raw).to_string());
+            let response: String = format!(
+                "HTTP/1.1 {} Stub\r\nLocation: /elsewhere\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                answer.load(Ordering::SeqCst)
+            );
+            let _written = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    StubReceiver { port, status, requests }
+}
+
+/// Reads the value of the supplied
+/// header from the head of a request.
+fn header_value(head: &str, name: &str) -> String {
+    head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _value)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_key, value)| value.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Queues the deliveries of an
+/// event in a transaction of its
+/// own.
+async fn queue_event(
+    user_id: &String,
+    event: &String,
+    data: &Value,
+    pool: &Pool<Postgres>
+) {
+    let mut tx = pool.begin().await.unwrap();
+    enqueue_webhook_event(user_id, event, data, &mut tx).await.unwrap();
+    tx.commit().await.unwrap();
+}
+
+/// The options to list
+/// deliveries with, newest
+/// first.
+fn newest_first() -> ListOptions {
+    ListOptions{
+        page: 1,
+        per_page: 50,
+        sort_by: "created_at".to_string(),
+        sort_order: "desc".to_string()
+    }
+}
+
+/// The function to test
+/// the signatures of deliveries.
+#[test]
+pub fn test_webhook_signature(){
+    let signature: String = sign_payload(
+        &"key".to_string(),
+        &"The quick brown fox jumps over the lazy dog".to_string()
+    ).unwrap();
+    assert_eq!(signature, "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+}
+
+/// The function to test the
+/// wait between attempts at
+/// a delivery.
+#[test]
+pub fn test_webhook_retry_delay(){
+    assert_eq!(retry_delay(0), TimeDelta::seconds(30));
+    assert_eq!(retry_delay(1), TimeDelta::seconds(30));
+    assert_eq!(retry_delay(2), TimeDelta::seconds(60));
+    assert_eq!(retry_delay(3), TimeDelta::seconds(120));
+    assert_eq!(retry_delay(MAX_WEBHOOK_ATTEMPTS - 1), TimeDelta::seconds(1920));
+    assert_eq!(retry_delay(11), TimeDelta::seconds(WEBHOOK_RETRY_MAX));
+    assert_eq!(retry_delay(i32::MAX), TimeDelta::seconds(WEBHOOK_RETRY_MAX));
+}
+
+/// The function to test that
+/// webhooks cannot target hosts
+/// that are not public unless they
+/// are allowlisted.
+#[tokio::test]
+pub async fn test_webhook_targets(){
+    let private: [&str; 10] = [
+        "http://127.0.0.1/hook",
+        "http://localhost:8000/hook",
+        "http://api.localhost/hook",
+        "http://10.0.0.1/hook",
+        "http://192.168.1.1/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://100.64.0.1/hook",
+        "http://2130706433/hook",
+        "http://[::1]/hook",
+        "http://[::ffff:127.0.0.1]/hook"
+    ];
+    for target_url in private {
+        assert!(check_webhook_target(target_url, &[]).is_err(), "{}", target_url);
+    }
+    assert!(check_webhook_target("ftp://example.com/hook", &[]).is_err());
+    assert!(check_webhook_target("example.com/hook", &[]).is_err());
+    assert!(check_webhook_target("https://example.com/hook", &[]).is_ok());
+    assert!(check_webhook_target("http://93.184.216.34/hook", &[]).is_ok());
+    assert!(check_webhook_target("http://localhost:8000/hook", &["LOCALHOST".to_string()]).is_ok());
+    assert!(check_webhook_target("http://[::1]/hook", &["[::1]".to_string()]).is_ok());
+    assert!(resolve_webhook_target("http://localhost:8000/hook", &[]).await.is_err());
+    assert!(resolve_webhook_target("http://127.0.0.1:8000/hook", &["127.0.0.1".to_string()]).await.is_ok());
+}
+
+/// The function to test that
+/// deliveries are signed, recorded,
+/// and retried later if the receiver
+/// does not accept them.
+#[tokio::test]
+pub async fn test_webhook_deliveries(){
+    let pool = test_pool().await;
+    ensure_instance_info(&pool).await;
+    let (admin, _token): (_, String) = create_test_user(true, &pool).await;
+    edit_webhook_allowlist(&admin, &vec!["127.0.0.1".to_string()], &pool).await.unwrap();
+    let receiver: StubReceiver = start_stub_receiver(200).await;
+    let webhook: Webhook = create_webhook(
+        &admin,
+        &format!("http://127.0.0.1:{}/hook", receiver.port),
+        &vec!["post.created".to_string()],
+        &pool
+    ).await.unwrap();
+    let event: String = "post.created".to_string();
+    queue_event(&admin.user_id, &event, &json!({ "content_id": "first" }), &pool).await;
+    deliver_due_webhooks(&pool).await.unwrap();
+    let request: String = receiver.requests.lock().unwrap()[0].clone();
+    let (head, body): (&str, &str) = request.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("POST /hook "));
+    assert_eq!(header_value(head, "x-cleo-event"), "post.created");
+    assert_eq!(
+        header_value(head, "x-cleo-signature"),
+        sign_payload(&webhook.secret, &body.to_string()).unwrap()
+    );
+    let (delivered, _total): (Vec<WebhookDelivery>, i64) = get_webhook_deliveries(
+        &admin,
+        &webhook.webhook_id,
+        &newest_first(),
+        &pool
+    ).await.unwrap();
+    assert_eq!(header_value(head, "x-cleo-delivery"), delivered[0].delivery_id);
+    assert_eq!(delivered[0].delivery_status, "delivered");
+    assert_eq!(delivered[0].attempts, 1);
+    assert_eq!(delivered[0].last_status_code, Some(200));
+    receiver.status.store(302, Ordering::SeqCst);
+    queue_event(&admin.user_id, &event, &json!({ "content_id": "second" }), &pool).await;
+    let before = Utc::now();
+    deliver_due_webhooks(&pool).await.unwrap();
+    let after = Utc::now();
+    assert_eq!(receiver.requests.lock().unwrap().len(), 2);
+    let (deliveries, total): (Vec<WebhookDelivery>, i64) = get_webhook_deliveries(
+        &admin,
+        &webhook.webhook_id,
+        &newest_first(),
+        &pool
+    ).await.unwrap();
+    let retried: &WebhookDelivery = &deliveries[0];
+    assert_eq!(total, 2);
+    assert_eq!(retried.delivery_status, "pending");
+    assert_eq!(retried.attempts, 1);
+    assert_eq!(retried.last_status_code, Some(302));
+    assert!(retried.last_error.is_some());
+    assert!(retried.next_attempt_at >= before + retry_delay(1));
+    assert!(retried.next_attempt_at <= after + retry_delay(1));
+    edit_webhook(&admin, &webhook.webhook_id, &None, &None, &Some(false), &pool).await.unwrap();
+}
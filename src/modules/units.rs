@@ -10,20 +10,68 @@ Licensed under the FSL v1.
 /// database connections.
 use sqlx::Pool;
 
+/// Importing the "Utc"
+/// structure for timestamps
+/// in UTC.
+use chrono::Utc;
+
+/// Importing the "Serialize"
+/// trait to derive it.
+use serde::Serialize;
+
+/// Importing the "DateTime"
+/// structure for timestamps.
+use chrono::DateTime;
+
+/// Importing the "Value"
+/// enum for the data of 
+/// changes to content.
+use serde_json::Value;
+
+/// Importing the "Deserialize"
+/// trait to derive it.
+use serde::Deserialize;
+
 /// Importing the "Postgres"
 /// structure from the "sqlx"
 /// crate.
 use sqlx::postgres::Postgres;
 
+/// Importing the "HashMap"
+/// structure for the extra
+/// fields of new posts.
+use std::collections::HashMap;
+
+/// Importing the "Sender"
+/// structure to send changes
+/// to content to open streams.
+use tokio::sync::broadcast::Sender;
+
+/// Importing the "channel"
+/// function to create a channel
+/// for changes to content.
+use tokio::sync::broadcast::channel;
+
 /// Importing the model for
 /// users for explicit typing.
 use crate::modules::models::CleoUser;
 
+/// Importing the "Receiver"
+/// structure for explicit typing.
+use tokio::sync::broadcast::Receiver;
+
+/// Importing the number of changes
+/// to content a channel holds before
+/// slow streams miss some.
+use crate::modules::changes::CHANGE_BUFFER;
+
 /// A structure containing
 /// a pool of database connections
-/// to make app data persist.
+/// and the sender of changes to 
+/// content to make app data persist.
 pub struct AppData {
-    pub pool: Pool<Postgres>
+    pub pool: Pool<Postgres>,
+    pub changes: Sender<ChangeEvent>
 }
 
 /// Implementing generic
@@ -36,7 +84,8 @@ impl AppData{
     /// of the "AppData"
     /// structure.
     pub fn new(pg_pool: &Pool<Postgres>) -> AppData{
-        AppData { pool: pg_pool.to_owned() }
+        let (changes, _receiver): (Sender<ChangeEvent>, Receiver<ChangeEvent>) = channel(CHANGE_BUFFER);
+        AppData { pool: pg_pool.to_owned(), changes }
     }
 
 }
@@ -78,3 +127,204 @@ pub struct AdminInfo{
     pub instance: usize
 
 }
+
+/// A data structure
+/// holding a snapshot of
+/// an extra content field
+/// as it was saved in
+/// a post revision. Snapshots
+/// saved before fields had types 
+/// have no type.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FieldSnapshot{
+    pub field_id: String,
+    pub field_key: String,
+    pub field_value: String,
+    pub field_type: Option<String>
+}
+
+/// A data structure
+/// describing a single
+/// line of a difference
+/// between two texts. The
+/// tag is either "equal",
+/// "insert", or "delete".
+#[derive(Serialize)]
+pub struct DiffLine{
+    pub tag: String,
+    pub text: String
+}
+
+/// A data structure
+/// holding the descriptive
+/// fields of a post. Fields
+/// that are "None" are not
+/// set or changed.
+#[derive(Clone)]
+pub struct PostDetails{
+    pub title: Option<String>,
+    pub excerpt: Option<String>,
+    pub cover_file_id: Option<String>,
+    pub author_name: Option<String>
+}
+
+/// A data structure
+/// holding everything a
+/// new post is created from.
+pub struct NewPost{
+    pub content_type: String,
+    pub content_text: String,
+    pub source_format: String,
+    pub slug: Option<String>,
+    pub details: PostDetails,
+    pub extra_fields: HashMap<String, String>
+}
+
+/// A data structure
+/// describing an extra
+/// content field a type
+/// of content accepts and
+/// the type of its values.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FieldDefinition{
+    pub field_key: String,
+    pub field_type: String,
+    pub is_required: bool
+}
+
+/// A data structure
+/// holding the checked
+/// options for listing
+/// a page of records.
+#[derive(Clone)]
+pub struct ListOptions{
+    pub page: i64,
+    pub per_page: i64,
+    pub sort_by: String,
+    pub sort_order: String
+}
+
+/// Implementing generic
+/// methods for the "ListOptions"
+/// structure.
+impl ListOptions{
+
+    /// Implementing a method
+    /// to get the number of records
+    /// to skip before the requested
+    /// page.
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+
+}
+
+/// A data structure
+/// holding the filters
+/// for listing the posts
+/// of a user. Filters that
+/// are not set are ignored.
+pub struct PostFilters{
+    pub content_type: Option<String>,
+    pub post_status: Option<String>,
+    pub field_key: Option<String>,
+    pub field_value: Option<String>
+}
+
+/// A data structure
+/// holding a single match
+/// of a search with its rank
+/// and a snippet of the text
+/// with the matching words 
+/// highlighted.
+pub struct SearchHit{
+    pub content_id: String,
+    pub rank: f32,
+    pub snippet: String
+}
+
+/// A data structure
+/// holding the filters
+/// for searching posts.
+/// Filters that are not
+/// set are ignored.
+pub struct SearchFilters{
+    pub author: Option<String>,
+    pub content_type: Option<String>
+}
+
+/// A data structure
+/// holding a tag or category
+/// together with the ID of a 
+/// post it is assigned to.
+pub struct AssignedTerm{
+    pub content_id: String,
+    pub term_id: String,
+    pub taxonomy: String,
+    pub term_name: String,
+    pub term_slug: String,
+    pub parent_id: Option<String>
+}
+
+/// A data structure
+/// holding what an item
+/// of a navigation menu 
+/// points to. Posts and pages
+/// are given by their ID, files
+/// by their ID, and external links
+/// by their URL.
+#[derive(Deserialize, Clone)]
+pub struct MenuTarget{
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub url: Option<String>
+}
+
+/// A structure holding
+/// the details of a feed
+/// that are shared by all
+/// feed formats.
+pub struct FeedMeta{
+    pub title: String,
+    pub description: String,
+    pub home_url: String,
+    pub feed_url: String,
+    pub updated: DateTime<Utc>
+}
+
+/// A structure holding
+/// the details of a post
+/// as an entry of a feed.
+pub struct FeedEntry{
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub author: String,
+    pub summary: String,
+    pub content_html: String,
+    pub published: DateTime<Utc>,
+    pub updated: DateTime<Utc>
+}
+
+/// A structure holding
+/// the details of a published
+/// post needed for an entry
+/// of a sitemap.
+pub struct SitemapEntry{
+    pub username: String,
+    pub content_type: String,
+    pub slug: String,
+    pub updated_at: DateTime<Utc>
+}
+
+/// A data structure
+/// describing a change to
+/// the content of a user.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ChangeEvent{
+    pub event_id: String,
+    pub user_id: String,
+    pub event: String,
+    pub created_at: String,
+    pub data: Value
+}
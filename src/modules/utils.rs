@@ -25,15 +25,39 @@ use sha2::Sha256;
 /// to send an email.
 use lettre::Message;
 
-/// Importing the prelude from
-/// the "rand" crate to get
-/// random items from a vector.
-use rand::prelude::*;
+/// Importing the "DateTime"
+/// structure to validate
+/// dates.
+use chrono::DateTime;
+
+/// Importing the "TextDiff"
+/// structure to compute the
+/// differences between texts.
+use similar::TextDiff;
+
+/// Importing the "Value"
+/// enum to return typed
+/// JSON values.
+use serde_json::Value;
+
+/// Importing the "NaiveDate"
+/// structure to validate
+/// dates without a time.
+use chrono::NaiveDate;
+
+/// Importing the "ChangeTag"
+/// enum to describe the type
+/// of a change between texts.
+use similar::ChangeTag;
 
 /// Importing this crate's
 /// error structure.
 use super::err::CleoErr;
 
+/// Importing the function to
+/// parse JSON strings.
+use serde_json::from_str;
+
 /// Using the "Local"
 /// structure from the "chrono"
 /// crate to retrieve the current 
@@ -46,11 +70,21 @@ use chrono::offset::Local;
 /// to send an email.
 use lettre::Tokio1Executor;
 
+/// Importing the structure
+/// describing a single line
+/// of a difference.
+use super::units::DiffLine;
+
 /// Importing the "Postgres"
 /// structure from the "sqlx"
 /// crate.
 use sqlx::postgres::Postgres;
 
+/// Importing the structure
+/// holding the options for
+/// listing records.
+use super::units::ListOptions;
+
 /// Importing the structure
 /// to enable the asynchronous
 /// sending of emails.
@@ -161,28 +195,6 @@ impl TimeNow{
     }
 }
 
-/// Attempts to generate a random character
-/// sequence of the supplied size. If this 
-/// operation fails, an error is returned.
-pub fn generate_key(size: &usize) -> Result<String, CleoErr> {
-    let mut char_vec: Vec<char> = Vec::new();
-    let alpha: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890"
-        .to_string()
-        .chars()
-        .collect::<Vec<char>>();
-    for _i in 1..(size+1){
-        let mut random: ThreadRng = rand::rng();
-        let rnd_char: char = match alpha.choose(&mut random){
-            Some(rnd_char) => *rnd_char,
-            None => return Err::<String, CleoErr>(CleoErr::new(&"Could not pick random character.".to_string()))
-        };
-        char_vec.push(
-            rnd_char
-        );
-    }
-    Ok(char_vec.into_iter().collect::<String>())
-}
-
 /// This function attempts to
 /// send an email to the specified
 /// sender with the specified parameters.
@@ -225,3 +237,186 @@ pub async fn send_email(
     };
     Ok(send_op.is_positive())
 }
+
+/// Computes a line-by-line difference
+/// between two texts and returns a vector
+/// of instances of the "DiffLine" structure.
+pub fn diff_text(old: &String, new: &String) -> Vec<DiffLine> {
+    let mut result: Vec<DiffLine> = Vec::new();
+    let diff = TextDiff::from_lines(old, new);
+    for change in diff.iter_all_changes() {
+        let tag: &str = match change.tag() {
+            ChangeTag::Equal => "equal",
+            ChangeTag::Insert => "insert",
+            ChangeTag::Delete => "delete"
+        };
+        result.push(
+            DiffLine{
+                tag: tag.to_string(),
+                text: change.value().to_string()
+            }
+        );
+    }
+    result
+}
+
+/// The maximum number of
+/// characters a generated
+/// slug may have.
+pub const MAX_SLUG_LENGTH: usize = 64;
+
+/// Turns the supplied string into a
+/// URL-friendly slug. Only lowercase
+/// ASCII letters, numbers, and single 
+/// hyphens are kept. The string must
+/// be plain text without markup.
+pub fn slugify(subject: &str) -> String {
+    let mut result: String = String::new();
+    for character in subject.chars() {
+        if character.is_ascii_alphanumeric() {
+            result.push(character.to_ascii_lowercase());
+        }
+        else if !result.ends_with('-') {
+            result.push('-');
+        }
+    }
+    let trimmed: String = result
+        .trim_matches('-')
+        .chars()
+        .take(MAX_SLUG_LENGTH)
+        .collect();
+    trimmed.trim_end_matches('-').to_string()
+}
+
+/// The types the values of
+/// extra content fields can
+/// have.
+pub const FIELD_TYPES: [&str; 8] = [
+    "string", "integer", "float", "boolean", 
+    "date", "url", "file", "json"
+];
+
+/// Checks whether the supplied
+/// string is a valid type for
+/// extra content fields. If it is
+/// not, an error is returned.
+pub fn validate_field_type(field_type: &String) -> Result<(), CleoErr> {
+    if FIELD_TYPES.contains(&field_type.as_str()) {
+        Ok(())
+    }
+    else {
+        let e: String = format!("\"{}\" is not a valid field type.", field_type);
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// Checks whether the supplied value
+/// is valid for the supplied field type.
+/// Dates must be in the RFC 3339 or the 
+/// "YYYY-MM-DD" format. URLs must be
+/// absolute HTTP or HTTPS URLs. If the
+/// value is not valid, an error is returned.
+pub fn validate_field_value(field_type: &String, value: &String) -> Result<(), CleoErr> {
+    let is_valid: bool = match field_type.as_str() {
+        "string" => true,
+        "integer" => value.parse::<i64>().is_ok(),
+        "float" => value.parse::<f64>().map(|number| number.is_finite()).unwrap_or(false),
+        "boolean" => value == "true" || value == "false",
+        "date" => DateTime::parse_from_rfc3339(value).is_ok() || 
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        "url" => (value.starts_with("http://") || value.starts_with("https://")) &&
+            !value.chars().any(|character| character.is_whitespace()),
+        "file" => !value.is_empty(),
+        "json" => from_str::<Value>(value).is_ok(),
+        _ => {
+            let e: String = format!("\"{}\" is not a valid field type.", field_type);
+            return Err::<(), CleoErr>(CleoErr::new(&e.to_string()));
+        }
+    };
+    if is_valid {
+        Ok(())
+    }
+    else {
+        let e: String = format!("\"{}\" is not a valid value of the type \"{}\".", value, field_type);
+        Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// Converts the stored value of an
+/// extra content field to a JSON value
+/// of the field's type. Values that
+/// cannot be converted are returned
+/// as strings.
+pub fn field_value_to_json(field_type: &str, value: &str) -> Value {
+    let converted: Option<Value> = match field_type {
+        "integer" => value.parse::<i64>().ok().map(Value::from),
+        "float" => value.parse::<f64>().ok().map(Value::from),
+        "boolean" => value.parse::<bool>().ok().map(Value::from),
+        "json" => from_str::<Value>(value).ok(),
+        _ => None
+    };
+    converted.unwrap_or(Value::String(value.to_owned()))
+}
+
+/// The number of records on
+/// a page of a list if no page
+/// size is given.
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+
+/// The largest number of
+/// records a page of a list
+/// may hold.
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Checks and fills in the options
+/// supplied for listing records. The 
+/// first of the supplied sort fields is
+/// used if no sort field is given and 
+/// records are sorted in descending order
+/// by default. The number of records to
+/// skip before the page must fit in an
+/// "i64". If any option is invalid, an
+/// error is returned.
+pub fn resolve_list_options(
+    page: &Option<i64>,
+    per_page: &Option<i64>,
+    sort_by: &Option<String>,
+    sort_order: &Option<String>,
+    sort_fields: &[&str]
+) -> Result<ListOptions, CleoErr> {
+    let page: i64 = page.unwrap_or(1);
+    if page < 1 {
+        let e: &str = "The page number must be at least 1.";
+        return Err::<ListOptions, CleoErr>(CleoErr::new(e));
+    }
+    let per_page: i64 = per_page.unwrap_or(DEFAULT_PAGE_SIZE);
+    if !(1..=MAX_PAGE_SIZE).contains(&per_page) {
+        let e: String = format!("The page size must be between 1 and {}.", MAX_PAGE_SIZE);
+        return Err::<ListOptions, CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    if (page - 1).checked_mul(per_page).is_none() {
+        let e: &str = "The page number is too large.";
+        return Err::<ListOptions, CleoErr>(CleoErr::new(e));
+    }
+    let sort_by: String = match sort_by {
+        Some(sort_by) => sort_by.to_lowercase(),
+        None => sort_fields[0].to_string()
+    };
+    if !sort_fields.contains(&sort_by.as_str()) {
+        let e: String = format!(
+            "Records cannot be sorted by \"{}\". Valid options are: {}.", 
+            sort_by,
+            sort_fields.join(", ")
+        );
+        return Err::<ListOptions, CleoErr>(CleoErr::new(&e.to_string()));
+    }
+    let sort_order: String = match sort_order {
+        Some(sort_order) => sort_order.to_lowercase(),
+        None => "desc".to_string()
+    };
+    if sort_order != "asc" && sort_order != "desc" {
+        let e: &str = "The sort order must be either \"asc\" or \"desc\".";
+        return Err::<ListOptions, CleoErr>(CleoErr::new(e));
+    }
+    Ok(ListOptions{ page, per_page, sort_by, sort_order })
+}
@@ -0,0 +1,554 @@
+/*
+Cleo by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+/// Importing the "Mac"
+/// trait to compute HMAC
+/// signatures.
+use hmac::Mac;
+
+/// Importing the
+/// "Pool" structure
+/// to accept multiple
+/// connections to a 
+/// database.
+use sqlx::Pool;
+
+/// Importing the "Hmac"
+/// structure to sign the
+/// bodies of deliveries.
+use hmac::Hmac;
+
+/// Importing the "Utc"
+/// structure to retrieve
+/// the current time.
+use chrono::Utc;
+
+/// Importing the "Sha256"
+/// structure to sign with
+/// SHA-256.
+use sha2::Sha256;
+
+/// Importing the "Url"
+/// structure to read the
+/// hosts of webhooks.
+use reqwest::Url;
+
+/// Importing the "Client"
+/// structure to send HTTP
+/// requests.
+use reqwest::Client;
+
+/// Importing the "IpAddr"
+/// enum to check where
+/// deliveries are sent.
+use std::net::IpAddr;
+
+/// Importing the "json"
+/// macro to build the data
+/// of events.
+use serde_json::json;
+
+/// Importing the "DateTime"
+/// structure for explicit
+/// typing.
+use chrono::DateTime;
+
+/// Importing the "Value"
+/// enum for the data of 
+/// events.
+use serde_json::Value;
+
+/// Importing the "TimeDelta"
+/// structure to compute the
+/// time of the next attempt.
+use chrono::TimeDelta;
+
+/// Importing the "Duration"
+/// structure to limit how long
+/// a delivery may take.
+use std::time::Duration;
+
+/// Importing the "timeout"
+/// function to limit how long
+/// an attempt may take.
+use tokio::time::timeout;
+
+/// Importing the "JoinSet"
+/// structure to attempt the
+/// deliveries of a batch at
+/// once.
+use tokio::task::JoinSet;
+
+/// Importing the "SocketAddr"
+/// structure to pin deliveries
+/// to checked addresses.
+use std::net::SocketAddr;
+
+/// Importing the function to
+/// resolve the hosts of webhooks.
+use tokio::net::lookup_host;
+
+/// Importing the "Postgres"
+/// to specify the type of
+/// database one is connecting
+/// to.
+use sqlx::postgres::Postgres;
+
+/// Importing the "Policy"
+/// structure to stop deliveries
+/// from following redirects.
+use reqwest::redirect::Policy;
+
+/// Importing the "HashMap"
+/// structure to cache the
+/// webhooks of deliveries.
+use std::collections::HashMap;
+
+/// Importing the "CleoErr"
+/// structure to catch and handle
+/// errors.
+use crate::modules::err::CleoErr;
+
+/// Importing the "Webhook" structure
+/// to read information about webhooks.
+use crate::modules::models::Webhook;
+
+/// Importing the "UserFile" structure
+/// to describe files in events.
+use crate::modules::models::UserFile;
+
+/// Importing the "UserPost" structure
+/// to describe posts in events.
+use crate::modules::models::UserPost;
+
+/// Importing the "WebhookDelivery" 
+/// structure to read information about
+/// deliveries of webhooks.
+use crate::modules::models::WebhookDelivery;
+
+/// Importing the "InstanceInformation"
+/// structure to read the allowlist
+/// of the instance.
+use crate::modules::models::InstanceInformation;
+
+/// Importing the function to read
+/// the allowlist of the instance.
+use crate::modules::db::general::get_instance_info;
+
+/// Importing the function to retrieve
+/// a webhook given its ID.
+use crate::modules::db::webhooks::get_webhook_by_id;
+
+/// Importing the function to claim
+/// the deliveries that are due.
+use crate::modules::db::webhooks::claim_due_deliveries;
+
+/// Importing the function to record
+/// the outcome of an attempt at a
+/// delivery.
+use crate::modules::db::webhooks::record_delivery_attempt;
+
+/// The number of times a delivery
+/// is attempted before it is marked
+/// as failed.
+pub const MAX_WEBHOOK_ATTEMPTS: i32 = 8;
+
+/// The number of seconds to wait
+/// before the first retry of a
+/// delivery. The wait doubles with
+/// every further attempt.
+pub const WEBHOOK_RETRY_BASE: i64 = 30;
+
+/// The longest number of seconds
+/// to wait between two attempts at
+/// a delivery.
+pub const WEBHOOK_RETRY_MAX: i64 = 6 * 60 * 60;
+
+/// The number of seconds a receiver
+/// has to answer a delivery, including
+/// the time to resolve its host.
+pub const WEBHOOK_TIMEOUT: u64 = 10;
+
+/// The largest number of deliveries
+/// that are claimed and attempted at
+/// once.
+pub const WEBHOOK_BATCH_SIZE: i64 = 50;
+
+/// The number of seconds a claimed
+/// delivery is hidden from other 
+/// dispatchers while it is attempted.
+/// A batch is attempted at once, so
+/// this only has to outlast the timeout
+/// of a single delivery.
+pub const WEBHOOK_LEASE: i64 = 5 * 60;
+
+/// Computes the HMAC-SHA256 signature
+/// of the supplied body with the supplied
+/// secret and returns it as lowercase 
+/// hexadecimal prefixed with "sha256=". 
+/// If this operation fails, an error is 
+/// returned.
+pub fn sign_payload(secret: &String, body: &String) -> Result<String, CleoErr> {
+    let mut mac: Hmac<Sha256> = match Hmac::<Sha256>::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(e) => return Err::<String, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    mac.update(body.as_bytes());
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    Ok(format!("sha256={}", signature))
+}
+
+/// Returns the time to wait before
+/// the next attempt at a delivery that
+/// has been attempted the supplied number
+/// of times.
+pub fn retry_delay(attempts: i32) -> TimeDelta {
+    let exponent: u32 = (attempts.max(1) - 1).min(20) as u32;
+    let seconds: i64 = WEBHOOK_RETRY_BASE
+        .saturating_mul(2_i64.saturating_pow(exponent))
+        .min(WEBHOOK_RETRY_MAX);
+    TimeDelta::seconds(seconds)
+}
+
+/// Brings the supplied host into
+/// the form hosts are compared in.
+/// Hosts are lowercased and lose any
+/// surrounding brackets and a trailing
+/// dot.
+pub fn normalize_webhook_host(host: &str) -> String {
+    host.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_lowercase()
+}
+
+/// Checks whether the supplied
+/// address can be reached from the
+/// public internet. Loopback, private,
+/// link-local, shared, unspecified,
+/// broadcast, and multicast addresses
+/// are not public. IPv4 addresses
+/// mapped into IPv6 are checked as
+/// IPv4 addresses.
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets: [u8; 4] = ip.octets();
+            !(ip.is_loopback() || 
+                ip.is_private() || 
+                ip.is_link_local() || 
+                ip.is_unspecified() || 
+                ip.is_broadcast() || 
+                ip.is_multicast() || 
+                octets[0] == 0 || 
+                (octets[0] == 100 && (octets[1] & 0xc0) == 64))
+        },
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ip(&IpAddr::V4(mapped)),
+            None => {
+                let first: u16 = ip.segments()[0];
+                !(ip.is_loopback() || 
+                    ip.is_unspecified() || 
+                    ip.is_multicast() || 
+                    (first & 0xfe00) == 0xfc00 || 
+                    (first & 0xffc0) == 0xfe80)
+            }
+        }
+    }
+}
+
+/// Checks whether the supplied host
+/// is on the supplied allowlist of the
+/// instance. Allowlisted hosts may be 
+/// reached even if they are not public.
+pub fn is_allowlisted_host(host: &str, allowlist: &[String]) -> bool {
+    let host: String = normalize_webhook_host(host);
+    allowlist.iter().any(|allowed| normalize_webhook_host(allowed) == host)
+}
+
+/// Reads the host and the port from
+/// the supplied URL of a webhook. Only
+/// absolute HTTP and HTTPS URLs with a
+/// host are accepted. If the URL is not
+/// valid, an error is returned.
+pub fn webhook_target(target_url: &str) -> Result<(String, u16), CleoErr> {
+    let e: String = format!("The URL \"{}\" cannot receive webhooks.", target_url);
+    let url: Url = match Url::parse(target_url.trim()) {
+        Ok(url) => url,
+        Err(_e) => return Err::<(String, u16), CleoErr>(CleoErr::new(&e))
+    };
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err::<(String, u16), CleoErr>(CleoErr::new(&e));
+    }
+    let host: String = match url.host_str() {
+        Some(host) if !host.is_empty() => normalize_webhook_host(host),
+        _ => return Err::<(String, u16), CleoErr>(CleoErr::new(&e))
+    };
+    let port: u16 = match url.port_or_known_default() {
+        Some(port) => port,
+        None => return Err::<(String, u16), CleoErr>(CleoErr::new(&e))
+    };
+    Ok((host, port))
+}
+
+/// Checks whether the supplied URL of
+/// a webhook points to a public host without
+/// resolving it. Addresses that are not public
+/// and names of the local machine are rejected
+/// unless the host is on the supplied allowlist.
+/// If the URL may not receive webhooks, an error
+/// is returned.
+pub fn check_webhook_target(target_url: &str, allowlist: &[String]) -> Result<(), CleoErr> {
+    let (host, _port): (String, u16) = match webhook_target(target_url) {
+        Ok(target) => target,
+        Err(e) => return Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if is_allowlisted_host(&host, allowlist) {
+        return Ok(());
+    }
+    let is_local_name: bool = host == "localhost" || host.ends_with(".localhost");
+    let is_private_ip: bool = match host.parse::<IpAddr>() {
+        Ok(ip) => !is_public_ip(&ip),
+        Err(_e) => false
+    };
+    if is_local_name || is_private_ip {
+        let e: String = format!("The URL \"{}\" points to a host that is not public.", target_url);
+        return Err::<(), CleoErr>(CleoErr::new(&e));
+    }
+    Ok(())
+}
+
+/// Resolves the host of the supplied
+/// URL of a webhook and checks every
+/// address it resolves to. Unless the host
+/// is on the supplied allowlist, all addresses
+/// must be public. If the operation is successful,
+/// the host and the checked addresses are returned.
+/// Allowlisted hosts and addresses in the URL
+/// come back without addresses. If the host
+/// cannot be resolved or is not public, an error
+/// is returned.
+pub async fn resolve_webhook_target(
+    target_url: &str,
+    allowlist: &[String]
+) -> Result<(String, Vec<SocketAddr>), CleoErr> {
+    let _checked: () = match check_webhook_target(target_url, allowlist) {
+        Ok(checked) => checked,
+        Err(e) => return Err::<(String, Vec<SocketAddr>), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let (host, port): (String, u16) = match webhook_target(target_url) {
+        Ok(target) => target,
+        Err(e) => return Err::<(String, Vec<SocketAddr>), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if is_allowlisted_host(&host, allowlist) || host.parse::<IpAddr>().is_ok() {
+        return Ok((host, Vec::new()));
+    }
+    let addrs: Vec<SocketAddr> = match lookup_host((host.as_str(), port)).await {
+        Ok(addrs) => addrs.collect::<Vec<SocketAddr>>(),
+        Err(e) => return Err::<(String, Vec<SocketAddr>), CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if addrs.is_empty() || addrs.iter().any(|addr| !is_public_ip(&addr.ip())) {
+        let e: String = format!("The host \"{}\" does not resolve to public addresses only.", &host);
+        return Err::<(String, Vec<SocketAddr>), CleoErr>(CleoErr::new(&e));
+    }
+    Ok((host, addrs))
+}
+
+/// Describes a post in the data
+/// of an event.
+pub fn post_event_data(post: &UserPost) -> Value {
+    json!({
+        "content_id": post.content_id,
+        "content_type": post.content_type,
+        "slug": post.slug,
+        "post_status": post.post_status
+    })
+}
+
+/// Describes a file in the data
+/// of an event.
+pub fn file_event_data(file: &UserFile) -> Value {
+    json!({
+        "file_id": file.file_id,
+        "file_url": file.file_url
+    })
+}
+
+/// Describes a change to an extra
+/// content field of a post in the data
+/// of an event.
+pub fn field_event_data(
+    post: &UserPost, 
+    field_id: &String, 
+    field_key: &String, 
+    change: &str
+) -> Value {
+    json!({
+        "content_id": post.content_id,
+        "content_type": post.content_type,
+        "field_id": field_id,
+        "field_key": field_key,
+        "change": change
+    })
+}
+
+/// Attempts to send a delivery to the
+/// URL of its webhook. The host of the URL
+/// is resolved and checked first and the
+/// request is pinned to the checked addresses.
+/// Redirects are not followed. The body is 
+/// signed with the secret of the webhook. If
+/// the receiver answers, its status code is 
+/// returned. If the request could not be
+/// sent, an error is returned.
+pub async fn send_delivery(
+    webhook: &Webhook,
+    delivery: &WebhookDelivery,
+    allowlist: &[String]
+) -> Result<u16, CleoErr> {
+    let (host, addrs): (String, Vec<SocketAddr>) = match resolve_webhook_target(&webhook.target_url, allowlist).await {
+        Ok(target) => target,
+        Err(e) => return Err::<u16, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT))
+        .redirect(Policy::none())
+        .no_proxy();
+    if !addrs.is_empty() {
+        builder = builder.resolve_to_addrs(&host, &addrs);
+    }
+    let client: Client = match builder.build() {
+        Ok(client) => client,
+        Err(e) => return Err::<u16, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let signature: String = match sign_payload(&webhook.secret, &delivery.payload) {
+        Ok(signature) => signature,
+        Err(e) => return Err::<u16, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let response = match client
+        .post(webhook.target_url.trim())
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "Cleo-Webhooks")
+        .header("X-Cleo-Event", &delivery.event)
+        .header("X-Cleo-Delivery", &delivery.delivery_id)
+        .header("X-Cleo-Signature", signature)
+        .body(delivery.payload.clone())
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return Err::<u16, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    Ok(response.status().as_u16())
+}
+
+/// Attempts a single delivery and
+/// records the outcome. If the webhook
+/// of the delivery could not be retrieved,
+/// the attempt is recorded as failed. The
+/// whole attempt is limited to the timeout
+/// for deliveries. Deliveries that fail are
+/// retried later with a growing wait until
+/// they have been attempted too often. If
+/// the outcome could not be recorded, an
+/// error is returned.
+pub async fn attempt_delivery(
+    webhook: &Result<Webhook, CleoErr>,
+    delivery: &WebhookDelivery,
+    allowlist: &[String],
+    pool: &Pool<Postgres>
+) -> Result<(), CleoErr> {
+    let sent: Result<u16, CleoErr> = match webhook {
+        Ok(webhook) => match timeout(Duration::from_secs(WEBHOOK_TIMEOUT), send_delivery(webhook, delivery, allowlist)).await {
+            Ok(sent) => sent,
+            Err(_elapsed) => Err::<u16, CleoErr>(CleoErr::new("The receiver did not answer in time."))
+        },
+        Err(e) => Err::<u16, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let attempts: i32 = delivery.attempts + 1;
+    let (status_code, error): (Option<i32>, Option<String>) = match sent {
+        Ok(status_code) if (200..300).contains(&status_code) => (Some(status_code as i32), None),
+        Ok(status_code) => (
+            Some(status_code as i32), 
+            Some(format!("The receiver answered with the status code {}.", status_code))
+        ),
+        Err(e) => (None, Some(e.to_string()))
+    };
+    let next_attempt_at: Option<DateTime<Utc>> = if error.is_some() && attempts < MAX_WEBHOOK_ATTEMPTS {
+        Some(Utc::now() + retry_delay(attempts))
+    }
+    else {
+        None
+    };
+    match record_delivery_attempt(
+        &delivery.delivery_id, 
+        &attempts, 
+        &status_code, 
+        &error, 
+        &next_attempt_at, 
+        pool
+    ).await {
+        Ok(recorded) => Ok(recorded),
+        Err(e) => Err::<(), CleoErr>(CleoErr::new(&e.to_string()))
+    }
+}
+
+/// Attempts all deliveries that are
+/// due at once and records the outcome
+/// of every attempt. An attempt that fails
+/// does not stop the others. If the operation
+/// is successful, the number of attempted
+/// deliveries is returned. If the deliveries
+/// could not be claimed or the outcome of
+/// an attempt could not be recorded, an
+/// error is returned.
+pub async fn deliver_due_webhooks(
+    pool: &Pool<Postgres>
+) -> Result<usize, CleoErr> {
+    let deliveries: Vec<WebhookDelivery> = match claim_due_deliveries(&WEBHOOK_BATCH_SIZE, pool).await {
+        Ok(deliveries) => deliveries,
+        Err(e) => return Err::<usize, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    if deliveries.is_empty() {
+        return Ok(0);
+    }
+    let info: InstanceInformation = match get_instance_info(pool).await {
+        Ok(info) => info,
+        Err(e) => return Err::<usize, CleoErr>(CleoErr::new(&e.to_string()))
+    };
+    let mut webhooks: HashMap<String, Result<Webhook, CleoErr>> = HashMap::new();
+    let mut attempts: JoinSet<Result<(), CleoErr>> = JoinSet::new();
+    let attempted: usize = deliveries.len();
+    for delivery in deliveries {
+        if !webhooks.contains_key(&delivery.webhook_id) {
+            let webhook: Result<Webhook, CleoErr> = get_webhook_by_id(&delivery.webhook_id, pool).await;
+            webhooks.insert(delivery.webhook_id.clone(), webhook);
+        }
+        let webhook: Result<Webhook, CleoErr> = webhooks[&delivery.webhook_id].clone();
+        let allowlist: Vec<String> = info.webhook_allowlist.clone();
+        let pool: Pool<Postgres> = pool.clone();
+        attempts.spawn(async move {
+            attempt_delivery(&webhook, &delivery, &allowlist, &pool).await
+        });
+    }
+    let mut failure: Option<CleoErr> = None;
+    while let Some(joined) = attempts.join_next().await {
+        match joined {
+            Ok(Ok(_recorded)) => {},
+            Ok(Err(e)) => failure = Some(e),
+            Err(e) => failure = Some(CleoErr::new(&e.to_string()))
+        };
+    }
+    match failure {
+        Some(e) => Err::<usize, CleoErr>(e),
+        None => Ok(attempted)
+    }
+}